@@ -0,0 +1,35 @@
+use ark_bn254::{Fr, G1Affine};
+use ark_std::{test_rng, UniformRand};
+use barustenberg::test_utils::ecc::{glv_msm, glv_mul, pippenger};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+pub fn mul_benchmark(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let point = G1Affine::rand(&mut rng);
+    let scalar = Fr::rand(&mut rng);
+
+    let mut group = c.benchmark_group("scalar mul");
+    group.bench_function("plain", |b| b.iter(|| point * scalar));
+    group.bench_function("glv", |b| b.iter(|| glv_mul(&point, &scalar)));
+    group.finish();
+}
+
+pub fn msm_benchmark(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let mut group = c.benchmark_group("msm");
+    group.sample_size(10);
+    for n in [1 << 10, 1 << 14] {
+        let points: Vec<G1Affine> = (0..n).map(|_| G1Affine::rand(&mut rng)).collect();
+        let scalars: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        group.bench_with_input(BenchmarkId::new("pippenger", n), &n, |b, _| {
+            b.iter(|| pippenger(&points, &scalars))
+        });
+        group.bench_with_input(BenchmarkId::new("glv", n), &n, |b, _| {
+            b.iter(|| glv_msm(&points, &scalars))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, mul_benchmark, msm_benchmark);
+criterion_main!(benches);
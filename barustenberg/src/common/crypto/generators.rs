@@ -0,0 +1,162 @@
+//! Generators for Pedersen hashes and commitments, with the fixed-base ladders used to multiply them.
+//!
+//! As in barretenberg, generators are [derived](derive_generators) from a domain separator, and a hash or
+//! commitment at offset `index` multiplies its `i`th input by generator `index + i`. The length of a hashed
+//! input is bound with a generator of its own domain.
+
+use ark_ff::PrimeField;
+use once_cell::sync::{Lazy, OnceCell};
+
+use crate::ecc::{
+    curves::grumpkin::{derive_generators, Affine, Fq, GrumpkinConfig, Projective},
+    fixed_base::{evaluate_fixed_base, FixedBaseTable},
+    wnaf::fixed_wnaf,
+};
+
+/// Domain separator of the generators hashes and commitments use by default.
+pub(crate) const DEFAULT_DOMAIN_SEPARATOR: &[u8] = b"DEFAULT_DOMAIN_SEPARATOR";
+/// Domain separator of the generator the length of a hashed input is multiplied by.
+pub(crate) const LENGTH_DOMAIN_SEPARATOR: &[u8] = b"pedersen_hash_length";
+/// Generators of the default domain we precompute, which bounds `index + sub_index`.
+pub(crate) const NUM_DEFAULT_GENERATORS: usize = 2048;
+
+/// Bits in a field element we hash or commit to.
+pub(crate) const NUM_BITS: usize = 254;
+/// Base 4 digits a `NUM_BITS` scalar is recoded into, besides the leading one.
+pub(crate) const NUM_QUADS: usize = num_quads(NUM_BITS);
+/// Bits the recoding covers, so that the leading digit sits above the scalar.
+const NUM_WNAF_BITS: usize = (NUM_QUADS << 1) + 1;
+
+const BIT_LENGTH: usize = 256;
+const QUAD_LENGTH: usize = BIT_LENGTH / 2 + 1;
+
+/// `n` such that a `num_bits` scalar is `4ⁿ` plus n signed base 4 digits.
+const fn num_quads(num_bits: usize) -> usize {
+    let n = (num_bits - 1) >> 1;
+    if (n << 1) + 1 < num_bits {
+        n + 1
+    } else {
+        n
+    }
+}
+
+/// Which generator to use: the `sub_index`th input hashed at offset `index`, which is generator
+/// `index + sub_index` of the default domain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct GeneratorIndex {
+    pub(crate) index: usize,
+    pub(crate) sub_index: usize,
+}
+
+impl GeneratorIndex {
+    pub(crate) fn new(index: usize, sub_index: usize) -> Self {
+        Self { index, sub_index }
+    }
+}
+
+/// `4ⁱ·G` and `3·4ⁱ·G` for one rung i of a ladder, a row of a [`FixedBaseTable`] with 2 bit windows.
+pub(crate) type FixedBaseLadder = Vec<Affine>;
+
+/// A generator and its ladder.
+#[derive(Debug)]
+pub(crate) struct GeneratorData {
+    pub(crate) generator: Affine,
+    /// rung `QUAD_LENGTH - 1 - i` holds `4ⁱ·generator`
+    ladder: FixedBaseTable<GrumpkinConfig>,
+}
+
+impl GeneratorData {
+    fn new(generator: Affine) -> Self {
+        Self {
+            generator,
+            ladder: FixedBaseTable::new(generator, 2 * QUAD_LENGTH, 2),
+        }
+    }
+
+    /// The rungs for a `num_bits` scalar: `4ⁿ·G` for the leading one, then one per digit.
+    pub(crate) fn get_ladder(&self, num_bits: usize) -> &[FixedBaseLadder] {
+        &self.ladder.rows[QUAD_LENGTH - num_quads(num_bits) - 1..]
+    }
+
+    /// `scalar·generator`, walked on the ladder.
+    pub(crate) fn mul(&self, scalar: &Fq) -> Projective {
+        fixed_base_mul(self.get_ladder(NUM_BITS), &self.generator, scalar)
+    }
+}
+
+static GENERATORS: Lazy<Vec<Affine>> =
+    Lazy::new(|| derive_generators(DEFAULT_DOMAIN_SEPARATOR, NUM_DEFAULT_GENERATORS, 0));
+static GENERATOR_DATA: Lazy<Vec<OnceCell<GeneratorData>>> = Lazy::new(|| {
+    (0..NUM_DEFAULT_GENERATORS)
+        .map(|_| OnceCell::new())
+        .collect()
+});
+static LENGTH_GENERATOR: Lazy<GeneratorData> =
+    Lazy::new(|| GeneratorData::new(derive_generators(LENGTH_DOMAIN_SEPARATOR, 1, 0)[0]));
+
+/// The generator data for `index`, computing its ladder the first time it is asked for.
+pub(crate) fn get_generator_data(index: GeneratorIndex) -> &'static GeneratorData {
+    let global_index = index.index + index.sub_index;
+    assert!(
+        global_index < NUM_DEFAULT_GENERATORS,
+        "generator index {:?} out of range",
+        index
+    );
+    GENERATOR_DATA[global_index].get_or_init(|| GeneratorData::new(GENERATORS[global_index]))
+}
+
+/// The generator data the length of a hashed input is multiplied by.
+pub(crate) fn get_length_generator_data() -> &'static GeneratorData {
+    &LENGTH_GENERATOR
+}
+
+/// Walks `ladder` one rung per wNAF digit of `scalar`. An even scalar is walked as `scalar + 1`, and
+/// `skew_generator` is subtracted for it, as barretenberg does.
+pub(crate) fn fixed_base_mul(
+    ladder: &[FixedBaseLadder],
+    skew_generator: &Affine,
+    scalar: &Fq,
+) -> Projective {
+    let wnaf = fixed_wnaf(&scalar.into_bigint(), NUM_WNAF_BITS, 2);
+    evaluate_fixed_base(ladder, &wnaf, skew_generator)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecc::curves::grumpkin::Fr;
+    use ark_ff::{BigInteger, MontFp};
+
+    #[test]
+    fn ladders_multiply_by_their_generators() {
+        let data = get_generator_data(GeneratorIndex::new(1, 3));
+        let full_width: Fq =
+            MontFp!("8348290926806238258312756739594987960355375850427625685168392983495899574803");
+        for scalar in [Fq::from(6u64), Fq::from(7u64), full_width, -Fq::from(2u64)] {
+            let x = Fr::from_le_bytes_mod_order(&scalar.into_bigint().to_bytes_le());
+            assert_eq!(data.mul(&scalar), data.generator * x);
+        }
+    }
+
+    #[test]
+    fn generator_indices_are_offsets() {
+        let a = get_generator_data(GeneratorIndex::new(0, 0));
+        let b = get_generator_data(GeneratorIndex::new(0, 1));
+        let c = get_generator_data(GeneratorIndex::new(1, 0));
+        assert_eq!(a.generator, GENERATORS[0]);
+        assert_eq!(b.generator, GENERATORS[1]);
+        assert_eq!(c.generator, b.generator);
+        assert_ne!(get_length_generator_data().generator, a.generator);
+    }
+
+    /// The x coordinate of barretenberg's length generator, 0x2df8b940...f2cdcd.
+    #[test]
+    fn length_generator_matches_barretenberg() {
+        assert_eq!(
+            get_length_generator_data().generator.x,
+            MontFp!(
+                "20793534830995666052889629834383221590246524226953098604058514977776739732941"
+            )
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub(crate) mod generators;
+pub(crate) mod pedersen_commitment;
+pub(crate) mod pedersen_hash;
@@ -0,0 +1,86 @@
+//! Native Pedersen commitments over Grumpkin: the sum of the inputs' [`hash_single`] under one hash index.
+//!
+//! As in barretenberg, a commitment does not bind the number of inputs, so [`compress`] is not the
+//! [Pedersen hash](super::pedersen_hash::hash_multiple) of the same inputs.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Zero;
+
+use crate::ecc::curves::grumpkin::{Affine, Fq, Projective};
+
+use super::{generators::GeneratorIndex, pedersen_hash::hash_single};
+
+/// `input` multiplied by the generator at `index`.
+pub(crate) fn commit_single(input: &Fq, index: GeneratorIndex) -> Projective {
+    hash_single(input, index)
+}
+
+/// The commitment to `inputs` under `hash_index`, with input i using generator `(hash_index, i)`.
+pub(crate) fn commit(inputs: &[Fq], hash_index: usize) -> Affine {
+    assert!(inputs.len() < (1 << 16), "too many inputs for 16 bit index");
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| commit_single(input, GeneratorIndex::new(hash_index, i)))
+        .sum::<Projective>()
+        .into_affine()
+}
+
+/// The x coordinate of [`commit`], or 0 if the commitment is the point at infinity.
+pub(crate) fn compress(inputs: &[Fq], hash_index: usize) -> Fq {
+    let commitment = commit(inputs, hash_index);
+    if commitment.is_zero() {
+        Fq::zero()
+    } else {
+        commitment.x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::crypto::generators::get_length_generator_data;
+    use crate::common::crypto::pedersen_hash::hash_multiple;
+    use ark_ff::MontFp;
+
+    /// barretenberg's pedersen_commitment tests: commitments to {1, 1}, (0x2f7a8f9a...66cdfe15,
+    /// 0x01916b31...2b6057e6), and to {0, 1}, (0x054aa86a...91bde402, 0x209dcfbf...87cac126).
+    #[test]
+    fn commitments_match_barretenberg() {
+        let one = Fq::from(1u64);
+        let expected = Affine::new(
+            MontFp!(
+                "21475250338311530111088781112432132511855209292730670949974692984887182229013"
+            ),
+            MontFp!("709245492126126701709902506217603794644991322680146492508959813283461748710"),
+        );
+        assert_eq!(commit(&[one, one], 0), expected);
+        let expected = Affine::new(
+            MontFp!("2393473289045184898987089634332637236754766663897650125720167164137088869378"),
+            MontFp!(
+                "14752839959415467457196082350231122454649853219840744672802853620609001898278"
+            ),
+        );
+        assert_eq!(commit(&[Fq::zero(), one], 0), expected);
+        assert_eq!(compress(&[Fq::zero(), one], 0), expected.x);
+    }
+
+    #[test]
+    fn the_hash_is_the_commitment_plus_the_length() {
+        let inputs = [Fq::from(3u64), Fq::from(5u64), -Fq::from(1u64)];
+        let length = get_length_generator_data().mul(&Fq::from(3u64));
+        assert_eq!(
+            (commit(&inputs, 4) + length).into_affine().x,
+            hash_multiple(&inputs, 4)
+        );
+        assert_ne!(compress(&inputs, 4), hash_multiple(&inputs, 4));
+    }
+
+    #[test]
+    fn commitments_are_deterministic_and_binding_to_the_index() {
+        let inputs = [Fq::from(1u64), Fq::from(1u64)];
+        assert_eq!(compress(&inputs, 0), compress(&inputs, 0));
+        assert_ne!(compress(&inputs, 0), compress(&inputs, 1));
+        assert_ne!(compress(&inputs, 0), compress(&inputs[..1], 0));
+    }
+}
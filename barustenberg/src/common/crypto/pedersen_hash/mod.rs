@@ -1,63 +1,68 @@
-mod crypto {
-    pub mod pedersen_hash {
-        use crate::generators::{get_generator_data, generator_index_t, fixed_base_ladder};
-        use crate::grumpkin::{g1::element as g1_element, fq};
-        use crate::barretenberg::fr;
-        use crate::barretenberg::wnaf::fixed_wnaf;
-        use std::ops::SubAssign;
-
-        pub fn hash_single(input: fr, index: generator_index_t) -> g1_element {
-            let gen_data = get_generator_data(index);
-            let mut scalar_multiplier = input.from_montgomery_form();
-
-            const NUM_BITS: usize = 254;
-            const NUM_QUADS_BASE: usize = (NUM_BITS - 1) >> 1;
-            const NUM_QUADS: usize = if ((NUM_QUADS_BASE << 1) + 1 < NUM_BITS) { NUM_QUADS_BASE + 1 } else { NUM_QUADS_BASE };
-            const NUM_WNAF_BITS: usize = (NUM_QUADS << 1) + 1;
-
-            let ladder = gen_data.get_hash_ladder(NUM_BITS);
-
-            let mut wnaf_entries = [0u64; NUM_QUADS + 2];
-            let mut skew = false;
-            fixed_wnaf::<NUM_WNAF_BITS, 1, 2>(&mut scalar_multiplier.data[0], &mut wnaf_entries[0], &mut skew, 0);
-
-            let mut accumulator = g1_element::new(ladder[0].one);
-            if skew {
-                accumulator.sub_assign(gen_data.skew_generator);
-            }
-
-            for i in 0..NUM_QUADS {
-                let entry = wnaf_entries[i + 1];
-                let point_to_add = if (entry & WNAF_MASK) == 1 { 
-                    ladder[i + 1].three 
-                } else { 
-                    ladder[i + 1].one 
-                };
-                let predicate = (entry >> 31) & 1;
-                accumulator.self_mixed_add_or_sub(point_to_add, predicate);
-            }
-            accumulator
-        }
-
-        pub fn hash_multiple(inputs: Vec<fq>, hash_index: usize) -> fq {
-            assert!(inputs.len() < (1 << 16), "too many inputs for 16 bit index");
-            let mut out = Vec::with_capacity(inputs.len());
-
-            for i in 0..inputs.len() {
-                let index = generator_index_t { hash_index, i };
-                out.push(hash_single(inputs[i], index));
-            }
-
-            let mut r = out[0];
-            for i in 1..inputs.len() {
-                r += out[i];
-            }
-            let result = if r.is_point_at_infinity() {
-                g1_element::new(0, 0)
-            } else {
-                g1_element::new(r)
-            };
-            result.x
-        }
+//! Native Pedersen hash over Grumpkin.
+//!
+//! As in barretenberg, the hash of n inputs is the x coordinate of `n·H + Σᵢ inputᵢ·Gᵢ`, where H is the
+//! [length generator](get_length_generator_data) and the Gᵢ are the generators at the hash index.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::Zero;
+
+use crate::ecc::curves::grumpkin::{Fq, Projective};
+
+use super::generators::{get_generator_data, get_length_generator_data, GeneratorIndex};
+
+/// `input` multiplied by the generator at `index`.
+pub(crate) fn hash_single(input: &Fq, index: GeneratorIndex) -> Projective {
+    get_generator_data(index).mul(input)
+}
+
+/// The x coordinate of `inputs.len()·H` plus the sum of `hash_single(inputs[i], (hash_index, i))`, or 0 if that
+/// is the point at infinity.
+pub(crate) fn hash_multiple(inputs: &[Fq], hash_index: usize) -> Fq {
+    assert!(inputs.len() < (1 << 16), "too many inputs for 16 bit index");
+    let length = get_length_generator_data().mul(&Fq::from(inputs.len() as u64));
+    let r: Projective = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| hash_single(input, GeneratorIndex::new(hash_index, i)))
+        .sum::<Projective>()
+        + length;
+    if r.is_zero() {
+        Fq::zero()
+    } else {
+        r.into_affine().x().copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ff::MontFp;
+
+    /// barretenberg's pedersen_hash tests: hashes of {1, 1} at indices 0 and 5,
+    /// 0x07ebfbf4...5463297b and 0x1c446df6...7861daa6.
+    #[test]
+    fn hashes_match_barretenberg() {
+        let one = Fq::from(1u64);
+        let expected: Fq =
+            MontFp!("3583137940367543141169889198758850326673923325182598243450662697654714313083");
+        assert_eq!(hash_multiple(&[one, one], 0), expected);
+        let expected: Fq = MontFp!(
+            "12785664284086914537273210116175139764153812914951498056047869066787449592486"
+        );
+        assert_eq!(hash_multiple(&[one, one], 5), expected);
+    }
+
+    #[test]
+    fn hash_binds_the_length() {
+        let zero = Fq::from(0u64);
+        assert_ne!(hash_multiple(&[zero], 0), hash_multiple(&[zero, zero], 0));
+    }
+
+    #[test]
+    fn hash_depends_on_input_order() {
+        let a = Fq::from(1u64);
+        let b = Fq::from(2u64);
+        assert_eq!(hash_multiple(&[a, b], 0), hash_multiple(&[a, b], 0));
+        assert_ne!(hash_multiple(&[a, b], 0), hash_multiple(&[b, a], 0));
     }
 }
@@ -1 +1,3 @@
+pub(crate) mod crypto;
 pub(crate) mod max_threads;
+pub(crate) mod thread;
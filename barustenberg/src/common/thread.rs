@@ -0,0 +1,194 @@
+//! Helpers for running loops on barustenberg's thread pool.
+//!
+//! With the `multithreading` feature enabled these dispatch onto a rayon thread pool, otherwise they
+//! fall back to plain serial loops. Either way the iteration space is split the same way, so callers
+//! get bit-for-bit identical results from both builds.
+
+#[cfg(feature = "multithreading")]
+use anyhow::{anyhow, Result};
+#[cfg(feature = "multithreading")]
+use once_cell::sync::OnceCell;
+#[cfg(feature = "multithreading")]
+use rayon::prelude::*;
+
+#[cfg(feature = "multithreading")]
+static THREAD_POOL: OnceCell<rayon::ThreadPool> = OnceCell::new();
+
+/// Configure the thread pool used for proving with `num_threads` worker threads.
+///
+/// This has to be called before any proving work happens, since the pool is created lazily (with one
+/// thread per available core) the first time it is needed. Calling it after that point is an error.
+#[cfg(feature = "multithreading")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multithreading")))]
+pub fn init_thread_pool(num_threads: usize) -> Result<()> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .thread_name(|i| format!("barustenberg-{}", i))
+        .build()?;
+    THREAD_POOL
+        .set(pool)
+        .map_err(|_| anyhow!("thread pool has already been initialized"))
+}
+
+#[cfg(feature = "multithreading")]
+fn thread_pool() -> &'static rayon::ThreadPool {
+    THREAD_POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .thread_name(|i| format!("barustenberg-{}", i))
+            .build()
+            .expect("failed to build thread pool")
+    })
+}
+
+/// Run `op` on the thread pool. If we're already on a rayon worker (e.g. a nested call, or a caller
+/// that installed their own pool) we stay where we are.
+pub(crate) fn in_thread_pool<R, OP>(op: OP) -> R
+where
+    R: Send,
+    OP: FnOnce() -> R + Send,
+{
+    #[cfg(feature = "multithreading")]
+    if rayon::current_thread_index().is_none() {
+        return thread_pool().install(op);
+    }
+    op()
+}
+
+/// Number of threads work will be spread across.
+pub(crate) fn get_num_cpus() -> usize {
+    #[cfg(feature = "multithreading")]
+    let num_cpus = in_thread_pool(rayon::current_num_threads);
+    #[cfg(not(feature = "multithreading"))]
+    let num_cpus = 1;
+    num_cpus
+}
+
+/// Collect `func(i)` for every `i` in `0..num_iterations`, in order.
+pub(crate) fn parallel_map<R, F>(num_iterations: usize, func: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Send + Sync,
+{
+    #[cfg(feature = "multithreading")]
+    let result = in_thread_pool(|| (0..num_iterations).into_par_iter().map(func).collect());
+    #[cfg(not(feature = "multithreading"))]
+    let result = (0..num_iterations).map(func).collect();
+    result
+}
+
+/// Split `data` into `chunk_size` pieces and call `func(chunk_index, chunk)` on each of them.
+pub(crate) fn parallel_for_chunks_mut<T, F>(data: &mut [T], chunk_size: usize, func: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Send + Sync,
+{
+    #[cfg(feature = "multithreading")]
+    in_thread_pool(|| {
+        data.par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, chunk)| func(i, chunk))
+    });
+    #[cfg(not(feature = "multithreading"))]
+    data.chunks_mut(chunk_size)
+        .enumerate()
+        .for_each(|(i, chunk)| func(i, chunk));
+}
+
+/// Split `left` and `right` into matching `chunk_size` pieces and call
+/// `func(chunk_index, left_chunk, right_chunk)` on each pair.
+pub(crate) fn parallel_for_chunks_mut_pair<T, F>(
+    left: &mut [T],
+    right: &mut [T],
+    chunk_size: usize,
+    func: F,
+) where
+    T: Send,
+    F: Fn(usize, &mut [T], &mut [T]) + Send + Sync,
+{
+    debug_assert_eq!(left.len(), right.len());
+    #[cfg(feature = "multithreading")]
+    in_thread_pool(|| {
+        left.par_chunks_mut(chunk_size)
+            .zip(right.par_chunks_mut(chunk_size))
+            .enumerate()
+            .for_each(|(i, (l, r))| func(i, l, r))
+    });
+    #[cfg(not(feature = "multithreading"))]
+    left.chunks_mut(chunk_size)
+        .zip(right.chunks_mut(chunk_size))
+        .enumerate()
+        .for_each(|(i, (l, r))| func(i, l, r));
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// Run `op` once on a single worker and once on `num_threads` workers, returning both results.
+    /// Without the `multithreading` feature both runs are serial.
+    pub(crate) fn run_serial_and_parallel<R, OP>(num_threads: usize, op: OP) -> (R, R)
+    where
+        R: Send,
+        OP: Fn() -> R + Send + Sync,
+    {
+        #[cfg(feature = "multithreading")]
+        let results = {
+            let serial = rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap();
+            let parallel = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            (serial.install(&op), parallel.install(&op))
+        };
+        #[cfg(not(feature = "multithreading"))]
+        let results = {
+            let _ = num_threads;
+            (op(), op())
+        };
+        results
+    }
+
+    #[test]
+    fn parallel_map_preserves_order() {
+        let (serial, parallel) = run_serial_and_parallel(4, || parallel_map(1000, |i| i * i));
+        assert_eq!(serial, (0..1000).map(|i| i * i).collect::<Vec<_>>());
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn parallel_for_chunks_mut_visits_every_element() {
+        let (serial, parallel) = run_serial_and_parallel(4, || {
+            let mut data = vec![0usize; 1001];
+            parallel_for_chunks_mut(&mut data, 64, |chunk_idx, chunk| {
+                for (i, x) in chunk.iter_mut().enumerate() {
+                    *x = chunk_idx * 64 + i;
+                }
+            });
+            data
+        });
+        assert_eq!(serial, (0..1001).collect::<Vec<_>>());
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn parallel_for_chunks_mut_pair_matches_zip() {
+        let (serial, parallel) = run_serial_and_parallel(4, || {
+            let mut left: Vec<u64> = (0..512).collect();
+            let mut right: Vec<u64> = (512..1024).collect();
+            parallel_for_chunks_mut_pair(&mut left, &mut right, 32, |_, l, r| {
+                for (a, b) in l.iter_mut().zip(r.iter_mut()) {
+                    let t = *b;
+                    *b = t - *a;
+                    *a += t;
+                }
+            });
+            (left, right)
+        });
+        assert!(serial.0.iter().all(|x| x % 2 == 0));
+        assert!(serial.1.iter().all(|x| *x == 512));
+        assert_eq!(serial, parallel);
+    }
+}
@@ -0,0 +1,148 @@
+//! Operations on many independent elements at once that share a single field inversion.
+
+use anyhow::{bail, Result};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{Field, Zero};
+
+use crate::common::{max_threads::compute_num_threads, thread::parallel_for_chunks_mut};
+
+/// Montgomery's trick on one chunk: a running product forwards, one inversion, then the inverses backwards.
+fn batch_invert_serial<F: Field>(values: &mut [F]) {
+    let mut products = Vec::with_capacity(values.len());
+    let mut accumulator = F::one();
+    for value in values.iter() {
+        products.push(accumulator);
+        accumulator *= value;
+    }
+    let mut inverse = accumulator
+        .inverse()
+        .expect("product of nonzero elements is nonzero");
+    for (value, product) in values.iter_mut().zip(products).rev() {
+        let value_inverse = inverse * product;
+        inverse *= *value;
+        *value = value_inverse;
+    }
+}
+
+/// Replaces every element of `values` by its inverse, with one field inversion per thread.
+///
+/// Fails without touching `values` if any of them is zero.
+pub(crate) fn batch_invert<F: Field>(values: &mut [F]) -> Result<()> {
+    if let Some(index) = values.iter().position(|value| value.is_zero()) {
+        bail!("cannot invert zero at index {}", index);
+    }
+    let num_threads = compute_num_threads();
+    let chunk_size = ((values.len() + num_threads - 1) / num_threads).max(1);
+    parallel_for_chunks_mut(values, chunk_size, |_, chunk| batch_invert_serial(chunk));
+    Ok(())
+}
+
+/// Whether `p + q` is the point at infinity without being a sum with it: `q = -p`.
+fn sums_to_infinity<P: SWCurveConfig>(p: &Affine<P>, q: &Affine<P>) -> bool {
+    p.x == q.x && (p.y != q.y || p.y.is_zero())
+}
+
+/// `lhs[i] + rhs[i]` for every i, in affine coordinates with one shared inversion.
+///
+/// Each sum is a chord or, when the points are equal, a tangent, and either way needs a single division;
+/// those are batched. Sums involving or giving the point at infinity need none.
+pub(crate) fn batch_affine_add<P: SWCurveConfig>(
+    lhs: &[Affine<P>],
+    rhs: &[Affine<P>],
+) -> Vec<Affine<P>> {
+    assert_eq!(lhs.len(), rhs.len(), "batch addition of unequal lengths");
+
+    // (numerator, denominator) of the slope of each sum that needs one
+    let mut slopes = Vec::with_capacity(lhs.len());
+    let mut denominators = Vec::with_capacity(lhs.len());
+    for (p, q) in lhs.iter().zip(rhs) {
+        if p.infinity || q.infinity || sums_to_infinity(p, q) {
+            continue;
+        }
+        let (numerator, denominator) = if p == q {
+            (
+                p.x.square() * P::BaseField::from(3u64) + P::COEFF_A,
+                p.y.double(),
+            )
+        } else {
+            (q.y - p.y, q.x - p.x)
+        };
+        slopes.push(numerator);
+        denominators.push(denominator);
+    }
+    batch_invert(&mut denominators).expect("slope denominators are nonzero");
+
+    let mut slopes = slopes.into_iter().zip(denominators);
+    lhs.iter()
+        .zip(rhs)
+        .map(|(p, q)| {
+            if p.infinity {
+                *q
+            } else if q.infinity {
+                *p
+            } else if sums_to_infinity(p, q) {
+                Affine::identity()
+            } else {
+                let (numerator, denominator_inverse) = slopes.next().unwrap();
+                let lambda = numerator * denominator_inverse;
+                let x = lambda.square() - p.x - q.x;
+                let y = lambda * (p.x - x) - p.y;
+                Affine::new_unchecked(x, y)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::{Fr, G1Affine, G1Projective};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{One, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn batch_inversion_inverts_everything() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let values: Vec<Fr> = (0..1000).map(|_| Fr::rand(&mut rng)).collect();
+        let mut inverses = values.clone();
+        batch_invert(&mut inverses).unwrap();
+        for (value, inverse) in values.iter().zip(&inverses) {
+            assert!((*value * inverse).is_one());
+        }
+
+        let mut empty: Vec<Fr> = vec![];
+        batch_invert(&mut empty).unwrap();
+    }
+
+    #[test]
+    fn batch_inversion_rejects_zero() {
+        let mut values = vec![Fr::from(2u64), Fr::zero(), Fr::from(3u64)];
+        let before = values.clone();
+        assert!(batch_invert(&mut values).is_err());
+        assert_eq!(values, before);
+    }
+
+    #[test]
+    fn batch_addition_matches_projective_addition() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let p = G1Affine::rand(&mut rng);
+        let q = G1Affine::rand(&mut rng);
+        let zero = G1Affine::zero();
+        // chords, a tangent, inverses, and the point at infinity on either side
+        let lhs = vec![p, q, p, p, zero, p, zero];
+        let rhs = vec![q, p, p, -p, q, zero, zero];
+        let sums = batch_affine_add(&lhs, &rhs);
+        for ((a, b), sum) in lhs.iter().zip(&rhs).zip(&sums) {
+            assert_eq!(*sum, (*a + *b).into_affine());
+        }
+
+        let points: Vec<G1Affine> = (0..100)
+            .map(|_| G1Projective::rand(&mut rng).into_affine())
+            .collect();
+        let doubled = batch_affine_add(&points, &points);
+        for (point, double) in points.iter().zip(&doubled) {
+            assert_eq!(*double, (*point * Fr::from(2u64)).into_affine());
+        }
+    }
+}
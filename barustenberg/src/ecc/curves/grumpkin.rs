@@ -0,0 +1,119 @@
+//! Grumpkin, the curve `y² = x³ - 17` over the scalar field of BN254, whose own scalar field is the base field of
+//! BN254.
+//!
+//! The `grumpkin` crate we depend on is built on `ff`/`group`, so it cannot be used with the arkworks traits the
+//! rest of the library is written against. This is the same curve as an arkworks short Weierstrass model.
+
+use ark_ec::{
+    models::CurveConfig,
+    short_weierstrass::{self as sw, SWCurveConfig},
+};
+use ark_ff::{BigInteger, Field, MontFp, PrimeField, Zero};
+
+/// Base field of Grumpkin, the scalar field of BN254.
+pub(crate) type Fq = ark_bn254::Fr;
+/// Scalar field of Grumpkin, the base field of BN254.
+pub(crate) type Fr = ark_bn254::Fq;
+
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub(crate) struct GrumpkinConfig;
+
+impl CurveConfig for GrumpkinConfig {
+    type BaseField = Fq;
+    type ScalarField = Fr;
+
+    const COFACTOR: &'static [u64] = &[1];
+    const COFACTOR_INV: Fr = MontFp!("1");
+}
+
+impl SWCurveConfig for GrumpkinConfig {
+    const COEFF_A: Fq = MontFp!("0");
+    const COEFF_B: Fq = MontFp!("-17");
+    const GENERATOR: Affine = Affine::new_unchecked(G_GENERATOR_X, G_GENERATOR_Y);
+
+    #[inline(always)]
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Fq::zero()
+    }
+}
+
+const G_GENERATOR_X: Fq = MontFp!("1");
+const G_GENERATOR_Y: Fq = MontFp!("17631683881184975370165255887551781615748388533673675138860");
+
+pub(crate) type Affine = sw::Affine<GrumpkinConfig>;
+pub(crate) type Projective = sw::Projective<GrumpkinConfig>;
+
+/// A point derived from `seed` with no known discrete logarithm relative to any other.
+///
+/// As in barretenberg, `seed ‖ attempt ‖ 0` and `seed ‖ attempt ‖ 1` are hashed with blake3, the two digests read
+/// as big-endian integers give the high and low halves of a 512 bit x coordinate reduced into the field, and
+/// the top bit of the first digest is the parity of y. If x is not on the curve the next attempt is made.
+pub(crate) fn hash_to_curve(seed: &[u8], attempt: u8) -> Affine {
+    let mut target_seed = seed.to_vec();
+    target_seed.extend([attempt, 0]);
+    let hash_hi = blake3::hash(&target_seed);
+    *target_seed.last_mut().unwrap() = 1;
+    let hash_lo = blake3::hash(&target_seed);
+
+    let mut x_le = [0u8; 64];
+    for (i, byte) in hash_lo.as_bytes().iter().rev().enumerate() {
+        x_le[i] = *byte;
+    }
+    for (i, byte) in hash_hi.as_bytes().iter().rev().enumerate() {
+        x_le[32 + i] = *byte;
+    }
+    let x = Fq::from_le_bytes_mod_order(&x_le);
+    let y_bit = hash_hi.as_bytes()[0] > 127;
+    let y_squared = x.square() * x + GrumpkinConfig::COEFF_B;
+    match y_squared.sqrt() {
+        Some(y) if y.into_bigint().is_odd() == y_bit => Affine::new_unchecked(x, y),
+        Some(y) => Affine::new_unchecked(x, -y),
+        None => hash_to_curve(seed, attempt + 1),
+    }
+}
+
+/// `count` generators for `domain_separator`, starting from the `starting_index`th.
+///
+/// As in barretenberg, generator i is [`hash_to_curve`] of the blake3 hash of the domain separator followed
+/// by i as a big-endian 32 bit integer, padded to 64 bytes.
+pub(crate) fn derive_generators(
+    domain_separator: &[u8],
+    count: usize,
+    starting_index: usize,
+) -> Vec<Affine> {
+    let mut preimage = blake3::hash(domain_separator).as_bytes().to_vec();
+    preimage.resize(64, 0);
+    (starting_index..starting_index + count)
+        .map(|i| {
+            preimage[32..36].copy_from_slice(&(i as u32).to_be_bytes());
+            hash_to_curve(&preimage, 0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::One;
+
+    #[test]
+    fn generator_is_on_the_curve_and_has_the_right_order() {
+        let g = Affine::generator();
+        assert!(g.is_on_curve());
+        assert!((g * (-Fr::one())).into_affine() == -g);
+        assert!((g * Fr::from(0u64)).is_zero());
+    }
+
+    #[test]
+    fn generators_are_distinct_points_on_the_curve() {
+        let generators = derive_generators(b"test", 16, 0);
+        assert_eq!(generators.len(), 16);
+        for (i, g) in generators.iter().enumerate() {
+            assert!(g.is_on_curve());
+            assert!(generators[..i].iter().all(|h| h != g && *h != -*g));
+        }
+        assert_eq!(derive_generators(b"test", 4, 12), generators[12..]);
+        assert_ne!(derive_generators(b"other", 1, 0)[0], generators[0]);
+    }
+}
@@ -1,20 +1,183 @@
-//pub(crate) mod grumpkin;
+pub(crate) mod grumpkin;
 
-use ark_ff::Field;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Mutex;
 
-pub(crate) fn coset_generator<F: Field>(idx: usize) -> F {
-    /*
-        ASSERT(idx < 7);
-    const field result{
-        Params::coset_generators_0[idx],
-        Params::coset_generators_1[idx],
-        Params::coset_generators_2[idx],
-        Params::coset_generators_3[idx],
-    };
-    return result; */
-    unimplemented!("coset_generator")
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{FftField, PrimeField};
+
+use super::serialize::PointEncoding;
+
+/// Number of coset generators we derive; the last two are reserved for tags and public inputs.
+const NUM_COSET_GENERATORS: usize = 8;
+
+lazy_static::lazy_static! {
+    /// The coset generators of every field asked for so far, keyed by the field's `TypeId`.
+    static ref COSET_GENERATORS: Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Field elements that each generate a distinct coset of the largest 2-adic subgroup. Deriving them takes
+/// an inversion per candidate, and the permutation widgets ask for them per proof, so each field's are
+/// derived once and cached.
+pub(crate) fn coset_generators<F: FftField>() -> [F; NUM_COSET_GENERATORS] {
+    let mut cache = COSET_GENERATORS.lock().unwrap();
+    *cache
+        .entry(TypeId::of::<F>())
+        .or_insert_with(|| Box::new(derive_coset_generators::<F>()))
+        .downcast_ref::<[F; NUM_COSET_GENERATORS]>()
+        .expect("coset generators are cached under their field's type")
+}
+
+/// Derives the coset generators the same way barretenberg does: start from the multiplicative generator,
+/// then walk upwards from the generator + 1 keeping every element whose quotient with each earlier pick
+/// falls outside the subgroup.
+fn derive_coset_generators<F: FftField>() -> [F; NUM_COSET_GENERATORS] {
+    let subgroup_size = [1u64 << F::TWO_ADICITY];
+    let mut result = [F::zero(); NUM_COSET_GENERATORS];
+    result[0] = F::GENERATOR;
+
+    let mut work_variable = F::GENERATOR + F::one();
+    let mut count = 1;
+    while count < NUM_COSET_GENERATORS {
+        let work_inverse = work_variable.inverse().unwrap();
+        let valid = result[..count]
+            .iter()
+            .all(|generator| (work_inverse * generator).pow(subgroup_size) != F::one());
+        if valid {
+            result[count] = work_variable;
+            count += 1;
+        }
+        work_variable += F::one();
+    }
+    result
+}
+
+/// Generator of the coset used for wire column `idx + 1` in the permutation argument.
+pub(crate) fn coset_generator<F: FftField>(idx: usize) -> F {
+    assert!(idx < NUM_COSET_GENERATORS - 1);
+    coset_generators::<F>()[idx]
+}
+
+/// Generator of the coset that public input cells are mapped into.
+pub(crate) fn external_coset_generator<F: FftField>() -> F {
+    coset_generators::<F>()[NUM_COSET_GENERATORS - 1]
+}
+
+/// Generator of the coset that tagged cells are mapped into.
+pub(crate) fn tag_coset_generator<F: FftField>() -> F {
+    coset_generators::<F>()[NUM_COSET_GENERATORS - 2]
+}
+
+/// The types a proof system needs from one curve, tied together so they cannot be mixed across curves: the
+/// scalar field circuits are written over, the base field of its points, the groups commitments and reference
+/// strings live in, and the cosets of the permutation argument.
+pub(crate) trait CurveConfig:
+    'static + Copy + Clone + Default + Debug + PartialEq + Eq + Send + Sync
+{
+    type Fr: PrimeField;
+    type Fq: PrimeField;
+    /// The group commitments live in.
+    type G1Affine: AffineRepr<ScalarField = Self::Fr, BaseField = Self::Fq> + PointEncoding;
+    /// The group of the verifier's reference string: G2 on a pairing curve, where it holds `[τ]₂`.
+    type G2Affine: AffineRepr<ScalarField = Self::Fr> + PointEncoding;
+
+    /// Generator of the coset used for wire column `idx + 1` in the permutation argument.
+    fn coset_generator(idx: usize) -> Self::Fr {
+        coset_generator(idx)
+    }
+
+    /// Generator of the coset that public input cells are mapped into.
+    fn external_coset_generator() -> Self::Fr {
+        external_coset_generator()
+    }
+
+    /// Generator of the coset that tagged cells are mapped into.
+    fn tag_coset_generator() -> Self::Fr {
+        tag_coset_generator()
+    }
 }
 
-pub(crate) fn external_coset_generator<F: Field>() -> F {
-    unimplemented!("external_coset_generator")
+/// A [`CurveConfig`] with a pairing between its groups, which Kate commitments are verified with.
+pub(crate) trait PairingCurveConfig: CurveConfig {
+    type Pairing: Pairing<
+        ScalarField = Self::Fr,
+        G1Affine = Self::G1Affine,
+        G2Affine = Self::G2Affine,
+    >;
+}
+
+/// BN254, the curve barretenberg proves over.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub(crate) struct Bn254;
+
+impl CurveConfig for Bn254 {
+    type Fr = ark_bn254::Fr;
+    type Fq = ark_bn254::Fq;
+    type G1Affine = ark_bn254::G1Affine;
+    type G2Affine = ark_bn254::G2Affine;
+}
+
+impl PairingCurveConfig for Bn254 {
+    type Pairing = ark_bn254::Bn254;
+}
+
+/// BLS12-381.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub(crate) struct Bls12_381;
+
+impl CurveConfig for Bls12_381 {
+    type Fr = ark_bls12_381::Fr;
+    type Fq = ark_bls12_381::Fq;
+    type G1Affine = ark_bls12_381::G1Affine;
+    type G2Affine = ark_bls12_381::G2Affine;
+}
+
+impl PairingCurveConfig for Bls12_381 {
+    type Pairing = ark_bls12_381::Bls12_381;
+}
+
+/// Grumpkin, which has no pairing. Its commitments are opened with an inner product argument, whose verifier
+/// reads Pedersen generators in the curve itself, so that is also the group of its verifier reference string.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub(crate) struct Grumpkin;
+
+impl CurveConfig for Grumpkin {
+    type Fr = grumpkin::Fr;
+    type Fq = grumpkin::Fq;
+    type G1Affine = grumpkin::Affine;
+    type G2Affine = grumpkin::Affine;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check_distinct_cosets<F: FftField>() {
+        let generators = coset_generators::<F>();
+        assert_eq!(generators[0], F::GENERATOR);
+        assert_eq!(coset_generators::<F>(), derive_coset_generators::<F>());
+
+        let subgroup_size = [1u64 << F::TWO_ADICITY];
+        for (i, a) in generators.iter().enumerate() {
+            assert_ne!(a.pow(subgroup_size), F::one());
+            for b in &generators[i + 1..] {
+                assert_ne!((*a / b).pow(subgroup_size), F::one());
+            }
+        }
+    }
+
+    #[test]
+    fn coset_generators_are_in_distinct_cosets() {
+        check_distinct_cosets::<ark_bn254::Fr>();
+        assert_eq!(coset_generators::<ark_bn254::Fr>()[0], 5u64.into());
+    }
+
+    #[test]
+    fn bls12_381_coset_generators_are_in_distinct_cosets() {
+        check_distinct_cosets::<ark_bls12_381::Fr>();
+        assert_eq!(coset_generators::<ark_bls12_381::Fr>()[0], 7u64.into());
+    }
 }
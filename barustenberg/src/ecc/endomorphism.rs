@@ -0,0 +1,324 @@
+//! Scalar multiplication with the GLV endomorphism.
+//!
+//! BN254 and Grumpkin both have `j = 0`, so `φ(x, y) = (β·x, y)` for a cube root of unity β in the base
+//! field is a group endomorphism, acting on the prime order group as multiplication by a cube root of unity
+//! λ in the scalar field. Splitting `k = k₁ + λ·k₂` with k₁ and k₂ of about half the bits turns `k·P` into
+//! `k₁·P + k₂·φ(P)`, which shares its doublings between the two halves. The Ultra elliptic gate applies the
+//! same map, with β as its endomorphism coefficient, so [`endomorphism`] is also what its witnesses use.
+
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup, Group,
+};
+use ark_ff::{BigInt, MontFp, Zero};
+use num_bigint::{BigInt as BigInteger, BigUint};
+use std::cmp::Ordering;
+
+use super::{
+    curves::grumpkin::GrumpkinConfig,
+    fixed_base::mixed_add_or_sub,
+    wnaf::{fixed_wnaf, Wnaf},
+};
+
+/// A short Weierstrass curve with the endomorphism `(x, y) ↦ (β·x, y) = λ·(x, y)`.
+pub(crate) trait EndomorphismConfig: SWCurveConfig {
+    /// β, a cube root of unity in the base field.
+    const BETA: Self::BaseField;
+    /// λ, the cube root of unity in the scalar field that φ multiplies by.
+    const LAMBDA: Self::ScalarField;
+    /// A reduced basis `(a, b)` of the lattice of `a + λ·b ≡ 0`, each entry below 2^127.
+    const LATTICE_BASIS: [(i128, i128); 2];
+}
+
+impl EndomorphismConfig for ark_bn254::g1::Config {
+    const BETA: ark_bn254::Fq =
+        MontFp!("2203960485148121921418603742825762020974279258880205651966");
+    const LAMBDA: ark_bn254::Fr =
+        MontFp!("4407920970296243842393367215006156084916469457145843978461");
+    const LATTICE_BASIS: [(i128, i128); 2] = [
+        (
+            9931322734385697763,
+            -147946756881789319000765030803803410728,
+        ),
+        (147946756881789319010696353538189108491, 9931322734385697763),
+    ];
+}
+
+impl EndomorphismConfig for GrumpkinConfig {
+    // Grumpkin's fields are BN254's swapped, and so are its β and λ
+    const BETA: ark_bn254::Fr =
+        MontFp!("4407920970296243842393367215006156084916469457145843978461");
+    const LAMBDA: ark_bn254::Fq =
+        MontFp!("2203960485148121921418603742825762020974279258880205651966");
+    const LATTICE_BASIS: [(i128, i128); 2] = [
+        (
+            9931322734385697762,
+            -147946756881789319000765030803803410729,
+        ),
+        (147946756881789319010696353538189108491, 9931322734385697762),
+    ];
+}
+
+/// Window of the recodings in [`glv_mul`].
+const GLV_WNAF_BITS: usize = 4;
+/// Bits of the halves of a split scalar.
+const GLV_NUM_BITS: usize = 128;
+
+/// `φ(point) = (β·x, y)`.
+pub(crate) fn endomorphism<P: EndomorphismConfig>(point: &Affine<P>) -> Affine<P> {
+    if point.is_zero() {
+        return *point;
+    }
+    Affine::new_unchecked(point.x * P::BETA, point.y)
+}
+
+/// `⌊n/d⌉`, rounding halves up.
+fn round_div(n: &BigInteger, d: &BigInteger) -> BigInteger {
+    let (n, d): (BigInteger, BigInteger) = if d.sign() == num_bigint::Sign::Minus {
+        (-n, -d)
+    } else {
+        (n.clone(), d.clone())
+    };
+    let numerator: BigInteger = (n << 1u32) + &d;
+    let denominator: BigInteger = d << 1u32;
+    let quotient = &numerator / &denominator;
+    // division truncates towards zero, so a negative inexact quotient is one too high
+    if numerator.sign() == num_bigint::Sign::Minus && &quotient * &denominator != numerator {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+/// `(k₁, k₂)` with `k₁ + λ·k₂ = k`, both below 2^127 in absolute value.
+///
+/// This is Babai rounding: `(k, 0)` is written in the lattice basis, its coordinates are rounded, and the
+/// difference between `(k, 0)` and the lattice point they give is short.
+pub(crate) fn split_scalar<P: EndomorphismConfig>(k: &P::ScalarField) -> (i128, i128) {
+    let k: BigUint = (*k).into();
+    let k = BigInteger::from(k);
+    let [(a1, b1), (a2, b2)] =
+        P::LATTICE_BASIS.map(|(a, b)| (BigInteger::from(a), BigInteger::from(b)));
+    let determinant = &a1 * &b2 - &a2 * &b1;
+
+    let c1 = round_div(&(&k * &b2), &determinant);
+    let c2 = round_div(&(-&k * &b1), &determinant);
+    let k1 = k - &c1 * &a1 - &c2 * &a2;
+    let k2 = -(&c1 * &b1) - &c2 * &b2;
+    (
+        i128::try_from(k1).expect("k1 is short"),
+        i128::try_from(k2).expect("k2 is short"),
+    )
+}
+
+/// `|k|·P` as `(P or -P, |k|)`.
+fn signed_term<P: SWCurveConfig>(point: Affine<P>, k: i128) -> (Affine<P>, u128) {
+    if k < 0 {
+        (-point, k.unsigned_abs())
+    } else {
+        (point, k.unsigned_abs())
+    }
+}
+
+/// `scalar·point`, as `k₁·P + k₂·φ(P)` with the two halves' wNAF digits added in the same pass.
+pub(crate) fn glv_mul<P: EndomorphismConfig>(
+    point: &Affine<P>,
+    scalar: &P::ScalarField,
+) -> Projective<P> {
+    let (k1, k2) = split_scalar::<P>(scalar);
+    let (p1, k1) = signed_term(*point, k1);
+    let (p2, k2) = signed_term(endomorphism(point), k2);
+
+    // odd multiples of p1; φ is linear, so mapping them gives those of ±p2
+    let row_size = 1 << (GLV_WNAF_BITS - 1);
+    let double = p1.into_group().double();
+    let mut multiples = Vec::with_capacity(row_size);
+    let mut multiple = p1.into_group();
+    for _ in 0..row_size {
+        multiples.push(multiple);
+        multiple += double;
+    }
+    let table1 = Projective::normalize_batch(&multiples);
+    let flip = endomorphism(&p1) != p2;
+    let table2: Vec<_> = table1
+        .iter()
+        .map(|multiple| {
+            let mapped = endomorphism(multiple);
+            if flip {
+                -mapped
+            } else {
+                mapped
+            }
+        })
+        .collect();
+
+    let recode = |k: u128| {
+        fixed_wnaf(
+            &BigInt::<2>([k as u64, (k >> 64) as u64]),
+            GLV_NUM_BITS,
+            GLV_WNAF_BITS,
+        )
+    };
+    let (wnaf1, wnaf2) = (recode(k1), recode(k2));
+
+    let mut accumulator = table1[Wnaf::table_index(wnaf1.digits[0])].into_group();
+    accumulator += table2[Wnaf::table_index(wnaf2.digits[0])];
+    for (d1, d2) in wnaf1.digits[1..].iter().zip(&wnaf2.digits[1..]) {
+        for _ in 0..GLV_WNAF_BITS {
+            accumulator.double_in_place();
+        }
+        mixed_add_or_sub(&mut accumulator, &table1[Wnaf::table_index(*d1)], *d1 < 0);
+        mixed_add_or_sub(&mut accumulator, &table2[Wnaf::table_index(*d2)], *d2 < 0);
+    }
+    if wnaf1.skew {
+        accumulator -= p1;
+    }
+    if wnaf2.skew {
+        accumulator -= p2;
+    }
+    accumulator
+}
+
+/// Bucket window of [`glv_msm`] for `n` terms, about `ln n` as in the arkworks MSM.
+fn msm_window(n: usize) -> usize {
+    if n < 32 {
+        3
+    } else {
+        (usize::BITS - n.leading_zeros()) as usize * 69 / 100 + 2
+    }
+}
+
+/// The signed base `2^window` digits of `k < 2^127`, least significant first, each in
+/// `[-2^(window-1), 2^(window-1)]`.
+fn signed_digits(k: u128, window: usize) -> impl Iterator<Item = i64> {
+    let mask = (1 << window) - 1;
+    let half = 1 << (window - 1);
+    let mut carry = 0;
+    (0..GLV_NUM_BITS).step_by(window).map(move |shift| {
+        let mut digit = ((k >> shift) as i64 & mask) + carry;
+        carry = 0;
+        if digit > half {
+            digit -= 1 << window;
+            carry = 1;
+        }
+        // bit 127 is clear, so the top digit never carries out
+        digit
+    })
+}
+
+/// `Σᵢ scalarsᵢ·pointsᵢ` as a Pippenger over twice the points with half length scalars.
+///
+/// The halves are below 2^127, so the windows only cover [`GLV_NUM_BITS`] bits. That is as many bucket
+/// additions as the plain MSM that [`PippengerRuntimeState`](super::PippengerRuntimeState) runs, but half
+/// as many windows to sum the buckets of; the `glv` bench in `barustenberg-benches` compares the two.
+pub(crate) fn glv_msm<P: EndomorphismConfig>(
+    points: &[Affine<P>],
+    scalars: &[P::ScalarField],
+) -> Projective<P> {
+    assert_eq!(points.len(), scalars.len());
+    let window = msm_window(2 * points.len());
+    let num_windows = (GLV_NUM_BITS + window - 1) / window;
+    let mut bases = Vec::with_capacity(2 * points.len());
+    // digits[i * num_windows + w] is the w-th digit of the i-th half
+    let mut digits = Vec::with_capacity(2 * points.len() * num_windows);
+    for (point, scalar) in points.iter().zip(scalars) {
+        let (k1, k2) = split_scalar::<P>(scalar);
+        for (base, k) in [
+            signed_term(*point, k1),
+            signed_term(endomorphism(point), k2),
+        ] {
+            bases.push(base);
+            digits.extend(signed_digits(k, window));
+        }
+    }
+
+    let window_sums: Vec<Projective<P>> = (0..num_windows)
+        .map(|w| {
+            let mut buckets = vec![Projective::<P>::zero(); 1 << (window - 1)];
+            for (base, digit) in bases.iter().zip(digits[w..].iter().step_by(num_windows)) {
+                match digit.cmp(&0) {
+                    Ordering::Greater => buckets[*digit as usize - 1] += base,
+                    Ordering::Less => buckets[digit.unsigned_abs() as usize - 1] -= base,
+                    Ordering::Equal => {}
+                }
+            }
+            // Σ d·bucket_d, as the sum of the running sums from the top bucket down
+            let mut running = Projective::<P>::zero();
+            let mut sum = Projective::<P>::zero();
+            for bucket in buckets.iter().rev() {
+                running += bucket;
+                sum += running;
+            }
+            sum
+        })
+        .collect();
+
+    window_sums
+        .iter()
+        .rev()
+        .fold(Projective::<P>::zero(), |mut accumulator, sum| {
+            for _ in 0..window {
+                accumulator.double_in_place();
+            }
+            accumulator + sum
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecc::curves::grumpkin;
+    use ark_ec::VariableBaseMSM;
+    use ark_ff::{Field, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn check_curve<P: EndomorphismConfig>() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let g = Affine::<P>::generator();
+        assert_eq!(P::BETA.pow([3]), P::BaseField::ONE);
+        assert_eq!(endomorphism(&g), (g * P::LAMBDA).into_affine());
+
+        for k in [
+            P::ScalarField::ZERO,
+            P::ScalarField::ONE,
+            -P::ScalarField::ONE,
+            P::LAMBDA,
+            P::ScalarField::rand(&mut rng),
+        ] {
+            let (k1, k2) = split_scalar::<P>(&k);
+            assert!(k1.unsigned_abs() < 1 << 127 && k2.unsigned_abs() < 1 << 127);
+            let signed = |k: i128| {
+                let magnitude = P::ScalarField::from(k.unsigned_abs());
+                if k < 0 {
+                    -magnitude
+                } else {
+                    magnitude
+                }
+            };
+            assert_eq!(signed(k1) + P::LAMBDA * signed(k2), k);
+            let point = Affine::<P>::rand(&mut rng);
+            assert_eq!(glv_mul(&point, &k), point * k);
+        }
+
+        // below and above the size where the bucket window starts growing
+        for n in [5, 100] {
+            let points: Vec<Affine<P>> = (0..n).map(|_| Affine::rand(&mut rng)).collect();
+            let scalars: Vec<P::ScalarField> =
+                (0..n).map(|_| P::ScalarField::rand(&mut rng)).collect();
+            assert_eq!(
+                glv_msm(&points, &scalars),
+                Projective::<P>::msm_unchecked(&points, &scalars)
+            );
+        }
+    }
+
+    #[test]
+    fn bn254_endomorphism_multiplies() {
+        check_curve::<ark_bn254::g1::Config>();
+    }
+
+    #[test]
+    fn grumpkin_endomorphism_multiplies() {
+        check_curve::<grumpkin::GrumpkinConfig>();
+    }
+}
@@ -0,0 +1,203 @@
+//! Byte encodings of field elements.
+//!
+//! Barretenberg has three: transcripts and proofs hold canonical big-endian integers, 32 bytes for the BN254
+//! fields; `uint256_t` casts see canonical little-endian 64 bit limbs; and proving keys are written as the raw
+//! Montgomery form, `a·R mod p` in little-endian limbs. An extension field element is its coefficients one
+//! after the other, `c0` first. Decoding checks that every integer is below the modulus.
+
+use anyhow::{ensure, Result};
+use ark_ff::{
+    BigInt, BigInteger, Field, Fp, FpConfig, MontBackend, MontConfig, PrimeField, QuadExtConfig,
+    QuadExtField, Zero,
+};
+
+/// Bytes in the big-endian encoding of an element of the prime field `F`.
+const fn prime_field_bytes<F: PrimeField>() -> usize {
+    ((F::MODULUS_BIT_SIZE + 7) / 8) as usize
+}
+
+/// Bytes in the big-endian encoding of an element of `F`.
+pub(crate) fn encoded_size<F: Field>() -> usize {
+    F::extension_degree() as usize * prime_field_bytes::<F::BasePrimeField>()
+}
+
+/// The canonical big-endian encoding of `element`.
+pub(crate) fn to_be_bytes<F: Field>(element: &F) -> Vec<u8> {
+    let num_bytes = prime_field_bytes::<F::BasePrimeField>();
+    let mut out = Vec::with_capacity(encoded_size::<F>());
+    for coefficient in element.to_base_prime_field_elements() {
+        let bytes = coefficient.into_bigint().to_bytes_be();
+        out.extend_from_slice(&bytes[bytes.len() - num_bytes..]);
+    }
+    out
+}
+
+/// The element with big-endian encoding `bytes`, which must be exactly [`encoded_size`] long and canonical.
+pub(crate) fn from_be_bytes<F: Field>(bytes: &[u8]) -> Result<F> {
+    ensure!(
+        bytes.len() == encoded_size::<F>(),
+        "field element of {} bytes, expected {}",
+        bytes.len(),
+        encoded_size::<F>()
+    );
+    let coefficients = bytes
+        .chunks(prime_field_bytes::<F::BasePrimeField>())
+        .map(|chunk| {
+            let coefficient = F::BasePrimeField::from_be_bytes_mod_order(chunk);
+            ensure!(
+                to_be_bytes(&coefficient) == chunk,
+                "field element is not below the modulus"
+            );
+            Ok(coefficient)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(F::from_base_prime_field_elems(&coefficients).expect("one coefficient per degree"))
+}
+
+/// The canonical little-endian limbs of `element`.
+pub(crate) fn to_le_limbs<F: PrimeField>(element: &F) -> Vec<u64> {
+    element.into_bigint().as_ref().to_vec()
+}
+
+/// The element with canonical little-endian limbs `limbs`.
+pub(crate) fn from_le_limbs<F: PrimeField>(limbs: &[u64]) -> Result<F> {
+    let mut bigint = F::BigInt::default();
+    ensure!(
+        limbs.len() == bigint.as_ref().len(),
+        "field element of {} limbs, expected {}",
+        limbs.len(),
+        bigint.as_ref().len()
+    );
+    bigint.as_mut().copy_from_slice(limbs);
+    F::from_bigint(bigint).ok_or_else(|| anyhow::anyhow!("field element is not below the modulus"))
+}
+
+/// `element` as an integer, failing unless it fits in a `usize`.
+///
+/// Barretenberg stores sizes in field elements and reads them back with `static_cast<uint256_t>`, which
+/// leaves Montgomery form; this is that cast followed by a checked narrowing.
+pub(crate) fn to_usize<F: Field>(element: &F) -> Result<usize> {
+    let mut coefficients = element.to_base_prime_field_elements();
+    let value = coefficients.next().expect("at least one coefficient");
+    ensure!(
+        coefficients.all(|c| c.is_zero()),
+        "field element is not in the prime subfield"
+    );
+    let bigint = value.into_bigint();
+    let limbs = bigint.as_ref();
+    ensure!(
+        limbs[1..].iter().all(|limb| *limb == 0) && usize::try_from(limbs[0]).is_ok(),
+        "field element {} does not fit in a usize",
+        value
+    );
+    Ok(limbs[0] as usize)
+}
+
+/// The raw Montgomery form of `element` in little-endian bytes, as barretenberg writes it into proving keys.
+pub(crate) fn to_montgomery_bytes<T: MontConfig<N>, const N: usize>(
+    element: &Fp<MontBackend<T, N>, N>,
+) -> Vec<u8> {
+    element.0.to_bytes_le()
+}
+
+/// The element whose raw Montgomery form is `bytes`, which must be below the modulus like any other
+/// representative.
+pub(crate) fn from_montgomery_bytes<T: MontConfig<N>, const N: usize>(
+    bytes: &[u8],
+) -> Result<Fp<MontBackend<T, N>, N>> {
+    ensure!(
+        bytes.len() == 8 * N,
+        "montgomery form of {} bytes, expected {}",
+        bytes.len(),
+        8 * N
+    );
+    let mut limbs = [0u64; N];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().expect("chunks of 8 bytes"));
+    }
+    let montgomery = BigInt(limbs);
+    ensure!(
+        montgomery < T::MODULUS,
+        "montgomery form is not below the modulus"
+    );
+    Ok(Fp::new_unchecked(montgomery))
+}
+
+/// Fields whose elements have a fixed size big-endian encoding, the coordinates of
+/// [`PointEncoding`](super::serialize::PointEncoding) points.
+pub(crate) trait FieldEncoding: Field {
+    /// Bytes in an encoded element.
+    const NUM_BYTES: usize;
+
+    /// The sign of RFC 9380: the parity of the first nonzero coefficient.
+    fn sgn0(&self) -> bool {
+        self.to_base_prime_field_elements()
+            .find(|c| !c.is_zero())
+            .map_or(false, |c| c.into_bigint().is_odd())
+    }
+}
+
+impl<P: FpConfig<N>, const N: usize> FieldEncoding for Fp<P, N> {
+    const NUM_BYTES: usize = prime_field_bytes::<Self>();
+}
+
+impl<P: QuadExtConfig> FieldEncoding for QuadExtField<P>
+where
+    P::BaseField: FieldEncoding,
+{
+    const NUM_BYTES: usize = 2 * P::BaseField::NUM_BYTES;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::{Fq2, Fr};
+    use ark_ff::UniformRand;
+    use proptest::prelude::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    proptest! {
+        #[test]
+        fn encodings_round_trip(seed in any::<u64>()) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let x = Fr::rand(&mut rng);
+            prop_assert_eq!(from_be_bytes::<Fr>(&to_be_bytes(&x)).unwrap(), x);
+            prop_assert_eq!(from_le_limbs::<Fr>(&to_le_limbs(&x)).unwrap(), x);
+            prop_assert_eq!(from_montgomery_bytes(&to_montgomery_bytes(&x)).unwrap(), x);
+            let y = Fq2::rand(&mut rng);
+            prop_assert_eq!(from_be_bytes::<Fq2>(&to_be_bytes(&y)).unwrap(), y);
+        }
+    }
+
+    #[test]
+    fn encodings_match_barretenberg_layouts() {
+        assert_eq!(encoded_size::<Fr>(), 32);
+        assert_eq!(encoded_size::<Fq2>(), 64);
+        let bytes = to_be_bytes(&Fr::from(0x0102u64));
+        assert!(bytes[..30].iter().all(|byte| *byte == 0));
+        assert_eq!(bytes[30..], [1, 2]);
+        assert_eq!(to_le_limbs(&Fr::from(7u64)), vec![7, 0, 0, 0]);
+        // R mod r, barretenberg's fr::one() in memory
+        assert_eq!(
+            from_montgomery_bytes::<ark_bn254::FrConfig, 4>(&Fr::R.to_bytes_le()).unwrap(),
+            Fr::from(1u64)
+        );
+    }
+
+    #[test]
+    fn decoding_rejects_non_canonical_input() {
+        let modulus = Fr::MODULUS;
+        assert!(from_be_bytes::<Fr>(&modulus.to_bytes_be()).is_err());
+        assert!(from_be_bytes::<Fr>(&[0; 31]).is_err());
+        assert!(from_le_limbs::<Fr>(modulus.as_ref()).is_err());
+        assert!(from_le_limbs::<Fr>(&[1, 0, 0]).is_err());
+        assert!(from_montgomery_bytes::<ark_bn254::FrConfig, 4>(&modulus.to_bytes_le()).is_err());
+    }
+
+    #[test]
+    fn sizes_convert_only_when_they_fit() {
+        assert_eq!(to_usize(&Fr::from(1024u64)).unwrap(), 1024);
+        assert!(to_usize(&-Fr::from(1u64)).is_err());
+        assert!(to_usize(&Fq2::new(1u64.into(), 1u64.into())).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Precomputed tables for multiplying a fixed point by many scalars.
+
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr, CurveGroup, Group,
+};
+use ark_ff::{Field, PrimeField, Zero};
+
+use super::wnaf::{fixed_wnaf, Wnaf};
+
+/// `accumulator += point`, or `accumulator -= point` if `predicate` is set.
+///
+/// Barretenberg's `self_mixed_add_or_sub`. The y coordinate is negated by multiplying with the predicate rather
+/// than by branching on it, so the sign costs the same either way. The arkworks addition still branches on its
+/// inputs, for doublings and the identity, so this leans toward constant time without being it.
+#[inline]
+pub(crate) fn mixed_add_or_sub<P: SWCurveConfig>(
+    accumulator: &mut Projective<P>,
+    point: &Affine<P>,
+    predicate: bool,
+) {
+    // y - 2y·predicate
+    let y = point.y - point.y.double() * P::BaseField::from(predicate);
+    *accumulator += Affine::<P> {
+        x: point.x,
+        y,
+        infinity: point.infinity,
+    };
+}
+
+/// `point` if `predicate` is set and the identity if not, selected coordinate by coordinate without a branch.
+#[inline]
+fn select_or_identity<P: SWCurveConfig>(point: &Affine<P>, predicate: bool) -> Projective<P> {
+    let mask = P::BaseField::from(predicate);
+    let identity = Projective::<P>::zero();
+    let point = point.into_group();
+    let select = |a: P::BaseField, b: P::BaseField| a + (b - a) * mask;
+    Projective::new_unchecked(
+        select(identity.x, point.x),
+        select(identity.y, point.y),
+        select(identity.z, point.z),
+    )
+}
+
+/// The odd multiples of a base point at every window of a wNAF recoding.
+///
+/// Row i holds `(2k + 1)·2^(w·(n - 1 - i))·G` for `k < 2^(w - 1)`, so, like the recoding, the rows are most
+/// significant first. With `w = 2` a row is barretenberg's fixed-base ladder rung, `one` and `three`.
+#[derive(Clone, Debug)]
+pub(crate) struct FixedBaseTable<P: SWCurveConfig> {
+    pub(crate) base: Affine<P>,
+    pub(crate) wnaf_bits: usize,
+    pub(crate) rows: Vec<Vec<Affine<P>>>,
+}
+
+impl<P: SWCurveConfig> FixedBaseTable<P> {
+    /// A table for recodings of `num_bits` bit scalars with windows of `wnaf_bits`.
+    pub(crate) fn new(base: Affine<P>, num_bits: usize, wnaf_bits: usize) -> Self {
+        let num_rows = (num_bits + wnaf_bits - 1) / wnaf_bits;
+        let row_size = 1 << (wnaf_bits - 1);
+        let mut points = Vec::with_capacity(num_rows * row_size);
+        let mut window_base = base.into_group();
+        for _ in 0..num_rows {
+            let double = window_base.double();
+            let mut multiple = window_base;
+            for _ in 0..row_size {
+                points.push(multiple);
+                multiple += double;
+            }
+            for _ in 0..wnaf_bits {
+                window_base.double_in_place();
+            }
+        }
+        let points = Projective::normalize_batch(&points);
+        let rows = points
+            .chunks(row_size)
+            .rev()
+            .map(|row| row.to_vec())
+            .collect();
+        Self {
+            base,
+            wnaf_bits,
+            rows,
+        }
+    }
+
+    /// `scalar·base`.
+    pub(crate) fn mul(&self, scalar: &P::ScalarField) -> Projective<P> {
+        let wnaf = fixed_wnaf(
+            &scalar.into_bigint(),
+            self.rows.len() * self.wnaf_bits,
+            self.wnaf_bits,
+        );
+        evaluate_fixed_base(&self.rows, &wnaf, &self.base)
+    }
+}
+
+/// `Σᵢ digitsᵢ·rowsᵢ`, less `skew_point` if the recoding is skewed.
+///
+/// There is one row per digit, and each digit picks its odd multiple out of the row. Every digit is nonzero, so
+/// this is a mixed addition per row whatever the scalar. The signs are applied with [`mixed_add_or_sub`], and the
+/// skew correction is always subtracted, as `skew_point` or the identity. The table lookups are still indexed
+/// by the digits, so this leans toward constant time without being it.
+pub(crate) fn evaluate_fixed_base<P: SWCurveConfig, R: AsRef<[Affine<P>]>>(
+    rows: &[R],
+    wnaf: &Wnaf,
+    skew_point: &Affine<P>,
+) -> Projective<P> {
+    assert_eq!(
+        rows.len(),
+        wnaf.digits.len(),
+        "table has {} rows for {} digits",
+        rows.len(),
+        wnaf.digits.len()
+    );
+    let mut accumulator = rows[0].as_ref()[Wnaf::table_index(wnaf.digits[0])].into_group();
+    accumulator -= select_or_identity(skew_point, wnaf.skew);
+    for (row, digit) in rows[1..].iter().zip(&wnaf.digits[1..]) {
+        let point = &row.as_ref()[Wnaf::table_index(*digit)];
+        mixed_add_or_sub(&mut accumulator, point, *digit < 0);
+    }
+    accumulator
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::{Fr, G1Affine};
+    use ark_ff::{Field, UniformRand};
+
+    #[test]
+    fn table_rows_hold_odd_multiples() {
+        let g = G1Affine::generator();
+        let table = FixedBaseTable::new(g, 8, 3);
+        assert_eq!(table.rows.len(), 3);
+        let last = table.rows.last().unwrap();
+        for (k, point) in last.iter().enumerate() {
+            assert_eq!(*point, (g * Fr::from(2 * k as u64 + 1)).into_affine());
+        }
+        assert_eq!(table.rows[1][0], (g * Fr::from(8u64)).into_affine());
+        assert_eq!(table.rows[0][0], (g * Fr::from(64u64)).into_affine());
+    }
+
+    #[test]
+    fn tables_multiply_by_their_base() {
+        let mut rng = rand::thread_rng();
+        let g = G1Affine::rand(&mut rng);
+        for wnaf_bits in [1, 2, 4] {
+            let table = FixedBaseTable::new(g, Fr::MODULUS_BIT_SIZE as usize, wnaf_bits);
+            for scalar in [
+                Fr::ZERO,
+                Fr::ONE,
+                Fr::from(2u64),
+                -Fr::ONE,
+                Fr::rand(&mut rng),
+            ] {
+                assert_eq!(table.mul(&scalar), g * scalar);
+            }
+        }
+    }
+
+    #[test]
+    fn add_or_sub_follows_the_predicate() {
+        let g = G1Affine::generator();
+        let mut accumulator = g * Fr::from(5u64);
+        mixed_add_or_sub(&mut accumulator, &g, true);
+        assert_eq!(accumulator, g * Fr::from(4u64));
+        mixed_add_or_sub(&mut accumulator, &g, false);
+        mixed_add_or_sub(&mut accumulator, &g, false);
+        assert_eq!(accumulator, g * Fr::from(6u64));
+    }
+
+    #[test]
+    fn select_picks_the_point_or_the_identity() {
+        let g = G1Affine::generator();
+        assert_eq!(select_or_identity(&g, true), g.into_group());
+        assert!(select_or_identity(&g, false).is_zero());
+        let mut accumulator = g * Fr::from(3u64);
+        accumulator -= select_or_identity(&g, false);
+        assert_eq!(accumulator, g * Fr::from(3u64));
+    }
+}
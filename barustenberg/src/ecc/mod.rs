@@ -1,33 +1,19 @@
 use std::marker::PhantomData;
 
-use ark_bn254::{Fq12, G1Affine};
-use ark_ec::AffineRepr;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
 use ark_ff::Field;
 use num_bigint::BigUint;
 
+use crate::common::thread::in_thread_pool;
+
 // TODO todo - stubs to get the compiler to cooperate.
+pub(crate) mod batch;
 pub(crate) mod curves;
-
-struct EllCoeffs<QuadFP: ark_ff::Field> {
-    o: QuadFP,
-    vw: QuadFP,
-    vv: QuadFP,
-}
-
-const PRECOMPUTED_COEFFICIENTS_LENGTH: usize = 87;
-
-pub(crate) struct MillerLines {
-    lines: [EllCoeffs<Fq12>; PRECOMPUTED_COEFFICIENTS_LENGTH],
-}
-
-pub(crate) fn reduced_ate_pairing_batch_precomputed(
-    _p_affines: &[G1Affine],
-    _miller_lines: &MillerLines,
-    _num_points: usize,
-) -> Fq12 {
-    // TODO compilation placeholder come back here bb
-    todo!("see comment")
-}
+pub(crate) mod endomorphism;
+pub(crate) mod fields;
+pub(crate) mod fixed_base;
+pub(crate) mod serialize;
+pub(crate) mod wnaf;
 
 #[derive(Clone, Default)]
 pub(crate) struct PippengerRuntimeState<Fr: Field, G1Affine: AffineRepr> {
@@ -36,34 +22,84 @@ pub(crate) struct PippengerRuntimeState<Fr: Field, G1Affine: AffineRepr> {
 
 impl<Fr: Field, G1Affine: AffineRepr> PippengerRuntimeState<Fr, G1Affine> {
     pub(crate) fn new(_size: usize) -> Self {
-        todo!()
+        // arkworks sizes its own buckets per call, so there's nothing to preallocate here
+        Self {
+            phantom: PhantomData,
+        }
     }
+    /// Compute `sum_i mul_scalars[i] * srs_points[i]` over the first `msm_size` terms.
+    /// "unsafe" as in barretenberg: the scalars are not checked to be nonzero, and may be clobbered.
     pub(crate) fn pippenger_unsafe(
         &mut self,
-        _mul_scalars: &mut [Fr],
-        _srs_points: &[G1Affine],
-        _msm_size: usize,
-    ) -> G1Affine {
-        todo!()
+        mul_scalars: &mut [Fr],
+        srs_points: &[G1Affine],
+        msm_size: usize,
+    ) -> G1Affine
+    where
+        G1Affine: AffineRepr<ScalarField = Fr>,
+    {
+        let mul_scalars = &mul_scalars[..msm_size];
+        in_thread_pool(|| {
+            <G1Affine::Group as VariableBaseMSM>::msm_unchecked(
+                &srs_points[..msm_size],
+                mul_scalars,
+            )
+            .into_affine()
+        })
     }
 }
 
+/// Barretenberg keeps field elements in [0, 2p) and negates a root of unity by subtracting it from 2p.
+/// Our elements are always reduced, so this is just a conditional negation.
 #[inline]
 pub(crate) fn conditionally_subtract_from_double_modulus<Fr: Field>(
     this: &Fr,
     predicate: u64,
 ) -> Fr {
-    todo!("see comment")
-    // yikes man
+    if predicate != 0 {
+        -*this
+    } else {
+        *this
+    }
 }
 
-#[inline]
-pub(crate) fn tag_coset_generator<Fr: Field>() -> Fr {
-    todo!("see comment")
-    // yikes man
-}
-#[inline]
-pub(crate) fn coset_generator<Fr: Field>(_n: u8) -> Fr {
-    todo!("see comment")
-    // yikes man
+#[cfg(test)]
+mod test {
+    use ark_bn254::{Fr, G1Affine, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::PippengerRuntimeState;
+    use crate::common::thread::test::run_serial_and_parallel;
+
+    #[test]
+    fn pippenger_matches_naive_msm() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 100;
+        let points: Vec<G1Affine> = (0..n)
+            .map(|_| G1Projective::rand(&mut rng).into_affine())
+            .collect();
+        let scalars: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+
+        // only the first msm_size terms count
+        let msm_size = n - 3;
+        let expected = points
+            .iter()
+            .zip(scalars.iter())
+            .take(msm_size)
+            .fold(G1Projective::default(), |acc, (p, s)| acc + *p * s)
+            .into_affine();
+
+        let (serial, parallel) = run_serial_and_parallel(4, || {
+            let mut scalars = scalars.clone();
+            PippengerRuntimeState::<Fr, G1Affine>::new(msm_size).pippenger_unsafe(
+                &mut scalars,
+                &points,
+                msm_size,
+            )
+        });
+        assert_eq!(serial, expected);
+        assert_eq!(parallel, expected);
+    }
 }
@@ -0,0 +1,219 @@
+//! Barretenberg's byte encodings of curve points.
+//!
+//! A point is `x || y`, each coordinate in the big-endian encoding of [`fields`](super::fields). The point
+//! at infinity is all ones. The compressed form is `x` alone, with the top bit of its first byte holding the
+//! sign of `y`; every modulus we use leaves that bit free. Decoding accepts only canonical coordinates of
+//! points on the curve and in its prime order subgroup.
+
+use anyhow::{anyhow, ensure, Result};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::Field;
+
+use super::fields::{from_be_bytes, to_be_bytes, FieldEncoding};
+
+/// Points with barretenberg's encodings, 64 and 32 bytes for BN254's G1 and 128 and 64 for its G2.
+pub(crate) trait PointEncoding: AffineRepr {
+    /// Bytes in an uncompressed point.
+    const UNCOMPRESSED_SIZE: usize;
+    /// Bytes in a compressed point.
+    const COMPRESSED_SIZE: usize;
+
+    fn to_uncompressed(&self) -> Vec<u8>;
+    fn from_uncompressed(bytes: &[u8]) -> Result<Self>;
+    fn to_compressed(&self) -> Vec<u8>;
+    fn from_compressed(bytes: &[u8]) -> Result<Self>;
+}
+
+/// The top bit of the first byte, where a compressed point keeps the sign of y.
+const SIGN_MASK: u8 = 0x80;
+
+fn is_infinity_encoding(bytes: &[u8]) -> bool {
+    bytes.iter().all(|byte| *byte == 0xff)
+}
+
+/// Rejects points off the curve or outside the prime order subgroup.
+fn validate<P: SWCurveConfig>(point: Affine<P>) -> Result<Affine<P>> {
+    ensure!(point.is_on_curve(), "point is not on the curve");
+    ensure!(
+        point.is_in_correct_subgroup_assuming_on_curve(),
+        "point is not in the prime order subgroup"
+    );
+    Ok(point)
+}
+
+impl<P: SWCurveConfig> PointEncoding for Affine<P>
+where
+    P::BaseField: FieldEncoding,
+{
+    const UNCOMPRESSED_SIZE: usize = 2 * P::BaseField::NUM_BYTES;
+    const COMPRESSED_SIZE: usize = P::BaseField::NUM_BYTES;
+
+    fn to_uncompressed(&self) -> Vec<u8> {
+        if self.infinity {
+            return vec![0xff; Self::UNCOMPRESSED_SIZE];
+        }
+        let mut bytes = Vec::with_capacity(Self::UNCOMPRESSED_SIZE);
+        bytes.extend(to_be_bytes(&self.x));
+        bytes.extend(to_be_bytes(&self.y));
+        bytes
+    }
+
+    fn from_uncompressed(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == Self::UNCOMPRESSED_SIZE,
+            "uncompressed point of {} bytes, expected {}",
+            bytes.len(),
+            Self::UNCOMPRESSED_SIZE
+        );
+        if is_infinity_encoding(bytes) {
+            return Ok(Self::identity());
+        }
+        let (x, y) = bytes.split_at(P::BaseField::NUM_BYTES);
+        validate(Self::new_unchecked(from_be_bytes(x)?, from_be_bytes(y)?))
+    }
+
+    fn to_compressed(&self) -> Vec<u8> {
+        if self.infinity {
+            return vec![0xff; Self::COMPRESSED_SIZE];
+        }
+        let mut bytes = Vec::with_capacity(Self::COMPRESSED_SIZE);
+        bytes.extend(to_be_bytes(&self.x));
+        if self.y.sgn0() {
+            bytes[0] |= SIGN_MASK;
+        }
+        bytes
+    }
+
+    fn from_compressed(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == Self::COMPRESSED_SIZE,
+            "compressed point of {} bytes, expected {}",
+            bytes.len(),
+            Self::COMPRESSED_SIZE
+        );
+        if is_infinity_encoding(bytes) {
+            return Ok(Self::identity());
+        }
+        let sign = bytes[0] & SIGN_MASK != 0;
+        let mut x = bytes.to_vec();
+        x[0] &= !SIGN_MASK;
+        let x: P::BaseField = from_be_bytes(&x)?;
+
+        let y_squared = P::add_b(x.square() * x + P::mul_by_a(x));
+        let mut y = y_squared
+            .sqrt()
+            .ok_or_else(|| anyhow!("no point on the curve has this x"))?;
+        if y.sgn0() != sign {
+            y = -y;
+        }
+        ensure!(y.sgn0() == sign, "sign bit set for y = 0");
+        validate(Self::new_unchecked(x, y))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecc::curves::grumpkin;
+    use ark_bn254::{Fq, G1Affine, G2Affine};
+    use ark_ff::{BigInteger, PrimeField, UniformRand};
+    use proptest::prelude::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn round_trips<C: PointEncoding + UniformRand>(seed: u64) {
+        let point = C::rand(&mut StdRng::seed_from_u64(seed));
+        for point in [point, C::zero()] {
+            let uncompressed = point.to_uncompressed();
+            assert_eq!(uncompressed.len(), C::UNCOMPRESSED_SIZE);
+            assert_eq!(C::from_uncompressed(&uncompressed).unwrap(), point);
+            let compressed = point.to_compressed();
+            assert_eq!(compressed.len(), C::COMPRESSED_SIZE);
+            assert_eq!(C::from_compressed(&compressed).unwrap(), point);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn g1_round_trips(seed in any::<u64>()) {
+            round_trips::<G1Affine>(seed);
+        }
+
+        #[test]
+        fn g2_round_trips(seed in any::<u64>()) {
+            round_trips::<G2Affine>(seed);
+        }
+
+        #[test]
+        fn grumpkin_round_trips(seed in any::<u64>()) {
+            round_trips::<grumpkin::Affine>(seed);
+        }
+
+        #[test]
+        fn bls12_381_round_trips(seed in any::<u64>()) {
+            round_trips::<ark_bls12_381::G1Affine>(seed);
+            round_trips::<ark_bls12_381::G2Affine>(seed);
+        }
+    }
+
+    #[test]
+    fn g1_encoding_is_big_endian_coordinates() {
+        assert_eq!(G1Affine::UNCOMPRESSED_SIZE, 64);
+        assert_eq!(G1Affine::COMPRESSED_SIZE, 32);
+        assert_eq!(G2Affine::UNCOMPRESSED_SIZE, 128);
+        assert_eq!(G2Affine::COMPRESSED_SIZE, 64);
+        assert_eq!(ark_bls12_381::G1Affine::UNCOMPRESSED_SIZE, 96);
+        assert_eq!(ark_bls12_381::G2Affine::COMPRESSED_SIZE, 96);
+
+        // the generator is (1, 2)
+        let bytes = G1Affine::generator().to_uncompressed();
+        assert!(bytes[..31].iter().all(|byte| *byte == 0) && bytes[31] == 1);
+        assert!(bytes[32..63].iter().all(|byte| *byte == 0) && bytes[63] == 2);
+        let compressed = G1Affine::generator().to_compressed();
+        assert_eq!(compressed, bytes[..32]);
+        let negated = (-G1Affine::generator()).to_compressed();
+        assert_eq!(negated[0], SIGN_MASK);
+        assert_eq!(negated[1..], bytes[1..32]);
+    }
+
+    #[test]
+    fn decoding_rejects_invalid_points() {
+        // off the curve
+        let mut bytes = G1Affine::generator().to_uncompressed();
+        bytes[63] = 3;
+        assert!(G1Affine::from_uncompressed(&bytes).is_err());
+
+        // x³ + 3 is not a square for x = 0
+        assert!(G1Affine::from_compressed(&[0; 32]).is_err());
+
+        // a coordinate above the modulus
+        let mut bytes = G1Affine::generator().to_uncompressed();
+        bytes[..32].copy_from_slice(&Fq::MODULUS.to_bytes_be());
+        bytes[31] += 1;
+        assert!(G1Affine::from_uncompressed(&bytes).is_err());
+
+        // wrong lengths
+        assert!(G1Affine::from_uncompressed(&[0; 63]).is_err());
+        assert!(G1Affine::from_compressed(&[0; 33]).is_err());
+
+        // on the twist but outside the prime order subgroup
+        let mut rng = StdRng::seed_from_u64(0);
+        let outside = loop {
+            let x = ark_bn254::Fq2::rand(&mut rng);
+            if let Some(point) = G2Affine::get_point_from_x_unchecked(x, true) {
+                if !point.is_in_correct_subgroup_assuming_on_curve() {
+                    break point;
+                }
+            }
+        };
+        assert!(G2Affine::from_uncompressed(&outside.to_uncompressed()).is_err());
+        assert!(G2Affine::from_compressed(&outside.to_compressed()).is_err());
+        let cleared = outside.clear_cofactor();
+        assert_eq!(
+            G2Affine::from_compressed(&cleared.to_compressed()).unwrap(),
+            cleared
+        );
+    }
+}
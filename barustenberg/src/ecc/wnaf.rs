@@ -0,0 +1,105 @@
+//! Windowed non-adjacent form recoding of scalars.
+
+use ark_ff::BigInteger;
+
+/// A scalar recoded into odd digits of `wnaf_bits` bits each.
+///
+/// With `w = wnaf_bits` and n digits, `scalar + skew = Σᵢ digitsᵢ·2^(w·(n - 1 - i))`: the digits are most
+/// significant first, the leading one is positive and every other one is odd with magnitude below `2^w`.
+/// Odd digits can only add up to an odd number, so an even scalar is recoded as `scalar + 1` with `skew`
+/// set, and whoever evaluates it subtracts the base once more.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Wnaf {
+    pub(crate) digits: Vec<i64>,
+    pub(crate) skew: bool,
+}
+
+impl Wnaf {
+    /// Position of `digit` in a table of the odd multiples `1, 3, 5, ...` of a point.
+    #[inline]
+    pub(crate) fn table_index(digit: i64) -> usize {
+        (digit.unsigned_abs() >> 1) as usize
+    }
+}
+
+/// Recodes the low `num_bits` bits of `scalar` into `⌈num_bits / wnaf_bits⌉` digits, as barretenberg's
+/// `fixed_wnaf`. Every digit is nonzero, so evaluating the recoding takes the same additions for every scalar.
+pub(crate) fn fixed_wnaf<B: BigInteger>(scalar: &B, num_bits: usize, wnaf_bits: usize) -> Wnaf {
+    assert!(
+        (1..=32).contains(&wnaf_bits),
+        "wnaf window of {} bits",
+        wnaf_bits
+    );
+    debug_assert!(
+        scalar.num_bits() as usize <= num_bits,
+        "scalar does not fit in {} bits",
+        num_bits
+    );
+    let num_entries = (num_bits + wnaf_bits - 1) / wnaf_bits;
+    let slice = |round: usize| {
+        (0..wnaf_bits)
+            .filter(|&j| scalar.get_bit(round * wnaf_bits + j))
+            .fold(0i64, |acc, j| acc | (1 << j))
+    };
+
+    let skew = !scalar.get_bit(0);
+    let mut digits = vec![0i64; num_entries];
+    let mut previous = slice(0) + skew as i64;
+    for round in 1..num_entries {
+        // an even window borrows one from the next, turning the current digit negative
+        let next = slice(round);
+        let borrow = (next & 1 == 0) as i64;
+        digits[num_entries - round] = previous - (borrow << wnaf_bits);
+        previous = next + borrow;
+    }
+    digits[0] = previous;
+    Wnaf { digits, skew }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::{Field, PrimeField, UniformRand};
+
+    fn recompose(wnaf: &Wnaf, wnaf_bits: usize) -> Fr {
+        let window = Fr::from(1u64 << wnaf_bits);
+        wnaf.digits
+            .iter()
+            .fold(Fr::ZERO, |acc, d| acc * window + Fr::from(*d))
+            - Fr::from(wnaf.skew as u64)
+    }
+
+    #[test]
+    fn recoding_recomposes_the_scalar() {
+        let mut rng = rand::thread_rng();
+        for wnaf_bits in [1, 2, 4, 5] {
+            for scalar in [
+                Fr::ZERO,
+                Fr::ONE,
+                Fr::from(2u64),
+                -Fr::ONE,
+                Fr::rand(&mut rng),
+            ] {
+                let wnaf = fixed_wnaf(&scalar.into_bigint(), 255, wnaf_bits);
+                assert_eq!(wnaf.digits.len(), (255 + wnaf_bits - 1) / wnaf_bits);
+                assert!(wnaf.digits[0] > 0);
+                assert!(wnaf
+                    .digits
+                    .iter()
+                    .all(|d| d % 2 != 0 && d.abs() < 1 << wnaf_bits));
+                assert_eq!(recompose(&wnaf, wnaf_bits), scalar);
+            }
+        }
+    }
+
+    #[test]
+    fn skew_is_set_for_even_scalars() {
+        assert!(fixed_wnaf(&Fr::from(6u64).into_bigint(), 8, 2).skew);
+        assert!(!fixed_wnaf(&Fr::from(7u64).into_bigint(), 8, 2).skew);
+        assert_eq!(
+            fixed_wnaf(&Fr::from(7u64).into_bigint(), 8, 2).digits,
+            vec![1, -3, -3, 3]
+        );
+    }
+}
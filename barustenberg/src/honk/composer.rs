@@ -0,0 +1,201 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use ark_ec::pairing::Pairing;
+use ark_ff::{One, PrimeField, Zero};
+
+use crate::{
+    ecc::curves::{coset_generator, external_coset_generator},
+    plonk::composer::composer_base::WireType,
+    polynomials::Polynomial,
+    proof_system::circuit_constructor::StandardCircuitConstructor,
+    transcript::BarretenHasher,
+};
+
+use super::{
+    flavor::{AllEntities, ProvingKey, VerificationKey},
+    grand_product::subgroup_generator,
+    pcs::{
+        kzg::{CommitmentKey, VerifierKey},
+        GeminiShplonkKzg, MultilinearCommitmentScheme,
+    },
+    prover::StandardProver,
+    verifier::StandardVerifier,
+};
+
+/// Turns a [`StandardCircuitConstructor`] into Standard Honk keys, provers and verifiers, which open the
+/// sumcheck evaluations with `P`.
+#[derive(Debug)]
+pub(crate) struct StandardHonkComposer<
+    E: Pairing,
+    H: BarretenHasher,
+    P: MultilinearCommitmentScheme<E> = GeminiShplonkKzg,
+> {
+    pub(crate) circuit_constructor: StandardCircuitConstructor<E::ScalarField>,
+    commitment_key: CommitmentKey<E>,
+    verifier_key: VerifierKey<E>,
+    proving_key: Option<Arc<ProvingKey<E::ScalarField>>>,
+    verification_key: Option<Arc<VerificationKey<E>>>,
+    phantom: PhantomData<(H, P)>,
+}
+
+impl<E: Pairing, H: BarretenHasher, P: MultilinearCommitmentScheme<E>> StandardHonkComposer<E, H, P>
+where
+    E::ScalarField: PrimeField,
+{
+    pub(crate) fn new(
+        circuit_constructor: StandardCircuitConstructor<E::ScalarField>,
+        commitment_key: CommitmentKey<E>,
+        verifier_key: VerifierKey<E>,
+    ) -> Self {
+        Self {
+            circuit_constructor,
+            commitment_key,
+            verifier_key,
+            proving_key: None,
+            verification_key: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The precomputed polynomials along with the wires, on a trace padded to a power of two with zero rows.
+    pub(crate) fn compute_proving_key(&mut self) -> Arc<ProvingKey<E::ScalarField>> {
+        if let Some(proving_key) = &self.proving_key {
+            return proving_key.clone();
+        }
+        let circuit = &self.circuit_constructor;
+        let circuit_size = circuit.get_total_num_rows().next_power_of_two().max(2);
+        let num_public_inputs = circuit.public_inputs.len();
+        let column = |f: &dyn Fn(usize) -> E::ScalarField| {
+            Polynomial::from_coefficients((0..circuit_size).map(f).collect(), circuit_size)
+        };
+        // gates sit after the public inputs, whose rows have no selectors
+        let gate_column = |selector: &[E::ScalarField]| {
+            column(&|i| {
+                i.checked_sub(num_public_inputs)
+                    .and_then(|gate| selector.get(gate))
+                    .copied()
+                    .unwrap_or_else(E::ScalarField::zero)
+            })
+        };
+        let wire_column = |wire: &[u32]| {
+            column(&|i| {
+                if i < num_public_inputs {
+                    circuit.get_variable(circuit.public_inputs[i])
+                } else {
+                    wire.get(i - num_public_inputs)
+                        .map(|&index| circuit.get_variable(index))
+                        .unwrap_or_else(E::ScalarField::zero)
+                }
+            })
+        };
+
+        let mut polynomials = AllEntities {
+            q_m: gate_column(&circuit.q_m),
+            q_l: gate_column(&circuit.q_1),
+            q_r: gate_column(&circuit.q_2),
+            q_o: gate_column(&circuit.q_3),
+            q_c: gate_column(&circuit.q_c),
+            lagrange_first: column(&|i| E::ScalarField::from((i == 0) as u64)),
+            lagrange_last: column(&|i| E::ScalarField::from((i == circuit_size - 1) as u64)),
+            w_l: wire_column(&circuit.w_l),
+            w_r: wire_column(&circuit.w_r),
+            w_o: wire_column(&circuit.w_o),
+            z_perm: Polynomial::new(circuit_size),
+            z_perm_shift: Polynomial::new(circuit_size),
+            ..Default::default()
+        };
+        self.compute_permutation_polynomials(circuit_size, &mut polynomials);
+
+        let proving_key = Arc::new(ProvingKey {
+            circuit_size,
+            log_circuit_size: circuit_size.trailing_zeros() as usize,
+            public_inputs: self.circuit_constructor.get_public_inputs(),
+            polynomials,
+        });
+        self.proving_key = Some(proving_key.clone());
+        proving_key
+    }
+
+    /// `id_k[i] = k_k·ωⁱ`, the cells of the Plonk permutation: `ω` generates the subgroup of order n, `k_1 = 1`
+    /// and the other columns sit in the cosets of [`coset_generator`]. σ maps every cell to the id of the next one
+    /// in its copy cycle. Cells that are in no cycle, such as padding, map to themselves.
+    fn compute_permutation_polynomials(
+        &self,
+        circuit_size: usize,
+        polynomials: &mut AllEntities<Polynomial<E::ScalarField>>,
+    ) {
+        let root = subgroup_generator::<E::ScalarField>(circuit_size)
+            .expect("the circuit fits in the two-adic subgroup of the scalar field");
+        let roots: Vec<E::ScalarField> =
+            std::iter::successors(Some(E::ScalarField::one()), |x| Some(*x * root))
+                .take(circuit_size)
+                .collect();
+        let column_generators = [
+            E::ScalarField::one(),
+            coset_generator(0),
+            coset_generator(1),
+        ];
+        let id = |row: usize, column: usize| column_generators[column] * roots[row];
+        let column = |wire_type: WireType| match wire_type {
+            WireType::Left => 0,
+            WireType::Right => 1,
+            WireType::Output => 2,
+            _ => unreachable!("the Standard arithmetization has three wires"),
+        };
+
+        let ids = [0, 1, 2].map(|k| {
+            Polynomial::from_coefficients(
+                (0..circuit_size).map(|i| id(i, k)).collect(),
+                circuit_size,
+            )
+        });
+        let mut sigmas = ids.clone();
+        for cycle in self.circuit_constructor.compute_wire_copy_cycles() {
+            for (node, next) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+                sigmas[column(node.wire_type)][node.gate_index as usize] =
+                    id(next.gate_index as usize, column(next.wire_type));
+            }
+        }
+        // take the public inputs' left wire cells out of their cycles, see
+        // [`compute_public_input_delta`](crate::plonk::proof_system::public_inputs::compute_public_input_delta)
+        for (i, root) in roots
+            .iter()
+            .take(self.circuit_constructor.public_inputs.len())
+            .enumerate()
+        {
+            sigmas[0][i] = external_coset_generator::<E::ScalarField>() * root;
+        }
+
+        [polynomials.id_1, polynomials.id_2, polynomials.id_3] = ids;
+        [
+            polynomials.sigma_1,
+            polynomials.sigma_2,
+            polynomials.sigma_3,
+        ] = sigmas;
+    }
+
+    /// Commitments to the precomputed polynomials.
+    pub(crate) fn compute_verification_key(&mut self) -> Arc<VerificationKey<E>> {
+        if let Some(verification_key) = &self.verification_key {
+            return verification_key.clone();
+        }
+        let proving_key = self.compute_proving_key();
+        let precomputed = proving_key.polynomials.precomputed();
+        let verification_key = Arc::new(VerificationKey {
+            circuit_size: proving_key.circuit_size,
+            log_circuit_size: proving_key.log_circuit_size,
+            num_public_inputs: proving_key.public_inputs.len(),
+            commitments: precomputed.map(|p| self.commitment_key.commit(&p.coefficients)),
+        });
+        self.verification_key = Some(verification_key.clone());
+        verification_key
+    }
+
+    pub(crate) fn create_prover(&mut self) -> StandardProver<E, H, P> {
+        StandardProver::new(self.compute_proving_key(), self.commitment_key.clone())
+    }
+
+    pub(crate) fn create_verifier(&mut self) -> StandardVerifier<E, H, P> {
+        StandardVerifier::new(self.compute_verification_key(), self.verifier_key)
+    }
+}
@@ -0,0 +1,158 @@
+use ark_ec::pairing::Pairing;
+use ark_ff::Field;
+
+use crate::polynomials::Polynomial;
+
+/// Selectors, permutation polynomials and Lagrange polynomials, fixed by the circuit.
+pub(crate) const NUM_PRECOMPUTED_ENTITIES: usize = 13;
+/// Wires and the permutation grand product, committed to by the prover.
+pub(crate) const NUM_WITNESS_ENTITIES: usize = 4;
+/// Everything sumcheck sees: the above plus the shift of the grand product.
+pub(crate) const NUM_ALL_ENTITIES: usize = NUM_PRECOMPUTED_ENTITIES + NUM_WITNESS_ENTITIES + 1;
+pub(crate) const NUM_UNSHIFTED_ENTITIES: usize = NUM_ALL_ENTITIES - 1;
+
+/// One more than the largest degree of any relation, in any single variable. The permutation relation
+/// multiplies the grand product by three wire terms.
+pub(crate) const MAX_RELATION_LENGTH: usize = 5;
+/// Length of the sumcheck round univariates, which also carry the linear pow_ζ factor.
+pub(crate) const BATCHED_RELATION_PARTIAL_LENGTH: usize = MAX_RELATION_LENGTH + 1;
+
+/// One `T` per polynomial of the Standard Honk flavor: the polynomials themselves for the prover, their
+/// evaluations in sumcheck, or their commitments for the verifier.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct AllEntities<T> {
+    pub(crate) q_m: T,
+    pub(crate) q_l: T,
+    pub(crate) q_r: T,
+    pub(crate) q_o: T,
+    pub(crate) q_c: T,
+    pub(crate) sigma_1: T,
+    pub(crate) sigma_2: T,
+    pub(crate) sigma_3: T,
+    pub(crate) id_1: T,
+    pub(crate) id_2: T,
+    pub(crate) id_3: T,
+    pub(crate) lagrange_first: T,
+    pub(crate) lagrange_last: T,
+    pub(crate) w_l: T,
+    pub(crate) w_r: T,
+    pub(crate) w_o: T,
+    pub(crate) z_perm: T,
+    pub(crate) z_perm_shift: T,
+}
+
+impl<T> AllEntities<T> {
+    pub(crate) fn from_array(entities: [T; NUM_ALL_ENTITIES]) -> Self {
+        let [q_m, q_l, q_r, q_o, q_c, sigma_1, sigma_2, sigma_3, id_1, id_2, id_3, lagrange_first, lagrange_last, w_l, w_r, w_o, z_perm, z_perm_shift] =
+            entities;
+        Self {
+            q_m,
+            q_l,
+            q_r,
+            q_o,
+            q_c,
+            sigma_1,
+            sigma_2,
+            sigma_3,
+            id_1,
+            id_2,
+            id_3,
+            lagrange_first,
+            lagrange_last,
+            w_l,
+            w_r,
+            w_o,
+            z_perm,
+            z_perm_shift,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> [&T; NUM_ALL_ENTITIES] {
+        [
+            &self.q_m,
+            &self.q_l,
+            &self.q_r,
+            &self.q_o,
+            &self.q_c,
+            &self.sigma_1,
+            &self.sigma_2,
+            &self.sigma_3,
+            &self.id_1,
+            &self.id_2,
+            &self.id_3,
+            &self.lagrange_first,
+            &self.lagrange_last,
+            &self.w_l,
+            &self.w_r,
+            &self.w_o,
+            &self.z_perm,
+            &self.z_perm_shift,
+        ]
+    }
+
+    pub(crate) fn as_array_mut(&mut self) -> [&mut T; NUM_ALL_ENTITIES] {
+        [
+            &mut self.q_m,
+            &mut self.q_l,
+            &mut self.q_r,
+            &mut self.q_o,
+            &mut self.q_c,
+            &mut self.sigma_1,
+            &mut self.sigma_2,
+            &mut self.sigma_3,
+            &mut self.id_1,
+            &mut self.id_2,
+            &mut self.id_3,
+            &mut self.lagrange_first,
+            &mut self.lagrange_last,
+            &mut self.w_l,
+            &mut self.w_r,
+            &mut self.w_o,
+            &mut self.z_perm,
+            &mut self.z_perm_shift,
+        ]
+    }
+
+    pub(crate) fn map<'a, U>(&'a self, f: impl FnMut(&'a T) -> U) -> AllEntities<U> {
+        AllEntities::from_array(self.as_array().map(f))
+    }
+
+    /// The entities fixed by the circuit.
+    pub(crate) fn precomputed(&self) -> [&T; NUM_PRECOMPUTED_ENTITIES] {
+        let all = self.as_array();
+        std::array::from_fn(|i| all[i])
+    }
+
+    /// Everything but the shifted grand product, which is opened through `z_perm` instead.
+    pub(crate) fn unshifted(&self) -> [&T; NUM_UNSHIFTED_ENTITIES] {
+        let all = self.as_array();
+        std::array::from_fn(|i| all[i])
+    }
+
+    pub(crate) fn to_be_shifted(&self) -> [&T; 1] {
+        [&self.z_perm]
+    }
+
+    pub(crate) fn shifted(&self) -> [&T; 1] {
+        [&self.z_perm_shift]
+    }
+}
+
+/// Proving key of the Standard Honk flavor. The witness entities are filled in by the prover.
+#[derive(Debug, Clone)]
+pub(crate) struct ProvingKey<F: Field> {
+    pub(crate) circuit_size: usize,
+    pub(crate) log_circuit_size: usize,
+    pub(crate) public_inputs: Vec<F>,
+    /// every polynomial as its values on the boolean hypercube of dimension `log_circuit_size`
+    pub(crate) polynomials: AllEntities<Polynomial<F>>,
+}
+
+/// Verification key of the Standard Honk flavor: commitments to the precomputed entities.
+#[derive(Debug, Clone)]
+pub(crate) struct VerificationKey<E: Pairing> {
+    pub(crate) circuit_size: usize,
+    pub(crate) log_circuit_size: usize,
+    pub(crate) num_public_inputs: usize,
+    pub(crate) commitments: [E::G1Affine; NUM_PRECOMPUTED_ENTITIES],
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use ark_ff::{FftField, Field};
+
+use crate::{ecc::batch::batch_invert, polynomials::Polynomial};
+
+use super::{flavor::AllEntities, relations::RelationParameters};
+
+/// The permutation grand product and its shift.
+///
+/// `z_perm` is 0 in the first row, as the shift of the last row has to be, and
+/// `z_perm[i + 1] = ∏_{j ≤ i} (wⱼ + β·idⱼ + γ)/(wⱼ + β·σⱼ + γ)` after that, products running over the three
+/// wires. See [`PermutationRelation`](super::relations::PermutationRelation) for the identity this satisfies.
+pub(crate) fn compute_permutation_grand_product<F: Field>(
+    circuit_size: usize,
+    polynomials: &AllEntities<Polynomial<F>>,
+    parameters: &RelationParameters<F>,
+) -> Result<(Polynomial<F>, Polynomial<F>)> {
+    let RelationParameters { beta, gamma, .. } = *parameters;
+    let wires = [&polynomials.w_l, &polynomials.w_r, &polynomials.w_o];
+    let ids = [&polynomials.id_1, &polynomials.id_2, &polynomials.id_3];
+    let sigmas = [
+        &polynomials.sigma_1,
+        &polynomials.sigma_2,
+        &polynomials.sigma_3,
+    ];
+
+    let mut numerators = vec![F::one(); circuit_size];
+    let mut denominators = vec![F::one(); circuit_size];
+    for k in 0..3 {
+        for i in 0..circuit_size {
+            numerators[i] *= wires[k][i] + beta * ids[k][i] + gamma;
+            denominators[i] *= wires[k][i] + beta * sigmas[k][i] + gamma;
+        }
+    }
+    batch_invert(&mut denominators)?;
+
+    let mut z_perm = Polynomial::new(circuit_size);
+    let mut z_perm_shift = Polynomial::new(circuit_size);
+    let mut acc = F::one();
+    for i in 0..circuit_size - 1 {
+        acc *= numerators[i] * denominators[i];
+        z_perm[i + 1] = acc;
+        z_perm_shift[i] = acc;
+    }
+    Ok((z_perm, z_perm_shift))
+}
+
+/// The generator `ω` of the subgroup of order `circuit_size`, which the permutation's ids are powers of.
+pub(crate) fn subgroup_generator<F: FftField>(circuit_size: usize) -> Result<F> {
+    F::get_root_of_unity(circuit_size as u64)
+        .ok_or_else(|| anyhow!("the scalar field has no subgroup of order {}", circuit_size))
+}
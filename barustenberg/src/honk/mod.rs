@@ -0,0 +1,14 @@
+//! Honk: a Plonk-style proof system that proves its relations with sumcheck over the boolean hypercube,
+//! instead of with a quotient polynomial over a multiplicative subgroup.
+//!
+//! Only the Standard flavor is implemented: the Standard arithmetic gate and the permutation argument, with
+//! multilinear evaluations opened through Gemini, Shplonk and KZG.
+
+pub(crate) mod composer;
+pub(crate) mod flavor;
+pub(crate) mod grand_product;
+pub(crate) mod pcs;
+pub(crate) mod prover;
+pub(crate) mod relations;
+pub(crate) mod sumcheck;
+pub(crate) mod verifier;
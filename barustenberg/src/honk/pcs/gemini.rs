@@ -0,0 +1,307 @@
+use anyhow::{anyhow, Result};
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::{Field, One, PrimeField, Zero};
+
+use crate::{
+    polynomials::{
+        polynomial_arithmetic::{evaluate, partially_evaluate_mle},
+        Polynomial,
+    },
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{kzg::CommitmentKey, OpeningClaim, OpeningPair, ProverOpeningClaim};
+
+/// Gemini reduces an evaluation claim `A(u) = v` for a multilinear A in d variables to univariate claims.
+///
+/// Read the values of A on the hypercube as the coefficients of a univariate `A₀(X)`. Fixing the lowest
+/// variable to `u₀` is then the same as `A₁(X²) = (1 - u₀)·(A₀(X) + A₀(-X))/2 + u₀·(A₀(X) - A₀(-X))/(2X)`, and
+/// after d such folds the constant `A_d` is `A(u)`. The prover commits to the folds `A₁, ..., A_{d-1}`, and
+/// the verifier, given `Aₗ(-r^(2ˡ))` for a random r, can recompute `Aₗ(r^(2ˡ))` from the top down. What
+/// is left are univariate claims, one per fold.
+///
+/// The batched polynomial is `A₀ = F + G/X`, where F batches the unshifted polynomials and G the ones that are
+/// opened shifted. `G/X` has no commitment of its own, so the claims about `A₀` at `r` and `-r` are made
+/// about `F + G/r` and `F - G/r` instead, which agree with `A₀` at those points.
+#[derive(Debug)]
+pub(crate) struct Gemini;
+
+/// `r, r², r⁴, ..., r^(2^(d-1))`
+fn squares_of_r<F: Field>(r: F, num_variables: usize) -> Vec<F> {
+    let mut squares = Vec::with_capacity(num_variables);
+    let mut square = r;
+    for _ in 0..num_variables {
+        squares.push(square);
+        square.square_in_place();
+    }
+    squares
+}
+
+impl Gemini {
+    /// Sends the folds and their evaluations at `-r^(2ˡ)`, and returns the univariate claims, in the order
+    /// `F + G/r` at r, `F - G/r` at -r, then `Aₗ` at `-r^(2ˡ)` for every fold.
+    pub(crate) fn reduce_prove<E: Pairing, H: BarretenHasher>(
+        commitment_key: &CommitmentKey<E>,
+        mle_opening_point: &[E::ScalarField],
+        batched_unshifted: Polynomial<E::ScalarField>,
+        batched_to_be_shifted: Polynomial<E::ScalarField>,
+        transcript: &mut Transcript<H>,
+    ) -> Vec<ProverOpeningClaim<E::ScalarField>>
+    where
+        E::ScalarField: PrimeField,
+    {
+        let num_variables = mle_opening_point.len();
+        let n = 1 << num_variables;
+
+        // A₀ = F + G/X
+        let mut fold_polynomials = Vec::with_capacity(num_variables);
+        fold_polynomials.push(
+            (0..n)
+                .map(|i| {
+                    batched_unshifted[i]
+                        + batched_to_be_shifted
+                            .coefficients
+                            .get(i + 1)
+                            .copied()
+                            .unwrap_or_else(E::ScalarField::zero)
+                })
+                .collect::<Vec<_>>(),
+        );
+        for (l, u) in mle_opening_point[..num_variables - 1].iter().enumerate() {
+            let fold = partially_evaluate_mle(&fold_polynomials[l], *u);
+            fold_polynomials.push(fold);
+        }
+        for (l, fold) in fold_polynomials.iter().enumerate().skip(1) {
+            transcript
+                .send_to_verifier(&format!("Gemini:FOLD_{}", l), &commitment_key.commit(fold));
+        }
+
+        let r = transcript.derive_challenge::<E::ScalarField>("Gemini:r");
+        let r_squares = squares_of_r(r, num_variables);
+        let evaluations: Vec<E::ScalarField> = fold_polynomials
+            .iter()
+            .zip(&r_squares)
+            .map(|(fold, r_square)| evaluate(fold, &-*r_square, fold.len()))
+            .collect();
+        for (l, evaluation) in evaluations.iter().enumerate() {
+            transcript.send_to_verifier(&format!("Gemini:a_{}", l), evaluation);
+        }
+
+        // F ± G/r
+        let r_inv = r.inverse().unwrap();
+        let mut positive = batched_unshifted.clone();
+        let mut negative = batched_unshifted;
+        for i in 0..n {
+            let shifted = batched_to_be_shifted[i] * r_inv;
+            positive[i] += shifted;
+            negative[i] -= shifted;
+        }
+
+        let mut claims = Vec::with_capacity(num_variables + 1);
+        claims.push(ProverOpeningClaim {
+            opening_pair: OpeningPair {
+                challenge: r,
+                evaluation: evaluate(&positive.coefficients, &r, n),
+            },
+            polynomial: positive,
+        });
+        claims.push(ProverOpeningClaim {
+            polynomial: negative,
+            opening_pair: OpeningPair {
+                challenge: -r,
+                evaluation: evaluations[0],
+            },
+        });
+        for (l, fold) in fold_polynomials.into_iter().enumerate().skip(1) {
+            let size = fold.len();
+            claims.push(ProverOpeningClaim {
+                polynomial: Polynomial::from_coefficients(fold, size),
+                opening_pair: OpeningPair {
+                    challenge: -r_squares[l],
+                    evaluation: evaluations[l],
+                },
+            });
+        }
+        claims
+    }
+
+    /// Reads the folds and their evaluations, recomputes `A₀(r)` from the batched evaluation, and returns the
+    /// claims matching [`Gemini::reduce_prove`].
+    pub(crate) fn reduce_verify<E: Pairing, H: BarretenHasher>(
+        mle_opening_point: &[E::ScalarField],
+        batched_evaluation: E::ScalarField,
+        batched_unshifted_commitment: E::G1,
+        batched_to_be_shifted_commitment: E::G1,
+        transcript: &mut Transcript<H>,
+    ) -> Result<Vec<OpeningClaim<E>>>
+    where
+        E::ScalarField: PrimeField,
+    {
+        let num_variables = mle_opening_point.len();
+        let mut fold_commitments = Vec::with_capacity(num_variables - 1);
+        for l in 1..num_variables {
+            fold_commitments.push(
+                transcript.receive_from_prover::<E::G1Affine>(&format!("Gemini:FOLD_{}", l))?,
+            );
+        }
+        let r = transcript.derive_challenge::<E::ScalarField>("Gemini:r");
+        let mut evaluations = Vec::with_capacity(num_variables);
+        for l in 0..num_variables {
+            evaluations.push(
+                transcript.receive_from_prover::<E::ScalarField>(&format!("Gemini:a_{}", l))?,
+            );
+        }
+        let r_squares = squares_of_r(r, num_variables);
+
+        // Aₗ(r_l) = (2·r_l·A_{l+1}(r_l²) - Aₗ(-r_l)·(r_l·(1 - uₗ) - uₗ)) / (r_l·(1 - uₗ) + uₗ),
+        // starting from the constant A_d = v
+        let one = E::ScalarField::one();
+        let mut evaluation_positive = batched_evaluation;
+        for l in (0..num_variables).rev() {
+            let r_l = r_squares[l];
+            let u = mle_opening_point[l];
+            let denominator = (r_l * (one - u) + u)
+                .inverse()
+                .ok_or_else(|| anyhow!("gemini challenge hit a pole"))?;
+            evaluation_positive = (r_l * evaluation_positive.double()
+                - evaluations[l] * (r_l * (one - u) - u))
+                * denominator;
+        }
+
+        let r_inv = r
+            .inverse()
+            .ok_or_else(|| anyhow!("gemini challenge is zero"))?;
+        let shifted = batched_to_be_shifted_commitment * r_inv;
+        let mut claims = Vec::with_capacity(num_variables + 1);
+        claims.push(OpeningClaim {
+            commitment: (batched_unshifted_commitment + shifted).into_affine(),
+            opening_pair: OpeningPair {
+                challenge: r,
+                evaluation: evaluation_positive,
+            },
+        });
+        claims.push(OpeningClaim {
+            commitment: (batched_unshifted_commitment - shifted).into_affine(),
+            opening_pair: OpeningPair {
+                challenge: -r,
+                evaluation: evaluations[0],
+            },
+        });
+        for (l, commitment) in fold_commitments.into_iter().enumerate() {
+            claims.push(OpeningClaim {
+                commitment,
+                opening_pair: OpeningPair {
+                    challenge: -r_squares[l + 1],
+                    evaluation: evaluations[l + 1],
+                },
+            });
+        }
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{honk::pcs::kzg::test::mock_keys, transcript::Keccak256};
+    use ark_bn254::{Bn254, Fr, G1Projective};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn univariate_claims_hold() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let num_variables = 4;
+        let n = 1 << num_variables;
+        let (commitment_key, _) = mock_keys::<Bn254>(n, &mut rng);
+        let random_polynomial = |rng: &mut StdRng| {
+            Polynomial::from_coefficients((0..n).map(|_| Fr::rand(rng)).collect(), n)
+        };
+        let unshifted = random_polynomial(&mut rng);
+        let mut to_be_shifted = random_polynomial(&mut rng);
+        to_be_shifted[0] = Fr::from(0u64);
+        let point: Vec<Fr> = (0..num_variables).map(|_| Fr::rand(&mut rng)).collect();
+        let batched_evaluation =
+            unshifted.evaluate_mle(&point, false) + to_be_shifted.evaluate_mle(&point, true);
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        let prover_claims = Gemini::reduce_prove(
+            &commitment_key,
+            &point,
+            unshifted.clone(),
+            to_be_shifted.clone(),
+            &mut transcript,
+        );
+        for claim in &prover_claims {
+            let size = claim.polynomial.size();
+            assert_eq!(
+                evaluate(
+                    &claim.polynomial.coefficients,
+                    &claim.opening_pair.challenge,
+                    size
+                ),
+                claim.opening_pair.evaluation
+            );
+        }
+
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let verifier_claims = Gemini::reduce_verify::<Bn254, _>(
+            &point,
+            batched_evaluation,
+            G1Projective::from(commitment_key.commit(&unshifted.coefficients)),
+            G1Projective::from(commitment_key.commit(&to_be_shifted.coefficients)),
+            &mut transcript,
+        )
+        .unwrap();
+        assert_eq!(verifier_claims.len(), prover_claims.len());
+        for (verifier_claim, prover_claim) in verifier_claims.iter().zip(&prover_claims) {
+            assert_eq!(verifier_claim.opening_pair, prover_claim.opening_pair);
+            assert_eq!(
+                verifier_claim.commitment,
+                commitment_key.commit(&prover_claim.polynomial.coefficients)
+            );
+        }
+        assert!(transcript.is_exhausted());
+    }
+
+    #[test]
+    fn wrong_batched_evaluation_breaks_the_first_claim() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let num_variables = 3;
+        let n = 1 << num_variables;
+        let (commitment_key, _) = mock_keys::<Bn254>(n, &mut rng);
+        let unshifted =
+            Polynomial::from_coefficients((0..n).map(|_| Fr::rand(&mut rng)).collect(), n);
+        let to_be_shifted = Polynomial::new(n);
+        let point: Vec<Fr> = (0..num_variables).map(|_| Fr::rand(&mut rng)).collect();
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        let prover_claims = Gemini::reduce_prove(
+            &commitment_key,
+            &point,
+            unshifted.clone(),
+            to_be_shifted,
+            &mut transcript,
+        );
+
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let verifier_claims = Gemini::reduce_verify::<Bn254, _>(
+            &point,
+            unshifted.evaluate_mle(&point, false) + Fr::from(1u64),
+            G1Projective::from(commitment_key.commit(&unshifted.coefficients)),
+            G1Projective::default(),
+            &mut transcript,
+        )
+        .unwrap();
+        assert_ne!(
+            verifier_claims[0].opening_pair,
+            prover_claims[0].opening_pair
+        );
+        assert_eq!(
+            verifier_claims[1].opening_pair,
+            prover_claims[1].opening_pair
+        );
+    }
+}
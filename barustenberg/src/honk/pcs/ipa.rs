@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{batch_inversion, Field, One, Zero};
+
+use crate::{
+    ecc::curves::grumpkin::{self, derive_generators},
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{OpeningPair, ProverOpeningClaim};
+
+const IPA_DOMAIN_SEPARATOR: &[u8] = b"IPA_COMMITMENT_KEY";
+
+/// Generators for Pedersen vector commitments, plus one more that the inner product gets bound to.
+///
+/// There is no trusted setup: the generators are derived by hashing to the curve, under their own domain
+/// separator, so nobody knows a relation between them, and the prover and the verifier use the same key.
+#[derive(Debug, Clone)]
+pub(crate) struct IpaCommitmentKey<C: AffineRepr> {
+    pub(crate) generators: Arc<Vec<C>>,
+    pub(crate) aux_generator: C,
+}
+
+impl IpaCommitmentKey<grumpkin::Affine> {
+    /// A key for polynomials of up to `size` coefficients over Grumpkin.
+    pub(crate) fn new(size: usize) -> Self {
+        let mut generators = derive_generators(IPA_DOMAIN_SEPARATOR, size + 1, 0);
+        let aux_generator = generators.pop().unwrap();
+        Self {
+            generators: Arc::new(generators),
+            aux_generator,
+        }
+    }
+}
+
+impl<C: AffineRepr> IpaCommitmentKey<C> {
+    /// `Σᵢ pᵢ·Gᵢ`
+    pub(crate) fn commit(&self, polynomial: &[C::ScalarField]) -> C {
+        assert!(
+            polynomial.len() <= self.generators.len(),
+            "polynomial of size {} is too large for {} generators",
+            polynomial.len(),
+            self.generators.len()
+        );
+        C::Group::msm_unchecked(&self.generators[..polynomial.len()], polynomial).into_affine()
+    }
+}
+
+/// The inner product argument of Bulletproofs, as an opening proof for Pedersen commitments.
+///
+/// `p(x) = v` is the inner product of the coefficients `a` of p with `b = (1, x, x², ...)`. With a random multiple U
+/// of the auxiliary generator, `C + v·U = <a, G> + <a, b>·U`, and each round halves the vectors: the prover sends
+/// the cross terms `L = <a_lo, G_hi> + <a_lo, b_hi>·U` and `R = <a_hi, G_lo> + <a_hi, b_lo>·U`, and for a
+/// challenge u both sides fold into `a' = u·a_lo + u⁻¹·a_hi`, `b' = u⁻¹·b_lo + u·b_hi`, `G' = u⁻¹·G_lo + u·G_hi`
+/// with `C' = C + u²·L + u⁻²·R`. Once a single coefficient `a₀` is left the verifier checks
+/// `C' = a₀·G₀ + a₀·b₀·U`, computing `G₀` and `b₀` from the challenges itself.
+///
+/// C, x and v go into the transcript before U is drawn. Otherwise U would not depend on the claim, and a prover
+/// could move any multiple of U between C and v·U to open C to a value it does not hold.
+#[derive(Debug)]
+pub(crate) struct Ipa;
+
+/// `(s, b₀)`, where `G₀ = <s, G>` and `b₀ = <s, (1, x, x², ...)>` after folding with `round_challenges`. The
+/// first round splits on the top bit of the index, so coefficient i is scaled by uⱼ in round j if bit
+/// `k - 1 - j` of i is set, and by uⱼ⁻¹ otherwise.
+fn folding_scalars<F: Field>(round_challenges: &[F], x: F) -> (Vec<F>, F) {
+    let num_rounds = round_challenges.len();
+    let mut inverses = round_challenges.to_vec();
+    batch_inversion(&mut inverses);
+
+    let mut s = vec![F::one(); 1 << num_rounds];
+    for (j, (u, u_inv)) in round_challenges.iter().zip(&inverses).enumerate() {
+        let bit = num_rounds - 1 - j;
+        for (i, s_i) in s.iter_mut().enumerate() {
+            *s_i *= if (i >> bit) & 1 == 1 { *u } else { *u_inv };
+        }
+    }
+
+    // b₀ = ∏ⱼ (uⱼ⁻¹ + uⱼ·x^(2^(k - 1 - j)))
+    let mut x_pows = vec![x; num_rounds];
+    for i in 1..num_rounds {
+        x_pows[i] = x_pows[i - 1].square();
+    }
+    let b_0 = round_challenges
+        .iter()
+        .zip(&inverses)
+        .enumerate()
+        .map(|(j, (u, u_inv))| *u_inv + *u * x_pows[num_rounds - 1 - j])
+        .product();
+    (s, b_0)
+}
+
+/// Binds the challenges to the claim that `commitment` opens to `opening_pair`.
+fn absorb_claim<C: AffineRepr, H: BarretenHasher>(
+    transcript: &mut Transcript<H>,
+    commitment: &C,
+    opening_pair: &OpeningPair<C::ScalarField>,
+) {
+    transcript.absorb("IPA:commitment", commitment);
+    transcript.absorb("IPA:challenge", &opening_pair.challenge);
+    transcript.absorb("IPA:evaluation", &opening_pair.evaluation);
+}
+
+impl Ipa {
+    /// Absorbs the claim, then sends the cross terms of every round and the final coefficient.
+    pub(crate) fn compute_opening_proof<C: AffineRepr, H: BarretenHasher>(
+        key: &IpaCommitmentKey<C>,
+        claim: ProverOpeningClaim<C::ScalarField>,
+        transcript: &mut Transcript<H>,
+    ) -> Result<()> {
+        let n = claim.polynomial.size();
+        ensure!(
+            n.is_power_of_two() && n <= key.generators.len(),
+            "cannot open {} coefficients with {} generators",
+            n,
+            key.generators.len()
+        );
+        let x = claim.opening_pair.challenge;
+
+        let commitment = key.commit(&claim.polynomial.coefficients[..n]);
+        absorb_claim(transcript, &commitment, &claim.opening_pair);
+        let generator_challenge =
+            transcript.derive_challenge::<C::ScalarField>("IPA:generator_challenge");
+        let aux_generator = key.aux_generator * generator_challenge;
+
+        let mut a = claim.polynomial.coefficients[..n].to_vec();
+        let mut b = Vec::with_capacity(n);
+        let mut x_pow = C::ScalarField::one();
+        for _ in 0..n {
+            b.push(x_pow);
+            x_pow *= x;
+        }
+        let mut g: Vec<C::Group> = key.generators[..n].iter().map(|g| g.into_group()).collect();
+
+        let inner_product = |lhs: &[C::ScalarField], rhs: &[C::ScalarField]| {
+            lhs.iter()
+                .zip(rhs)
+                .map(|(l, r)| *l * r)
+                .sum::<C::ScalarField>()
+        };
+        let mut round = 0;
+        let mut size = n;
+        while size > 1 {
+            let half = size / 2;
+            let (a_lo, a_hi) = a.split_at(half);
+            let (b_lo, b_hi) = b.split_at(half);
+            let (g_lo, g_hi) = g.split_at(half);
+            let g_lo_affine = C::Group::normalize_batch(g_lo);
+            let g_hi_affine = C::Group::normalize_batch(g_hi);
+
+            let l = C::Group::msm_unchecked(&g_hi_affine, a_lo)
+                + aux_generator * inner_product(a_lo, b_hi);
+            let r = C::Group::msm_unchecked(&g_lo_affine, a_hi)
+                + aux_generator * inner_product(a_hi, b_lo);
+            transcript.send_to_verifier(&format!("IPA:L_{}", round), &l.into_affine());
+            transcript.send_to_verifier(&format!("IPA:R_{}", round), &r.into_affine());
+
+            let u = transcript
+                .derive_challenge::<C::ScalarField>(&format!("IPA:round_challenge_{}", round));
+            let u_inv = u
+                .inverse()
+                .ok_or_else(|| anyhow!("IPA round challenge is zero"))?;
+            a = (0..half).map(|i| u * a[i] + u_inv * a[half + i]).collect();
+            b = (0..half).map(|i| u_inv * b[i] + u * b[half + i]).collect();
+            g = (0..half).map(|i| g[i] * u_inv + g[half + i] * u).collect();
+
+            round += 1;
+            size = half;
+        }
+        transcript.send_to_verifier("IPA:a_0", &a[0]);
+        Ok(())
+    }
+
+    /// Checks that `commitment` opens to `opening_pair` given the proof in `transcript`.
+    pub(crate) fn verify<C: AffineRepr, H: BarretenHasher>(
+        key: &IpaCommitmentKey<C>,
+        commitment: C,
+        opening_pair: &OpeningPair<C::ScalarField>,
+        num_coefficients: usize,
+        transcript: &mut Transcript<H>,
+    ) -> Result<bool> {
+        ensure!(
+            num_coefficients.is_power_of_two() && num_coefficients <= key.generators.len(),
+            "cannot open {} coefficients with {} generators",
+            num_coefficients,
+            key.generators.len()
+        );
+        let num_rounds = num_coefficients.trailing_zeros() as usize;
+
+        absorb_claim(transcript, &commitment, opening_pair);
+        let generator_challenge =
+            transcript.derive_challenge::<C::ScalarField>("IPA:generator_challenge");
+        let aux_generator = key.aux_generator * generator_challenge;
+
+        let mut folded_commitment =
+            commitment.into_group() + aux_generator * opening_pair.evaluation;
+        let mut round_challenges = Vec::with_capacity(num_rounds);
+        for round in 0..num_rounds {
+            let l: C = transcript.receive_from_prover(&format!("IPA:L_{}", round))?;
+            let r: C = transcript.receive_from_prover(&format!("IPA:R_{}", round))?;
+            let u = transcript
+                .derive_challenge::<C::ScalarField>(&format!("IPA:round_challenge_{}", round));
+            let u_sqr = u.square();
+            let u_sqr_inv = u_sqr
+                .inverse()
+                .ok_or_else(|| anyhow!("IPA round challenge is zero"))?;
+            folded_commitment += l * u_sqr + r * u_sqr_inv;
+            round_challenges.push(u);
+        }
+        let a_0: C::ScalarField = transcript.receive_from_prover("IPA:a_0")?;
+
+        let (s, b_0) = folding_scalars(&round_challenges, opening_pair.challenge);
+        let g_0 = C::Group::msm_unchecked(&key.generators[..num_coefficients], &s);
+        let expected = g_0 * a_0 + aux_generator * (a_0 * b_0);
+        Ok((folded_commitment - expected).is_zero())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ecc::curves::grumpkin::{Affine, Fr},
+        polynomials::{polynomial_arithmetic::evaluate, Polynomial},
+        transcript::Keccak256,
+    };
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Opens a random polynomial at a random point, and verifies the opening with `tamper` applied to the
+    /// verifier's view of the commitment, opening pair and proof.
+    fn open(tamper: impl FnOnce(&mut Affine, &mut OpeningPair<Fr>, &mut Vec<u8>)) -> Result<bool> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 16;
+        let key = IpaCommitmentKey::new(n);
+        let polynomial =
+            Polynomial::from_coefficients((0..n).map(|_| Fr::rand(&mut rng)).collect(), n);
+        let challenge = Fr::rand(&mut rng);
+        let mut opening_pair = OpeningPair {
+            challenge,
+            evaluation: evaluate(&polynomial.coefficients, &challenge, n),
+        };
+        let mut commitment = key.commit(&polynomial.coefficients);
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        Ipa::compute_opening_proof(
+            &key,
+            ProverOpeningClaim {
+                polynomial,
+                opening_pair,
+            },
+            &mut transcript,
+        )?;
+
+        let mut proof_data = transcript.export_transcript();
+        tamper(&mut commitment, &mut opening_pair, &mut proof_data);
+        let mut transcript = Transcript::<Keccak256>::new_streaming_from_proof(&proof_data);
+        let verified = Ipa::verify(&key, commitment, &opening_pair, n, &mut transcript)?;
+        Ok(verified && transcript.is_exhausted())
+    }
+
+    #[test]
+    fn folding_scalars_match_folding_the_vectors() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let challenges: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+        let x = Fr::rand(&mut rng);
+        let mut b: Vec<Fr> = (0..8).map(|i| x.pow([i as u64])).collect();
+        let mut unit_vectors: Vec<Vec<Fr>> = (0..8)
+            .map(|i| (0..8).map(|j| Fr::from((i == j) as u64)).collect())
+            .collect();
+        for u in &challenges {
+            let u_inv = u.inverse().unwrap();
+            let half = b.len() / 2;
+            b = (0..half).map(|i| u_inv * b[i] + *u * b[half + i]).collect();
+            for v in unit_vectors.iter_mut() {
+                *v = (0..half).map(|i| u_inv * v[i] + *u * v[half + i]).collect();
+            }
+        }
+        let (s, b_0) = folding_scalars(&challenges, x);
+        assert_eq!(b_0, b[0]);
+        for (s_i, v) in s.iter().zip(&unit_vectors) {
+            assert_eq!(*s_i, v[0]);
+        }
+    }
+
+    #[test]
+    fn honest_opening_verifies() {
+        assert!(open(|_, _, _| {}).unwrap());
+    }
+
+    #[test]
+    fn wrong_evaluation_is_rejected() {
+        assert!(!open(|_, opening_pair, _| opening_pair.evaluation += Fr::one()).unwrap());
+    }
+
+    #[test]
+    fn wrong_commitment_is_rejected() {
+        assert!(!open(|commitment, _, _| {
+            *commitment = (*commitment + Affine::generator()).into_affine()
+        })
+        .unwrap());
+    }
+
+    /// Moving δ·U from the commitment into the evaluation keeps `C + v·U` the same, which only the transcript
+    /// catches: U is drawn after absorbing both.
+    #[test]
+    fn shifting_the_commitment_into_the_evaluation_is_rejected() {
+        assert!(!open(|commitment, opening_pair, _| {
+            let mut transcript = Transcript::<Keccak256>::new_streaming();
+            absorb_claim(&mut transcript, commitment, opening_pair);
+            let generator_challenge: Fr = transcript.derive_challenge("IPA:generator_challenge");
+            let aux_generator = IpaCommitmentKey::new(16).aux_generator * generator_challenge;
+            let delta = Fr::from(3u64);
+            *commitment = (commitment.into_group() - aux_generator * delta).into_affine();
+            opening_pair.evaluation += delta;
+        })
+        .unwrap());
+    }
+
+    #[test]
+    fn wrong_challenge_is_rejected() {
+        assert!(!open(|_, opening_pair, _| opening_pair.challenge += Fr::one()).unwrap());
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        // flip a bit of the final coefficient
+        assert!(!open(|_, _, proof_data| {
+            let last = proof_data.len() - 1;
+            proof_data[last - 8] ^= 1;
+        })
+        .unwrap());
+    }
+
+    #[test]
+    fn opening_more_coefficients_than_generators_is_an_error() {
+        let key = IpaCommitmentKey::new(4);
+        for n in [3, 8] {
+            let claim = ProverOpeningClaim {
+                polynomial: Polynomial::from_coefficients(vec![Fr::one(); n], n),
+                opening_pair: OpeningPair {
+                    challenge: Fr::one(),
+                    evaluation: Fr::from(n as u64),
+                },
+            };
+            let mut transcript = Transcript::<Keccak256>::new_streaming();
+            assert!(Ipa::compute_opening_proof(&key, claim, &mut transcript).is_err());
+        }
+    }
+}
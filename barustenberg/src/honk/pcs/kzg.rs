@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+
+use crate::{
+    ecc::PippengerRuntimeState,
+    polynomials::polynomial_arithmetic::factor_root,
+    srs::reference_string::{ProverReferenceString, VerifierReferenceString},
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{OpeningClaim, ProverOpeningClaim};
+
+/// The prover's half of the SRS: `[τⁱ]₁` for every coefficient we may have to commit to.
+#[derive(Debug, Clone)]
+pub(crate) struct CommitmentKey<E: Pairing> {
+    monomial_points: Arc<Vec<E::G1Affine>>,
+}
+
+impl<E: Pairing> CommitmentKey<E> {
+    pub(crate) fn new(monomial_points: Arc<Vec<E::G1Affine>>) -> Self {
+        Self { monomial_points }
+    }
+
+    pub(crate) fn from_reference_string(crs: &mut dyn ProverReferenceString<E::G1Affine>) -> Self {
+        Self::new(crs.get_monomial_points())
+    }
+
+    /// `Σᵢ cᵢ·[τⁱ]₁` over the coefficients of `polynomial`.
+    pub(crate) fn commit(&self, polynomial: &[E::ScalarField]) -> E::G1Affine {
+        let n = polynomial.len();
+        assert!(
+            n <= self.monomial_points.len(),
+            "polynomial of size {} is too large for an SRS of size {}",
+            n,
+            self.monomial_points.len()
+        );
+        let mut scalars = polynomial.to_vec();
+        PippengerRuntimeState::<E::ScalarField, E::G1Affine>::new(n).pippenger_unsafe(
+            &mut scalars,
+            &self.monomial_points,
+            n,
+        )
+    }
+}
+
+/// The verifier's half of the SRS: the G1 generator, and the G2 generator along with `[τ]₂`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct VerifierKey<E: Pairing> {
+    pub(crate) g1: E::G1Affine,
+    pub(crate) g2: E::G2Affine,
+    pub(crate) tau_g2: E::G2Affine,
+}
+
+impl<E: Pairing> VerifierKey<E> {
+    pub(crate) fn from_reference_string(crs: &dyn VerifierReferenceString<E::G2Affine>) -> Self {
+        Self {
+            g1: E::G1Affine::generator(),
+            g2: E::G2Affine::generator(),
+            tau_g2: crs.get_g2x(),
+        }
+    }
+
+    /// Whether `e(p0, [1]₂) = e(p1, [τ]₂)`.
+    pub(crate) fn pairing_check(&self, p0: E::G1Affine, p1: E::G1Affine) -> bool {
+        E::pairing(p0, self.g2) == E::pairing(p1, self.tau_g2)
+    }
+}
+
+/// KZG opening of a single claim.
+#[derive(Debug)]
+pub(crate) struct Kzg;
+
+impl Kzg {
+    /// Commits to the quotient `W(X) = (p(X) - v) / (X - z)` and sends it.
+    pub(crate) fn compute_opening_proof<E: Pairing, H: BarretenHasher>(
+        commitment_key: &CommitmentKey<E>,
+        claim: ProverOpeningClaim<E::ScalarField>,
+        transcript: &mut Transcript<H>,
+    ) {
+        let mut quotient = claim.polynomial.coefficients;
+        quotient[0] -= claim.opening_pair.evaluation;
+        factor_root(&mut quotient, &claim.opening_pair.challenge);
+        transcript.send_to_verifier("KZG:W", &commitment_key.commit(&quotient));
+    }
+
+    /// Checks `e(C - v·[1]₁ + z·[W]₁, [1]₂) = e([W]₁, [τ]₂)`, which holds iff `C - v = (τ - z)·W` in the
+    /// exponent.
+    pub(crate) fn verify<E: Pairing, H: BarretenHasher>(
+        verifier_key: &VerifierKey<E>,
+        claim: &OpeningClaim<E>,
+        transcript: &mut Transcript<H>,
+    ) -> Result<bool> {
+        let quotient_commitment: E::G1Affine = transcript.receive_from_prover("KZG:W")?;
+        let p0 = claim.commitment.into_group() - verifier_key.g1 * claim.opening_pair.evaluation
+            + quotient_commitment * claim.opening_pair.challenge;
+        Ok(verifier_key.pairing_check(p0.into_affine(), quotient_commitment))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::{
+        honk::pcs::OpeningPair,
+        polynomials::{polynomial_arithmetic::evaluate, Polynomial},
+        transcript::Keccak256,
+    };
+    use ark_bn254::{Bn254, Fr};
+    use ark_ec::{pairing::Pairing, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    /// Keys for an SRS of `size` points with a known τ.
+    pub(crate) fn mock_keys<E: Pairing>(
+        size: usize,
+        rng: &mut impl RngCore,
+    ) -> (CommitmentKey<E>, VerifierKey<E>) {
+        let tau = E::ScalarField::rand(rng);
+        let g1 = E::G1Affine::generator();
+        let g2 = E::G2Affine::generator();
+        let mut powers = Vec::with_capacity(size);
+        let mut power = E::ScalarField::ONE;
+        for _ in 0..size {
+            powers.push(g1 * power);
+            power *= tau;
+        }
+        let monomial_points = E::G1::normalize_batch(&powers);
+        (
+            CommitmentKey::new(Arc::new(monomial_points)),
+            VerifierKey {
+                g1,
+                g2,
+                tau_g2: (g2 * tau).into_affine(),
+            },
+        )
+    }
+
+    fn open(claimed_evaluation_offset: Fr) -> bool {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 16;
+        let (commitment_key, verifier_key) = mock_keys::<Bn254>(n, &mut rng);
+        let polynomial =
+            Polynomial::from_coefficients((0..n).map(|_| Fr::rand(&mut rng)).collect(), n);
+        let challenge = Fr::rand(&mut rng);
+        let opening_pair = OpeningPair {
+            challenge,
+            evaluation: evaluate(&polynomial.coefficients, &challenge, n),
+        };
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        let commitment = commitment_key.commit(&polynomial.coefficients);
+        Kzg::compute_opening_proof(
+            &commitment_key,
+            ProverOpeningClaim {
+                polynomial,
+                opening_pair,
+            },
+            &mut transcript,
+        );
+
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let claim = OpeningClaim::<Bn254> {
+            commitment,
+            opening_pair: OpeningPair {
+                challenge,
+                evaluation: opening_pair.evaluation + claimed_evaluation_offset,
+            },
+        };
+        Kzg::verify(&verifier_key, &claim, &mut transcript).unwrap()
+    }
+
+    #[test]
+    fn correct_opening_verifies() {
+        assert!(open(Fr::from(0u64)));
+    }
+
+    #[test]
+    fn wrong_evaluation_is_rejected() {
+        assert!(!open(Fr::from(1u64)));
+    }
+}
@@ -0,0 +1,174 @@
+//! Univariate commitment schemes for Honk.
+//!
+//! Sumcheck leaves us with claims about multilinear polynomials at a point of the hypercube. Gemini turns them
+//! into claims about univariates at a handful of points, Shplonk batches those into a single claim, and KZG
+//! opens that one claim. Zeromorph is an alternative to the first two steps, which relates the multilinear claim
+//! to a single univariate identity directly.
+//!
+//! The inner product argument needs no trusted setup, and works over any curve; we use it over Grumpkin, whose
+//! scalar field is the base field of BN254.
+
+use anyhow::Result;
+use ark_ec::pairing::Pairing;
+use ark_ff::{Field, One, Zero};
+
+use crate::{
+    polynomials::Polynomial,
+    transcript::{BarretenHasher, Transcript},
+};
+
+use self::{
+    gemini::Gemini,
+    kzg::{CommitmentKey, Kzg, VerifierKey},
+    shplonk::Shplonk,
+};
+
+pub(crate) mod gemini;
+pub(crate) mod ipa;
+pub(crate) mod kzg;
+pub(crate) mod shplonk;
+pub(crate) mod zeromorph;
+
+/// A point and the value a polynomial is claimed to take there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OpeningPair<F: Field> {
+    pub(crate) challenge: F,
+    pub(crate) evaluation: F,
+}
+
+/// Prover side of an opening claim: the polynomial itself, in monomial form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProverOpeningClaim<F: Field> {
+    pub(crate) polynomial: Polynomial<F>,
+    pub(crate) opening_pair: OpeningPair<F>,
+}
+
+/// Verifier side of an opening claim: a commitment to the polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OpeningClaim<E: Pairing> {
+    pub(crate) commitment: E::G1Affine,
+    pub(crate) opening_pair: OpeningPair<E::ScalarField>,
+}
+
+/// Opens multilinear polynomials, given by their values on the hypercube, at a single point.
+///
+/// Some of the polynomials are opened shifted, that is as the multilinear polynomial with values `p[i + 1]`.
+/// Their first value has to be zero. The evaluations are assumed to be known to the verifier already, sumcheck
+/// having sent them.
+pub(crate) trait MultilinearCommitmentScheme<E: Pairing> {
+    fn prove<H: BarretenHasher>(
+        commitment_key: &CommitmentKey<E>,
+        point: &[E::ScalarField],
+        unshifted: &[&Polynomial<E::ScalarField>],
+        to_be_shifted: &[&Polynomial<E::ScalarField>],
+        transcript: &mut Transcript<H>,
+    );
+
+    /// Takes the commitment to each polynomial along with its evaluation, or the evaluation of its shift.
+    fn verify<H: BarretenHasher>(
+        verifier_key: &VerifierKey<E>,
+        point: &[E::ScalarField],
+        unshifted: &[(E::G1Affine, E::ScalarField)],
+        to_be_shifted: &[(E::G1Affine, E::ScalarField)],
+        transcript: &mut Transcript<H>,
+    ) -> Result<bool>;
+}
+
+/// Batches the polynomials with powers of ρ, the unshifted ones first: `(Σ ρⁱ·fᵢ, Σ ρ^(m + j)·gⱼ)` for `m`
+/// unshifted polynomials.
+pub(crate) fn batch_polynomials<F: Field>(
+    rho: F,
+    size: usize,
+    unshifted: &[&Polynomial<F>],
+    to_be_shifted: &[&Polynomial<F>],
+) -> (Polynomial<F>, Polynomial<F>) {
+    let mut rho_power = F::one();
+    let mut batch = |polynomials: &[&Polynomial<F>]| {
+        let mut batched = Polynomial::new(size);
+        for polynomial in polynomials {
+            for (b, p) in batched
+                .coefficients
+                .iter_mut()
+                .zip(&polynomial.coefficients)
+            {
+                *b += rho_power * p;
+            }
+            rho_power *= rho;
+        }
+        batched
+    };
+    let batched_unshifted = batch(unshifted);
+    let batched_to_be_shifted = batch(to_be_shifted);
+    (batched_unshifted, batched_to_be_shifted)
+}
+
+/// The verifier's side of [`batch_polynomials`]: the batched commitments, and the batched evaluation of
+/// `F + G↺` where G↺ is the shift of the batched G.
+pub(crate) fn batch_claims<E: Pairing>(
+    rho: E::ScalarField,
+    unshifted: &[(E::G1Affine, E::ScalarField)],
+    to_be_shifted: &[(E::G1Affine, E::ScalarField)],
+) -> (E::G1, E::G1, E::ScalarField) {
+    let mut rho_power = E::ScalarField::one();
+    let mut batched_evaluation = E::ScalarField::zero();
+    let mut batch = |claims: &[(E::G1Affine, E::ScalarField)]| {
+        let mut batched_commitment = E::G1::default();
+        for (commitment, evaluation) in claims {
+            batched_commitment += *commitment * rho_power;
+            batched_evaluation += rho_power * evaluation;
+            rho_power *= rho;
+        }
+        batched_commitment
+    };
+    let batched_unshifted = batch(unshifted);
+    let batched_to_be_shifted = batch(to_be_shifted);
+    (batched_unshifted, batched_to_be_shifted, batched_evaluation)
+}
+
+/// Gemini, then Shplonk, then KZG.
+#[derive(Debug)]
+pub(crate) struct GeminiShplonkKzg;
+
+impl<E: Pairing> MultilinearCommitmentScheme<E> for GeminiShplonkKzg {
+    fn prove<H: BarretenHasher>(
+        commitment_key: &CommitmentKey<E>,
+        point: &[E::ScalarField],
+        unshifted: &[&Polynomial<E::ScalarField>],
+        to_be_shifted: &[&Polynomial<E::ScalarField>],
+        transcript: &mut Transcript<H>,
+    ) {
+        let rho = transcript.derive_challenge::<E::ScalarField>("rho");
+        let (batched_unshifted, batched_to_be_shifted) =
+            batch_polynomials(rho, 1 << point.len(), unshifted, to_be_shifted);
+        let gemini_claims = Gemini::reduce_prove(
+            commitment_key,
+            point,
+            batched_unshifted,
+            batched_to_be_shifted,
+            transcript,
+        );
+        let shplonk_claim = Shplonk::reduce_prove(commitment_key, gemini_claims, transcript);
+        Kzg::compute_opening_proof(commitment_key, shplonk_claim, transcript);
+    }
+
+    fn verify<H: BarretenHasher>(
+        verifier_key: &VerifierKey<E>,
+        point: &[E::ScalarField],
+        unshifted: &[(E::G1Affine, E::ScalarField)],
+        to_be_shifted: &[(E::G1Affine, E::ScalarField)],
+        transcript: &mut Transcript<H>,
+    ) -> Result<bool> {
+        let rho = transcript.derive_challenge::<E::ScalarField>("rho");
+        let (batched_unshifted, batched_to_be_shifted, batched_evaluation) =
+            batch_claims::<E>(rho, unshifted, to_be_shifted);
+        let gemini_claims = Gemini::reduce_verify::<E, H>(
+            point,
+            batched_evaluation,
+            batched_unshifted,
+            batched_to_be_shifted,
+            transcript,
+        )?;
+        let shplonk_claim = Shplonk::reduce_verify(verifier_key.g1, &gemini_claims, transcript)?;
+        Kzg::verify(verifier_key, &shplonk_claim, transcript)
+    }
+}
@@ -0,0 +1,176 @@
+use anyhow::{anyhow, Result};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{Field, One, PrimeField, Zero};
+
+use crate::{
+    polynomials::{polynomial_arithmetic::factor_root, Polynomial},
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{kzg::CommitmentKey, OpeningClaim, OpeningPair, ProverOpeningClaim};
+
+/// Shplonk batches claims `pᵢ(xᵢ) = vᵢ` at distinct points into one claim at a fresh point z.
+///
+/// The prover commits to `Q = Σ νⁱ·(pᵢ - vᵢ)/(X - xᵢ)`, which is only a polynomial if every claim holds. Then
+/// `G = Q - Σ νⁱ·(pᵢ - vᵢ)/(z - xᵢ)` vanishes at z, and the verifier can compute `[G]` from `[Q]` and the
+/// `[pᵢ]` on its own.
+#[derive(Debug)]
+pub(crate) struct Shplonk;
+
+impl Shplonk {
+    /// Sends `[Q]` and returns the claim `G(z) = 0`.
+    pub(crate) fn reduce_prove<E: Pairing, H: BarretenHasher>(
+        commitment_key: &CommitmentKey<E>,
+        claims: Vec<ProverOpeningClaim<E::ScalarField>>,
+        transcript: &mut Transcript<H>,
+    ) -> ProverOpeningClaim<E::ScalarField>
+    where
+        E::ScalarField: PrimeField,
+    {
+        let nu = transcript.derive_challenge::<E::ScalarField>("Shplonk:nu");
+        let size = claims
+            .iter()
+            .map(|claim| claim.polynomial.size())
+            .max()
+            .unwrap_or(0);
+
+        let mut quotient = Polynomial::new(size);
+        let mut nu_power = E::ScalarField::one();
+        for claim in &claims {
+            let mut tmp = claim.polynomial.coefficients.clone();
+            tmp[0] -= claim.opening_pair.evaluation;
+            factor_root(&mut tmp, &claim.opening_pair.challenge);
+            for (q, t) in quotient.coefficients.iter_mut().zip(&tmp) {
+                *q += nu_power * t;
+            }
+            nu_power *= nu;
+        }
+        transcript.send_to_verifier("Shplonk:Q", &commitment_key.commit(&quotient.coefficients));
+
+        let z = transcript.derive_challenge::<E::ScalarField>("Shplonk:z");
+        let mut batched = quotient;
+        let mut nu_power = E::ScalarField::one();
+        for claim in &claims {
+            let scaling_factor = nu_power
+                * (z - claim.opening_pair.challenge)
+                    .inverse()
+                    .expect("shplonk challenge collides with an opening point");
+            batched[0] += scaling_factor * claim.opening_pair.evaluation;
+            for (g, p) in batched
+                .coefficients
+                .iter_mut()
+                .zip(&claim.polynomial.coefficients)
+            {
+                *g -= scaling_factor * p;
+            }
+            nu_power *= nu;
+        }
+
+        ProverOpeningClaim {
+            polynomial: batched,
+            opening_pair: OpeningPair {
+                challenge: z,
+                evaluation: E::ScalarField::zero(),
+            },
+        }
+    }
+
+    /// Reads `[Q]` and returns `[G]` with the claim `G(z) = 0`, where
+    /// `[G] = [Q] - Σ sᵢ·[pᵢ] + (Σ sᵢ·vᵢ)·[1]₁` and `sᵢ = νⁱ/(z - xᵢ)`.
+    pub(crate) fn reduce_verify<E: Pairing, H: BarretenHasher>(
+        g1: E::G1Affine,
+        claims: &[OpeningClaim<E>],
+        transcript: &mut Transcript<H>,
+    ) -> Result<OpeningClaim<E>>
+    where
+        E::ScalarField: PrimeField,
+    {
+        let nu = transcript.derive_challenge::<E::ScalarField>("Shplonk:nu");
+        let quotient_commitment: E::G1Affine = transcript.receive_from_prover("Shplonk:Q")?;
+        let z = transcript.derive_challenge::<E::ScalarField>("Shplonk:z");
+
+        let mut commitment = quotient_commitment.into_group();
+        let mut constant = E::ScalarField::zero();
+        let mut nu_power = E::ScalarField::one();
+        for claim in claims {
+            let scaling_factor = nu_power
+                * (z - claim.opening_pair.challenge)
+                    .inverse()
+                    .ok_or_else(|| anyhow!("shplonk challenge collides with an opening point"))?;
+            commitment -= claim.commitment * scaling_factor;
+            constant += scaling_factor * claim.opening_pair.evaluation;
+            nu_power *= nu;
+        }
+        commitment += g1 * constant;
+
+        Ok(OpeningClaim {
+            commitment: commitment.into_affine(),
+            opening_pair: OpeningPair {
+                challenge: z,
+                evaluation: E::ScalarField::zero(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        honk::pcs::kzg::{test::mock_keys, Kzg},
+        polynomials::polynomial_arithmetic::evaluate,
+        transcript::Keccak256,
+    };
+    use ark_bn254::{Bn254, Fr};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Opens two polynomials of different sizes through Shplonk and KZG, with the second claim's value offset
+    /// by `offset` on the verifier's side.
+    fn open_batched(offset: Fr) -> bool {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (commitment_key, verifier_key) = mock_keys::<Bn254>(16, &mut rng);
+        let mut prover_claims = Vec::new();
+        let mut verifier_claims = Vec::new();
+        for size in [16, 8] {
+            let polynomial = Polynomial::from_coefficients(
+                (0..size).map(|_| Fr::rand(&mut rng)).collect(),
+                size,
+            );
+            let challenge = Fr::rand(&mut rng);
+            let opening_pair = OpeningPair {
+                challenge,
+                evaluation: evaluate(&polynomial.coefficients, &challenge, size),
+            };
+            verifier_claims.push(OpeningClaim::<Bn254> {
+                commitment: commitment_key.commit(&polynomial.coefficients),
+                opening_pair,
+            });
+            prover_claims.push(ProverOpeningClaim {
+                polynomial,
+                opening_pair,
+            });
+        }
+        verifier_claims[1].opening_pair.evaluation += offset;
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        let claim = Shplonk::reduce_prove(&commitment_key, prover_claims, &mut transcript);
+        Kzg::compute_opening_proof(&commitment_key, claim, &mut transcript);
+
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let claim =
+            Shplonk::reduce_verify(verifier_key.g1, &verifier_claims, &mut transcript).unwrap();
+        Kzg::verify(&verifier_key, &claim, &mut transcript).unwrap()
+    }
+
+    #[test]
+    fn batched_claims_verify() {
+        assert!(open_batched(Fr::from(0u64)));
+    }
+
+    #[test]
+    fn one_wrong_claim_is_rejected() {
+        assert!(!open_batched(Fr::from(1u64)));
+    }
+}
@@ -0,0 +1,354 @@
+use anyhow::{anyhow, Result};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{Field, One, Zero};
+
+use crate::{
+    polynomials::Polynomial,
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{
+    batch_claims, batch_polynomials,
+    kzg::{CommitmentKey, Kzg, VerifierKey},
+    MultilinearCommitmentScheme, OpeningClaim, OpeningPair, ProverOpeningClaim,
+};
+
+/// Zeromorph opens a multilinear polynomial through its values read as the coefficients of a univariate.
+///
+/// `f(X) - v = Σₖ (Xₖ - uₖ)·qₖ(X₀, ..., X_{k-1})` holds for the multilinear quotients `qₖ` iff `f(u) = v`, and
+/// maps to the univariate identity
+///
+/// ```text
+/// f(X) - v·Φ_d(X) = Σₖ (X^(2ᵏ)·Φ_{d-k-1}(X^(2^(k+1))) - uₖ·Φ_{d-k}(X^(2ᵏ)))·qₖ(X)
+/// ```
+///
+/// where `Φₘ(X) = Σ_{i < 2^m} Xⁱ` and the polynomials are read as univariates. The prover commits to the
+/// quotients, and to `q̂ = Σₖ yᵏ·X^(N - 2ᵏ)·qₖ` to show their degrees are small enough. At a random x both
+/// `ζ = q̂ - Σₖ yᵏ·x^(N - 2ᵏ)·qₖ` and the difference `Z` of the two sides above vanish, which one KZG opening of
+/// `ζ + z·Z` at x shows. The shifted polynomials are opened as `F + G/X`, as in
+/// [`Gemini`](super::gemini::Gemini).
+#[derive(Debug)]
+pub(crate) struct Zeromorph;
+
+/// `Φₘ(x) = (x^(2^m) - 1)/(x - 1)`
+fn phi<F: Field>(x: F, m: usize) -> F {
+    let mut x_pow = x;
+    for _ in 0..m {
+        x_pow.square_in_place();
+    }
+    if x.is_one() {
+        F::from(1u64 << m)
+    } else {
+        (x_pow - F::one()) * (x - F::one()).inverse().unwrap()
+    }
+}
+
+/// The multilinear quotients `q₀, ..., q_{d-1}`, `qₖ` with `2ᵏ` values, and the evaluation `f(u)`.
+fn compute_multilinear_quotients<F: Field>(values: &[F], point: &[F]) -> (Vec<Vec<F>>, F) {
+    let num_variables = point.len();
+    let mut quotients = vec![vec![]; num_variables];
+    let mut remainder = values.to_vec();
+    for k in (0..num_variables).rev() {
+        let half = 1 << k;
+        let (lo, hi) = remainder.split_at(half);
+        let quotient: Vec<F> = lo.iter().zip(hi).map(|(lo, hi)| *hi - lo).collect();
+        remainder = lo
+            .iter()
+            .zip(&quotient)
+            .map(|(lo, q)| *lo + point[k] * q)
+            .collect();
+        quotients[k] = quotient;
+    }
+    (quotients, remainder[0])
+}
+
+/// `x^(2ᵏ)·Φ_{d-k-1}(x^(2^(k+1))) - uₖ·Φ_{d-k}(x^(2ᵏ))` for every k: the univariate factor of `qₖ` at x.
+fn quotient_scalars<F: Field>(x: F, point: &[F]) -> Vec<F> {
+    let num_variables = point.len();
+    let mut x_pow = x;
+    point
+        .iter()
+        .enumerate()
+        .map(|(k, u)| {
+            let x_pow_sqr = x_pow.square();
+            let scalar =
+                x_pow * phi(x_pow_sqr, num_variables - k - 1) - *u * phi(x_pow, num_variables - k);
+            x_pow = x_pow_sqr;
+            scalar
+        })
+        .collect()
+}
+
+impl<E: Pairing> MultilinearCommitmentScheme<E> for Zeromorph {
+    fn prove<H: BarretenHasher>(
+        commitment_key: &CommitmentKey<E>,
+        point: &[E::ScalarField],
+        unshifted: &[&Polynomial<E::ScalarField>],
+        to_be_shifted: &[&Polynomial<E::ScalarField>],
+        transcript: &mut Transcript<H>,
+    ) {
+        let num_variables = point.len();
+        let n = 1 << num_variables;
+        let rho = transcript.derive_challenge::<E::ScalarField>("rho");
+        let (batched_unshifted, batched_to_be_shifted) =
+            batch_polynomials(rho, n, unshifted, to_be_shifted);
+
+        // F + G↺, whose value at u is the batched evaluation
+        let values: Vec<E::ScalarField> = (0..n)
+            .map(|i| {
+                batched_unshifted[i]
+                    + batched_to_be_shifted
+                        .coefficients
+                        .get(i + 1)
+                        .copied()
+                        .unwrap_or_else(E::ScalarField::zero)
+            })
+            .collect();
+        let (quotients, evaluation) = compute_multilinear_quotients(&values, point);
+        for (k, quotient) in quotients.iter().enumerate() {
+            transcript.send_to_verifier(&format!("ZM:C_q_{}", k), &commitment_key.commit(quotient));
+        }
+
+        // q̂ = Σₖ yᵏ·X^(N - 2ᵏ)·qₖ
+        let y = transcript.derive_challenge::<E::ScalarField>("ZM:y");
+        let mut batched_quotient = Polynomial::new(n);
+        let mut y_power = E::ScalarField::one();
+        for quotient in &quotients {
+            let offset = n - quotient.len();
+            for (b, q) in batched_quotient.coefficients[offset..]
+                .iter_mut()
+                .zip(quotient)
+            {
+                *b += y_power * q;
+            }
+            y_power *= y;
+        }
+        transcript.send_to_verifier(
+            "ZM:C_q",
+            &commitment_key.commit(&batched_quotient.coefficients),
+        );
+
+        let x = transcript.derive_challenge::<E::ScalarField>("ZM:x");
+        let z = transcript.derive_challenge::<E::ScalarField>("ZM:z");
+
+        // ζ + z·Z, where ζ = q̂ - Σₖ yᵏ·x^(N - 2ᵏ)·qₖ and Z = F + G/x - v·Φ_d(x) - Σₖ scalarₖ·qₖ
+        let scalars = quotient_scalars(x, point);
+        let x_inv = x.inverse().unwrap();
+        let mut opened = batched_quotient;
+        opened[0] -= z * evaluation * phi(x, num_variables);
+        for i in 0..n {
+            opened[i] += z * (batched_unshifted[i] + batched_to_be_shifted[i] * x_inv);
+        }
+        let mut y_power = E::ScalarField::one();
+        for (quotient, scalar) in quotients.iter().zip(&scalars) {
+            let scalar = y_power * x.pow([(n - quotient.len()) as u64]) + z * scalar;
+            for (o, q) in opened.coefficients.iter_mut().zip(quotient) {
+                *o -= scalar * q;
+            }
+            y_power *= y;
+        }
+
+        Kzg::compute_opening_proof(
+            commitment_key,
+            ProverOpeningClaim {
+                polynomial: opened,
+                opening_pair: OpeningPair {
+                    challenge: x,
+                    evaluation: E::ScalarField::zero(),
+                },
+            },
+            transcript,
+        );
+    }
+
+    fn verify<H: BarretenHasher>(
+        verifier_key: &VerifierKey<E>,
+        point: &[E::ScalarField],
+        unshifted: &[(E::G1Affine, E::ScalarField)],
+        to_be_shifted: &[(E::G1Affine, E::ScalarField)],
+        transcript: &mut Transcript<H>,
+    ) -> Result<bool> {
+        let num_variables = point.len();
+        let n = 1u64 << num_variables;
+        let rho = transcript.derive_challenge::<E::ScalarField>("rho");
+        let (batched_unshifted, batched_to_be_shifted, batched_evaluation) =
+            batch_claims::<E>(rho, unshifted, to_be_shifted);
+
+        let mut quotient_commitments = Vec::with_capacity(num_variables);
+        for k in 0..num_variables {
+            quotient_commitments
+                .push(transcript.receive_from_prover::<E::G1Affine>(&format!("ZM:C_q_{}", k))?);
+        }
+        let y = transcript.derive_challenge::<E::ScalarField>("ZM:y");
+        let batched_quotient_commitment: E::G1Affine = transcript.receive_from_prover("ZM:C_q")?;
+        let x = transcript.derive_challenge::<E::ScalarField>("ZM:x");
+        let z = transcript.derive_challenge::<E::ScalarField>("ZM:z");
+
+        let x_inv = x
+            .inverse()
+            .ok_or_else(|| anyhow!("zeromorph challenge is zero"))?;
+        let scalars = quotient_scalars(x, point);
+        let mut commitment = batched_quotient_commitment.into_group()
+            + (batched_unshifted + batched_to_be_shifted * x_inv) * z
+            - verifier_key.g1 * (z * batched_evaluation * phi(x, num_variables));
+        let mut y_power = E::ScalarField::one();
+        for (k, (quotient_commitment, scalar)) in
+            quotient_commitments.iter().zip(&scalars).enumerate()
+        {
+            let degree_shift = n - (1u64 << k);
+            commitment -= *quotient_commitment * (y_power * x.pow([degree_shift]) + z * scalar);
+            y_power *= y;
+        }
+
+        Kzg::verify(
+            verifier_key,
+            &OpeningClaim {
+                commitment: commitment.into_affine(),
+                opening_pair: OpeningPair {
+                    challenge: x,
+                    evaluation: E::ScalarField::zero(),
+                },
+            },
+            transcript,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        honk::{
+            composer::StandardHonkComposer,
+            pcs::{kzg::test::mock_keys, GeminiShplonkKzg},
+        },
+        proof_system::{
+            arithmetization::gate_data::MulTriple, circuit_constructor::StandardCircuitConstructor,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::{Bn254, Fr};
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn quotients_decompose_the_polynomial() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let num_variables = 3;
+        let values: Vec<Fr> = (0..1 << num_variables)
+            .map(|_| Fr::rand(&mut rng))
+            .collect();
+        let point: Vec<Fr> = (0..num_variables).map(|_| Fr::rand(&mut rng)).collect();
+        let (quotients, evaluation) = compute_multilinear_quotients(&values, &point);
+        let polynomial = Polynomial::from_coefficients(values.clone(), values.len());
+        assert_eq!(evaluation, polynomial.evaluate_mle(&point, false));
+
+        // f(X) - v = Σₖ (Xₖ - uₖ)·qₖ on every point of the hypercube
+        for (i, value) in values.iter().enumerate() {
+            let sum: Fr = quotients
+                .iter()
+                .enumerate()
+                .map(|(k, q)| (Fr::from(((i >> k) & 1) as u64) - point[k]) * q[i & ((1 << k) - 1)])
+                .sum();
+            assert_eq!(*value - evaluation, sum);
+        }
+    }
+
+    /// Opens two unshifted polynomials and one shifted one, with the verifier's claimed evaluation of the
+    /// `tampered` one (0 or 1 unshifted, 2 shifted) off by one.
+    fn open(tampered: Option<usize>) -> bool {
+        let mut rng = StdRng::seed_from_u64(1);
+        let num_variables = 4;
+        let n = 1 << num_variables;
+        let (commitment_key, verifier_key) = mock_keys::<Bn254>(n, &mut rng);
+        let random_polynomial = |rng: &mut StdRng| {
+            Polynomial::from_coefficients((0..n).map(|_| Fr::rand(rng)).collect(), n)
+        };
+        let f_0 = random_polynomial(&mut rng);
+        let f_1 = random_polynomial(&mut rng);
+        let mut g = random_polynomial(&mut rng);
+        g[0] = Fr::zero();
+        let point: Vec<Fr> = (0..num_variables).map(|_| Fr::rand(&mut rng)).collect();
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        Zeromorph::prove(
+            &commitment_key,
+            &point,
+            &[&f_0, &f_1],
+            &[&g],
+            &mut transcript,
+        );
+
+        let mut claims = [(&f_0, false), (&f_1, false), (&g, true)].map(|(p, shift)| {
+            (
+                commitment_key.commit(&p.coefficients),
+                p.evaluate_mle(&point, shift),
+            )
+        });
+        if let Some(i) = tampered {
+            claims[i].1 += Fr::one();
+        }
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let verified = Zeromorph::verify(
+            &verifier_key,
+            &point,
+            &claims[..2],
+            &claims[2..],
+            &mut transcript,
+        )
+        .unwrap();
+        assert!(transcript.is_exhausted());
+        verified
+    }
+
+    #[test]
+    fn correct_evaluations_verify() {
+        assert!(open(None));
+    }
+
+    #[test]
+    fn mismatched_evaluations_are_rejected() {
+        for i in 0..3 {
+            assert!(!open(Some(i)));
+        }
+    }
+
+    #[test]
+    fn honk_proves_with_zeromorph() {
+        let circuit = || {
+            let mut circuit = StandardCircuitConstructor::<Fr>::new();
+            let a = circuit.add_public_variable(Fr::from(6u64));
+            let b = circuit.add_variable(Fr::from(7u64));
+            let c = circuit.add_variable(Fr::from(42u64));
+            circuit.create_mul_gate(&MulTriple {
+                a,
+                b,
+                c,
+                mul_scaling: Fr::one(),
+                c_scaling: -Fr::one(),
+                const_scaling: Fr::zero(),
+            });
+            circuit
+        };
+        let keys = || mock_keys::<Bn254>(4, &mut StdRng::seed_from_u64(2));
+
+        let (commitment_key, verifier_key) = keys();
+        let mut composer = StandardHonkComposer::<Bn254, Keccak256, Zeromorph>::new(
+            circuit(),
+            commitment_key,
+            verifier_key,
+        );
+        let proof = composer.create_prover().construct_proof().unwrap();
+        assert!(composer.create_verifier().verify_proof(&proof));
+
+        // the same proof means nothing to a verifier that expects Gemini
+        let (commitment_key, verifier_key) = keys();
+        let mut composer = StandardHonkComposer::<Bn254, Keccak256, GeminiShplonkKzg>::new(
+            circuit(),
+            commitment_key,
+            verifier_key,
+        );
+        assert!(!composer.create_verifier().verify_proof(&proof));
+    }
+}
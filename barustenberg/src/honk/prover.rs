@@ -0,0 +1,136 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use anyhow::Result;
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+
+use crate::{
+    plonk::proof_system::{public_inputs::compute_public_input_delta, types::proof::Proof},
+    polynomials::Polynomial,
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{
+    flavor::{AllEntities, ProvingKey},
+    grand_product::{compute_permutation_grand_product, subgroup_generator},
+    pcs::{kzg::CommitmentKey, GeminiShplonkKzg, MultilinearCommitmentScheme},
+    relations::RelationParameters,
+    sumcheck::SumcheckProver,
+};
+
+/// Standard Honk prover, opening the sumcheck evaluations with `P`.
+pub(crate) struct StandardProver<
+    E: Pairing,
+    H: BarretenHasher,
+    P: MultilinearCommitmentScheme<E> = GeminiShplonkKzg,
+> {
+    proving_key: Arc<ProvingKey<E::ScalarField>>,
+    commitment_key: CommitmentKey<E>,
+    pub(crate) transcript: Transcript<H>,
+    /// the proving key's polynomials, with the grand product filled in
+    polynomials: AllEntities<Polynomial<E::ScalarField>>,
+    relation_parameters: RelationParameters<E::ScalarField>,
+    phantom: PhantomData<P>,
+}
+
+impl<E: Pairing, H: BarretenHasher, P: MultilinearCommitmentScheme<E>> StandardProver<E, H, P>
+where
+    E::ScalarField: PrimeField,
+{
+    pub(crate) fn new(
+        proving_key: Arc<ProvingKey<E::ScalarField>>,
+        commitment_key: CommitmentKey<E>,
+    ) -> Self {
+        let polynomials = proving_key.polynomials.clone();
+        Self {
+            proving_key,
+            commitment_key,
+            transcript: Transcript::new_streaming(),
+            polynomials,
+            relation_parameters: RelationParameters::default(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Circuit size and public inputs, then the wire commitments.
+    fn execute_wire_commitments_round(&mut self) {
+        let circuit_size = self.proving_key.circuit_size as u64;
+        let num_public_inputs = self.proving_key.public_inputs.len() as u64;
+        self.transcript
+            .send_to_verifier("circuit_size", &circuit_size);
+        self.transcript
+            .send_to_verifier("public_input_size", &num_public_inputs);
+        for (i, public_input) in self.proving_key.public_inputs.iter().enumerate() {
+            self.transcript
+                .send_to_verifier(&format!("public_input_{}", i), public_input);
+        }
+
+        let wires = [
+            &self.polynomials.w_l,
+            &self.polynomials.w_r,
+            &self.polynomials.w_o,
+        ];
+        for (i, wire) in wires.into_iter().enumerate() {
+            let commitment = self.commitment_key.commit(&wire.coefficients);
+            self.transcript
+                .send_to_verifier(&format!("W_{}", i + 1), &commitment);
+        }
+    }
+
+    /// β and γ, then the commitment to the permutation grand product.
+    fn execute_grand_product_computation_round(&mut self) -> Result<()> {
+        let beta = self.transcript.derive_challenge::<E::ScalarField>("beta");
+        let gamma = self.transcript.derive_challenge::<E::ScalarField>("gamma");
+        self.relation_parameters = RelationParameters {
+            beta,
+            gamma,
+            public_input_delta: compute_public_input_delta(
+                &self.proving_key.public_inputs,
+                beta,
+                gamma,
+                subgroup_generator::<E::ScalarField>(self.proving_key.circuit_size)?,
+            )?,
+        };
+
+        let (z_perm, z_perm_shift) = compute_permutation_grand_product(
+            self.proving_key.circuit_size,
+            &self.polynomials,
+            &self.relation_parameters,
+        )?;
+        self.polynomials.z_perm = z_perm;
+        self.polynomials.z_perm_shift = z_perm_shift;
+        let commitment = self
+            .commitment_key
+            .commit(&self.polynomials.z_perm.coefficients);
+        self.transcript.send_to_verifier("Z_PERM", &commitment);
+        Ok(())
+    }
+
+    /// Sumcheck, then the opening of the evaluations it leaves us with.
+    fn execute_sumcheck_and_opening_rounds(&mut self) {
+        let sumcheck_output =
+            SumcheckProver::new(self.proving_key.log_circuit_size, &self.polynomials)
+                .prove(&mut self.transcript, &self.relation_parameters);
+
+        P::prove(
+            &self.commitment_key,
+            &sumcheck_output.challenge,
+            &self.polynomials.unshifted(),
+            &self.polynomials.to_be_shifted(),
+            &mut self.transcript,
+        );
+    }
+
+    pub(crate) fn construct_proof(&mut self) -> Result<Proof> {
+        self.execute_wire_commitments_round();
+        self.execute_grand_product_computation_round()?;
+        self.execute_sumcheck_and_opening_rounds();
+        Ok(self.export_proof())
+    }
+
+    pub(crate) fn export_proof(&self) -> Proof {
+        Proof {
+            proof_data: self.transcript.export_transcript(),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use ark_ff::Field;
+
+use super::flavor::AllEntities;
+
+/// Challenges and public data the relations depend on beyond the polynomials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RelationParameters<F: Field> {
+    pub(crate) beta: F,
+    pub(crate) gamma: F,
+    pub(crate) public_input_delta: F,
+}
+
+impl<F: Field> Default for RelationParameters<F> {
+    fn default() -> Self {
+        Self {
+            beta: F::zero(),
+            gamma: F::zero(),
+            public_input_delta: F::one(),
+        }
+    }
+}
+
+/// Number of subrelations across all relations, each of which gets its own power of α.
+pub(crate) const NUM_SUBRELATIONS: usize = 3;
+
+/// A polynomial identity that has to hold at every point of the boolean hypercube.
+///
+/// Relations are evaluated on values of the entities at a single point. Sumcheck calls them on each point of
+/// an edge's extension, and the verifier calls them once, on the purported evaluations.
+pub(crate) trait Relation<F: Field> {
+    const NUM_SUBRELATIONS: usize;
+
+    /// Writes the value of each subrelation into `evals`, which has `NUM_SUBRELATIONS` entries.
+    fn evaluate(entities: &AllEntities<F>, parameters: &RelationParameters<F>, evals: &mut [F]);
+}
+
+/// The Standard gate: `q_m·w_l·w_r + q_l·w_l + q_r·w_r + q_o·w_o + q_c`.
+#[derive(Debug)]
+pub(crate) struct ArithmeticRelation;
+
+impl<F: Field> Relation<F> for ArithmeticRelation {
+    const NUM_SUBRELATIONS: usize = 1;
+
+    fn evaluate(e: &AllEntities<F>, _parameters: &RelationParameters<F>, evals: &mut [F]) {
+        evals[0] = e.q_m * e.w_l * e.w_r + e.q_l * e.w_l + e.q_r * e.w_r + e.q_o * e.w_o + e.q_c;
+    }
+}
+
+/// The copy constraint grand product. Row by row,
+///
+/// ```text
+/// (z_perm + L_first)·∏ᵢ(wᵢ + β·idᵢ + γ) = (z_perm_shift + L_last·Δ)·∏ᵢ(wᵢ + β·σᵢ + γ)
+/// ```
+///
+/// where Δ is the public input delta, and the grand product has to end at zero: `L_last·z_perm_shift = 0`.
+/// Together these say that `z_perm` starts at 0, steps from 1 to the product of the ratios, and that
+/// the product over the whole trace is Δ.
+#[derive(Debug)]
+pub(crate) struct PermutationRelation;
+
+impl<F: Field> Relation<F> for PermutationRelation {
+    const NUM_SUBRELATIONS: usize = 2;
+
+    fn evaluate(e: &AllEntities<F>, parameters: &RelationParameters<F>, evals: &mut [F]) {
+        let RelationParameters {
+            beta,
+            gamma,
+            public_input_delta,
+        } = *parameters;
+        let numerator = (e.w_l + beta * e.id_1 + gamma)
+            * (e.w_r + beta * e.id_2 + gamma)
+            * (e.w_o + beta * e.id_3 + gamma);
+        let denominator = (e.w_l + beta * e.sigma_1 + gamma)
+            * (e.w_r + beta * e.sigma_2 + gamma)
+            * (e.w_o + beta * e.sigma_3 + gamma);
+        evals[0] = (e.z_perm + e.lagrange_first) * numerator
+            - (e.z_perm_shift + e.lagrange_last * public_input_delta) * denominator;
+        evals[1] = e.lagrange_last * e.z_perm_shift;
+    }
+}
+
+/// Every subrelation, in the order their α powers are assigned.
+pub(crate) fn evaluate_subrelations<F: Field>(
+    entities: &AllEntities<F>,
+    parameters: &RelationParameters<F>,
+) -> [F; NUM_SUBRELATIONS] {
+    let mut evals = [F::zero(); NUM_SUBRELATIONS];
+    let (arithmetic, permutation) =
+        evals.split_at_mut(<ArithmeticRelation as Relation<F>>::NUM_SUBRELATIONS);
+    ArithmeticRelation::evaluate(entities, parameters, arithmetic);
+    PermutationRelation::evaluate(entities, parameters, permutation);
+    evals
+}
+
+/// `Σᵢ αⁱ·Rᵢ` over the subrelations.
+pub(crate) fn evaluate_batched<F: Field>(
+    entities: &AllEntities<F>,
+    parameters: &RelationParameters<F>,
+    alpha: F,
+) -> F {
+    evaluate_subrelations(entities, parameters)
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, eval| acc * alpha + eval)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    /// A multiplication gate 3·4 = 12 in a row that is its own permutation cycle.
+    fn mul_row() -> AllEntities<Fr> {
+        AllEntities {
+            q_m: Fr::one(),
+            q_o: -Fr::one(),
+            w_l: Fr::from(3u64),
+            w_r: Fr::from(4u64),
+            w_o: Fr::from(12u64),
+            id_1: Fr::from(5u64),
+            id_2: Fr::from(13u64),
+            id_3: Fr::from(21u64),
+            sigma_1: Fr::from(5u64),
+            sigma_2: Fr::from(13u64),
+            sigma_3: Fr::from(21u64),
+            z_perm: Fr::from(7u64),
+            z_perm_shift: Fr::from(7u64),
+            ..Default::default()
+        }
+    }
+
+    fn parameters() -> RelationParameters<Fr> {
+        RelationParameters {
+            beta: Fr::from(11u64),
+            gamma: Fr::from(17u64),
+            public_input_delta: Fr::one(),
+        }
+    }
+
+    #[test]
+    fn satisfied_rows_vanish() {
+        let row = mul_row();
+        assert_eq!(
+            evaluate_subrelations(&row, &parameters()),
+            [Fr::zero(); NUM_SUBRELATIONS]
+        );
+    }
+
+    #[test]
+    fn each_subrelation_gets_its_own_alpha_power() {
+        let alpha = Fr::from(1000u64);
+
+        let mut row = mul_row();
+        row.w_o = Fr::from(13u64);
+        let arithmetic = evaluate_subrelations(&row, &parameters())[0];
+        assert_eq!(arithmetic, -Fr::one());
+
+        let mut row = mul_row();
+        row.lagrange_last = Fr::one();
+        row.z_perm_shift = Fr::from(2u64);
+        let evals = evaluate_subrelations(&row, &parameters());
+        assert_eq!(evals[2], Fr::from(2u64));
+        assert_eq!(
+            evaluate_batched(&row, &parameters(), alpha),
+            evals[0] + alpha * evals[1] + alpha * alpha * evals[2]
+        );
+    }
+}
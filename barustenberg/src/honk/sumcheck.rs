@@ -0,0 +1,351 @@
+use anyhow::{ensure, Result};
+use ark_ff::{Field, PrimeField};
+
+use crate::{
+    common::{max_threads::compute_num_threads, thread::parallel_map},
+    polynomials::{
+        polynomial_arithmetic::partially_evaluate_mle, univariate::Univariate, Polynomial,
+    },
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{
+    flavor::{AllEntities, BATCHED_RELATION_PARTIAL_LENGTH, MAX_RELATION_LENGTH, NUM_ALL_ENTITIES},
+    relations::{evaluate_batched, RelationParameters},
+};
+
+/// The batching polynomial `pow_ζ(X) = ∏ₗ (1 + (ζ^(2ˡ) - 1)·Xₗ)`, which equals `ζ^i` on the hypercube point with
+/// index `i`.
+///
+/// Sumcheck proves `Σᵢ pow_ζ(i)·F(i) = 0` rather than `Σᵢ F(i) = 0`: the batched relation F could cancel out
+/// over the hypercube without vanishing everywhere, but for a random ζ that only happens with negligible
+/// probability. Variables are fixed lowest first, and the factors for the fixed ones are collected in
+/// `partial_evaluation_constant`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PowUnivariate<F: Field> {
+    /// ζ^(2ˡ) for the current variable l
+    pub(crate) zeta_pow: F,
+    /// ζ^(2ˡ⁺¹), the step between consecutive edges of the current round
+    pub(crate) zeta_pow_sqr: F,
+    pub(crate) partial_evaluation_constant: F,
+}
+
+impl<F: Field> PowUnivariate<F> {
+    pub(crate) fn new(zeta: F) -> Self {
+        Self {
+            zeta_pow: zeta,
+            zeta_pow_sqr: zeta.square(),
+            partial_evaluation_constant: F::one(),
+        }
+    }
+
+    /// The factor of the current variable, `1 + (ζ^(2ˡ) - 1)·u`.
+    pub(crate) fn univariate_eval(&self, u: F) -> F {
+        F::one() + (self.zeta_pow - F::one()) * u
+    }
+
+    pub(crate) fn partially_evaluate(&mut self, u: F) {
+        self.partial_evaluation_constant *= self.univariate_eval(u);
+        self.zeta_pow = self.zeta_pow_sqr;
+        self.zeta_pow_sqr.square_in_place();
+    }
+}
+
+/// The point sumcheck reduced the claim to, and the prover's claimed evaluations of every entity there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SumcheckOutput<F: Field> {
+    pub(crate) challenge: Vec<F>,
+    pub(crate) purported_evaluations: AllEntities<F>,
+}
+
+/// Sum of `pow_ζ·F` over the remaining hypercube as a univariate in the current variable.
+fn compute_univariate<F: Field>(
+    polynomials: &AllEntities<&[F]>,
+    round_size: usize,
+    parameters: &RelationParameters<F>,
+    alpha: F,
+    pow: &PowUnivariate<F>,
+) -> Univariate<F, BATCHED_RELATION_PARTIAL_LENGTH> {
+    let num_edges = round_size / 2;
+    let num_threads = compute_num_threads().min(num_edges);
+    let edges_per_thread = num_edges / num_threads;
+
+    let thread_accumulators = parallel_map(num_threads, |thread| {
+        let start = thread * edges_per_thread;
+        let end = if thread == num_threads - 1 {
+            num_edges
+        } else {
+            start + edges_per_thread
+        };
+        let mut accumulator = Univariate::<F, MAX_RELATION_LENGTH>::default();
+        // the remaining variables of edge j contribute (ζ^(2ˡ⁺¹))^j to pow_ζ
+        let mut weight = pow.zeta_pow_sqr.pow([start as u64]);
+        for j in start..end {
+            let edges = polynomials
+                .map(|p| Univariate::<F, MAX_RELATION_LENGTH>::from_edge(p[2 * j], p[2 * j + 1]));
+            for k in 0..MAX_RELATION_LENGTH {
+                let point = edges.map(|edge| edge.value_at(k));
+                accumulator.evaluations[k] += weight * evaluate_batched(&point, parameters, alpha);
+            }
+            weight *= pow.zeta_pow_sqr;
+        }
+        accumulator
+    });
+
+    let mut accumulator = Univariate::<F, MAX_RELATION_LENGTH>::default();
+    for thread_accumulator in thread_accumulators {
+        accumulator += thread_accumulator;
+    }
+    let pow_factor =
+        Univariate::<F, BATCHED_RELATION_PARTIAL_LENGTH>::from_edge(F::one(), pow.zeta_pow);
+    let mut result = accumulator.extend_to::<BATCHED_RELATION_PARTIAL_LENGTH>() * pow_factor;
+    result *= pow.partial_evaluation_constant;
+    result
+}
+
+/// Sumcheck prover over the hypercube of dimension `multivariate_d`.
+#[derive(Debug)]
+pub(crate) struct SumcheckProver<'a, F: Field> {
+    pub(crate) multivariate_d: usize,
+    polynomials: &'a AllEntities<Polynomial<F>>,
+}
+
+impl<'a, F: PrimeField> SumcheckProver<'a, F> {
+    pub(crate) fn new(multivariate_d: usize, polynomials: &'a AllEntities<Polynomial<F>>) -> Self {
+        Self {
+            multivariate_d,
+            polynomials,
+        }
+    }
+
+    /// Runs every round, then sends the evaluations of the entities at the challenge point.
+    pub(crate) fn prove<H: BarretenHasher>(
+        &self,
+        transcript: &mut Transcript<H>,
+        parameters: &RelationParameters<F>,
+    ) -> SumcheckOutput<F> {
+        let alpha = transcript.derive_challenge::<F>("Sumcheck:alpha");
+        let zeta = transcript.derive_challenge::<F>("Sumcheck:zeta");
+        let mut pow = PowUnivariate::new(zeta);
+        let mut challenge = Vec::with_capacity(self.multivariate_d);
+
+        // the first round reads the full polynomials, every later one the folded copies
+        let mut partially_evaluated: Option<AllEntities<Vec<F>>> = None;
+        let mut round_size = 1 << self.multivariate_d;
+        for round in 0..self.multivariate_d {
+            let views = match &partially_evaluated {
+                Some(polynomials) => polynomials.map(|p| p.as_slice()),
+                None => self.polynomials.map(|p| &p.coefficients[..round_size]),
+            };
+            let univariate = compute_univariate(&views, round_size, parameters, alpha, &pow);
+            transcript.send_to_verifier(
+                &format!("Sumcheck:univariate_{}", round),
+                &univariate.evaluations,
+            );
+            let u = transcript.derive_challenge::<F>(&format!("Sumcheck:u_{}", round));
+
+            partially_evaluated = Some(views.map(|p| partially_evaluate_mle(p, u)));
+            pow.partially_evaluate(u);
+            challenge.push(u);
+            round_size >>= 1;
+        }
+
+        let purported_evaluations = match partially_evaluated {
+            Some(polynomials) => polynomials.map(|p| p[0]),
+            None => self.polynomials.map(|p| p[0]),
+        };
+        let evaluations: [F; NUM_ALL_ENTITIES] = purported_evaluations.as_array().map(|e| *e);
+        transcript.send_to_verifier("Sumcheck:evaluations", &evaluations);
+
+        SumcheckOutput {
+            challenge,
+            purported_evaluations,
+        }
+    }
+}
+
+/// Sumcheck verifier over the hypercube of dimension `multivariate_d`.
+#[derive(Debug)]
+pub(crate) struct SumcheckVerifier {
+    pub(crate) multivariate_d: usize,
+}
+
+impl SumcheckVerifier {
+    pub(crate) fn new(multivariate_d: usize) -> Self {
+        Self { multivariate_d }
+    }
+
+    /// Checks every round univariate against the running target, and finally that the purported evaluations
+    /// satisfy the relations. The evaluations themselves still have to be checked by a commitment opening.
+    pub(crate) fn verify<H: BarretenHasher, F: PrimeField>(
+        &self,
+        transcript: &mut Transcript<H>,
+        parameters: &RelationParameters<F>,
+    ) -> Result<SumcheckOutput<F>> {
+        let alpha = transcript.derive_challenge::<F>("Sumcheck:alpha");
+        let zeta = transcript.derive_challenge::<F>("Sumcheck:zeta");
+        let mut pow = PowUnivariate::new(zeta);
+        let mut challenge = Vec::with_capacity(self.multivariate_d);
+
+        let mut target = F::zero();
+        for round in 0..self.multivariate_d {
+            let univariate = Univariate::<F, BATCHED_RELATION_PARTIAL_LENGTH>::new(
+                transcript.receive_from_prover(&format!("Sumcheck:univariate_{}", round))?,
+            );
+            ensure!(
+                univariate.value_at(0) + univariate.value_at(1) == target,
+                "sumcheck round {} does not add up to the previous one",
+                round
+            );
+            let u = transcript.derive_challenge::<F>(&format!("Sumcheck:u_{}", round));
+            target = univariate.evaluate(u);
+            pow.partially_evaluate(u);
+            challenge.push(u);
+        }
+
+        let evaluations: [F; NUM_ALL_ENTITIES] =
+            transcript.receive_from_prover("Sumcheck:evaluations")?;
+        let purported_evaluations = AllEntities::from_array(evaluations);
+        let full_honk_relation_purported_value = pow.partial_evaluation_constant
+            * evaluate_batched(&purported_evaluations, parameters, alpha);
+        ensure!(
+            full_honk_relation_purported_value == target,
+            "purported evaluations do not satisfy the relations"
+        );
+
+        Ok(SumcheckOutput {
+            challenge,
+            purported_evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{honk::flavor::AllEntities, transcript::Keccak256};
+    use ark_bn254::Fr;
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_serialize::CanonicalSerialize;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Rows of `a + b = c` with all copy-constraint terms trivially satisfied: id = σ and a constant
+    /// grand product that is closed off in the last row.
+    fn addition_polynomials(rows: &[(u64, u64, u64)]) -> AllEntities<Polynomial<Fr>> {
+        let n = rows.len();
+        let column =
+            |f: &dyn Fn(usize) -> Fr| Polynomial::from_coefficients((0..n).map(f).collect(), n);
+        let mut polynomials = AllEntities::from_array(std::array::from_fn(|_| Polynomial::new(n)));
+        polynomials.q_l = column(&|_| Fr::one());
+        polynomials.q_r = column(&|_| Fr::one());
+        polynomials.q_o = column(&|_| -Fr::one());
+        polynomials.w_l = column(&|i| Fr::from(rows[i].0));
+        polynomials.w_r = column(&|i| Fr::from(rows[i].1));
+        polynomials.w_o = column(&|i| Fr::from(rows[i].2));
+        for (k, (id, sigma)) in [
+            (&mut polynomials.id_1, &mut polynomials.sigma_1),
+            (&mut polynomials.id_2, &mut polynomials.sigma_2),
+            (&mut polynomials.id_3, &mut polynomials.sigma_3),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            *id = column(&|i| Fr::from((i + k * n) as u64));
+            *sigma = id.clone();
+        }
+        polynomials.lagrange_first = column(&|i| Fr::from((i == 0) as u64));
+        polynomials.lagrange_last = column(&|i| Fr::from((i == n - 1) as u64));
+        // z starts at 0, is 1 after the first row, and the shift falls off the end
+        polynomials.z_perm = column(&|i| Fr::from((i != 0) as u64));
+        polynomials.z_perm_shift = column(&|i| Fr::from((i != n - 1) as u64));
+        polynomials
+    }
+
+    fn rows() -> Vec<(u64, u64, u64)> {
+        (0..8u64).map(|i| (i, 2 * i + 1, 3 * i + 1)).collect()
+    }
+
+    fn parameters() -> RelationParameters<Fr> {
+        RelationParameters {
+            beta: Fr::from(3u64),
+            gamma: Fr::from(5u64),
+            public_input_delta: Fr::one(),
+        }
+    }
+
+    #[test]
+    fn pow_univariate_matches_zeta_powers() {
+        let zeta = Fr::from(7u64);
+        let mut pow = PowUnivariate::new(zeta);
+        // the point with index 0b101 has X_0 = 1, X_1 = 0, X_2 = 1
+        for u in [Fr::one(), Fr::zero(), Fr::one()] {
+            pow.partially_evaluate(u);
+        }
+        assert_eq!(pow.partial_evaluation_constant, zeta.pow([5u64]));
+    }
+
+    #[test]
+    fn honest_prover_convinces_verifier() {
+        let polynomials = addition_polynomials(&rows());
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        let prover_output =
+            SumcheckProver::new(3, &polynomials).prove(&mut transcript, &parameters());
+
+        // the evaluations are those of the multilinear extensions at the challenge
+        let challenge = &prover_output.challenge;
+        assert_eq!(
+            prover_output.purported_evaluations.w_o,
+            polynomials.w_o.evaluate_mle(challenge, false)
+        );
+        assert_eq!(
+            prover_output.purported_evaluations.z_perm_shift,
+            polynomials.z_perm.evaluate_mle(challenge, true)
+        );
+
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let verifier_output = SumcheckVerifier::new(3)
+            .verify(&mut transcript, &parameters())
+            .unwrap();
+        assert_eq!(verifier_output, prover_output);
+        assert!(transcript.is_exhausted());
+    }
+
+    #[test]
+    fn unsatisfied_row_is_rejected() {
+        let mut rows = rows();
+        rows[5].2 += 1;
+        let polynomials = addition_polynomials(&rows);
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        SumcheckProver::new(3, &polynomials).prove(&mut transcript, &parameters());
+
+        let mut transcript =
+            Transcript::<Keccak256>::new_streaming_from_proof(&transcript.export_transcript());
+        let err = SumcheckVerifier::new(3)
+            .verify::<_, Fr>(&mut transcript, &parameters())
+            .unwrap_err();
+        assert!(err.to_string().contains("sumcheck round 0"));
+    }
+
+    #[test]
+    fn tampered_evaluations_are_rejected() {
+        let polynomials = addition_polynomials(&rows());
+        let mut transcript = Transcript::<Keccak256>::new_streaming();
+        SumcheckProver::new(3, &polynomials).prove(&mut transcript, &parameters());
+
+        // change the last claimed evaluation (z_perm_shift) to something random
+        let mut proof_data = transcript.export_transcript();
+        let num_bytes = Fr::from(0u64).uncompressed_size();
+        let offset = proof_data.len() - num_bytes;
+        let mut rng = StdRng::seed_from_u64(0);
+        Fr::rand(&mut rng)
+            .serialize_uncompressed(&mut proof_data[offset..])
+            .unwrap();
+
+        let mut transcript = Transcript::<Keccak256>::new_streaming_from_proof(&proof_data);
+        let err = SumcheckVerifier::new(3)
+            .verify::<_, Fr>(&mut transcript, &parameters())
+            .unwrap_err();
+        assert!(err.to_string().contains("purported evaluations"));
+    }
+}
@@ -0,0 +1,289 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use anyhow::{ensure, Result};
+use ark_ec::pairing::Pairing;
+use ark_ff::PrimeField;
+use tracing::warn;
+
+use crate::{
+    plonk::proof_system::{public_inputs::compute_public_input_delta, types::proof::Proof},
+    transcript::{BarretenHasher, Transcript},
+};
+
+use super::{
+    flavor::{AllEntities, VerificationKey},
+    grand_product::subgroup_generator,
+    pcs::{kzg::VerifierKey, GeminiShplonkKzg, MultilinearCommitmentScheme},
+    relations::RelationParameters,
+    sumcheck::SumcheckVerifier,
+};
+
+/// Standard Honk verifier, checking the opening of the sumcheck evaluations with `P`.
+#[derive(Debug)]
+pub(crate) struct StandardVerifier<
+    E: Pairing,
+    H: BarretenHasher,
+    P: MultilinearCommitmentScheme<E> = GeminiShplonkKzg,
+> {
+    verification_key: Arc<VerificationKey<E>>,
+    verifier_key: VerifierKey<E>,
+    phantom: PhantomData<(H, P)>,
+}
+
+impl<E: Pairing, H: BarretenHasher, P: MultilinearCommitmentScheme<E>> StandardVerifier<E, H, P>
+where
+    E::ScalarField: PrimeField,
+{
+    pub(crate) fn new(
+        verification_key: Arc<VerificationKey<E>>,
+        verifier_key: VerifierKey<E>,
+    ) -> Self {
+        Self {
+            verification_key,
+            verifier_key,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Replays the prover's transcript, runs the sumcheck verifier, and checks the opening of the purported
+    /// evaluations.
+    pub(crate) fn verify_proof(&self, proof: &Proof) -> bool {
+        match self.verify(proof) {
+            Ok(verified) => verified,
+            Err(err) => {
+                warn!("rejecting Honk proof: {}", err);
+                false
+            }
+        }
+    }
+
+    fn verify(&self, proof: &Proof) -> Result<bool> {
+        let key = &self.verification_key;
+        let mut transcript = Transcript::<H>::new_streaming_from_proof(&proof.proof_data);
+
+        let circuit_size: u64 = transcript.receive_from_prover("circuit_size")?;
+        let num_public_inputs: u64 = transcript.receive_from_prover("public_input_size")?;
+        ensure!(
+            circuit_size as usize == key.circuit_size,
+            "proof is for a circuit of size {}, the key for one of size {}",
+            circuit_size,
+            key.circuit_size
+        );
+        ensure!(
+            num_public_inputs as usize == key.num_public_inputs,
+            "proof has {} public inputs, the key expects {}",
+            num_public_inputs,
+            key.num_public_inputs
+        );
+        let mut public_inputs = Vec::with_capacity(key.num_public_inputs);
+        for i in 0..key.num_public_inputs {
+            public_inputs.push(
+                transcript.receive_from_prover::<E::ScalarField>(&format!("public_input_{}", i))?,
+            );
+        }
+
+        let mut commitments = AllEntities::<E::G1Affine>::default();
+        for (commitment, key_commitment) in commitments
+            .as_array_mut()
+            .into_iter()
+            .zip(key.commitments.iter())
+        {
+            *commitment = *key_commitment;
+        }
+        commitments.w_l = transcript.receive_from_prover("W_1")?;
+        commitments.w_r = transcript.receive_from_prover("W_2")?;
+        commitments.w_o = transcript.receive_from_prover("W_3")?;
+
+        let beta = transcript.derive_challenge::<E::ScalarField>("beta");
+        let gamma = transcript.derive_challenge::<E::ScalarField>("gamma");
+        let relation_parameters = RelationParameters {
+            beta,
+            gamma,
+            public_input_delta: compute_public_input_delta(
+                &public_inputs,
+                beta,
+                gamma,
+                subgroup_generator::<E::ScalarField>(key.circuit_size)?,
+            )?,
+        };
+        commitments.z_perm = transcript.receive_from_prover("Z_PERM")?;
+
+        let sumcheck_output = SumcheckVerifier::new(key.log_circuit_size)
+            .verify::<H, E::ScalarField>(&mut transcript, &relation_parameters)?;
+
+        let evaluations = &sumcheck_output.purported_evaluations;
+        let unshifted: Vec<_> = commitments
+            .unshifted()
+            .into_iter()
+            .zip(evaluations.unshifted())
+            .map(|(commitment, evaluation)| (*commitment, *evaluation))
+            .collect();
+        let to_be_shifted: Vec<_> = commitments
+            .to_be_shifted()
+            .into_iter()
+            .zip(evaluations.shifted())
+            .map(|(commitment, evaluation)| (*commitment, *evaluation))
+            .collect();
+        let verified = P::verify(
+            &self.verifier_key,
+            &sumcheck_output.challenge,
+            &unshifted,
+            &to_be_shifted,
+            &mut transcript,
+        )?;
+        ensure!(
+            transcript.is_exhausted(),
+            "proof has trailing data after the opening proof"
+        );
+        Ok(verified)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        honk::{composer::StandardHonkComposer, pcs::kzg::test::mock_keys, prover::StandardProver},
+        proof_system::{
+            arithmetization::gate_data::{AddTriple, MulTriple},
+            circuit_constructor::StandardCircuitConstructor,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::{Bn254, Fr};
+    use ark_ec::pairing::Pairing;
+    use ark_ff::{Field, PrimeField, Zero};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// `x² + y = out` with x and out public, followed by `x·x` again to exercise copy constraints across gates.
+    fn circuit_over<F: Field>(x: u64, y: u64, out: u64) -> StandardCircuitConstructor<F> {
+        let mut circuit = StandardCircuitConstructor::new();
+        let x = circuit.add_public_variable(F::from(x));
+        let y = circuit.add_variable(F::from(y));
+        let out_value = F::from(out);
+        let x_squared = circuit.add_variable(circuit.get_variable(x).square());
+        let out = circuit.add_public_variable(out_value);
+        circuit.create_mul_gate(&MulTriple {
+            a: x,
+            b: x,
+            c: x_squared,
+            mul_scaling: F::one(),
+            c_scaling: -F::one(),
+            const_scaling: F::from(0u64),
+        });
+        circuit.create_add_gate(&AddTriple {
+            a: x_squared,
+            b: y,
+            c: out,
+            a_scaling: F::one(),
+            b_scaling: F::one(),
+            c_scaling: -F::one(),
+            const_scaling: F::from(0u64),
+        });
+        let x_squared_again = circuit.add_variable(circuit.get_variable(x_squared));
+        circuit.create_mul_gate(&MulTriple {
+            a: x,
+            b: x,
+            c: x_squared_again,
+            mul_scaling: F::one(),
+            c_scaling: -F::one(),
+            const_scaling: F::from(0u64),
+        });
+        circuit.assert_equal(x_squared, x_squared_again, None);
+        circuit
+    }
+
+    fn circuit(x: u64, y: u64, out: u64) -> StandardCircuitConstructor<Fr> {
+        circuit_over(x, y, out)
+    }
+
+    fn composer_over<E: Pairing>(
+        circuit: StandardCircuitConstructor<E::ScalarField>,
+    ) -> StandardHonkComposer<E, Keccak256>
+    where
+        E::ScalarField: PrimeField,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (commitment_key, verifier_key) = mock_keys::<E>(16, &mut rng);
+        StandardHonkComposer::new(circuit, commitment_key, verifier_key)
+    }
+
+    fn composer(circuit: StandardCircuitConstructor<Fr>) -> StandardHonkComposer<Bn254, Keccak256> {
+        composer_over(circuit)
+    }
+
+    #[test]
+    fn valid_circuit_verifies() {
+        let circuit = circuit(3, 5, 14);
+        assert!(circuit.check_circuit());
+        let mut composer = composer(circuit);
+        let proof = composer.create_prover().construct_proof().unwrap();
+        assert!(composer.create_verifier().verify_proof(&proof));
+    }
+
+    /// The same circuit, proven and verified over both pairing curves.
+    #[test]
+    fn same_circuit_verifies_on_both_pairing_curves() {
+        fn prove_and_verify<E: Pairing>() -> bool
+        where
+            E::ScalarField: PrimeField,
+        {
+            let mut composer = composer_over::<E>(circuit_over(3, 5, 14));
+            let proof = composer.create_prover().construct_proof().unwrap();
+            composer.create_verifier().verify_proof(&proof)
+        }
+        assert!(prove_and_verify::<Bn254>());
+        assert!(prove_and_verify::<ark_bls12_381::Bls12_381>());
+    }
+
+    #[test]
+    fn unsatisfied_gate_is_rejected() {
+        let circuit = circuit(3, 5, 15);
+        assert!(!circuit.check_circuit());
+        let mut composer = composer(circuit);
+        let proof = composer.create_prover().construct_proof().unwrap();
+        assert!(!composer.create_verifier().verify_proof(&proof));
+    }
+
+    #[test]
+    fn broken_copy_constraint_is_rejected() {
+        let mut composer = composer(circuit(3, 5, 14));
+        let mut proving_key = (*composer.compute_proving_key()).clone();
+        // replace x by 4 in the last gate, which still holds with 16 as its output, but no longer matches
+        // the copies of x and x² elsewhere
+        let last = proving_key.circuit_size - 1;
+        let row = (0..proving_key.circuit_size)
+            .rev()
+            .find(|&i| !proving_key.polynomials.q_m[i].is_zero())
+            .unwrap();
+        assert!(row < last);
+        proving_key.polynomials.w_l[row] = Fr::from(4u64);
+        proving_key.polynomials.w_r[row] = Fr::from(4u64);
+        proving_key.polynomials.w_o[row] = Fr::from(16u64);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let (commitment_key, _) = mock_keys::<Bn254>(16, &mut rng);
+        let proof = StandardProver::<Bn254, Keccak256>::new(Arc::new(proving_key), commitment_key)
+            .construct_proof()
+            .unwrap();
+        assert!(!composer.create_verifier().verify_proof(&proof));
+    }
+
+    #[test]
+    fn wrong_public_input_is_rejected() {
+        let mut composer = composer(circuit(3, 5, 14));
+        let mut proof = composer.create_prover().construct_proof().unwrap();
+        // the first public input comes right after the circuit size and the number of public inputs
+        proof.proof_data[16] ^= 1;
+        assert!(!composer.create_verifier().verify_proof(&proof));
+    }
+
+    #[test]
+    fn truncated_proof_is_rejected() {
+        let mut composer = composer(circuit(3, 5, 14));
+        let mut proof = composer.create_prover().construct_proof().unwrap();
+        proof.proof_data.pop();
+        assert!(!composer.create_verifier().verify_proof(&proof));
+    }
+}
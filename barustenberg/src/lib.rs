@@ -7,6 +7,8 @@
 pub(crate) mod common;
 /// elliptic curves stuff (currently a thin wrapper on other people's grumpkin and bn254)
 pub(crate) mod ecc;
+/// honk
+pub(crate) mod honk;
 /// bitops mostly
 pub(crate) mod numeric;
 /// plonk
@@ -37,6 +39,12 @@ pub(crate) mod transcript;
 // audit how str versus String is used around the library. probably should have static strings.
 // audit where rwlocks are used and where arcs are used?
 
+pub use transcript::trace::{diff_trace_files, TraceMismatch};
+
+#[cfg(feature = "multithreading")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multithreading")))]
+pub use common::thread::init_thread_pool;
+
 /// Test utilities.
 #[cfg(any(test, feature = "test_utils"))]
 #[cfg_attr(docsrs, doc(cfg(feature = "test_utils")))]
@@ -1,19 +1,25 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
-use ark_ec::AffineRepr;
+use anyhow::{anyhow, Result};
 use rand::RngCore;
 use std::default::Default;
 
 use crate::{
-    plonk::proof_system::{proving_key::ProvingKey, verification_key::VerificationKey},
+    ecc::curves::CurveConfig,
+    plonk::proof_system::{
+        proving_key::ProvingKey,
+        utils::permutation::{PermutationMapping, PermutationSubgroupElement},
+        verification_key::VerificationKey,
+    },
     srs::reference_string::{
         file_reference_string::FileReferenceStringFactory, BaseReferenceStringFactory,
         ReferenceStringFactory,
     },
 };
 
-use ark_ff::{FftField, Field};
-
 pub(crate) const DUMMY_TAG: u32 = 0;
 pub(crate) const REAL_VARIABLE: u32 = u32::MAX - 1;
 pub(crate) const FIRST_VARIABLE_IN_CLASS: u32 = u32::MAX - 2;
@@ -28,10 +34,17 @@ pub(crate) enum WireType {
     Output = 1 << 31,
     Fourth = 0xc0000000,
 }
-pub(crate) enum ComposerType {
+
+/// The arithmetisation a circuit is built with.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ComposerType {
+    /// Width 3 arithmetic gates.
     Standard,
+    /// Width 4 with fixed-base, range and logic gates.
     Turbo,
+    /// Ultra: turbo's width with lookups, sorted ranges, elliptic and memory gates.
     Plookup,
+    /// Width 3 arithmetic gates, proven with Honk.
     StandardHonk,
 }
 
@@ -46,6 +59,13 @@ pub(crate) struct SelectorProperties {
     pub(crate) requires_lagrange_base_polynomial: bool,
 }
 
+impl WireType {
+    /// The column this wire occupies in the execution trace.
+    pub(crate) fn column_index(&self) -> usize {
+        (*self as u32 >> 30) as usize
+    }
+}
+
 impl CycleNode {
     pub(crate) fn new(gate_index: u32, wire_type: WireType) -> Self {
         Self {
@@ -55,16 +75,15 @@ impl CycleNode {
     }
 }
 
-pub(crate) struct ComposerBase<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
-{
+pub(crate) struct ComposerBase<'a, C: CurveConfig> {
     pub(crate) num_gates: usize,
-    crs_factory: Arc<dyn ReferenceStringFactory<G1Affine, G2Affine>>,
+    crs_factory: Arc<dyn ReferenceStringFactory<C::G1Affine, C::G2Affine>>,
     num_selectors: usize,
-    selectors: Vec<Vec<F>>,
+    selectors: Vec<Vec<C::Fr>>,
     selector_properties: Vec<SelectorProperties>,
-    rand_engine: Option<Box<dyn RngCore>>,
-    circuit_proving_key: Option<Arc<ProvingKey<'a, F, G1Affine>>>,
-    circuit_verification_key: Option<Arc<VerificationKey<'a, F>>>,
+    rand_engine: Option<Box<dyn RngCore + Send>>,
+    circuit_proving_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+    circuit_verification_key: Option<Arc<VerificationKey<'a, C>>>,
     w_l: Vec<u32>,
     w_r: Vec<u32>,
     w_o: Vec<u32>,
@@ -73,7 +92,7 @@ pub(crate) struct ComposerBase<'a, F: Field + FftField, G1Affine: AffineRepr, G2
     _err: Option<String>,
     zero_idx: u32,
     public_inputs: Vec<u32>,
-    variables: Vec<F>,
+    variables: Vec<C::Fr>,
     /// index of next variable in equivalence class (=REAL_VARIABLE if you're last)
     next_var_index: Vec<u32>,
     /// index of  previous variable in equivalence class (=FIRST if you're in a cycle alone)
@@ -90,9 +109,7 @@ pub(crate) struct ComposerBase<'a, F: Field + FftField, G1Affine: AffineRepr, G2
     computed_witness: bool,
 }
 
-impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
-    ComposerBase<'a, F, G1Affine, G2Affine>
-{
+impl<'a, C: CurveConfig> ComposerBase<'a, C> {
     pub(crate) fn new(
         num_selectors: usize,
         size_hint: usize,
@@ -107,7 +124,9 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
     pub(crate) fn default() -> Self {
         Self {
             num_gates: 0,
-            crs_factory: Arc::new(BaseReferenceStringFactory::<G1Affine, G2Affine>::default()),
+            crs_factory: Arc::new(
+                BaseReferenceStringFactory::<C::G1Affine, C::G2Affine>::default(),
+            ),
             num_selectors: 0,
             selectors: Default::default(),
             selector_properties: Default::default(),
@@ -135,7 +154,7 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
     }
 
     pub(crate) fn with_crs_factory(
-        crs_factory: Arc<dyn ReferenceStringFactory<G1Affine, G2Affine>>,
+        crs_factory: Arc<dyn ReferenceStringFactory<C::G1Affine, C::G2Affine>>,
         num_selectors: usize,
         size_hint: usize,
         selector_properties: Vec<SelectorProperties>,
@@ -152,8 +171,8 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
         selfie
     }
     pub(crate) fn with_keys(
-        p_key: Arc<ProvingKey<'a, F, G1Affine>>,
-        v_key: Arc<VerificationKey<'a, F>>,
+        p_key: Arc<RwLock<ProvingKey<'a, C>>>,
+        v_key: Arc<VerificationKey<'a, C>>,
         num_selectors: usize,
         size_hint: usize,
         selector_properties: Vec<SelectorProperties>,
@@ -200,7 +219,7 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
     ///
     /// * The value of the variable.
     #[inline]
-    fn get_variable(&self, index: u32) -> F {
+    fn get_variable(&self, index: u32) -> C::Fr {
         assert!(self.variables.len() > index as usize);
         self.variables[self.real_variable_index[index as usize] as usize]
     }
@@ -216,16 +235,16 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
     ///
     /// * The value of the variable.
     #[inline]
-    fn get_variable_reference(&self, index: u32) -> &F {
+    fn get_variable_reference(&self, index: u32) -> &C::Fr {
         assert!(self.variables.len() > index as usize);
         &self.variables[self.real_variable_index[index as usize] as usize]
     }
 
-    fn get_public_input(&self, index: u32) -> F {
+    fn get_public_input(&self, index: u32) -> C::Fr {
         self.get_variable(self.public_inputs[index as usize])
     }
 
-    fn get_public_inputs(&self) -> Vec<F> {
+    fn get_public_inputs(&self) -> Vec<C::Fr> {
         let mut result = Vec::new();
         for i in 0..self.get_num_public_inputs() {
             result.push(self.get_public_input(i.try_into().unwrap()));
@@ -241,7 +260,7 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
     /// # Returns
     ///
     /// * The index of the new variable in the variables vector
-    fn add_variable(&mut self, in_value: F) -> u32 {
+    fn add_variable(&mut self, in_value: C::Fr) -> u32 {
         self.variables.push(in_value);
 
         // By default, we assume each new variable belongs in its own copy-cycle. These defaults can be modified later
@@ -272,7 +291,7 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
     /// # Returns
     ///
     /// * The index of the new variable in the variables vector
-    fn add_public_variable(&mut self, in_value: F) -> u32 {
+    fn add_public_variable(&mut self, in_value: C::Fr) -> u32 {
         let index = self.add_variable(in_value);
         self.public_inputs.push(index);
         index
@@ -297,10 +316,175 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
         );
     }
 
-    // fn assert_equal(&mut self, a_idx: u32, b_idx: u32, msg: Option<&str>);
+    fn failure(&mut self, msg: String) {
+        self.failed = true;
+        self._err = Some(msg);
+    }
+
+    /// Constrain the variables at `a_variable_idx` and `b_variable_idx` to be equal by merging their
+    /// equivalence classes. The merged class keeps whichever tag either class had; two different tags clash.
+    ///
+    /// # Arguments
+    ///
+    /// * `a_variable_idx` - The index of the first variable.
+    /// * `b_variable_idx` - The index of the second variable.
+    /// * `msg` - The failure message recorded if the values or tags don't match.
+    pub(crate) fn assert_equal(
+        &mut self,
+        a_variable_idx: u32,
+        b_variable_idx: u32,
+        msg: Option<&str>,
+    ) {
+        self.assert_valid_variables(&[a_variable_idx, b_variable_idx]);
+        let msg = msg.unwrap_or("assert_equal").to_string();
+
+        let values_equal = self.get_variable(a_variable_idx) == self.get_variable(b_variable_idx);
+        if !values_equal && !self.failed {
+            self.failure(msg.clone());
+        }
+
+        let a_real_idx = self.real_variable_index[a_variable_idx as usize];
+        let b_real_idx = self.real_variable_index[b_variable_idx as usize];
+        // a == b is already enforced
+        if a_real_idx == b_real_idx {
+            return;
+        }
+
+        // Point every member of b's class at a's real variable, then tie the last (real) element of b's chain to
+        // the first element of a's chain.
+        let b_start_idx = self.get_first_variable_in_class(b_variable_idx as usize) as u32;
+        self.update_real_variable_indices(b_start_idx, a_real_idx);
+        let a_start_idx = self.get_first_variable_in_class(a_variable_idx as usize) as u32;
+        self.next_var_index[b_real_idx as usize] = a_start_idx;
+        self.prev_var_index[a_start_idx as usize] = b_real_idx;
+
+        let a_tag = self.real_variable_tags[a_real_idx as usize];
+        let b_tag = self.real_variable_tags[b_real_idx as usize];
+        let no_tag_clash = a_tag == DUMMY_TAG || b_tag == DUMMY_TAG || a_tag == b_tag;
+        if !no_tag_clash && !self.failed {
+            self.failure(msg);
+        }
+        if a_tag == DUMMY_TAG {
+            self.real_variable_tags[a_real_idx as usize] = b_tag;
+        }
+    }
+
+    /// Allocate a new tag.
+    pub(crate) fn get_new_tag(&mut self) -> u32 {
+        self.current_tag += 1;
+        self.current_tag
+    }
+
+    /// Record that the set of cells tagged `tag_index` must be a permutation of the set tagged `tau_index`, and
+    /// allocate a new tag.
+    ///
+    /// Tags come in pairs: to prove multiset equality between two sets of variables, tag the first set with `a`
+    /// and the second with `b`, then call `create_tag(a, b)` and `create_tag(b, a)`.
+    pub(crate) fn create_tag(&mut self, tag_index: u32, tau_index: u32) -> u32 {
+        self.tau.insert(tag_index, tau_index);
+        self.get_new_tag()
+    }
+
+    /// Tag the equivalence class of `variable_index`. A class can only be tagged once.
+    pub(crate) fn assign_tag(&mut self, variable_index: u32, tag: u32) {
+        assert!(tag <= self.current_tag);
+        let real_index = self.real_variable_index[variable_index as usize] as usize;
+        assert_eq!(self.real_variable_tags[real_index], DUMMY_TAG);
+        self.real_variable_tags[real_index] = tag;
+    }
+
+    /// Collect, for every real variable, the wire cells that hold it.
+    ///
+    /// Public inputs occupy the first rows of the trace, in both the left and the right wire. These two nodes need
+    /// to be adjacent in the cycle for the public input delta to work out. The gates follow after them.
+    pub(crate) fn compute_wire_copy_cycles(&mut self, program_width: usize) {
+        let num_public_inputs = self.public_inputs.len();
+        let mut copy_cycles = vec![Vec::new(); self.variables.len()];
+
+        for (i, &public_input) in self.public_inputs.iter().enumerate() {
+            let real_index = self.real_variable_index[public_input as usize] as usize;
+            copy_cycles[real_index].push(CycleNode::new(i as u32, WireType::Left));
+            copy_cycles[real_index].push(CycleNode::new(i as u32, WireType::Right));
+        }
+
+        let wires = [
+            (&self.w_l, WireType::Left),
+            (&self.w_r, WireType::Right),
+            (&self.w_o, WireType::Output),
+            (&self.w_4, WireType::Fourth),
+        ];
+        for i in 0..self.num_gates {
+            for (wire, wire_type) in wires.iter().take(program_width) {
+                let real_index = self.real_variable_index[wire[i] as usize] as usize;
+                copy_cycles[real_index]
+                    .push(CycleNode::new((i + num_public_inputs) as u32, *wire_type));
+            }
+        }
+        self.wire_copy_cycles = copy_cycles;
+    }
+
+    /// Compute the σ permutation (and for `generalized` permutations, the id permutation) from the wire copy
+    /// cycles.
+    ///
+    /// Each node points to the next node in its cycle. In a generalized permutation the first node of a tagged
+    /// cycle maps into the tag coset under id at its tag, and the last node maps into it under σ at τ(tag), so the
+    /// grand product only balances if every tag's multiset of values matches that of its τ partner. Fails if a
+    /// tag was assigned without `create_tag` giving it a partner.
+    pub(crate) fn compute_permutation_mapping(
+        &mut self,
+        program_width: usize,
+        circuit_size: usize,
+        generalized: bool,
+    ) -> Result<PermutationMapping> {
+        self.compute_wire_copy_cycles(program_width);
+        let mut mapping = PermutationMapping::identity(program_width, circuit_size, generalized);
+
+        for (cycle_index, copy_cycle) in self.wire_copy_cycles.iter().enumerate() {
+            let tag = self.real_variable_tags[cycle_index];
+            for (node_idx, current) in copy_cycle.iter().enumerate() {
+                let next_idx = (node_idx + 1) % copy_cycle.len();
+                let next = &copy_cycle[next_idx];
+                let current_column = current.wire_type.column_index();
+                let current_row = current.gate_index as usize;
 
-    // Add the implementation for `compute_wire_copy_cycles` and `compute_sigma_permutations` when needed.
-    // These methods are generic and may require additional code and context.
+                mapping.sigmas[current_column][current_row] = PermutationSubgroupElement::new(
+                    next.gate_index,
+                    next.wire_type.column_index() as u8,
+                );
+
+                if generalized {
+                    if node_idx == 0 {
+                        let id = &mut mapping.ids[current_column][current_row];
+                        id.is_tag = true;
+                        id.subgroup_index = tag;
+                    }
+                    if next_idx == 0 {
+                        let sigma = &mut mapping.sigmas[current_column][current_row];
+                        sigma.is_tag = true;
+                        // untagged cycles map to themselves
+                        sigma.subgroup_index = if tag == DUMMY_TAG {
+                            DUMMY_TAG
+                        } else {
+                            *self
+                                .tau
+                                .get(&tag)
+                                .ok_or_else(|| anyhow!("tag {} has no τ partner", tag))?
+                        };
+                    }
+                }
+            }
+        }
+
+        // Public inputs sit at the top of the trace, and their left wire maps into the external coset.
+        for i in 0..self.public_inputs.len() {
+            let sigma = &mut mapping.sigmas[0][i];
+            sigma.subgroup_index = i as u32;
+            sigma.column_index = 0;
+            sigma.is_public_input = true;
+            assert!(!sigma.is_tag, "mapping is both a tag and a public input");
+        }
+        Ok(mapping)
+    }
 
     fn get_circuit_subgroup_size(&self, num_gates: usize) -> usize {
         let log2_n = num_gates.next_power_of_two().trailing_zeros() as usize;
@@ -320,6 +504,220 @@ impl<'a, F: Field + FftField, G1Affine: AffineRepr, G2Affine: AffineRepr>
         (self.variables.len() as u32) > variable_index
     }
 }
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ecc::curves::Bn254,
+        plonk::proof_system::{
+            types::prover_settings::StandardSettings,
+            utils::permutation::compute_permutation_polynomials_from_mapping,
+            widgets::random_widgets::permutation_widget::compute_permutation_grand_product,
+        },
+        polynomials::{evaluation_domain::EvaluationDomain, polynomial_arithmetic::evaluate},
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{Field, One};
+
+    type Composer<'a> = ComposerBase<'a, Bn254>;
+
+    /// One gate per value, with the value in the left wire and zeros elsewhere.
+    fn composer_with_values(values: &[u64]) -> (Composer<'static>, Vec<u32>) {
+        let mut composer = Composer::default();
+        let zero = composer.add_variable(Fr::from(0u64));
+        let indices = values
+            .iter()
+            .map(|&value| composer.add_variable(Fr::from(value)))
+            .collect::<Vec<_>>();
+        for &index in &indices {
+            composer.w_l.push(index);
+            composer.w_r.push(zero);
+            composer.w_o.push(zero);
+            composer.num_gates += 1;
+        }
+        (composer, indices)
+    }
+
+    #[test]
+    fn assert_equal_merges_tags_and_flags_clashes() {
+        let (mut composer, indices) = composer_with_values(&[1, 1, 1]);
+        let a = composer.get_new_tag();
+        let b = composer.get_new_tag();
+        composer.assign_tag(indices[0], a);
+
+        composer.assert_equal(indices[1], indices[0], None);
+        let real_index = composer.real_variable_index[indices[1] as usize] as usize;
+        assert_eq!(composer.real_variable_tags[real_index], a);
+        assert!(!composer.failed);
+
+        composer.assign_tag(indices[2], b);
+        composer.assert_equal(indices[2], indices[0], Some("tag clash"));
+        assert!(composer.failed);
+        assert_eq!(composer._err.as_deref(), Some("tag clash"));
+    }
+
+    #[test]
+    fn tagged_cycles_map_into_the_tag_coset() {
+        let (mut composer, indices) = composer_with_values(&[3, 5, 5, 3]);
+        let a = composer.get_new_tag();
+        let b = composer.get_new_tag();
+        composer.create_tag(a, b);
+        composer.create_tag(b, a);
+        composer.assign_tag(indices[0], a);
+        composer.assign_tag(indices[1], a);
+        composer.assign_tag(indices[2], b);
+        composer.assign_tag(indices[3], b);
+
+        let mapping = composer.compute_permutation_mapping(3, 8, true).unwrap();
+
+        // each tagged variable sits alone in its cycle, in the left wire of its own row
+        for (row, tag, tau) in [(0, a, b), (1, a, b), (2, b, a), (3, b, a)] {
+            let id = mapping.ids[0][row];
+            assert!(id.is_tag);
+            assert_eq!(id.subgroup_index, tag);
+            let sigma = mapping.sigmas[0][row];
+            assert!(sigma.is_tag);
+            assert_eq!(sigma.subgroup_index, tau);
+        }
+
+        // the zero variable is untagged, so its cycle ends back in the dummy tag
+        let zero_cycle = &composer.wire_copy_cycles[0];
+        let first = zero_cycle[0];
+        let last = zero_cycle[zero_cycle.len() - 1];
+        let first_id = mapping.ids[first.wire_type.column_index()][first.gate_index as usize];
+        let last_sigma = mapping.sigmas[last.wire_type.column_index()][last.gate_index as usize];
+        assert!(first_id.is_tag && last_sigma.is_tag);
+        assert_eq!(first_id.subgroup_index, DUMMY_TAG);
+        assert_eq!(last_sigma.subgroup_index, DUMMY_TAG);
+
+        // and otherwise points to the next cell holding zero
+        let second = zero_cycle[1];
+        assert_eq!(
+            mapping.sigmas[first.wire_type.column_index()][first.gate_index as usize],
+            PermutationSubgroupElement::new(
+                second.gate_index,
+                second.wire_type.column_index() as u8
+            )
+        );
+        // rows past the gates are untouched
+        assert_eq!(mapping.sigmas[1][6], PermutationSubgroupElement::new(6, 1));
+        assert_eq!(mapping.ids[1][6], PermutationSubgroupElement::new(6, 1));
+    }
+
+    #[test]
+    fn tags_without_a_partner_are_an_error() {
+        let (mut composer, indices) = composer_with_values(&[3]);
+        let a = composer.get_new_tag();
+        composer.assign_tag(indices[0], a);
+        assert!(composer.compute_permutation_mapping(3, 8, true).is_err());
+    }
+
+    /// The wires of `composer` in Lagrange form, padded with zeros to `n` rows.
+    fn wire_values(composer: &Composer<'_>, n: usize) -> Vec<Vec<Fr>> {
+        [&composer.w_l, &composer.w_r, &composer.w_o]
+            .iter()
+            .map(|wire| {
+                let mut values = vec![Fr::from(0u64); n];
+                for (value, &index) in values.iter_mut().zip(wire.iter()) {
+                    *value = composer.get_variable(index);
+                }
+                values
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sigma_and_id_polynomials_balance_the_grand_product() {
+        const N: usize = 8;
+        let (mut composer, indices) = composer_with_values(&[3, 5, 5, 3, 7, 7]);
+        // a copy constraint between the last two rows, and a multiset equality between the first four
+        composer.assert_equal(indices[4], indices[5], None);
+        let a = composer.get_new_tag();
+        let b = composer.get_new_tag();
+        composer.create_tag(a, b);
+        composer.create_tag(b, a);
+        composer.assign_tag(indices[0], a);
+        composer.assign_tag(indices[1], a);
+        composer.assign_tag(indices[2], b);
+        composer.assign_tag(indices[3], b);
+        let mapping = composer.compute_permutation_mapping(3, N, true).unwrap();
+
+        let mut key = ProvingKey::<Bn254> {
+            circuit_size: N,
+            small_domain: EvaluationDomain::new(N, None),
+            large_domain: EvaluationDomain::new(4 * N, None),
+            ..Default::default()
+        };
+        key.small_domain.compute_lookup_table();
+        key.large_domain.compute_lookup_table();
+        compute_permutation_polynomials_from_mapping::<Keccak256, Bn254, StandardSettings<_>>(
+            "sigma",
+            &mapping.sigmas,
+            &mut key,
+        )
+        .unwrap();
+        compute_permutation_polynomials_from_mapping::<Keccak256, Bn254, StandardSettings<_>>(
+            "id",
+            &mapping.ids,
+            &mut key,
+        )
+        .unwrap();
+
+        let get = |label: String| {
+            key.polynomial_store
+                .get(&label)
+                .unwrap()
+                .read()
+                .unwrap()
+                .coefficients
+                .clone()
+        };
+        let lagrange = |label: &str| {
+            (1..=3)
+                .map(|column| get(format!("{}_{}_lagrange", label, column)))
+                .collect::<Vec<_>>()
+        };
+        let (sigmas, ids) = (lagrange("sigma"), lagrange("id"));
+
+        // the monomial and coset forms agree with the Lagrange one
+        for label in ["sigma_1", "id_2"] {
+            let values = get(format!("{}_lagrange", label));
+            let monomial = get(label.to_string());
+            let fft = get(format!("{}_fft", label));
+            for (i, value) in values.iter().enumerate() {
+                let x = key.small_domain.root.pow([i as u64]);
+                assert_eq!(evaluate(&monomial, &x, N), *value);
+            }
+            for (i, value) in fft.iter().enumerate() {
+                let x = key.large_domain.generator * key.large_domain.root.pow([i as u64]);
+                assert_eq!(evaluate(&monomial, &x, N), *value, "{}_fft[{}]", label, i);
+            }
+        }
+
+        let grand_product = |wires: &[Vec<Fr>]| {
+            let wires = wires.iter().map(|w| w.as_slice()).collect::<Vec<_>>();
+            let sigmas = sigmas.iter().map(|s| s.as_slice()).collect::<Vec<_>>();
+            let ids = ids.iter().map(|s| s.as_slice()).collect::<Vec<_>>();
+            compute_permutation_grand_product(
+                &wires,
+                &sigmas,
+                Some(&ids),
+                Fr::from(3u64),
+                Fr::from(7u64),
+                &key.small_domain,
+            )
+        };
+        let z = grand_product(&wire_values(&composer, N));
+        assert!(z[6..].iter().all(|z_i| z_i.is_one()));
+
+        // a value outside the multiset of its tag's partner leaves the product unbalanced
+        composer.variables[indices[1] as usize] = Fr::from(4u64);
+        let z = grand_product(&wire_values(&composer, N));
+        assert!(!z[6].is_one());
+    }
+}
+
 // /**
 //  * Composer Example: Pythagorean triples.
 //  *
@@ -0,0 +1,372 @@
+//! The transcript layouts of the Plonk composers, as in barretenberg's `create_manifest`.
+//!
+//! Every composer runs the same rounds, `init`, `eta`, `beta`, `alpha`, `z`, `nu` and `separator`, and they
+//! differ in what gets committed when and in which evaluations are opened. The order of the elements within a
+//! round is the order they are hashed in and the order they appear in a serialized proof. The public layouts
+//! are barretenberg's, with BN254 commitments; the prover and verifier size theirs by the curve they run on.
+
+use anyhow::{bail, Result};
+
+use crate::ecc::{curves::CurveConfig, serialize::PointEncoding};
+
+pub use crate::transcript::{Manifest, ManifestEntry, RoundManifest};
+
+pub use super::composer_base::ComposerType;
+
+/// Bytes in a BN254 commitment sent to the verifier.
+const G1_SIZE: usize = 64;
+/// Bytes in a field element sent to the verifier.
+const FR_SIZE: usize = 32;
+
+/// Challenges generated in the `nu` round, one per polynomial the standard composer opens.
+pub(crate) const STANDARD_MANIFEST_SIZE: usize = 12;
+/// Challenges generated in the `nu` round, one per polynomial the turbo composer opens.
+pub(crate) const TURBO_MANIFEST_SIZE: usize = 20;
+/// Challenges generated in the `nu` round, one per polynomial the ultra composer opens.
+pub(crate) const ULTRA_MANIFEST_SIZE: usize = 30;
+
+/// The circuit and public input sizes, which the verifier knows from its key.
+fn init_round() -> RoundManifest {
+    RoundManifest::new(
+        vec![
+            ManifestEntry::new("circuit_size", 4, true, 0),
+            ManifestEntry::new("public_input_size", 4, true, 0),
+        ],
+        "init",
+        1,
+        false,
+    )
+}
+
+fn public_inputs(num_public_inputs: usize) -> ManifestEntry {
+    ManifestEntry::new("public_inputs", FR_SIZE * num_public_inputs, false, 0)
+}
+
+fn commitments(labels: &[&str], g1_size: usize) -> Vec<ManifestEntry> {
+    labels
+        .iter()
+        .map(|label| ManifestEntry::new(label, g1_size, false, 0))
+        .collect()
+}
+
+/// The quotient evaluation the verifier reconstructs, then the evaluations the prover sends, each with the
+/// index of the `nu` challenge that batches its polynomial. A shifted evaluation shares its polynomial's index.
+fn nu_round(evaluations: &[(&str, i32)], num_challenges: usize) -> RoundManifest {
+    let mut elements = vec![ManifestEntry::new("t", FR_SIZE, true, -1)];
+    elements.extend(
+        evaluations
+            .iter()
+            .map(|(name, index)| ManifestEntry::new(name, FR_SIZE, false, *index)),
+    );
+    RoundManifest::new(elements, "nu", num_challenges, true)
+}
+
+/// The opening proofs at `z` and `z·ω`.
+fn separator_round(num_challenges: usize, g1_size: usize) -> RoundManifest {
+    RoundManifest::new(
+        commitments(&["PI_Z", "PI_Z_OMEGA"], g1_size),
+        "separator",
+        num_challenges,
+        false,
+    )
+}
+
+/// The transcript of a standard composer proof with `num_public_inputs` public inputs.
+pub fn standard_manifest(num_public_inputs: usize) -> Manifest {
+    standard(num_public_inputs, G1_SIZE)
+}
+
+/// [`standard_manifest`] with the commitments of curve `C`.
+pub(crate) fn standard_manifest_for<C: CurveConfig>(num_public_inputs: usize) -> Manifest {
+    standard(num_public_inputs, C::G1Affine::UNCOMPRESSED_SIZE)
+}
+
+fn standard(num_public_inputs: usize, g1_size: usize) -> Manifest {
+    let mut beta_elements = vec![public_inputs(num_public_inputs)];
+    beta_elements.extend(commitments(&["W_1", "W_2", "W_3"], g1_size));
+    Manifest::new(vec![
+        init_round(),
+        RoundManifest::new(vec![], "eta", 0, false),
+        RoundManifest::new(beta_elements, "beta", 2, false),
+        RoundManifest::new(commitments(&["Z_PERM"], g1_size), "alpha", 1, false),
+        RoundManifest::new(commitments(&["T_1", "T_2", "T_3"], g1_size), "z", 1, false),
+        nu_round(
+            &[
+                ("w_1", 0),
+                ("w_2", 1),
+                ("w_3", 2),
+                ("sigma_1", 3),
+                ("sigma_2", 4),
+                ("sigma_3", 5),
+                ("q_1", 6),
+                ("q_2", 7),
+                ("q_3", 8),
+                ("q_m", 9),
+                ("q_c", 10),
+                ("z_perm", 11),
+                ("z_perm_omega", 11),
+            ],
+            STANDARD_MANIFEST_SIZE,
+        ),
+        separator_round(1, g1_size),
+    ])
+}
+
+/// The transcript of a turbo composer proof with `num_public_inputs` public inputs.
+pub fn turbo_manifest(num_public_inputs: usize) -> Manifest {
+    turbo(num_public_inputs, G1_SIZE)
+}
+
+/// [`turbo_manifest`] with the commitments of curve `C`.
+pub(crate) fn turbo_manifest_for<C: CurveConfig>(num_public_inputs: usize) -> Manifest {
+    turbo(num_public_inputs, C::G1Affine::UNCOMPRESSED_SIZE)
+}
+
+fn turbo(num_public_inputs: usize, g1_size: usize) -> Manifest {
+    let mut beta_elements = vec![public_inputs(num_public_inputs)];
+    beta_elements.extend(commitments(&["W_1", "W_2", "W_3", "W_4"], g1_size));
+    Manifest::new(vec![
+        init_round(),
+        RoundManifest::new(vec![], "eta", 0, false),
+        RoundManifest::new(beta_elements, "beta", 2, false),
+        RoundManifest::new(commitments(&["Z_PERM"], g1_size), "alpha", 1, false),
+        RoundManifest::new(
+            commitments(&["T_1", "T_2", "T_3", "T_4"], g1_size),
+            "z",
+            1,
+            false,
+        ),
+        nu_round(
+            &[
+                ("w_1", 0),
+                ("w_2", 1),
+                ("w_3", 2),
+                ("w_4", 3),
+                ("z_perm", 4),
+                ("q_1", 5),
+                ("q_2", 6),
+                ("q_3", 7),
+                ("q_4", 8),
+                ("q_5", 9),
+                ("q_m", 10),
+                ("q_c", 11),
+                ("q_arith", 12),
+                ("q_fixed_base", 13),
+                ("q_range", 14),
+                ("q_logic", 15),
+                ("sigma_1", 16),
+                ("sigma_2", 17),
+                ("sigma_3", 18),
+                ("sigma_4", 19),
+                ("w_1_omega", 0),
+                ("w_2_omega", 1),
+                ("w_3_omega", 2),
+                ("w_4_omega", 3),
+                ("z_perm_omega", 4),
+            ],
+            TURBO_MANIFEST_SIZE,
+        ),
+        separator_round(1, g1_size),
+    ])
+}
+
+/// The transcript of an ultra composer proof with `num_public_inputs` public inputs. The fourth wire is
+/// committed after `eta`, which the memory records in it depend on, and the separator round derives three
+/// challenges for recursion.
+pub fn ultra_manifest(num_public_inputs: usize) -> Manifest {
+    ultra(num_public_inputs, G1_SIZE)
+}
+
+/// [`ultra_manifest`] with the commitments of curve `C`.
+pub(crate) fn ultra_manifest_for<C: CurveConfig>(num_public_inputs: usize) -> Manifest {
+    ultra(num_public_inputs, C::G1Affine::UNCOMPRESSED_SIZE)
+}
+
+fn ultra(num_public_inputs: usize, g1_size: usize) -> Manifest {
+    let mut eta_elements = vec![public_inputs(num_public_inputs)];
+    eta_elements.extend(commitments(&["W_1", "W_2", "W_3"], g1_size));
+    Manifest::new(vec![
+        init_round(),
+        RoundManifest::new(eta_elements, "eta", 1, false),
+        RoundManifest::new(commitments(&["W_4", "S"], g1_size), "beta", 2, false),
+        RoundManifest::new(
+            commitments(&["Z_PERM", "Z_LOOKUP"], g1_size),
+            "alpha",
+            1,
+            false,
+        ),
+        RoundManifest::new(
+            commitments(&["T_1", "T_2", "T_3", "T_4"], g1_size),
+            "z",
+            1,
+            false,
+        ),
+        nu_round(
+            &[
+                ("w_1", 0),
+                ("w_2", 1),
+                ("w_3", 2),
+                ("w_4", 3),
+                ("s", 4),
+                ("z_perm", 5),
+                ("z_lookup", 6),
+                ("q_1", 7),
+                ("q_2", 8),
+                ("q_3", 9),
+                ("q_4", 10),
+                ("q_m", 11),
+                ("q_c", 12),
+                ("q_arith", 13),
+                ("q_sort", 14),
+                ("q_elliptic", 15),
+                ("q_aux", 16),
+                ("sigma_1", 17),
+                ("sigma_2", 18),
+                ("sigma_3", 19),
+                ("sigma_4", 20),
+                ("table_value_1", 21),
+                ("table_value_2", 22),
+                ("table_value_3", 23),
+                ("table_value_4", 24),
+                ("table_type", 25),
+                ("id_1", 26),
+                ("id_2", 27),
+                ("id_3", 28),
+                ("id_4", 29),
+                ("w_1_omega", 0),
+                ("w_2_omega", 1),
+                ("w_3_omega", 2),
+                ("w_4_omega", 3),
+                ("s_omega", 4),
+                ("z_perm_omega", 5),
+                ("z_lookup_omega", 6),
+                ("table_value_1_omega", 21),
+                ("table_value_2_omega", 22),
+                ("table_value_3_omega", 23),
+                ("table_value_4_omega", 24),
+            ],
+            ULTRA_MANIFEST_SIZE,
+        ),
+        separator_round(3, g1_size),
+    ])
+}
+
+/// The transcript of a `composer_type` proof with `num_public_inputs` public inputs.
+pub fn create_manifest(composer_type: ComposerType, num_public_inputs: usize) -> Result<Manifest> {
+    Ok(match composer_type {
+        ComposerType::Standard => standard_manifest(num_public_inputs),
+        ComposerType::Turbo => turbo_manifest(num_public_inputs),
+        ComposerType::Plookup => ultra_manifest(num_public_inputs),
+        ComposerType::StandardHonk => bail!("honk proofs have no plonk transcript manifest"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ecc::curves::{Bls12_381, Bn254},
+        plonk::proof_system::types::PolynomialManifest,
+        transcript::{Keccak256, Transcript},
+    };
+
+    const COMPOSERS: [ComposerType; 3] = [
+        ComposerType::Standard,
+        ComposerType::Turbo,
+        ComposerType::Plookup,
+    ];
+
+    /// Bytes the prover sends, the size of a serialized proof.
+    fn proof_size(manifest: &Manifest) -> usize {
+        manifest
+            .round_manifests
+            .iter()
+            .flat_map(|round| &round.elements)
+            .filter(|element| !element.derived_by_verifier)
+            .map(|element| element.num_bytes)
+            .sum()
+    }
+
+    #[test]
+    fn rounds_follow_the_prover() {
+        for composer_type in COMPOSERS {
+            let manifest = create_manifest(composer_type, 0).unwrap();
+            let challenges: Vec<_> = manifest
+                .round_manifests
+                .iter()
+                .map(|round| round.challenge.as_str())
+                .collect();
+            assert_eq!(
+                challenges,
+                ["init", "eta", "beta", "alpha", "z", "nu", "separator"]
+            );
+            assert_eq!(manifest.get_num_rounds(), 7);
+        }
+        assert!(create_manifest(ComposerType::StandardHonk, 0).is_err());
+    }
+
+    #[test]
+    fn ultra_proof_size_matches_barretenberg() {
+        assert_eq!(proof_size(&ultra_manifest(0)), 2144);
+        assert_eq!(proof_size(&ultra_manifest(3)), 2144 + 3 * 32);
+    }
+
+    #[test]
+    fn every_polynomial_is_opened() {
+        for composer_type in COMPOSERS {
+            let manifest = create_manifest(composer_type, 0).unwrap();
+            let nu = manifest.get_round_manifest(5);
+            let polynomials = PolynomialManifest::new(composer_type as u32);
+            assert_eq!(nu.num_challenges, polynomials.len());
+            for descriptor in polynomials {
+                let label = &descriptor.polynomial_label;
+                let index = |name: &str| {
+                    nu.elements
+                        .iter()
+                        .find(|element| element.name == name)
+                        .map(|element| element.challenge_map_index)
+                };
+                let unshifted = index(label).unwrap_or_else(|| panic!("{} is not opened", label));
+                let shifted = index(&format!("{}_omega", label));
+                if descriptor.requires_shifted_evaluation {
+                    assert_eq!(shifted, Some(unshifted), "{}_omega", label);
+                } else {
+                    assert_eq!(shifted, None, "{}_omega", label);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn commitments_are_sized_by_the_curve() {
+        let bn254 = proof_size(&standard_manifest(1));
+        assert_eq!(proof_size(&standard_manifest_for::<Bn254>(1)), bn254);
+        // W_1..3, Z_PERM, T_1..3 and the two opening proofs
+        let g1_size = <Bls12_381 as CurveConfig>::G1Affine::UNCOMPRESSED_SIZE;
+        assert_eq!(
+            proof_size(&standard_manifest_for::<Bls12_381>(1)),
+            bn254 + 9 * (g1_size - G1_SIZE)
+        );
+        assert_eq!(
+            proof_size(&turbo_manifest_for::<Bn254>(2)),
+            proof_size(&turbo_manifest(2))
+        );
+    }
+
+    #[test]
+    fn serialized_proofs_parse_against_the_manifest() {
+        let manifest = turbo_manifest(2);
+        let proof: Vec<u8> = (0..proof_size(&manifest)).map(|i| i as u8).collect();
+        let transcript = Transcript::<Keccak256, Bn254>::new_from_transcript(&proof, manifest, 16);
+        assert_eq!(transcript.get_element("public_inputs"), proof[..64]);
+        assert_eq!(transcript.get_element("W_1"), proof[64..128]);
+        assert_eq!(transcript.get_element_size("PI_Z_OMEGA"), 64);
+        assert_eq!(
+            transcript
+                .get_challenge_index_from_map("w_4_omega")
+                .unwrap(),
+            3
+        );
+        assert_eq!(transcript.export_transcript(), proof);
+    }
+}
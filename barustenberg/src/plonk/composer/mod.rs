@@ -1 +1,3 @@
 pub(crate) mod composer_base;
+/// The transcript manifests of the composers.
+pub mod manifest;
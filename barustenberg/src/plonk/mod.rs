@@ -1,2 +1,3 @@
-pub(crate) mod composer;
+/// Circuit builders.
+pub mod composer;
 pub(crate) mod proof_system;
@@ -1,92 +1,185 @@
-use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
 
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One, Zero};
 
+use anyhow::{anyhow, ensure, Result};
+
+use crate::ecc::curves::{grumpkin, CurveConfig, Grumpkin, PairingCurveConfig};
+use crate::honk::pcs::ipa::{Ipa, IpaCommitmentKey};
+use crate::honk::pcs::{OpeningPair, ProverOpeningClaim};
+use crate::plonk::composer::composer_base::ComposerType;
 use crate::polynomials::{polynomial_arithmetic, Polynomial};
-use crate::proof_system::work_queue::{Work, WorkItem, WorkQueue};
+use crate::proof_system::work_queue::{Work, WorkExecutor, WorkItem, WorkQueue};
 use crate::transcript::{BarretenHasher, Transcript};
 
 use super::proving_key::ProvingKey;
+use super::types::polynomial_manifest::PolynomialSource;
 use super::types::proof::CommitmentOpenProof;
 use super::types::prover_settings::Settings;
 use super::verification_key::VerificationKey;
 
-/// A polynomial commitment scheme defined over two fields, a group, a hash function.
+/// A polynomial commitment scheme over a curve, with a hash function for its transcript.
 /// kate commitments are one example
-pub(crate) trait CommitmentScheme<
-    Fq: Field,
-    Fr: Field + FftField,
-    G1Affine: AffineRepr,
-    H: BarretenHasher,
->
-{
+pub(crate) trait CommitmentScheme<C: CurveConfig, H: BarretenHasher>: Send + Sync {
     fn commit(
         &mut self,
-        coefficients: Rc<RefCell<Polynomial<Fr>>>,
+        coefficients: Arc<RwLock<Polynomial<C::Fr>>>,
         tag: String,
-        item_constant: Fr,
-        queue: &mut WorkQueue<'_, H, Fr, G1Affine>,
+        item_constant: C::Fr,
+        queue: &mut WorkQueue<'_, H, C>,
     );
 
-    fn compute_opening_polynomial(&self, src: &[Fr], dest: &mut [Fr], z: &Fr, n: usize);
+    fn compute_opening_polynomial(&self, src: &[C::Fr], dest: &mut [C::Fr], z: &C::Fr, n: usize);
 
     #[allow(clippy::too_many_arguments)]
     fn generic_batch_open(
         &mut self,
-        src: &[Fr],
-        dest: Rc<RefCell<Polynomial<Fr>>>,
+        src: &[C::Fr],
+        dest: Arc<RwLock<Polynomial<C::Fr>>>,
         num_polynomials: usize,
-        z_points: &[Fr],
+        z_points: &[C::Fr],
         num_z_points: usize,
-        challenges: &[Fr],
+        challenges: &[C::Fr],
         n: usize,
         tags: &[String],
-        item_constants: &[Fr],
-        queue: &mut WorkQueue<'_, H, Fr, G1Affine>,
+        item_constants: &[C::Fr],
+        queue: &mut WorkQueue<'_, H, C>,
     );
 
+    /// Queues the commitments to the opening proofs of every polynomial in the key's manifest, at `z` and,
+    /// for those with shifted evaluations, at `z·ω`.
     fn batch_open<'a>(
         &mut self,
-        transcript: &Transcript<H, Fr, G1Affine>,
-        queue: &mut WorkQueue<'a, H, Fr, G1Affine>,
-        input_key: Option<Ref<'_, ProvingKey<'a, Fr, G1Affine>>>,
-    );
+        transcript: &Transcript<H, C>,
+        queue: &mut WorkQueue<'a, H, C>,
+        input_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+    ) -> Result<()>;
 
+    /// Adds the commitments and scalars of the batched opening to the verifier's multi-scalar multiplication.
     fn batch_verify<'a>(
         &self,
-        transcript: &Transcript<H, Fr, G1Affine>,
-        kate_g1_elements: &mut HashMap<String, G1Affine>,
-        kate_fr_elements: &mut HashMap<String, Fr>,
-        input_key: Option<&'a VerificationKey<'a, Fr>>,
-    );
+        transcript: &Transcript<H, C>,
+        kate_g1_elements: &mut HashMap<String, C::G1Affine>,
+        kate_fr_elements: &mut HashMap<String, C::Fr>,
+        input_key: Option<&'a VerificationKey<'a, C>>,
+    ) -> Result<()>;
 
+    /// Adds the evaluations at `z`, and at `z·ω` where the manifest asks for them, of every polynomial in the
+    /// key's manifest.
     fn add_opening_evaluations_to_transcript<'a>(
         &self,
-        transcript: &mut Transcript<H, Fr, G1Affine>,
-        input_key: Option<&'a ProvingKey<'a, Fr, G1Affine>>,
+        transcript: &mut Transcript<H, C>,
+        input_key: Option<&'a ProvingKey<'a, C>>,
         in_lagrange_form: bool,
+    ) -> Result<()>;
+}
+
+/// Adds the evaluations at `z`, and at `z·ω` where the manifest asks for them, of every polynomial in the
+/// key's manifest.
+fn add_opening_evaluations<C: CurveConfig, H: BarretenHasher>(
+    transcript: &mut Transcript<H, C>,
+    input_key: Option<&ProvingKey<'_, C>>,
+    in_lagrange_form: bool,
+) -> Result<()> {
+    let key = input_key.ok_or_else(|| anyhow!("opening evaluations need a proving key"))?;
+    // barretenberg evaluates the lagrange forms barycentrically, which nothing here needs yet
+    ensure!(
+        !in_lagrange_form,
+        "opening evaluations are only computed from monomial forms"
     );
+    let n = key.circuit_size;
+    let zeta = transcript.get_challenge_field_element("z", None);
+    let shifted_zeta = zeta * key.small_domain.root;
+
+    for descriptor in key.polynomial_manifest.iter() {
+        let label = &descriptor.polynomial_label;
+        let polynomial = key.polynomial_store.get(label)?;
+        let polynomial = polynomial.read().unwrap();
+        let evaluation = polynomial_arithmetic::evaluate(&polynomial.coefficients, &zeta, n);
+        transcript.add_field_element(label, &evaluation);
+        if descriptor.requires_shifted_evaluation {
+            let evaluation =
+                polynomial_arithmetic::evaluate(&polynomial.coefficients, &shifted_zeta, n);
+            transcript.add_field_element(&format!("{}_omega", label), &evaluation);
+        }
+    }
+    Ok(())
+}
+
+/// The coefficients of the polynomials opened at ʓ and at ʓ.ω, in that order.
+type OpeningPolynomials<F> = (Vec<F>, Vec<F>);
+
+/// The polynomials opened at ʓ and at ʓ.ω. The one opened at ʓ is
+///
+///   t_1(X) + ʓ^n.t_2(X) + ... + ʓ^{(w - 1)n}.t_w(X) + Σ ν_i.p_i(X)
+///
+/// over the first `num_quotient_parts` parts of t, and the verifier reconstructs its evaluation at ʓ from t(ʓ)
+/// and the p_i(ʓ). The quotient parts carry a blinding coefficient at X^n, so it has n + 1 coefficients. The
+/// one opened at ʓ.ω batches the polynomials with shifted evaluations, each with the same ν as at ʓ.
+fn batch_opening_polynomials<C: CurveConfig, H: BarretenHasher>(
+    transcript: &Transcript<H, C>,
+    key: &ProvingKey<'_, C>,
+    num_quotient_parts: usize,
+) -> Result<OpeningPolynomials<C::Fr>> {
+    let n = key.circuit_size;
+    let zeta = transcript.get_challenge_field_element("z", None);
+    let zeta_pow_n = zeta.pow([n as u64]);
+
+    let mut opening_poly = vec![C::Fr::zero(); n + 1];
+    let mut shifted_opening_poly = vec![C::Fr::zero(); n];
+    let mut quotient_multiplier = C::Fr::one();
+    for part in key
+        .quotient_polynomial_parts
+        .iter()
+        .take(num_quotient_parts)
+    {
+        let part = part.read().unwrap();
+        for (opening_coeff, coeff) in opening_poly.iter_mut().zip(&part.coefficients) {
+            *opening_coeff += quotient_multiplier * coeff;
+        }
+        quotient_multiplier *= zeta_pow_n;
+    }
+    for descriptor in key.polynomial_manifest.iter() {
+        let label = &descriptor.polynomial_label;
+        let polynomial = key.polynomial_store.get(label)?;
+        let polynomial = polynomial.read().unwrap();
+        let nu = transcript.get_challenge_field_element_from_map("nu", label);
+        for (opening_coeff, coeff) in opening_poly.iter_mut().zip(&polynomial.coefficients[..n]) {
+            *opening_coeff += nu * coeff;
+        }
+        if descriptor.requires_shifted_evaluation {
+            let nu =
+                transcript.get_challenge_field_element_from_map("nu", &format!("{}_omega", label));
+            for (opening_coeff, coeff) in shifted_opening_poly
+                .iter_mut()
+                .zip(&polynomial.coefficients[..n])
+            {
+                *opening_coeff += nu * coeff;
+            }
+        }
+    }
+    Ok((opening_poly, shifted_opening_poly))
 }
 
 #[derive(Default)]
 pub(crate) struct KateCommitmentScheme<H: BarretenHasher, S: Settings<H>> {
     _kate_open_proof: CommitmentOpenProof,
-    phantom: PhantomData<(H, S)>,
+    settings: S,
+    phantom: PhantomData<H>,
 }
 
-impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S: Settings<H>>
-    CommitmentScheme<Fq, Fr, G1Affine, H> for KateCommitmentScheme<H, S>
+impl<C: PairingCurveConfig, H: BarretenHasher, S: Settings<H>> CommitmentScheme<C, H>
+    for KateCommitmentScheme<H, S>
 {
     fn commit(
         &mut self,
-        coefficients: Rc<RefCell<Polynomial<Fr>>>,
+        coefficients: Arc<RwLock<Polynomial<C::Fr>>>,
         tag: String,
-        item_constant: Fr,
-        queue: &mut WorkQueue<'_, H, Fr, G1Affine>,
+        item_constant: C::Fr,
+        queue: &mut WorkQueue<'_, H, C>,
     ) {
         queue.add_to_queue(WorkItem {
             work: Work::ScalarMultiplication {
@@ -99,29 +192,36 @@ impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S
 
     fn add_opening_evaluations_to_transcript<'a>(
         &self,
-        _transcript: &mut Transcript<H, Fr, G1Affine>,
-        _input_key: Option<&'a ProvingKey<'a, Fr, G1Affine>>,
-        _in_lagrange_form: bool,
-    ) {
-        todo!()
+        transcript: &mut Transcript<H, C>,
+        input_key: Option<&'a ProvingKey<'a, C>>,
+        in_lagrange_form: bool,
+    ) -> Result<()> {
+        add_opening_evaluations(transcript, input_key, in_lagrange_form)
     }
 
-    fn compute_opening_polynomial(&self, _src: &[Fr], _dest: &mut [Fr], _z: &Fr, _n: usize) {
-        todo!()
+    fn compute_opening_polynomial(&self, src: &[C::Fr], dest: &mut [C::Fr], z: &C::Fr, n: usize) {
+        // W(X) = (F(X) - F(z)) / (X - z). Comparing coefficients of F(X) - F(z) = (X - z).W(X) from the bottom
+        // up gives w_0 = (f_0 - F(z)) / (-z) and w_i = (f_i - w_{i - 1}) / (-z).
+        let f_eval = polynomial_arithmetic::evaluate(src, z, n);
+        let divisor = (-*z).inverse().unwrap();
+        dest[0] = (src[0] - f_eval) * divisor;
+        for i in 1..n {
+            dest[i] = (src[i] - dest[i - 1]) * divisor;
+        }
     }
 
     fn generic_batch_open(
         &mut self,
-        src: &[Fr],
-        dest: Rc<RefCell<Polynomial<Fr>>>,
+        src: &[C::Fr],
+        dest: Arc<RwLock<Polynomial<C::Fr>>>,
         num_polynomials: usize,
-        z_points: &[Fr],
+        z_points: &[C::Fr],
         num_z_points: usize,
-        challenges: &[Fr],
+        challenges: &[C::Fr],
         n: usize,
         tags: &[String],
-        item_constants: &[Fr],
-        queue: &mut WorkQueue<'_, H, Fr, G1Affine>,
+        item_constants: &[C::Fr],
+        queue: &mut WorkQueue<'_, H, C>,
     ) {
         // In this function, we compute the opening polynomials using Kate scheme for multiple input
         // polynomials with multiple evaluation points. The input polynomials are separated according
@@ -145,7 +245,7 @@ impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S
         // opening test case.
 
         // compute [-z, -z', ... ]
-        let mut divisors = vec![Fr::zero(); num_z_points];
+        let mut divisors = vec![C::Fr::zero(); num_z_points];
         for i in 0..num_z_points {
             divisors[i] = -z_points[i];
         }
@@ -157,7 +257,7 @@ impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S
 
         for i in 0..num_z_points {
             {
-                let mut dest_mut = dest.borrow_mut();
+                let mut dest_mut = dest.write().unwrap();
                 let challenge = challenges[i];
                 let divisor = divisors[i];
                 let src_offset = i * n * num_polynomials;
@@ -166,8 +266,8 @@ impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S
                 // compute i-th linear combination polynomial
                 // F_i(X) = \sum_{j = 1, 2, ..., num_poly} \gamma^{j - 1} * f_{i, j}(X)
                 for k in 0..n {
-                    let mut coeff_sum = Fr::zero();
-                    let mut challenge_pow = Fr::one();
+                    let mut coeff_sum = C::Fr::zero();
+                    let mut challenge_pow = C::Fr::one();
                     for j in 0..num_polynomials {
                         coeff_sum += challenge_pow * src[src_offset + (j * n) + k];
                         challenge_pow *= challenge;
@@ -189,7 +289,7 @@ impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S
                 }
             }
             // commit to the i-th opened polynomial
-            <KateCommitmentScheme<H, S> as CommitmentScheme<Fq, Fr, G1Affine, H>>::commit(
+            <KateCommitmentScheme<H, S> as CommitmentScheme<C, H>>::commit(
                 self,
                 dest.clone(),
                 tags[i].clone(),
@@ -201,28 +301,558 @@ impl<Fq: Field, Fr: Field + FftField, G1Affine: AffineRepr, H: BarretenHasher, S
 
     fn batch_open<'a>(
         &mut self,
-        _transcript: &Transcript<H, Fr, G1Affine>,
-        _queue: &mut WorkQueue<'a, H, Fr, G1Affine>,
-        _input_key: Option<Ref<'_, ProvingKey<'a, Fr, G1Affine>>>,
-    ) {
-        todo!()
+        transcript: &Transcript<H, C>,
+        queue: &mut WorkQueue<'a, H, C>,
+        input_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+    ) -> Result<()> {
+        let input_key = input_key.ok_or_else(|| anyhow!("batch opening needs a proving key"))?;
+        let key = input_key.read().unwrap();
+        let n = key.circuit_size;
+        let zeta = transcript.get_challenge_field_element("z", None);
+        let (opening_poly, shifted_opening_poly) =
+            batch_opening_polynomials(transcript, &key, self.settings.program_width())?;
+
+        let mut opening_proof = Polynomial::new(n + 1);
+        <KateCommitmentScheme<H, S> as CommitmentScheme<C, H>>::compute_opening_polynomial(
+            self,
+            &opening_poly,
+            &mut opening_proof.coefficients,
+            &zeta,
+            n + 1,
+        );
+        let mut shifted_opening_proof = Polynomial::new(n);
+        <KateCommitmentScheme<H, S> as CommitmentScheme<C, H>>::compute_opening_polynomial(
+            self,
+            &shifted_opening_poly,
+            &mut shifted_opening_proof.coefficients,
+            &(zeta * key.small_domain.root),
+            n,
+        );
+
+        // dividing out (X - z) leaves the top coefficient at zero, so n points are enough for either
+        for (proof, tag) in [
+            (opening_proof, "PI_Z"),
+            (shifted_opening_proof, "PI_Z_OMEGA"),
+        ] {
+            <KateCommitmentScheme<H, S> as CommitmentScheme<C, H>>::commit(
+                self,
+                Arc::new(RwLock::new(proof)),
+                tag.to_string(),
+                C::Fr::from(n as u64),
+                queue,
+            );
+        }
+        Ok(())
     }
 
     fn batch_verify<'a>(
         &self,
-        _transcript: &Transcript<H, Fr, G1Affine>,
-        _kate_g1_elements: &mut HashMap<String, G1Affine>,
-        _kate_fr_elements: &mut HashMap<String, Fr>,
-        _input_key: Option<&'a VerificationKey<'a, Fr>>,
+        transcript: &Transcript<H, C>,
+        kate_g1_elements: &mut HashMap<String, C::G1Affine>,
+        kate_fr_elements: &mut HashMap<String, C::Fr>,
+        input_key: Option<&'a VerificationKey<'a, C>>,
+    ) -> Result<()> {
+        let key =
+            input_key.ok_or_else(|| anyhow!("batch verification needs a verification key"))?;
+        let zeta = transcript.get_challenge_field_element("z", None);
+        let separator = transcript.get_challenge_field_element("separator", None);
+
+        // Each polynomial enters the batch once, with ν_i for its opening at ʓ plus separator.ν_i for the one at
+        // ʓ.ω. The claimed evaluations are batched the same way into a single multiple of [1]₁.
+        let mut batch_evaluation = C::Fr::zero();
+        for descriptor in key.polynomial_manifest.iter() {
+            let label = &descriptor.polynomial_label;
+            let nu = transcript.get_challenge_field_element_from_map("nu", label);
+            let mut kate_scalar = nu;
+            batch_evaluation += nu * transcript.get_field_element(label);
+            if descriptor.requires_shifted_evaluation {
+                let shifted_label = format!("{}_omega", label);
+                let nu = transcript.get_challenge_field_element_from_map("nu", &shifted_label)
+                    * separator;
+                kate_scalar += nu;
+                batch_evaluation += nu * transcript.get_field_element(&shifted_label);
+            }
+
+            let commitment_label = &descriptor.commitment_label;
+            let commitment = match descriptor.source {
+                PolynomialSource::Witness => transcript.get_group_element(commitment_label)?,
+                _ => *key.commitments.get(commitment_label).ok_or_else(|| {
+                    anyhow!("verification key has no commitment {}", commitment_label)
+                })?,
+            };
+            kate_g1_elements.insert(commitment_label.clone(), commitment);
+            kate_fr_elements.insert(commitment_label.clone(), kate_scalar);
+        }
+
+        // t(ʓ) is opened through its parts, t_1 + ʓ^n.t_2 + ..., and its ν is one
+        let zeta_pow_n = zeta.pow([key.circuit_size as u64]);
+        let mut quotient_multiplier = C::Fr::one();
+        for i in 0..key.program_width {
+            let quotient_label = format!("T_{}", i + 1);
+            kate_g1_elements.insert(
+                quotient_label.clone(),
+                transcript.get_group_element(&quotient_label)?,
+            );
+            kate_fr_elements.insert(quotient_label, quotient_multiplier);
+            quotient_multiplier *= zeta_pow_n;
+        }
+        batch_evaluation += transcript.get_field_element("t");
+
+        kate_g1_elements.insert("BATCH_EVALUATION".to_string(), C::G1Affine::generator());
+        kate_fr_elements.insert("BATCH_EVALUATION".to_string(), -batch_evaluation);
+        Ok(())
+    }
+}
+
+/// Does the work of the queue that the IPA scheme cannot hand to the proving key's reference string: Pedersen
+/// commitments with the scheme's own generators, and adding the opening proofs to the transcript.
+///
+/// The prover reads its transcript while it batch-opens, so the proofs wait here until the queue is processed.
+#[derive(Debug)]
+struct IpaWorkExecutor {
+    key: IpaCommitmentKey<grumpkin::Affine>,
+    opening_proofs: Mutex<Vec<(String, Vec<u8>)>>,
+}
+
+impl<'a, H: BarretenHasher> WorkExecutor<'a, H, Grumpkin> for IpaWorkExecutor {
+    fn execute(&self, queue: &WorkQueue<'a, H, Grumpkin>) -> Result<()> {
+        let num_commitments = queue.get_queued_work_item_info().num_scalar_multiplications;
+        for i in 0..num_commitments {
+            let polynomial = queue
+                .get_scalar_multiplication_data(i)
+                .ok_or_else(|| anyhow!("no scalar multiplication {} in the queue", i))?;
+            let polynomial = polynomial.read().unwrap();
+            // the size is a degree bound, and coefficients past the end of the polynomial are zero
+            let size = std::cmp::min(
+                queue.get_scalar_multiplication_size(i),
+                polynomial.coefficients.len(),
+            );
+            ensure!(
+                size <= self.key.generators.len(),
+                "commitment of size {} exceeds the {} generators of the IPA key",
+                size,
+                self.key.generators.len()
+            );
+            queue.put_scalar_multiplication_data(
+                self.key.commit(&polynomial.coefficients[..size]),
+                i,
+            )?;
+        }
+        for (tag, proof) in self.opening_proofs.lock().unwrap().drain(..) {
+            queue.put_opening_proof_data(&tag, proof);
+        }
+        Ok(())
+    }
+}
+
+/// Pedersen vector commitments over Grumpkin, opened with an inner product argument.
+///
+/// Commitments and opening proofs go through the work queue, whose executor is set on the first commitment.
+/// A batched opening is the same pair of polynomials Kate opens at ʓ and ʓ.ω, and each gets an IPA proof in
+/// place of a quotient commitment, under `PI_Z` and `PI_Z_OMEGA`. [`batch_verify`](CommitmentScheme::batch_verify)
+/// checks both proofs itself, so it leaves nothing in the pairing maps. The proofs are longer than the group
+/// elements the Kate manifests reserve for those tags, so such a transcript cannot be exported with them.
+///
+/// Grumpkin's scalar field is the base field of BN254, which only has roots of unity of order 2, so Plonk
+/// over Grumpkin is limited to circuits of two rows.
+#[derive(Debug)]
+pub(crate) struct IpaCommitmentScheme<H: BarretenHasher> {
+    pub(crate) key: IpaCommitmentKey<grumpkin::Affine>,
+    executor: Arc<IpaWorkExecutor>,
+    phantom: PhantomData<H>,
+}
+
+impl<H: BarretenHasher> IpaCommitmentScheme<H> {
+    pub(crate) fn new(key: IpaCommitmentKey<grumpkin::Affine>) -> Self {
+        Self {
+            executor: Arc::new(IpaWorkExecutor {
+                key: key.clone(),
+                opening_proofs: Mutex::new(vec![]),
+            }),
+            key,
+            phantom: PhantomData,
+        }
+    }
+
+    pub(crate) fn compute_opening_proof(
+        &self,
+        polynomial: Polynomial<grumpkin::Fr>,
+        opening_pair: OpeningPair<grumpkin::Fr>,
+        transcript: &mut Transcript<H>,
+    ) -> Result<()> {
+        Ipa::compute_opening_proof(
+            &self.key,
+            ProverOpeningClaim {
+                polynomial,
+                opening_pair,
+            },
+            transcript,
+        )
+    }
+
+    pub(crate) fn verify(
+        &self,
+        commitment: grumpkin::Affine,
+        opening_pair: &OpeningPair<grumpkin::Fr>,
+        num_coefficients: usize,
+        transcript: &mut Transcript<H>,
+    ) -> Result<bool> {
+        Ipa::verify(
+            &self.key,
+            commitment,
+            opening_pair,
+            num_coefficients,
+            transcript,
+        )
+    }
+}
+
+impl<H: BarretenHasher> CommitmentScheme<Grumpkin, H> for IpaCommitmentScheme<H> {
+    fn commit(
+        &mut self,
+        coefficients: Arc<RwLock<Polynomial<grumpkin::Fr>>>,
+        tag: String,
+        item_constant: grumpkin::Fr,
+        queue: &mut WorkQueue<'_, H, Grumpkin>,
     ) {
-        todo!()
+        queue.set_executor(self.executor.clone());
+        queue.add_to_queue(WorkItem {
+            work: Work::ScalarMultiplication {
+                mul_scalars: coefficients,
+                constant: item_constant,
+            },
+            tag,
+        })
+    }
+
+    fn add_opening_evaluations_to_transcript<'a>(
+        &self,
+        transcript: &mut Transcript<H, Grumpkin>,
+        input_key: Option<&'a ProvingKey<'a, Grumpkin>>,
+        in_lagrange_form: bool,
+    ) -> Result<()> {
+        add_opening_evaluations(transcript, input_key, in_lagrange_form)
+    }
+
+    /// The inner product argument opens the polynomial itself, so there is no quotient to compute.
+    fn compute_opening_polynomial(
+        &self,
+        src: &[grumpkin::Fr],
+        dest: &mut [grumpkin::Fr],
+        _z: &grumpkin::Fr,
+        n: usize,
+    ) {
+        dest[..n].copy_from_slice(&src[..n]);
+    }
+
+    /// Batches the polynomials opened at each point with powers of that point's challenge, and commits to each
+    /// batch. Unlike Kate the batches are not divided by `X - zᵢ`: each is opened with its own inner product
+    /// argument.
+    fn generic_batch_open(
+        &mut self,
+        src: &[grumpkin::Fr],
+        dest: Arc<RwLock<Polynomial<grumpkin::Fr>>>,
+        num_polynomials: usize,
+        _z_points: &[grumpkin::Fr],
+        num_z_points: usize,
+        challenges: &[grumpkin::Fr],
+        n: usize,
+        tags: &[String],
+        item_constants: &[grumpkin::Fr],
+        queue: &mut WorkQueue<'_, H, Grumpkin>,
+    ) {
+        for i in 0..num_z_points {
+            {
+                let mut dest_mut = dest.write().unwrap();
+                let src_offset = i * n * num_polynomials;
+                let dest_offset = i * n;
+                for k in 0..n {
+                    let mut coeff_sum = grumpkin::Fr::zero();
+                    let mut challenge_pow = grumpkin::Fr::one();
+                    for j in 0..num_polynomials {
+                        coeff_sum += challenge_pow * src[src_offset + (j * n) + k];
+                        challenge_pow *= challenges[i];
+                    }
+                    dest_mut[dest_offset + k] = coeff_sum;
+                }
+            }
+            <IpaCommitmentScheme<H> as CommitmentScheme<Grumpkin, H>>::commit(
+                self,
+                dest.clone(),
+                tags[i].clone(),
+                item_constants[i],
+                queue,
+            );
+        }
+    }
+
+    /// Proves the openings at ʓ and ʓ.ω, each padded to a power of two coefficients, and queues the proofs for
+    /// the executor to add to the transcript.
+    fn batch_open<'a>(
+        &mut self,
+        transcript: &Transcript<H, Grumpkin>,
+        queue: &mut WorkQueue<'a, H, Grumpkin>,
+        input_key: Option<Arc<RwLock<ProvingKey<'a, Grumpkin>>>>,
+    ) -> Result<()> {
+        let input_key = input_key.ok_or_else(|| anyhow!("batch opening needs a proving key"))?;
+        let key = input_key.read().unwrap();
+        let zeta = transcript.get_challenge_field_element("z", None);
+        let num_quotient_parts = if key.composer_type == ComposerType::Standard as u32 {
+            3
+        } else {
+            4
+        };
+        let (opening_poly, shifted_opening_poly) =
+            batch_opening_polynomials(transcript, &key, num_quotient_parts)?;
+
+        let mut opening_proofs = Vec::with_capacity(2);
+        for (mut coefficients, challenge, tag) in [
+            (opening_poly, zeta, "PI_Z"),
+            (
+                shifted_opening_poly,
+                zeta * key.small_domain.root,
+                "PI_Z_OMEGA",
+            ),
+        ] {
+            let size = coefficients.len().next_power_of_two();
+            coefficients.resize(size, grumpkin::Fr::zero());
+            let opening_pair = OpeningPair {
+                challenge,
+                evaluation: polynomial_arithmetic::evaluate(&coefficients, &challenge, size),
+            };
+            let mut proof_transcript = Transcript::<H>::new_streaming();
+            self.compute_opening_proof(
+                Polynomial::from_coefficients(coefficients, size),
+                opening_pair,
+                &mut proof_transcript,
+            )?;
+            opening_proofs.push((tag.to_string(), proof_transcript.export_transcript()));
+        }
+        self.executor
+            .opening_proofs
+            .lock()
+            .unwrap()
+            .extend(opening_proofs);
+        queue.set_executor(self.executor.clone());
+        Ok(())
+    }
+
+    /// Rebuilds the commitments and evaluations of both batched polynomials from the transcript and the key, and
+    /// verifies their opening proofs. The pairing maps are left untouched.
+    fn batch_verify<'a>(
+        &self,
+        transcript: &Transcript<H, Grumpkin>,
+        _kate_g1_elements: &mut HashMap<String, grumpkin::Affine>,
+        _kate_fr_elements: &mut HashMap<String, grumpkin::Fr>,
+        input_key: Option<&'a VerificationKey<'a, Grumpkin>>,
+    ) -> Result<()> {
+        let key =
+            input_key.ok_or_else(|| anyhow!("batch verification needs a verification key"))?;
+        let n = key.circuit_size;
+        let zeta = transcript.get_challenge_field_element("z", None);
+
+        let mut commitment = grumpkin::Projective::zero();
+        let mut evaluation = grumpkin::Fr::zero();
+        let mut shifted_commitment = grumpkin::Projective::zero();
+        let mut shifted_evaluation = grumpkin::Fr::zero();
+        for descriptor in key.polynomial_manifest.iter() {
+            let label = &descriptor.polynomial_label;
+            let commitment_label = &descriptor.commitment_label;
+            let polynomial_commitment = match descriptor.source {
+                PolynomialSource::Witness => transcript.get_group_element(commitment_label)?,
+                _ => *key.commitments.get(commitment_label).ok_or_else(|| {
+                    anyhow!("verification key has no commitment {}", commitment_label)
+                })?,
+            };
+            let nu = transcript.get_challenge_field_element_from_map("nu", label);
+            commitment += polynomial_commitment * nu;
+            evaluation += nu * transcript.get_field_element(label);
+            if descriptor.requires_shifted_evaluation {
+                let shifted_label = format!("{}_omega", label);
+                let nu = transcript.get_challenge_field_element_from_map("nu", &shifted_label);
+                shifted_commitment += polynomial_commitment * nu;
+                shifted_evaluation += nu * transcript.get_field_element(&shifted_label);
+            }
+        }
+
+        // t(ʓ) is opened through its parts, t_1 + ʓ^n.t_2 + ...
+        let zeta_pow_n = zeta.pow([n as u64]);
+        let mut quotient_multiplier = grumpkin::Fr::one();
+        for i in 0..key.program_width {
+            let quotient: grumpkin::Affine =
+                transcript.get_group_element(&format!("T_{}", i + 1))?;
+            commitment += quotient * quotient_multiplier;
+            quotient_multiplier *= zeta_pow_n;
+        }
+        evaluation += transcript.get_field_element("t");
+
+        for (commitment, opening_pair, num_coefficients, tag) in [
+            (
+                commitment,
+                OpeningPair {
+                    challenge: zeta,
+                    evaluation,
+                },
+                (n + 1).next_power_of_two(),
+                "PI_Z",
+            ),
+            (
+                shifted_commitment,
+                OpeningPair {
+                    challenge: zeta * key.domain.root,
+                    evaluation: shifted_evaluation,
+                },
+                n.next_power_of_two(),
+                "PI_Z_OMEGA",
+            ),
+        ] {
+            let mut proof_transcript =
+                Transcript::<H>::new_streaming_from_proof(&transcript.get_element(tag));
+            let verified = self.verify(
+                commitment.into_affine(),
+                &opening_pair,
+                num_coefficients,
+                &mut proof_transcript,
+            )?;
+            ensure!(
+                verified && proof_transcript.is_exhausted(),
+                "IPA opening proof {} does not verify",
+                tag
+            );
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::plonk::composer::manifest::standard_manifest_for;
+    use crate::plonk::proof_system::types::PolynomialManifest;
+    use crate::polynomials::evaluation_domain::EvaluationDomain;
+    use crate::srs::reference_string::VerifierReferenceString;
+    use crate::transcript::Keccak256;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
     #[test]
     fn test_commitment_scheme() {
         todo!("see commitment_scheme.test.cpp")
     }
+
+    /// Pedersen commitments have no `[τ]₂`.
+    struct NoG2;
+
+    impl VerifierReferenceString<grumpkin::Affine> for NoG2 {
+        fn get_g2x(&self) -> grumpkin::Affine {
+            grumpkin::Affine::zero()
+        }
+    }
+
+    /// Commits to every polynomial of a two-row standard circuit through the IPA scheme, batch-opens them and
+    /// checks the openings, with `tamper` applied to the transcript before verifying.
+    fn ipa_round_trip(tamper: impl FnOnce(&mut Transcript<Keccak256, Grumpkin>)) -> Result<()> {
+        const N: usize = 2;
+        let composer_type = ComposerType::Standard as u32;
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut random_polynomial = |size: usize| {
+            Polynomial::from_coefficients(
+                (0..size).map(|_| grumpkin::Fr::rand(&mut rng)).collect(),
+                size,
+            )
+        };
+        let mut scheme = IpaCommitmentScheme::<Keccak256>::new(IpaCommitmentKey::new(4));
+        let mut verification_key =
+            VerificationKey::<Grumpkin>::new(N, 0, Arc::new(NoG2), composer_type);
+
+        let mut key = ProvingKey::<Grumpkin> {
+            composer_type,
+            circuit_size: N,
+            small_domain: EvaluationDomain::new(N, None),
+            polynomial_manifest: PolynomialManifest::new(composer_type),
+            ..Default::default()
+        };
+        let mut witnesses = vec![];
+        for descriptor in PolynomialManifest::new(composer_type).iter() {
+            let polynomial = random_polynomial(N);
+            if descriptor.source == PolynomialSource::Witness {
+                witnesses.push((descriptor.commitment_label.clone(), polynomial.clone()));
+            } else {
+                verification_key.commitments.insert(
+                    descriptor.commitment_label.clone(),
+                    scheme.key.commit(&polynomial.coefficients),
+                );
+            }
+            key.polynomial_store
+                .put(descriptor.polynomial_label.clone(), polynomial)?;
+        }
+        for (i, part) in key.quotient_polynomial_parts.iter_mut().take(3).enumerate() {
+            *part = Arc::new(RwLock::new(random_polynomial(N + 1)));
+            witnesses.push((format!("T_{}", i + 1), part.read().unwrap().clone()));
+        }
+
+        let mut transcript =
+            Transcript::<Keccak256, Grumpkin>::new(Some(standard_manifest_for::<Grumpkin>(0)), 16);
+        transcript.mock_inputs_prior_to_challenge("nu", N)?;
+        let zeta = transcript.get_challenge_field_element("z", None);
+        let t = key.quotient_polynomial_parts.iter().take(3).rev().fold(
+            grumpkin::Fr::zero(),
+            |acc, part| {
+                acc * zeta.pow([N as u64])
+                    + polynomial_arithmetic::evaluate(
+                        &part.read().unwrap().coefficients,
+                        &zeta,
+                        N + 1,
+                    )
+            },
+        );
+        scheme.add_opening_evaluations_to_transcript(&mut transcript, Some(&key), false)?;
+        transcript.add_field_element("t", &t);
+        transcript.apply_fiat_shamir("nu")?;
+
+        let key = Arc::new(RwLock::new(key));
+        let transcript = Arc::new(RwLock::new(transcript));
+        let mut queue = WorkQueue::new(Some(key.clone()), Some(transcript.clone()));
+        for (tag, polynomial) in witnesses {
+            let size = polynomial.size();
+            <IpaCommitmentScheme<Keccak256> as CommitmentScheme<Grumpkin, _>>::commit(
+                &mut scheme,
+                Arc::new(RwLock::new(polynomial)),
+                tag,
+                grumpkin::Fr::from(size as u64),
+                &mut queue,
+            );
+        }
+        queue.process_queue()?;
+        scheme.batch_open(&transcript.read().unwrap(), &mut queue, Some(key))?;
+        queue.process_queue()?;
+
+        let mut transcript = transcript.write().unwrap();
+        tamper(&mut transcript);
+        scheme.batch_verify(
+            &transcript,
+            &mut HashMap::new(),
+            &mut HashMap::new(),
+            Some(&verification_key),
+        )
+    }
+
+    #[test]
+    fn ipa_batch_opening_verifies() {
+        ipa_round_trip(|_| {}).unwrap();
+    }
+
+    #[test]
+    fn ipa_batch_opening_of_a_wrong_evaluation_is_rejected() {
+        assert!(ipa_round_trip(|transcript| {
+            let t = transcript.get_field_element("t");
+            transcript.add_field_element("t", &(t + grumpkin::Fr::one()));
+        })
+        .is_err());
+        assert!(ipa_round_trip(|transcript| {
+            let z_perm = transcript.get_field_element("z_perm_omega");
+            transcript.add_field_element("z_perm_omega", &(z_perm + grumpkin::Fr::one()));
+        })
+        .is_err());
+    }
 }
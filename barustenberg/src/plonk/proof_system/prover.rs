@@ -1,24 +1,40 @@
-use std::{cell::RefCell, marker::PhantomData, ops::IndexMut, rc::Rc};
+use std::{
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use ark_ff::{Field, One, UniformRand, Zero};
+use rand::{rngs::StdRng, SeedableRng};
 
 use super::{
     commitment_scheme::{CommitmentScheme, KateCommitmentScheme},
     proving_key::ProvingKey,
-    types::{prover_settings::Settings, Proof},
+    types::{polynomial_manifest::ProverRound, prover_settings::Settings, Proof},
     widgets::{
-        random_widgets::random_widget::ProverRandomWidget,
-        transition_widgets::transition_widget::TransitionWidgetBase,
+        random_widgets::{
+            permutation_widget::ProverPermutationWidget, plookup_widget::ProverPlookupWidget,
+            random_widget::ProverRandomWidget,
+        },
+        transition_widgets::{
+            arithmetic_widget::ProverArithmeticWidget, transition_widget::TransitionWidgetBase,
+            turbo_arithmetic_widget::ProverTurboArithmeticWidget,
+            turbo_fixed_base_widget::ProverTurboFixedBaseWidget,
+            turbo_logic_widget::ProverTurboLogicWidget, turbo_range_widget::ProverTurboRangeWidget,
+            ultra_arithmetic_widget::ProverUltraArithmeticWidget,
+            ultra_aux_widget::ProverUltraAuxWidget,
+            ultra_elliptic_widget::ProverUltraEllipticWidget,
+            ultra_sort_widget::ProverUltraSortWidget,
+        },
     },
 };
 
-use typenum::Unsigned;
-
 use crate::{
+    ecc::curves::{CurveConfig, PairingCurveConfig},
+    plonk::composer::manifest::{standard_manifest_for, turbo_manifest_for, ultra_manifest_for},
     polynomials::{polynomial_arithmetic, Polynomial},
     proof_system::work_queue::{self, Work, WorkItem},
-    transcript::{BarretenHasher, Manifest, Transcript},
+    transcript::{trace::write_trace, BarretenHasher, Manifest, Transcript},
 };
 
 use anyhow::{ensure, Result};
@@ -29,52 +45,47 @@ use crate::proof_system::work_queue::WorkQueue;
 
 pub(crate) struct Prover<
     'a,
-    Fq: Field,
-    Fr: Field + FftField,
-    G1Affine: AffineRepr,
+    C: CurveConfig,
     H: BarretenHasher,
     S: Settings<H>,
-    CS: CommitmentScheme<Fq, Fr, G1Affine, H>,
+    CS: CommitmentScheme<C, H>,
 > {
     pub(crate) circuit_size: usize,
-    pub(crate) transcript: Rc<RefCell<Transcript<H, Fr, G1Affine>>>,
-    pub(crate) key: Rc<RefCell<ProvingKey<'a, Fr, G1Affine>>>,
-    pub(crate) queue: WorkQueue<'a, H, Fr, G1Affine>,
-    pub(crate) random_widgets: Vec<Box<dyn ProverRandomWidget<'a, H, Fr, G1Affine>>>,
-    pub(crate) transition_widgets: Vec<Box<dyn TransitionWidgetBase<'a, H, Fr, G1Affine>>>,
+    pub(crate) transcript: Arc<RwLock<Transcript<H, C>>>,
+    pub(crate) key: Arc<RwLock<ProvingKey<'a, C>>>,
+    pub(crate) queue: WorkQueue<'a, H, C>,
+    pub(crate) random_widgets: Vec<Box<dyn ProverRandomWidget<'a, H, C> + 'a>>,
+    pub(crate) transition_widgets: Vec<Box<dyn TransitionWidgetBase<'a, H, C> + 'a>>,
     pub(crate) commitment_scheme: CS,
     pub(crate) settings: S,
-    pub(crate) rng: Box<dyn rand::RngCore>,
-    phantom: PhantomData<Fq>,
+    pub(crate) rng: Box<dyn rand::RngCore + Send>,
+    /// Where [`construct_proof`](Self::construct_proof) writes the transcript's trace, if anywhere.
+    trace_path: Option<PathBuf>,
+    phantom: PhantomData<C>,
 }
 
-impl<
-        'a,
-        Fq: Field,
-        Fr: Field + FftField,
-        G1Affine: AffineRepr,
-        H: BarretenHasher + Default,
-        S: Settings<H> + Default,
-    > Prover<'a, Fq, Fr, G1Affine, H, S, KateCommitmentScheme<H, S>>
+impl<'a, C: PairingCurveConfig, H: BarretenHasher + Default, S: Settings<H> + Default>
+    Prover<'a, C, H, S, KateCommitmentScheme<H, S>>
 {
     pub(crate) fn new(
-        input_key: Option<Rc<RefCell<ProvingKey<'a, Fr, G1Affine>>>>,
+        input_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
         input_manifest: Option<Manifest>,
         input_settings: Option<S>,
     ) -> Self {
         let circuit_size = input_key
             .as_ref()
-            .map_or(0, |key| key.borrow().circuit_size);
-        let transcript = Rc::new(RefCell::new(Transcript::new(
+            .map_or(0, |key| key.read().unwrap().circuit_size);
+        let settings = input_settings.unwrap_or_default();
+        // the verifier parses the proof with the same manifest and challenge size
+        let transcript = Arc::new(RwLock::new(Transcript::new(
             input_manifest,
-            H::PrngOutputSize::USIZE,
+            settings.num_challenge_bytes(),
         )));
         let input_key = match input_key {
             Some(ik) => ik,
-            None => Rc::new(RefCell::new(ProvingKey::default())),
+            None => Arc::new(RwLock::new(ProvingKey::default())),
         };
         let queue = WorkQueue::new(Some(input_key.clone()), Some(transcript.clone()));
-        let settings = input_settings.unwrap_or_default();
 
         Self {
             circuit_size,
@@ -86,20 +97,144 @@ impl<
             commitment_scheme: KateCommitmentScheme::<H, S>::default(),
             settings,
             phantom: PhantomData,
-            rng: Box::new(rand::thread_rng()),
+            rng: Box::new(StdRng::from_entropy()),
+            trace_path: None,
         }
     }
+
+    /// Creates a prover for the standard arithmetisation, with the width-3 permutation widget and
+    /// the arithmetic widget wired into the quotient computation.
+    pub(crate) fn new_standard_prover(
+        input_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+        input_manifest: Option<Manifest>,
+        input_settings: Option<S>,
+    ) -> Self
+    where
+        C: 'a,
+        H: 'a,
+        S: 'a,
+    {
+        // without a manifest, prove against the standard composer's for the key's public inputs
+        let input_manifest = input_manifest.or_else(|| {
+            input_key
+                .as_ref()
+                .map(|key| standard_manifest_for::<C>(key.read().unwrap().num_public_inputs))
+        });
+        let mut prover = Self::new(input_key, input_manifest, input_settings);
+        prover.random_widgets.push(Box::new(
+            ProverPermutationWidget::<'a, H, C, 3, false, 4>::new(prover.key.clone()),
+        ));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverArithmeticWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+    }
+
+    /// Creates a prover for the turbo arithmetisation, with the width-4 permutation widget and the
+    /// arithmetic, fixed-base, range and logic widgets wired into the quotient computation.
+    pub(crate) fn new_turbo_prover(
+        input_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+        input_manifest: Option<Manifest>,
+        input_settings: Option<S>,
+    ) -> Self
+    where
+        C: 'a,
+        H: 'a,
+        S: 'a,
+    {
+        // without a manifest, prove against the turbo composer's for the key's public inputs
+        let input_manifest = input_manifest.or_else(|| {
+            input_key
+                .as_ref()
+                .map(|key| turbo_manifest_for::<C>(key.read().unwrap().num_public_inputs))
+        });
+        let mut prover = Self::new(input_key, input_manifest, input_settings);
+        prover.random_widgets.push(Box::new(
+            ProverPermutationWidget::<'a, H, C, 4, false, 4>::new(prover.key.clone()),
+        ));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverTurboArithmeticWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverTurboFixedBaseWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverTurboRangeWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverTurboLogicWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+    }
+
+    /// A prover for the ultra composer: the permutation argument with identity polynomials, the lookup
+    /// argument, and the arithmetic, sorted range, elliptic and memory gates.
+    pub(crate) fn new_ultra_prover(
+        input_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+        input_manifest: Option<Manifest>,
+        input_settings: Option<S>,
+    ) -> Self
+    where
+        C: 'a,
+        H: 'a,
+        S: 'a,
+    {
+        // without a manifest, prove against the ultra composer's for the key's public inputs
+        let input_manifest = input_manifest.or_else(|| {
+            input_key
+                .as_ref()
+                .map(|key| ultra_manifest_for::<C>(key.read().unwrap().num_public_inputs))
+        });
+        let mut prover = Self::new(input_key, input_manifest, input_settings);
+        prover.random_widgets.push(Box::new(
+            ProverPermutationWidget::<'a, H, C, 4, true, 4>::new(prover.key.clone()),
+        ));
+        prover
+            .random_widgets
+            .push(Box::new(ProverPlookupWidget::<'a, H, C, 4>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverUltraArithmeticWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverUltraSortWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverUltraEllipticWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+            .transition_widgets
+            .push(Box::new(ProverUltraAuxWidget::<'a, H, C, S>::new(
+                prover.key.clone(),
+            )));
+        prover
+    }
 }
 
 impl<
         'a,
-        Fq: Field,
-        Fr: Field + FftField,
-        G1Affine: AffineRepr,
+        C: CurveConfig,
         H: BarretenHasher + Default,
         S: Settings<H> + Default,
-        CS: CommitmentScheme<Fq, Fr, G1Affine, H>,
-    > Prover<'a, Fq, Fr, G1Affine, H, S, CS>
+        CS: CommitmentScheme<C, H>,
+    > Prover<'a, C, H, S, CS>
 {
     fn copy_placeholder(&self) {
         todo!("LOOK AT THE COMMENTS IN PROVERBASE");
@@ -115,7 +250,7 @@ impl<
     fn execute_preamble_round(&mut self) -> Result<()> {
         self.queue.flush_queue();
 
-        (*self.transcript).borrow_mut().add_element(
+        self.transcript.write().unwrap().add_element(
             "circuit_size",
             vec![
                 (self.circuit_size >> 24) as u8,
@@ -125,17 +260,17 @@ impl<
             ],
         );
 
-        (*self.transcript).borrow_mut().add_element(
+        self.transcript.write().unwrap().add_element(
             "public_input_size",
             vec![
-                (self.key.borrow().num_public_inputs >> 24) as u8,
-                (self.key.borrow().num_public_inputs >> 16) as u8,
-                (self.key.borrow().num_public_inputs >> 8) as u8,
-                (self.key.borrow().num_public_inputs) as u8,
+                (self.key.read().unwrap().num_public_inputs >> 24) as u8,
+                (self.key.read().unwrap().num_public_inputs >> 16) as u8,
+                (self.key.read().unwrap().num_public_inputs >> 8) as u8,
+                (self.key.read().unwrap().num_public_inputs) as u8,
             ],
         );
 
-        (*self.transcript).borrow_mut().apply_fiat_shamir("init");
+        self.transcript.write().unwrap().apply_fiat_shamir("init")?;
 
         // If this is a plookup proof, do not queue up an ifft on W_4 - we can only finish computing
         // the lagrange-base values in W_4 once eta has been generated.
@@ -149,11 +284,12 @@ impl<
             let wire_tag = format!("w_{}", i + 1);
             let wire_lagrange = self
                 .key
-                .borrow()
+                .read()
+                .unwrap()
                 .polynomial_store
                 .get(&format!("{}_lagrange", wire_tag))?
                 .clone();
-            let mut wire_lagrange = wire_lagrange.borrow_mut();
+            let mut wire_lagrange = wire_lagrange.write().unwrap();
 
             /*
             Adding zero knowledge to the witness polynomials.
@@ -182,7 +318,7 @@ impl<
                 wire_lagrange.set_coefficient(
                     self.circuit_size - self.settings.num_roots_cut_out_of_vanishing_polynomial()
                         + k,
-                    Fr::rand(&mut self.rng),
+                    C::Fr::rand(&mut self.rng),
                 )
             }
         }
@@ -213,10 +349,11 @@ impl<
 
         for widget in self.random_widgets.iter() {
             widget.compute_round_commitments(
-                &mut (*self.transcript).borrow_mut(),
+                &mut self.transcript.write().unwrap(),
                 1,
                 &mut self.queue,
-            );
+                &mut self.rng,
+            )?;
         }
         Ok(())
     }
@@ -229,14 +366,15 @@ impl<
     fn execute_second_round(&mut self) -> Result<()> {
         self.queue.flush_queue();
 
-        (*self.transcript).borrow_mut().apply_fiat_shamir("eta");
+        self.transcript.write().unwrap().apply_fiat_shamir("eta")?;
 
         for widget in self.random_widgets.iter() {
             widget.compute_round_commitments(
-                &mut (*self.transcript).borrow_mut(),
+                &mut self.transcript.write().unwrap(),
                 2,
                 &mut self.queue,
-            );
+                &mut self.rng,
+            )?;
         }
 
         // RAM/ROM memory subprotocol requires eta is generated before w_4 is comitted
@@ -246,10 +384,11 @@ impl<
 
             let w_4_lagrange = self
                 .key
-                .borrow()
+                .read()
+                .unwrap()
                 .polynomial_store
                 .get(&format!("{}_lagrange", wire_tag))?;
-            let mut w_4_lagrange = w_4_lagrange.borrow_mut();
+            let mut w_4_lagrange = w_4_lagrange.write().unwrap();
 
             // add randomness to w_4_lagrange
             let w_randomness = 3;
@@ -259,27 +398,29 @@ impl<
                 w_4_lagrange.set_coefficient(
                     self.circuit_size - self.settings.num_roots_cut_out_of_vanishing_polynomial()
                         + k,
-                    Fr::rand(&mut self.rng),
+                    C::Fr::rand(&mut self.rng),
                 );
             }
 
             // compute poly w_4 from w_4_lagrange and add it to the cache
             let mut w_4 = w_4_lagrange.clone();
-            self.key.borrow().small_domain.ifft_inplace(&mut w_4);
+            self.key.read().unwrap().small_domain.ifft_inplace(&mut w_4);
             self.key
-                .borrow_mut()
+                .write()
+                .unwrap()
                 .polynomial_store
-                .put(wire_tag.to_string(), w_4);
+                .put(wire_tag.to_string(), w_4)?;
 
             // commit to w_4 using the monomial srs.
             self.queue.add_to_queue(WorkItem {
                 work: work_queue::Work::ScalarMultiplication {
                     mul_scalars: self
                         .key
-                        .borrow()
+                        .read()
+                        .unwrap()
                         .polynomial_store
                         .get(&wire_tag.to_string())?,
-                    constant: Fr::from((self.key.borrow().circuit_size + 1) as u64),
+                    constant: C::Fr::from((self.key.read().unwrap().circuit_size + 1) as u64),
                 },
                 tag: "W_4".to_owned(),
             });
@@ -293,17 +434,18 @@ impl<
     /// - FFT the wires.
     ///
     /// *For example, standard composer executes permutation widget for z polynomial construction at this round.
-    fn execute_third_round(&mut self) {
+    fn execute_third_round(&mut self) -> Result<()> {
         self.queue.flush_queue();
 
-        (*self.transcript).borrow_mut().apply_fiat_shamir("beta");
+        self.transcript.write().unwrap().apply_fiat_shamir("beta")?;
 
         for widget in &mut self.random_widgets {
             widget.compute_round_commitments(
-                &mut (*self.transcript).borrow_mut(),
+                &mut self.transcript.write().unwrap(),
                 3,
                 &mut self.queue,
-            );
+                &mut self.rng,
+            )?;
         }
 
         for i in 0..self.settings.program_width() {
@@ -313,29 +455,45 @@ impl<
                 tag: wire_tag,
             });
         }
+        Ok(())
     }
 
     /// Computes the quotient polynomial, then commits to its degree-n split parts.
-    fn execute_fourth_round(&mut self) {
+    fn execute_fourth_round(&mut self) -> Result<()> {
         self.queue.flush_queue();
-        (*self.transcript).borrow_mut().apply_fiat_shamir("alpha");
-
-        let mut alpha_base = (*self.transcript)
-            .borrow_mut()
+        self.transcript
+            .write()
+            .unwrap()
+            .apply_fiat_shamir("alpha")?;
+
+        let mut alpha_base = self
+            .transcript
+            .write()
+            .unwrap()
             .get_challenge_field_element("alpha", None);
 
         // Compute FFT of lagrange polynomial L_1 (needed in random widgets only)
-        self.compute_lagrange_1_fft();
+        self.compute_lagrange_1_fft()?;
+
+        // The widgets accumulate into the quotient, so clear what a previous proof left behind.
+        for quotient_part in self.key.read().unwrap().quotient_polynomial_parts.iter() {
+            quotient_part
+                .write()
+                .unwrap()
+                .coefficients
+                .iter_mut()
+                .for_each(|coefficient| *coefficient = C::Fr::zero());
+        }
 
         for widget in &mut self.random_widgets {
-            alpha_base =
-                widget.compute_quotient_contribution(alpha_base, &self.transcript.borrow());
+            alpha_base = widget
+                .compute_quotient_contribution(alpha_base, &self.transcript.read().unwrap())?;
         }
 
         for widget in &mut self.transition_widgets {
             alpha_base = widget.compute_quotient_contribution(
                 alpha_base,
-                &self.transcript.borrow(),
+                &self.transcript.read().unwrap(),
                 &mut self.rng,
             );
         }
@@ -345,63 +503,55 @@ impl<
         // as well as iFFT (coset) are to be performed on the polynomial t(X) as a whole.
         // We avoid redundant copy of the parts t_1, t_2, t_3, t_4 and instead just tweak the
         // relevant functions to work on quotient polynomial parts.
-        // TODO this does not work so good in rust. for now, we copy... is it still okay to do this?
-        let mut quotient_poly_parts: Vec<&mut [&mut Fr]> = Vec::new();
         {
-            let key = self.key.borrow();
-            let mut poly0 = (*key.quotient_polynomial_parts[0]).borrow_mut();
-            let mut poly_sliced0 = [poly0.index_mut(0)];
-            quotient_poly_parts.push(&mut poly_sliced0);
-            let mut poly1 = (*key.quotient_polynomial_parts[1]).borrow_mut();
-            let mut poly_sliced1 = [poly1.index_mut(0)];
-            quotient_poly_parts.push(&mut poly_sliced1);
-            let mut poly2 = (*key.quotient_polynomial_parts[2]).borrow_mut();
-            let mut poly_sliced2 = [poly2.index_mut(0)];
-            quotient_poly_parts.push(&mut poly_sliced2);
-            let mut poly3 = (*key.quotient_polynomial_parts[3]).borrow_mut();
-            let mut poly_sliced3 = [poly3.index_mut(0)];
-            quotient_poly_parts.push(&mut poly_sliced3);
-
-            self.key
-                .borrow()
-                .small_domain
-                .divide_by_pseudo_vanishing_polynomial(
-                    &quotient_poly_parts,
-                    &self.key.borrow().large_domain,
-                    0,
-                );
-
-            self.key
-                .borrow()
-                .large_domain
-                .coset_ifft_vec(quotient_poly_parts.as_mut_slice());
+            let key = self.key.read().unwrap();
+            let mut parts: Vec<_> = key
+                .quotient_polynomial_parts
+                .iter()
+                .map(|part| part.write().unwrap())
+                .collect();
+            // the first n coefficients of part i hold the coset evaluations i*n..(i+1)*n
+            let mut quotient_poly_parts: Vec<&mut [C::Fr]> = parts
+                .iter_mut()
+                .map(|part| &mut part.coefficients[..self.circuit_size])
+                .collect();
+
+            key.small_domain.divide_by_pseudo_vanishing_polynomial(
+                &mut quotient_poly_parts,
+                &key.large_domain,
+                self.settings.num_roots_cut_out_of_vanishing_polynomial(),
+            )?;
+
+            key.large_domain.coset_ifft_vec(&mut quotient_poly_parts);
         }
         // Manually copy the (n + 1)th coefficient of t_3 for StandardPlonk from t_4.
         // This is because the degree of t_3 for StandardPlonk is n.
         if self.settings.program_width() == 3 {
-            self.key.borrow().quotient_polynomial_parts[2].borrow_mut()[self.circuit_size] =
-                self.key.borrow().quotient_polynomial_parts[3].borrow_mut()[0];
-            self.key.borrow().quotient_polynomial_parts[3].borrow_mut()[0] = Fr::zero();
+            let key = self.key.read().unwrap();
+            let mut t_4 = key.quotient_polynomial_parts[3].write().unwrap();
+            key.quotient_polynomial_parts[2].write().unwrap()[self.circuit_size] = t_4[0];
+            t_4[0] = C::Fr::zero();
         }
 
         self.add_blinding_to_quotient_polynomial_parts();
 
         self.compute_quotient_commitments();
+        Ok(())
     }
     fn execute_fifth_round(&mut self) -> Result<()> {
         self.queue.flush_queue();
-        (*self.transcript).borrow_mut().apply_fiat_shamir("z"); // end of 4th round
+        self.transcript.write().unwrap().apply_fiat_shamir("z")?; // end of 4th round
         self.compute_quotient_evaluation()
     }
 
-    fn execute_sixth_round(&mut self) {
+    fn execute_sixth_round(&mut self) -> Result<()> {
         self.queue.flush_queue();
-        (*self.transcript).borrow_mut().apply_fiat_shamir("nu");
+        self.transcript.write().unwrap().apply_fiat_shamir("nu")?;
         self.commitment_scheme.batch_open(
-            &(*self.transcript).borrow(),
+            &self.transcript.read().unwrap(),
             &mut self.queue,
-            Some(self.key.clone().borrow()),
-        );
+            Some(self.key.clone()),
+        )
     }
 
     /// note that this is never defined in barettenberg
@@ -417,7 +567,7 @@ impl<
         } else {
             self.settings.program_width()
         };
-        let key = self.key.borrow();
+        let key = self.key.read().unwrap();
         for i in 0..end {
             let wire_tag = format!("w_{}", i + 1);
             let commit_tag = format!("W_{}", i + 1);
@@ -432,7 +582,7 @@ impl<
             self.commitment_scheme.commit(
                 coefficients,
                 commit_tag,
-                Fr::from(domain_size_flag as u64),
+                C::Fr::from(domain_size_flag as u64),
                 &mut self.queue,
             );
         }
@@ -441,10 +591,11 @@ impl<
         let public_wires_source = key.polynomial_store.get(&"w_2_lagrange".to_string())?;
         let mut public_wires = vec![];
         for i in 0..key.num_public_inputs {
-            public_wires.push(public_wires_source.borrow()[i]);
+            public_wires.push(public_wires_source.read().unwrap()[i]);
         }
-        (*self.transcript)
-            .borrow_mut()
+        self.transcript
+            .write()
+            .unwrap()
             .put_field_element_vector("public_inputs", &public_wires);
         Ok(())
     }
@@ -488,7 +639,7 @@ impl<
     /// computing the commitments to these polynomials.
     ///
     fn compute_quotient_commitments(&mut self) {
-        let key = self.key.borrow();
+        let key = self.key.read().unwrap();
         for i in 0..self.settings.program_width() {
             let coefficients = key.quotient_polynomial_parts[i].clone();
             let quotient_tag = format!("T_{}", i + 1);
@@ -499,11 +650,11 @@ impl<
             } else {
                 key.circuit_size + 1
             };
-            // TODO concerned about the fr::from on domain_size_flag. this may not be correct. bberg just uses equivalent of std::mem::transmute
+            // the work queue reads this back with `fields::to_usize`, barretenberg's cast through uint256_t
             self.commitment_scheme.commit(
                 coefficients,
                 quotient_tag,
-                Fr::from(domain_size_flag as u64),
+                C::Fr::from(domain_size_flag as u64),
                 &mut self.queue,
             );
         }
@@ -518,20 +669,24 @@ impl<
         // We can only compute memory record values once W_1, W_2, W_3 have been comitted to,
         // due to the dependence on the `eta` challenge.
 
-        let eta = (*self.transcript).borrow_mut().get_field_element("eta");
-        let key = self.key.borrow();
+        let eta = self
+            .transcript
+            .read()
+            .unwrap()
+            .get_challenge_field_element("eta", None);
+        let key = self.key.read().unwrap();
 
         // We need the lagrange-base forms of the first 3 wires to compute the plookup memory record
         // value. w4 = w3 * eta^3 + w2 * eta^2 + w1 * eta + read_write_flag;
         // a RAM write. See plookup_auxiliary_widget.hpp for details)
         let w_1 = key.polynomial_store.get(&"w_1_lagrange".to_string())?;
-        let w_1 = w_1.borrow();
+        let w_1 = w_1.read().unwrap();
         let w_2 = key.polynomial_store.get(&"w_2_lagrange".to_string())?;
-        let w_2 = w_2.borrow();
+        let w_2 = w_2.read().unwrap();
         let w_3 = key.polynomial_store.get(&"w_3_lagrange".to_string())?;
-        let w_3 = w_3.borrow();
+        let w_3 = w_3.read().unwrap();
         let w_4 = key.polynomial_store.get(&"w_4_lagrange".to_string())?;
-        let mut w_4 = w_4.borrow_mut();
+        let mut w_4 = w_4.write().unwrap();
         for gate_idx in key.memory_read_records.iter() {
             w_4[*gate_idx] += w_3[*gate_idx];
             w_4[*gate_idx] *= eta;
@@ -547,36 +702,41 @@ impl<
             w_4[*gate_idx] *= eta;
             w_4[*gate_idx] += w_1[*gate_idx];
             w_4[*gate_idx] *= eta;
-            w_4[*gate_idx] += Fr::one();
+            w_4[*gate_idx] += C::Fr::one();
         }
         Ok(())
     }
 
     fn compute_quotient_evaluation(&self) -> Result<()> {
-        let key = self.key.borrow();
+        let key = self.key.read().unwrap();
 
-        let zeta = (*self.transcript).borrow_mut().get_field_element("zeta");
+        let zeta = self
+            .transcript
+            .read()
+            .unwrap()
+            .get_challenge_field_element("z", None);
 
         self.commitment_scheme
             .add_opening_evaluations_to_transcript(
-                &mut (*self.transcript).borrow_mut(),
-                Some(&self.key.borrow()),
+                &mut self.transcript.write().unwrap(),
+                Some(&key),
                 false,
-            );
-
-        let mut t_eval = polynomial_arithmetic::evaluate(
-            &[
-                key.quotient_polynomial_parts[0].borrow()[0],
-                key.quotient_polynomial_parts[1].borrow()[0],
-                key.quotient_polynomial_parts[2].borrow()[0],
-                key.quotient_polynomial_parts[3].borrow()[0],
-            ],
-            &zeta,
-            4 * self.circuit_size,
-        );
+            )?;
 
-        // TODO are these limbs wrong?
+        // t(X) = t_1(X) + X^n t_2(X) + X^2n t_3(X) + X^3n t_4(X), over the first n coefficients of each part
         let zeta_pow_n = zeta.pow([key.circuit_size as u64]);
+        let mut t_eval = C::Fr::zero();
+        let mut scalar = C::Fr::one();
+        for part in key.quotient_polynomial_parts.iter() {
+            let part = part.read().unwrap();
+            t_eval += polynomial_arithmetic::evaluate(
+                &part.coefficients[..key.circuit_size],
+                &zeta,
+                key.circuit_size,
+            ) * scalar;
+            scalar *= zeta_pow_n;
+        }
+
         let mut scalar = zeta_pow_n;
         // Adjust the evaluation to consider the (n + 1)th coefficient when needed (note that width 3 is just an avatar for
         // StandardComposer here)
@@ -586,12 +746,13 @@ impl<
             self.settings.program_width() - 1
         };
         for j in 0..num_deg_n_poly {
-            t_eval += key.quotient_polynomial_parts[j].borrow()[key.circuit_size] * scalar;
+            t_eval += key.quotient_polynomial_parts[j].read().unwrap()[key.circuit_size] * scalar;
             scalar *= zeta_pow_n;
         }
 
-        (*self.transcript)
-            .borrow_mut()
+        self.transcript
+            .write()
+            .unwrap()
             .add_field_element("t", &t_eval);
         Ok(())
     }
@@ -611,38 +772,47 @@ impl<
         //              t_4 = t_4' - b_2
         //
         // For details, please head to: https://hackmd.io/JiyexiqRQJW55TMRrBqp1g.
-        let key = self.key.borrow();
+        let key = self.key.read().unwrap();
         for i in 0..self.settings.program_width() - 1 {
             // Note that only program_width-1 random elements are required for full blinding
-            let quotient_randomness = Fr::rand(&mut self.rng);
+            let quotient_randomness = C::Fr::rand(&mut self.rng);
 
-            key.quotient_polynomial_parts[i].borrow_mut()[key.circuit_size] += quotient_randomness; // update coefficient of X^n'th term
-            key.quotient_polynomial_parts[i + 1].borrow_mut()[0] -= quotient_randomness;
+            key.quotient_polynomial_parts[i].write().unwrap()[key.circuit_size] +=
+                quotient_randomness; // update coefficient of X^n'th term
+            key.quotient_polynomial_parts[i + 1].write().unwrap()[0] -= quotient_randomness;
             // update constant coefficient
         }
     }
 
     /// Compute FFT of lagrange polynomial L_1 needed in random widgets only
-    fn compute_lagrange_1_fft(&self) {
-        let mut lagrange_1_fft: Polynomial<Fr> = Polynomial::new(4 * self.circuit_size + 8);
+    fn compute_lagrange_1_fft(&self) -> Result<()> {
+        let mut lagrange_1_fft: Polynomial<C::Fr> = Polynomial::new(4 * self.circuit_size + 8);
 
         {
-            let key = self.key.borrow();
+            let key = self.key.read().unwrap();
             key.small_domain
-                .compute_lagrange_polynomial_fft(&mut lagrange_1_fft, &key.large_domain);
+                .compute_lagrange_polynomial_fft(&mut lagrange_1_fft, &key.large_domain)?;
             for i in 0..8 {
                 lagrange_1_fft[4 * self.circuit_size + i] = lagrange_1_fft[i];
             }
         }
         self.key
-            .borrow_mut()
+            .write()
+            .unwrap()
             .polynomial_store
-            .put("lagrange_1_fft".to_string(), lagrange_1_fft);
+            .put("lagrange_1_fft".to_string(), lagrange_1_fft)
+    }
+
+    /// Trace the transcript of the proofs constructed from now on and write each trace to `path`, to be compared
+    /// with a verifier's by [`diff_trace_files`](crate::transcript::trace::diff_trace_files).
+    pub(crate) fn trace_to(&mut self, path: impl Into<PathBuf>) {
+        self.trace_path = Some(path.into());
+        self.transcript.write().unwrap().enable_trace();
     }
 
     fn export_proof(&self) -> Proof {
         Proof {
-            proof_data: (*self.transcript).borrow_mut().export_transcript(),
+            proof_data: self.transcript.write().unwrap().export_transcript(),
         }
     }
 
@@ -654,46 +824,688 @@ impl<
         // Compute wire precommitments and sometimes random widget round commitments
         self.execute_first_round()?;
         self.queue.process_queue()?;
+        self.free_polynomials_consumed_by(ProverRound::First)?;
 
         // Fiat-Shamir eta + execute random widgets.
         self.execute_second_round()?;
         self.queue.process_queue()?;
+        self.free_polynomials_consumed_by(ProverRound::Second)?;
 
         // Fiat-Shamir beta & gamma, execute random widgets (Permutation widget is executed here)
         // and fft the witnesses
-        self.execute_third_round();
+        self.execute_third_round()?;
         self.queue.process_queue()?;
+        self.free_polynomials_consumed_by(ProverRound::Third)?;
 
         // Fiat-Shamir alpha, compute & commit to quotient polynomial.
-        self.execute_fourth_round();
+        self.execute_fourth_round()?;
         self.queue.process_queue()?;
+        self.free_polynomials_consumed_by(ProverRound::Fourth)?;
 
         self.execute_fifth_round()?;
+        self.free_polynomials_consumed_by(ProverRound::Fifth)?;
 
-        self.execute_sixth_round();
+        self.execute_sixth_round()?;
         self.queue.process_queue()?;
+        self.free_polynomials_consumed_by(ProverRound::Sixth)?;
+        // only the verifier needs the separator, derived from the opening proofs, but closing the last round here
+        // too keeps the prover's trace in step with the verifier's
+        self.transcript
+            .write()
+            .unwrap()
+            .apply_fiat_shamir("separator")?;
 
         self.queue.flush_queue();
 
+        if let Some(path) = &self.trace_path {
+            write_trace(path, &self.transcript.write().unwrap().take_trace())?;
+        }
         Ok(self.export_proof())
     }
 
+    /// Drop the witness polynomials that nothing after `round` reads, according to the key's
+    /// polynomial manifest. Mostly this gets rid of the 4n coset FFTs once the quotient is done.
+    fn free_polynomials_consumed_by(&mut self, round: ProverRound) -> Result<()> {
+        let mut key = self.key.write().unwrap();
+        for label in key.polynomial_manifest.get_labels_last_consumed_in(round) {
+            // not every circuit has every form
+            if key.polynomial_store.contains(&label) {
+                key.polynomial_store.remove(label)?;
+            }
+        }
+        Ok(())
+    }
+
     fn get_circuit_size(&self) -> usize {
         todo!("implement me")
     }
     fn reset(&mut self) {
-        let manifest = (*self.transcript).borrow_mut().get_manifest();
-        *(*self.transcript).borrow_mut() = Transcript::<H, Fr, G1Affine>::new(
-            Some(manifest),
-            (*self.transcript).borrow().num_challenge_bytes,
-        );
+        let mut transcript = self.transcript.write().unwrap();
+        let manifest = transcript.get_manifest();
+        let num_challenge_bytes = transcript.num_challenge_bytes;
+        *transcript = Transcript::<H, C>::new(Some(manifest), num_challenge_bytes);
+        if self.trace_path.is_some() {
+            transcript.enable_trace();
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{KateCommitmentScheme, Prover, ProvingKey};
+    use crate::{
+        ecc::curves::{Bls12_381, Bn254, PairingCurveConfig},
+        plonk::{
+            composer::{
+                composer_base::ComposerType,
+                manifest::{
+                    standard_manifest, standard_manifest_for, turbo_manifest_for,
+                    ultra_manifest_for,
+                },
+            },
+            proof_system::{
+                types::{
+                    prover_settings::{StandardSettings, TurboSettings, UltraWithKeccakSettings},
+                    Proof,
+                },
+                utils::permutation::{
+                    compute_permutation_polynomials_from_mapping, PermutationMapping,
+                    PermutationSubgroupElement,
+                },
+                verification_key::VerificationKey,
+                verifier::{Verifier, VerifierBase},
+            },
+        },
+        polynomials::Polynomial,
+        srs::io::test::mock_srs,
+        transcript::{Keccak256, PedersenBlake3s},
+    };
+    use ark_ff::{One, Zero};
+    use std::sync::{Arc, RwLock};
+
+    type StandardProver = Prover<
+        'static,
+        Bn254,
+        Keccak256,
+        StandardSettings<Keccak256>,
+        KateCommitmentScheme<Keccak256, StandardSettings<Keccak256>>,
+    >;
+    type StandardVerifier = Verifier<'static, Bn254, Keccak256, StandardSettings<Keccak256>>;
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn proving_types_are_send() {
+        // compile-time check: proving and verifying must be able to move across threads
+        assert_send::<ProvingKey<'static, Bn254>>();
+        assert_send::<StandardProver>();
+        assert_send::<StandardVerifier>();
+    }
+
+    #[test]
+    fn standard_prover_wires_the_standard_widgets() {
+        let key = ProvingKey {
+            num_public_inputs: 2,
+            ..Default::default()
+        };
+        let prover =
+            StandardProver::new_standard_prover(Some(Arc::new(RwLock::new(key))), None, None);
+        assert_eq!(prover.random_widgets.len(), 1);
+        assert_eq!(prover.transition_widgets.len(), 1);
+
+        // without a manifest, the prover follows the standard composer's for the key's public inputs
+        let manifest = prover.transcript.read().unwrap().get_manifest();
+        let beta = manifest.get_round_manifest(2);
+        assert_eq!(beta.challenge, "beta");
+        assert_eq!(beta.elements[0].name, "public_inputs");
+        assert_eq!(beta.elements[0].num_bytes, 2 * 32);
+    }
+
+    #[test]
+    fn verifier_parses_proofs_with_the_prover_manifest() {
+        let key = ProvingKey {
+            num_public_inputs: 1,
+            ..Default::default()
+        };
+        let prover =
+            StandardProver::new_standard_prover(Some(Arc::new(RwLock::new(key))), None, None);
+        let mut prover_transcript = prover.transcript.write().unwrap();
+        let manifest = prover_transcript.get_manifest();
+        let element = |round: usize, index: usize, num_bytes: usize| {
+            vec![(16 * round + index) as u8; num_bytes]
+        };
+        // every element goes in, so the proof is whole, but only the rounds up to z are hashed
+        for (round, round_manifest) in manifest.round_manifests.iter().enumerate() {
+            for (index, entry) in round_manifest.elements.iter().enumerate() {
+                prover_transcript.add_element(&entry.name, element(round, index, entry.num_bytes));
+            }
+            if round < 5 {
+                prover_transcript
+                    .apply_fiat_shamir(&round_manifest.challenge)
+                    .unwrap();
+            }
+        }
+        let proof_data = prover_transcript.export_transcript();
+
+        let verifier = <StandardVerifier as VerifierBase<_, _, _>>::new(None, standard_manifest(1));
+        let mut verifier_transcript = verifier.transcript(&Proof { proof_data });
+        assert_eq!(
+            verifier_transcript.num_challenge_bytes,
+            prover_transcript.num_challenge_bytes
+        );
+        for (round, round_manifest) in manifest.round_manifests.iter().take(5).enumerate() {
+            for (index, entry) in round_manifest.elements.iter().enumerate() {
+                if entry.derived_by_verifier {
+                    verifier_transcript
+                        .add_element(&entry.name, element(round, index, entry.num_bytes));
+                }
+            }
+            verifier_transcript
+                .apply_fiat_shamir(&round_manifest.challenge)
+                .unwrap();
+            for index in 0..round_manifest.num_challenges {
+                assert_eq!(
+                    verifier_transcript
+                        .get_challenge(&round_manifest.challenge, Some(index))
+                        .unwrap(),
+                    prover_transcript
+                        .get_challenge(&round_manifest.challenge, Some(index))
+                        .unwrap()
+                );
+            }
+        }
+    }
+
+    const N: usize = 16;
+
+    /// Stores the lagrange, monomial and coset FFT forms of each selector, as the widgets read them.
+    fn put_selectors<C: PairingCurveConfig>(
+        key: &mut ProvingKey<'static, C>,
+        selectors: impl IntoIterator<Item = (&'static str, Polynomial<C::Fr>)>,
+    ) {
+        for (label, lagrange) in selectors {
+            let mut monomial = lagrange.clone();
+            key.small_domain.ifft_inplace(&mut monomial);
+            let mut fft = Polynomial::new(4 * N + 4);
+            key.large_domain
+                .coset_fft(&monomial.coefficients, &mut fft.coefficients[..4 * N]);
+            for i in 0..4 {
+                fft[4 * N + i] = fft[i];
+            }
+            let store = &mut key.polynomial_store;
+            store.put(format!("{}_lagrange", label), lagrange).unwrap();
+            store.put(format!("{}_fft", label), fft).unwrap();
+            store.put(label.to_string(), monomial).unwrap();
+        }
+    }
+
+    /// Keys for `x·y = 35` and `x + y = 12` with 35 public, over `N` rows. Row 0 holds the public input and
+    /// rows 1 and 2 the gates, with `product` in the output of the multiplication.
+    fn standard_keys<C: PairingCurveConfig>(
+        product: u64,
+    ) -> (
+        Arc<RwLock<ProvingKey<'static, C>>>,
+        Arc<VerificationKey<'static, C>>,
+    ) {
+        let srs = mock_srs::<C::Pairing>(2 * N);
+        let mut key = ProvingKey::<C>::new(
+            N - 1,
+            1,
+            Arc::new(RwLock::new(srs.clone())),
+            ComposerType::Standard,
+        );
+
+        let value = |v: u64| C::Fr::from(v);
+        let one = C::Fr::one();
+        let column = |cells: &[(usize, C::Fr)]| {
+            let mut column = vec![C::Fr::zero(); N];
+            for (row, cell) in cells {
+                column[*row] = *cell;
+            }
+            Polynomial::from_coefficients(column, N)
+        };
+        let wires = [
+            (
+                "w_1",
+                column(&[(0, value(35)), (1, value(5)), (2, value(5))]),
+            ),
+            (
+                "w_2",
+                column(&[(0, value(35)), (1, value(7)), (2, value(7))]),
+            ),
+            ("w_3", column(&[(1, value(product)), (2, value(12))])),
+        ];
+        let selectors = [
+            ("q_1", column(&[(2, one)])),
+            ("q_2", column(&[(2, one)])),
+            ("q_3", column(&[(1, -one), (2, -one)])),
+            ("q_m", column(&[(1, one)])),
+            ("q_c", column(&[])),
+        ];
+        for (label, lagrange) in wires {
+            key.polynomial_store
+                .put(format!("{}_lagrange", label), lagrange)
+                .unwrap();
+        }
+        put_selectors(&mut key, selectors);
+
+        // the public input (0, L) leaves its cycle (0, L) -> (0, R) -> (1, O); x and y are copied from row 1 to 2
+        let cell = |row: u32, column: u8| PermutationSubgroupElement::new(row, column);
+        let mut mapping = PermutationMapping::identity(3, N, false);
+        mapping.sigmas[0][0].is_public_input = true;
+        mapping.sigmas[1][0] = cell(1, 2);
+        mapping.sigmas[2][1] = cell(0, 0);
+        mapping.sigmas[0][1] = cell(2, 0);
+        mapping.sigmas[0][2] = cell(1, 0);
+        mapping.sigmas[1][1] = cell(2, 1);
+        mapping.sigmas[1][2] = cell(1, 1);
+        compute_permutation_polynomials_from_mapping::<Keccak256, C, StandardSettings<Keccak256>>(
+            "sigma",
+            &mapping.sigmas,
+            &mut key,
+        )
+        .unwrap();
+
+        let verification_key = VerificationKey::from_proving_key(&key, Arc::new(srs)).unwrap();
+        (Arc::new(RwLock::new(key)), Arc::new(verification_key))
+    }
+
+    fn check_prove_verify<C: PairingCurveConfig>() {
+        let prove = |product: u64| {
+            let (key, verification_key) = standard_keys::<C>(product);
+            let mut prover = Prover::<
+                C,
+                Keccak256,
+                StandardSettings<Keccak256>,
+                KateCommitmentScheme<Keccak256, StandardSettings<Keccak256>>,
+            >::new_standard_prover(Some(key), None, None);
+            (prover.construct_proof().unwrap(), verification_key)
+        };
+        let verifies = |proof_data: &[u8], verification_key: &Arc<VerificationKey<'static, C>>| {
+            let verifier =
+                <Verifier<'static, C, Keccak256, StandardSettings<Keccak256>> as VerifierBase<
+                    _,
+                    _,
+                    _,
+                >>::new(
+                    Some(verification_key.clone()),
+                    standard_manifest_for::<C>(1),
+                );
+            verifier.verify_proof(&Proof {
+                proof_data: proof_data.to_vec(),
+            })
+        };
+
+        let (proof, verification_key) = prove(35);
+        assert!(verifies(&proof.proof_data, &verification_key));
+
+        // the proof opens with the public input, 35 in big-endian
+        assert_eq!(proof.proof_data[31], 35);
+        let mut wrong_public_input = proof.proof_data.clone();
+        wrong_public_input[31] = 36;
+        assert!(!verifies(&wrong_public_input, &verification_key));
+        assert!(!verifies(
+            &proof.proof_data[..proof.proof_data.len() - 1],
+            &verification_key
+        ));
+
+        // a witness breaking the multiplication gate has no quotient polynomial
+        let (proof, verification_key) = prove(36);
+        assert!(!verifies(&proof.proof_data, &verification_key));
+    }
+
+    #[test]
+    fn proving_within_a_memory_budget_spills_and_still_verifies() {
+        let (key, verification_key) = standard_keys::<Bn254>(35);
+        // about two of the 4n coset FFTs fit, so the quotient round has to page polynomials out
+        key.write().unwrap().set_memory_budget(Some(5 * 1024));
+        let mut prover = Prover::<
+            Bn254,
+            Keccak256,
+            StandardSettings<Keccak256>,
+            KateCommitmentScheme<Keccak256, StandardSettings<Keccak256>>,
+        >::new_standard_prover(Some(key.clone()), None, None);
+        let proof = prover.construct_proof().unwrap();
+
+        let stats = key.read().unwrap().polynomial_store.stats();
+        assert!(stats.num_spills > 0);
+        assert!(stats.num_page_ins > 0);
+
+        let verifier =
+            <Verifier<'static, Bn254, Keccak256, StandardSettings<Keccak256>> as VerifierBase<
+                _,
+                _,
+                _,
+            >>::new(Some(verification_key), standard_manifest_for::<Bn254>(1));
+        assert!(verifier.verify_proof(&proof));
+    }
+
+    #[test]
+    fn proving_frees_the_witness_polynomials_it_is_done_with() {
+        let (key, _) = standard_keys::<Bn254>(35);
+        // selectors and sigmas, without the witness the key comes with
+        let key_bytes: usize = key
+            .read()
+            .unwrap()
+            .polynomial_store
+            .stats()
+            .polynomials
+            .iter()
+            .filter(|p| !p.name.starts_with("w_"))
+            .map(|p| p.num_bytes)
+            .sum();
+        let mut prover = Prover::<
+            Bn254,
+            Keccak256,
+            StandardSettings<Keccak256>,
+            KateCommitmentScheme<Keccak256, StandardSettings<Keccak256>>,
+        >::new_standard_prover(Some(key.clone()), None, None);
+        prover.construct_proof().unwrap();
+
+        let stats = key.read().unwrap().polynomial_store.stats();
+        // only the key's own polynomials and the L_1 coset FFT outlive the proof
+        assert!(stats
+            .polynomials
+            .iter()
+            .all(|p| !p.name.starts_with("w_") && !p.name.starts_with("z_perm")));
+        let lagrange_1_bytes = (4 * N + 8) * std::mem::size_of::<ark_bn254::Fr>();
+        assert_eq!(stats.resident_bytes, key_bytes + lagrange_1_bytes);
+        // holding the lagrange, monomial and coset FFT forms of w_1, w_2, w_3 and z_perm at once
+        // takes at least this much, but the lagrange forms are gone before the FFTs are made
+        let every_witness_form_bytes = 4 * (N + N + 4 * N) * std::mem::size_of::<ark_bn254::Fr>();
+        assert!(
+            stats.peak_resident_bytes < key_bytes + lagrange_1_bytes + every_witness_form_bytes
+        );
+    }
+
+    /// Turbo keys for `x·y = 35` and `x + y + z = w` with 35 public, and a range gate over the base-4
+    /// digits 1, 2, 3 of `accumulator`, over `N` rows. Row 0 holds the public input and rows 1 and 2 the
+    /// width-4 arithmetic gates, with `sum` in the fourth wire of the addition. Row 3 accumulates the
+    /// digits from w_4 to w_1, and the fourth wire of row 4 takes `accumulator`. The fixed-base and logic
+    /// selectors are all zero.
+    fn turbo_keys<C: PairingCurveConfig>(
+        sum: u64,
+        accumulator: u64,
+    ) -> (
+        Arc<RwLock<ProvingKey<'static, C>>>,
+        Arc<VerificationKey<'static, C>>,
+    ) {
+        let srs = mock_srs::<C::Pairing>(2 * N);
+        let mut key = ProvingKey::<C>::new(
+            N - 1,
+            1,
+            Arc::new(RwLock::new(srs.clone())),
+            ComposerType::Turbo,
+        );
+
+        let value = |v: u64| C::Fr::from(v);
+        let one = C::Fr::one();
+        let column = |cells: &[(usize, C::Fr)]| {
+            let mut column = vec![C::Fr::zero(); N];
+            for (row, cell) in cells {
+                column[*row] = *cell;
+            }
+            Polynomial::from_coefficients(column, N)
+        };
+        let wires = [
+            (
+                "w_1",
+                column(&[(0, value(35)), (1, value(5)), (2, value(5)), (3, value(27))]),
+            ),
+            (
+                "w_2",
+                column(&[(0, value(35)), (1, value(7)), (2, value(7)), (3, value(6))]),
+            ),
+            (
+                "w_3",
+                column(&[(1, value(35)), (2, value(3)), (3, value(1))]),
+            ),
+            ("w_4", column(&[(2, value(sum)), (4, value(accumulator))])),
+        ];
+        for (label, lagrange) in wires {
+            key.polynomial_store
+                .put(format!("{}_lagrange", label), lagrange)
+                .unwrap();
+        }
+        put_selectors(
+            &mut key,
+            [
+                ("q_1", column(&[(2, one)])),
+                ("q_2", column(&[(2, one)])),
+                ("q_3", column(&[(1, -one), (2, one)])),
+                ("q_4", column(&[(2, -one)])),
+                ("q_5", column(&[])),
+                ("q_m", column(&[(1, one)])),
+                ("q_c", column(&[])),
+                ("q_arith", column(&[(1, one), (2, one)])),
+                ("q_fixed_base", column(&[])),
+                ("q_range", column(&[(3, one)])),
+                ("q_logic", column(&[])),
+            ],
+        );
+
+        // the same copies as the standard circuit, the fourth column only maps to itself
+        let cell = |row: u32, column: u8| PermutationSubgroupElement::new(row, column);
+        let mut mapping = PermutationMapping::identity(4, N, false);
+        mapping.sigmas[0][0].is_public_input = true;
+        mapping.sigmas[1][0] = cell(1, 2);
+        mapping.sigmas[2][1] = cell(0, 0);
+        mapping.sigmas[0][1] = cell(2, 0);
+        mapping.sigmas[0][2] = cell(1, 0);
+        mapping.sigmas[1][1] = cell(2, 1);
+        mapping.sigmas[1][2] = cell(1, 1);
+        compute_permutation_polynomials_from_mapping::<PedersenBlake3s, C, TurboSettings>(
+            "sigma",
+            &mapping.sigmas,
+            &mut key,
+        )
+        .unwrap();
+
+        let verification_key = VerificationKey::from_proving_key(&key, Arc::new(srs)).unwrap();
+        (Arc::new(RwLock::new(key)), Arc::new(verification_key))
+    }
+
+    #[test]
+    fn bn254_turbo_proof_verifies() {
+        let prove = |sum: u64, accumulator: u64| {
+            let (key, verification_key) = turbo_keys::<Bn254>(sum, accumulator);
+            let mut prover = Prover::<
+                Bn254,
+                PedersenBlake3s,
+                TurboSettings,
+                KateCommitmentScheme<PedersenBlake3s, TurboSettings>,
+            >::new_turbo_prover(Some(key), None, None);
+            (prover.construct_proof().unwrap(), verification_key)
+        };
+        let verifies = |proof: &Proof, verification_key: Arc<VerificationKey<'static, Bn254>>| {
+            let verifier =
+                <Verifier<'static, Bn254, PedersenBlake3s, TurboSettings> as VerifierBase<
+                    _,
+                    _,
+                    _,
+                >>::new(Some(verification_key), turbo_manifest_for::<Bn254>(1));
+            verifier.verify_proof(proof)
+        };
+
+        // 109 is 1231 in base 4
+        let (proof, verification_key) = prove(15, 109);
+        assert!(verifies(&proof, verification_key));
+
+        // 5 + 7 + 3 is not 16
+        let (proof, verification_key) = prove(16, 109);
+        assert!(!verifies(&proof, verification_key));
+
+        // 112 - 4·27 = 4 is not a base-4 digit
+        let (proof, verification_key) = prove(15, 112);
+        assert!(!verifies(&proof, verification_key));
+    }
+
+    /// Ultra keys for `x·y = 35` and `x + y + z = w` with 35 public, as in [`turbo_keys`], and lookups of
+    /// `lookups` into the table of `(k, k²)` for k in 1..=4, over `N` rows. The lookups go in rows 3 and
+    /// on, and the table sits in the rows right before the ones cut out of the vanishing polynomial.
+    /// `table_squares` are the second column of the table in the proving key, the verification key always
+    /// has the honest one.
+    fn ultra_keys<C: PairingCurveConfig>(
+        sum: u64,
+        lookups: &[(u64, u64)],
+        table_squares: [u64; 4],
+    ) -> (
+        Arc<RwLock<ProvingKey<'static, C>>>,
+        Arc<VerificationKey<'static, C>>,
+    ) {
+        let srs = mock_srs::<C::Pairing>(2 * N);
+        let value = |v: u64| C::Fr::from(v);
+        let one = C::Fr::one();
+        let column = |cells: &[(usize, C::Fr)]| {
+            let mut column = vec![C::Fr::zero(); N];
+            for (row, cell) in cells {
+                column[*row] = *cell;
+            }
+            Polynomial::from_coefficients(column, N)
+        };
+
+        let key = |table_squares: [u64; 4]| {
+            let mut key = ProvingKey::<C>::new(
+                N - 1,
+                1,
+                Arc::new(RwLock::new(srs.clone())),
+                ComposerType::Plookup,
+            );
+            let lookup_rows = (3..3 + lookups.len()).collect::<Vec<_>>();
+            let mut w_1 = vec![(0, value(35)), (1, value(5)), (2, value(5))];
+            let mut w_2 = vec![(0, value(35)), (1, value(7)), (2, value(7))];
+            for (row, (k, square)) in lookup_rows.iter().zip(lookups) {
+                w_1.push((*row, value(*k)));
+                w_2.push((*row, value(*square)));
+            }
+            let wires = [
+                ("w_1", column(&w_1)),
+                ("w_2", column(&w_2)),
+                ("w_3", column(&[(1, value(35)), (2, value(3))])),
+                ("w_4", column(&[(2, value(sum))])),
+            ];
+            for (label, lagrange) in wires {
+                key.polynomial_store
+                    .put(format!("{}_lagrange", label), lagrange)
+                    .unwrap();
+            }
+
+            // the table ends in row N - 4, and table 1 is told apart by its fourth column
+            let table_rows = N - 4 - 3..=N - 4;
+            let table_column = |values: [u64; 4]| {
+                column(
+                    &table_rows
+                        .clone()
+                        .zip(values)
+                        .map(|(row, v)| (row, value(v)))
+                        .collect::<Vec<_>>(),
+                )
+            };
+            let lookup_column = |cell: C::Fr| {
+                column(
+                    &lookup_rows
+                        .iter()
+                        .map(|row| (*row, cell))
+                        .collect::<Vec<_>>(),
+                )
+            };
+            let mut q_3 = vec![(1, -one), (2, one)];
+            q_3.extend(lookup_rows.iter().map(|row| (*row, one)));
+            put_selectors(
+                &mut key,
+                [
+                    ("q_1", column(&[(2, one)])),
+                    ("q_2", column(&[(2, one)])),
+                    ("q_3", column(&q_3)),
+                    ("q_4", column(&[(2, -one)])),
+                    ("q_m", column(&[(1, one)])),
+                    ("q_c", column(&[])),
+                    ("q_arith", column(&[(1, one), (2, one)])),
+                    ("q_sort", column(&[])),
+                    ("q_elliptic", column(&[])),
+                    ("q_aux", column(&[])),
+                    ("table_value_1", table_column([1, 2, 3, 4])),
+                    ("table_value_2", table_column(table_squares)),
+                    ("table_value_3", column(&[])),
+                    ("table_value_4", table_column([1, 1, 1, 1])),
+                    ("table_type", lookup_column(one)),
+                ],
+            );
+
+            let cell = |row: u32, column: u8| PermutationSubgroupElement::new(row, column);
+            let mut mapping = PermutationMapping::identity(4, N, true);
+            mapping.sigmas[0][0].is_public_input = true;
+            mapping.sigmas[1][0] = cell(1, 2);
+            mapping.sigmas[2][1] = cell(0, 0);
+            mapping.sigmas[0][1] = cell(2, 0);
+            mapping.sigmas[0][2] = cell(1, 0);
+            mapping.sigmas[1][1] = cell(2, 1);
+            mapping.sigmas[1][2] = cell(1, 1);
+            for (label, columns) in [("sigma", &mapping.sigmas), ("id", &mapping.ids)] {
+                compute_permutation_polynomials_from_mapping::<
+                    Keccak256,
+                    C,
+                    UltraWithKeccakSettings,
+                >(label, columns, &mut key)
+                .unwrap();
+            }
+            key
+        };
+
+        let verification_key =
+            VerificationKey::from_proving_key(&key([1, 4, 9, 16]), Arc::new(srs.clone())).unwrap();
+        (
+            Arc::new(RwLock::new(key(table_squares))),
+            Arc::new(verification_key),
+        )
+    }
+
+    #[test]
+    fn bn254_ultra_proof_verifies() {
+        let prove = |sum: u64, lookups: &[(u64, u64)], table_squares: [u64; 4]| {
+            let (key, verification_key) = ultra_keys::<Bn254>(sum, lookups, table_squares);
+            let mut prover = Prover::<
+                Bn254,
+                Keccak256,
+                UltraWithKeccakSettings,
+                KateCommitmentScheme<Keccak256, UltraWithKeccakSettings>,
+            >::new_ultra_prover(Some(key), None, None);
+            (prover.construct_proof(), verification_key)
+        };
+        let verifies = |proof: &Proof, verification_key: Arc<VerificationKey<'static, Bn254>>| {
+            let verifier =
+                <Verifier<'static, Bn254, Keccak256, UltraWithKeccakSettings> as VerifierBase<
+                    _,
+                    _,
+                    _,
+                >>::new(Some(verification_key), ultra_manifest_for::<Bn254>(1));
+            verifier.verify_proof(proof)
+        };
+        let honest_table = [1, 4, 9, 16];
+
+        let (proof, verification_key) = prove(15, &[(3, 9), (2, 4), (3, 9)], honest_table);
+        assert!(verifies(&proof.unwrap(), verification_key));
+
+        // 5 + 7 + 3 is not 16
+        let (proof, verification_key) = prove(16, &[(3, 9), (2, 4)], honest_table);
+        assert!(!verifies(&proof.unwrap(), verification_key));
+
+        // (3, 10) is in no table, so there is no sorted list to commit to
+        let (proof, _) = prove(15, &[(3, 10)], honest_table);
+        assert!(proof.is_err());
+
+        // nor does a proof against a table that has it pass for the circuit's table
+        let (proof, verification_key) = prove(15, &[(3, 10)], [1, 4, 10, 16]);
+        assert!(!verifies(&proof.unwrap(), verification_key));
+    }
+
+    #[test]
+    fn bn254_standard_proof_verifies() {
+        check_prove_verify::<Bn254>();
+    }
+
     #[test]
-    fn test_prover() {
-        todo!("get it from prover.test.cpp. there is like 300 lines in there.")
+    fn bls12_381_standard_proof_verifies() {
+        check_prove_verify::<Bls12_381>();
     }
 }
@@ -1,13 +1,10 @@
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use ark_ff::Field;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::cell::RefCell;
 use std::io::Read;
-use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::vec::Vec;
 
-use crate::ecc::PippengerRuntimeState;
+use crate::ecc::{curves::CurveConfig, PippengerRuntimeState};
 use crate::plonk::proof_system::constants::NUM_QUOTIENT_PARTS;
 
 use crate::plonk::composer::composer_base::ComposerType;
@@ -32,7 +29,7 @@ pub(crate) struct ProvingKeyData<F: Field> {
     polynomial_store: PolynomialStore<F>,
 }
 
-pub(crate) struct ProvingKey<'a, Fr: Field + FftField, G1Affine: AffineRepr> {
+pub(crate) struct ProvingKey<'a, C: CurveConfig> {
     pub(crate) composer_type: u32,
     pub(crate) circuit_size: usize,
     pub(crate) log_circuit_size: usize,
@@ -43,19 +40,19 @@ pub(crate) struct ProvingKey<'a, Fr: Field + FftField, G1Affine: AffineRepr> {
     pub(crate) memory_read_records: Vec<usize>,
     /// Used by UltraComposer only, for RAM writes.
     pub(crate) memory_write_records: Vec<usize>,
-    pub(crate) polynomial_store: PolynomialStore<Fr>,
-    pub(crate) small_domain: EvaluationDomain<'a, Fr>,
-    pub(crate) large_domain: EvaluationDomain<'a, Fr>,
+    pub(crate) polynomial_store: PolynomialStore<C::Fr>,
+    pub(crate) small_domain: EvaluationDomain<'a, C::Fr>,
+    pub(crate) large_domain: EvaluationDomain<'a, C::Fr>,
     /// The reference_string object contains the monomial SRS. We can access it using:
     /// Monomial SRS: reference_string->get_monomial_points()
-    pub(crate) reference_string: Rc<RefCell<dyn ProverReferenceString<G1Affine>>>,
+    pub(crate) reference_string: Arc<RwLock<dyn ProverReferenceString<C::G1Affine>>>,
     pub(crate) quotient_polynomial_parts:
-        [Rc<RefCell<Polynomial<Fr>>>; NUM_QUOTIENT_PARTS as usize],
-    pub(crate) pippenger_runtime_state: PippengerRuntimeState<Fr, G1Affine>,
+        [Arc<RwLock<Polynomial<C::Fr>>>; NUM_QUOTIENT_PARTS as usize],
+    pub(crate) pippenger_runtime_state: PippengerRuntimeState<C::Fr, C::G1Affine>,
     pub(crate) polynomial_manifest: PolynomialManifest,
 }
 
-impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> Default for ProvingKey<'a, Fr, G1Affine> {
+impl<'a, C: CurveConfig> Default for ProvingKey<'a, C> {
     fn default() -> Self {
         Self {
             composer_type: 0,
@@ -69,7 +66,7 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> Default for ProvingKey<'a,
             polynomial_store: PolynomialStore::new(),
             small_domain: EvaluationDomain::new(0, None),
             large_domain: EvaluationDomain::new(0, None),
-            reference_string: Rc::new(RefCell::new(FileReferenceString::<G1Affine>::default())),
+            reference_string: Arc::new(RwLock::new(FileReferenceString::<C::G1Affine>::default())),
             quotient_polynomial_parts: Default::default(),
             pippenger_runtime_state: PippengerRuntimeState::default(),
             polynomial_manifest: PolynomialManifest::default(),
@@ -77,10 +74,10 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> Default for ProvingKey<'a,
     }
 }
 
-impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> ProvingKey<'a, Fr, G1Affine> {
+impl<'a, C: CurveConfig> ProvingKey<'a, C> {
     pub(crate) fn new_with_data(
-        data: ProvingKeyData<Fr>,
-        crs: Rc<RefCell<dyn ProverReferenceString<G1Affine>>>,
+        data: ProvingKeyData<C::Fr>,
+        crs: Arc<RwLock<dyn ProverReferenceString<C::G1Affine>>>,
     ) -> Self {
         let ProvingKeyData {
             composer_type,
@@ -95,7 +92,8 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> ProvingKey<'a, Fr, G1Affine
 
         let log_circuit_size = (circuit_size as f64).log2().ceil() as usize;
         let small_domain = EvaluationDomain::new(circuit_size as usize, None);
-        let large_domain = EvaluationDomain::new(1usize << log_circuit_size, None);
+        // the quotient has degree ~4n, so we evaluate it over a 4n coset
+        let large_domain = EvaluationDomain::new(4 * circuit_size as usize, None);
 
         let mut ret = Self {
             composer_type,
@@ -112,7 +110,7 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> ProvingKey<'a, Fr, G1Affine
             reference_string: crs,
             quotient_polynomial_parts: Default::default(),
             pippenger_runtime_state: PippengerRuntimeState::default(),
-            polynomial_manifest: PolynomialManifest::default(),
+            polynomial_manifest: PolynomialManifest::new(composer_type),
         };
         ret.init();
         ret
@@ -121,7 +119,7 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> ProvingKey<'a, Fr, G1Affine
     pub(crate) fn new(
         num_gates: usize,
         num_inputs: usize,
-        crs: Rc<RefCell<dyn ProverReferenceString<G1Affine>>>,
+        crs: Arc<RwLock<dyn ProverReferenceString<C::G1Affine>>>,
         type_: ComposerType,
     ) -> Self {
         let data = ProvingKeyData {
@@ -154,25 +152,30 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> ProvingKey<'a, Fr, G1Affine
         // t_i for i = 1,2,3 have n+1 coefficients after blinding. t_4 has only n coefficients.
         // TODO unclear if this is necessary
         self.quotient_polynomial_parts[0] =
-            Rc::new(RefCell::new(Polynomial::new(self.circuit_size + 1)));
+            Arc::new(RwLock::new(Polynomial::new(self.circuit_size + 1)));
         self.quotient_polynomial_parts[1] =
-            Rc::new(RefCell::new(Polynomial::new(self.circuit_size + 1)));
+            Arc::new(RwLock::new(Polynomial::new(self.circuit_size + 1)));
         self.quotient_polynomial_parts[2] =
-            Rc::new(RefCell::new(Polynomial::new(self.circuit_size + 1)));
+            Arc::new(RwLock::new(Polynomial::new(self.circuit_size + 1)));
         self.quotient_polynomial_parts[3] =
-            Rc::new(RefCell::new(Polynomial::new(self.circuit_size)));
+            Arc::new(RwLock::new(Polynomial::new(self.circuit_size)));
+    }
+
+    /// Cap the bytes of polynomials the store keeps in memory while proving; past it the least
+    /// recently used ones spill to disk. `None` keeps everything resident.
+    pub(crate) fn set_memory_budget(&mut self, memory_budget: Option<usize>) {
+        self.polynomial_store.set_memory_budget(memory_budget);
     }
 
-    pub(crate) fn from_reader<R: Read>(
-        _reader: &mut R,
-        crs_path: &str,
-    ) -> Result<Self, std::io::Error> {
-        let _crs = Arc::new(FileReferenceString::<G1Affine>::read_from_path(crs_path)?);
+    pub(crate) fn from_reader<R: Read>(_reader: &mut R, crs_path: &str) -> anyhow::Result<Self> {
+        let _crs = Arc::new(FileReferenceString::<C::G1Affine>::read_from_path(
+            crs_path,
+        )?);
         todo!();
     }
 }
 
-impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> Serialize for ProvingKey<'a, Fr, G1Affine> {
+impl<'a, C: CurveConfig> Serialize for ProvingKey<'a, C> {
     fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
         // TODO
         /*
@@ -237,9 +240,7 @@ impl<'a, Fr: Field + FftField, G1Affine: AffineRepr> Serialize for ProvingKey<'a
     }
 }
 
-impl<'a, 'de, Fr: Field + FftField, G1Affine: AffineRepr> Deserialize<'de>
-    for ProvingKey<'a, Fr, G1Affine>
-{
+impl<'a, 'de, C: CurveConfig> Deserialize<'de> for ProvingKey<'a, C> {
     fn deserialize<D>(_deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
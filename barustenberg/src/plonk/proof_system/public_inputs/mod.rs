@@ -1,4 +1,5 @@
-use ark_ff::Field;
+use anyhow::{anyhow, Result};
+use ark_ff::FftField;
 
 use crate::ecc::curves::{coset_generator, external_coset_generator};
 
@@ -109,15 +110,17 @@ use crate::ecc::curves::{coset_generator, external_coset_generator};
  * the memory cells on the second column map to our public inputs. We can then use traditional copy constraints to map
  * these cells to other locations in program memory.
  **/
-pub(crate) fn compute_public_input_delta<F: Field>(
+pub(crate) fn compute_public_input_delta<F: FftField>(
     public_inputs: &[F],
     beta: F,
     gamma: F,
     subgroup_generator: F,
-) -> F {
+) -> Result<F> {
     let mut numerator = F::one();
     let mut denominator = F::one();
 
+    let coset_generator = coset_generator::<F>(0);
+    let external_coset_generator = external_coset_generator::<F>();
     let mut work_root = F::one();
     let mut t0;
     let mut t1;
@@ -126,14 +129,111 @@ pub(crate) fn compute_public_input_delta<F: Field>(
     for witness in public_inputs {
         t0 = *witness + gamma;
         t1 = work_root * beta;
-        t2 = t1 * coset_generator::<F>(0);
-        t3 = t1 * external_coset_generator::<F>();
+        t2 = t1 * coset_generator;
+        t3 = t1 * external_coset_generator;
         t2 += t0;
         t3 += t0;
         numerator *= t2;
         denominator *= t3;
         work_root *= subgroup_generator;
     }
-    t0 = numerator / denominator;
-    t0
+    let denominator = denominator
+        .inverse()
+        .ok_or_else(|| anyhow!("a public input cancels its term of the public input delta"))?;
+    Ok(numerator * denominator)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::{Field, One};
+    use proptest::{collection::vec, prelude::*};
+
+    const CIRCUIT_SIZE: usize = 16;
+
+    /// The product of the permutation grand product's per-row terms over a two column trace whose first
+    /// rows hold `witnesses` in both wires. The w_1 cell of each of those rows is copied into a later row,
+    /// while the w_2 cell is mapped into the external coset, the way public inputs are wired. Every other
+    /// cell holds `padding` and maps to itself.
+    fn grand_product(witnesses: &[Fr], padding: &[Fr], beta: Fr, gamma: Fr) -> Fr {
+        let root = Fr::get_root_of_unity(CIRCUIT_SIZE as u64).unwrap();
+        let k_1 = coset_generator::<Fr>(0);
+        let k_ext = external_coset_generator::<Fr>();
+        let m = witnesses.len();
+
+        let roots = (0..CIRCUIT_SIZE)
+            .map(|i| root.pow([i as u64]))
+            .collect::<Vec<_>>();
+        let mut wires = [padding.to_vec(), padding.to_vec()];
+        let mut sigmas = [roots.clone(), roots.iter().map(|r| *r * k_1).collect()];
+        for (i, witness) in witnesses.iter().enumerate() {
+            wires[0][i] = *witness;
+            wires[1][i] = *witness;
+            wires[0][m + i] = *witness;
+            sigmas[0].swap(i, m + i);
+            sigmas[1][i] = roots[i] * k_ext;
+        }
+
+        let mut numerator = Fr::one();
+        let mut denominator = Fr::one();
+        for i in 0..CIRCUIT_SIZE {
+            for (column, id) in [Fr::one(), k_1].into_iter().enumerate() {
+                numerator *= wires[column][i] + beta * id * roots[i] + gamma;
+                denominator *= wires[column][i] + beta * sigmas[column][i] + gamma;
+            }
+        }
+        numerator / denominator
+    }
+
+    fn field_elements(
+        len: impl Into<proptest::collection::SizeRange>,
+    ) -> impl Strategy<Value = Vec<Fr>> {
+        vec(any::<u64>().prop_map(Fr::from), len)
+    }
+
+    #[test]
+    fn public_input_cancelling_a_denominator_term_is_an_error() {
+        let (beta, gamma) = (Fr::from(3u64), Fr::from(5u64));
+        let root = Fr::get_root_of_unity(CIRCUIT_SIZE as u64).unwrap();
+        let cancelling = -(gamma + beta * root * external_coset_generator::<Fr>());
+        let public_inputs = [Fr::from(7u64), cancelling];
+        assert!(compute_public_input_delta(&public_inputs, beta, gamma, root).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn grand_product_balances_with_the_correct_public_inputs(
+            public_inputs in field_elements(0..=CIRCUIT_SIZE / 2),
+            padding in field_elements(CIRCUIT_SIZE),
+            beta in 1..u64::MAX,
+            gamma in 1..u64::MAX,
+        ) {
+            let (beta, gamma) = (Fr::from(beta), Fr::from(gamma));
+            let root = Fr::get_root_of_unity(CIRCUIT_SIZE as u64).unwrap();
+            prop_assert_eq!(
+                grand_product(&public_inputs, &padding, beta, gamma),
+                compute_public_input_delta(&public_inputs, beta, gamma, root).unwrap()
+            );
+        }
+
+        #[test]
+        fn grand_product_does_not_balance_with_wrong_public_inputs(
+            public_inputs in field_elements(1..=CIRCUIT_SIZE / 2),
+            padding in field_elements(CIRCUIT_SIZE),
+            beta in 1..u64::MAX,
+            gamma in 1..u64::MAX,
+            tampered in any::<prop::sample::Index>(),
+            offset in 1..u64::MAX,
+        ) {
+            let (beta, gamma) = (Fr::from(beta), Fr::from(gamma));
+            let root = Fr::get_root_of_unity(CIRCUIT_SIZE as u64).unwrap();
+            let mut claimed = public_inputs.clone();
+            claimed[tampered.index(public_inputs.len())] += Fr::from(offset);
+            prop_assert_ne!(
+                grand_product(&public_inputs, &padding, beta, gamma),
+                compute_public_input_delta(&claimed, beta, gamma, root).unwrap()
+            );
+        }
+    }
 }
@@ -1,12 +1,48 @@
 use std::ops::Index;
 
+use crate::plonk::composer::composer_base::ComposerType;
+
+/// The rounds of the prover, in the order `Prover::construct_proof` runs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum ProverRound {
+    Preamble,
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+}
+
+/// The forms the prover keeps a polynomial in. Each one lives in the polynomial store under its own label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PolynomialForm {
+    Lagrange,
+    Monomial,
+    CosetFft,
+}
+
+impl PolynomialForm {
+    /// The polynomial store label of this form of `polynomial_label`.
+    pub(crate) fn label(&self, polynomial_label: &str) -> String {
+        match self {
+            PolynomialForm::Lagrange => format!("{}_lagrange", polynomial_label),
+            PolynomialForm::Monomial => polynomial_label.to_string(),
+            PolynomialForm::CosetFft => format!("{}_fft", polynomial_label),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct PolynomialDescriptor {
-    commitment_label: String,
+    pub(crate) commitment_label: String,
     pub(crate) polynomial_label: String,
     pub(crate) requires_shifted_evaluation: bool,
-    source: PolynomialSource,
+    pub(crate) source: PolynomialSource,
     pub(crate) index: PolynomialIndex,
+    /// Which prover rounds read which form of the polynomial. Only tracked for witnesses, everything
+    /// else is part of the proving key and sticks around for the next proof.
+    pub(crate) consumer_rounds: Vec<(PolynomialForm, ProverRound)>,
 }
 
 impl PolynomialDescriptor {
@@ -23,20 +59,268 @@ impl PolynomialDescriptor {
             requires_shifted_evaluation,
             source,
             index,
+            consumer_rounds: Vec::new(),
         }
     }
+
+    fn selector(label: &str, requires_shifted_evaluation: bool, index: PolynomialIndex) -> Self {
+        Self::new(
+            label.to_uppercase(),
+            label.to_string(),
+            requires_shifted_evaluation,
+            PolynomialSource::Selector,
+            index,
+        )
+    }
+
+    fn permutation(label: &str, index: PolynomialIndex) -> Self {
+        Self::new(
+            label.to_uppercase(),
+            label.to_string(),
+            false,
+            PolynomialSource::Permutation,
+            index,
+        )
+    }
+
+    /// A witness whose lagrange form is read in `lagrange_rounds` and monomial form in `monomial_rounds`.
+    /// The coset FFT form is only ever needed for the quotient.
+    fn witness(
+        commitment_label: &str,
+        polynomial_label: &str,
+        index: PolynomialIndex,
+        lagrange_rounds: &[ProverRound],
+        monomial_rounds: &[ProverRound],
+    ) -> Self {
+        let mut descriptor = Self::new(
+            commitment_label.to_string(),
+            polynomial_label.to_string(),
+            true,
+            PolynomialSource::Witness,
+            index,
+        );
+        descriptor.consumer_rounds.extend(
+            lagrange_rounds
+                .iter()
+                .map(|round| (PolynomialForm::Lagrange, *round)),
+        );
+        descriptor.consumer_rounds.extend(
+            monomial_rounds
+                .iter()
+                .map(|round| (PolynomialForm::Monomial, *round)),
+        );
+        descriptor
+            .consumer_rounds
+            .push((PolynomialForm::CosetFft, ProverRound::Fourth));
+        descriptor
+    }
+
+    /// The last round that reads `form` of this polynomial, if any.
+    pub(crate) fn last_consumer_round(&self, form: PolynomialForm) -> Option<ProverRound> {
+        self.consumer_rounds
+            .iter()
+            .filter(|(consumed_form, _)| *consumed_form == form)
+            .map(|(_, round)| *round)
+            .max()
+    }
 }
+
 #[derive(Clone, Default)]
 pub(crate) struct PolynomialManifest {
     manifest: Vec<PolynomialDescriptor>,
 }
 
 impl PolynomialManifest {
+    /// The polynomials used by a given composer, as in barretenberg's `polynomial_manifest.hpp`.
+    pub(crate) fn new(composer_type: u32) -> Self {
+        use PolynomialIndex::*;
+        use ProverRound::*;
+
+        // wires are ifft'd and committed in the first round and their lagrange forms go into the
+        // grand product in the third. Monomial forms get fft'd in the third round and opened at the end.
+        let wire_lagrange_rounds: &[ProverRound] = if composer_type == ComposerType::Plookup as u32
+        {
+            // plookup also reads the wires for the sorted list and memory records
+            &[First, Second, Third]
+        } else {
+            &[First, Third]
+        };
+        let monomial_rounds = &[Third, Fifth, Sixth];
+        let wire = |i: usize, index: PolynomialIndex, requires_shifted_evaluation: bool| {
+            let mut descriptor = PolynomialDescriptor::witness(
+                &format!("W_{}", i),
+                &format!("w_{}", i),
+                index,
+                wire_lagrange_rounds,
+                monomial_rounds,
+            );
+            descriptor.requires_shifted_evaluation = requires_shifted_evaluation;
+            descriptor
+        };
+        let z_perm =
+            PolynomialDescriptor::witness("Z_PERM", "z_perm", Z, &[Third], monomial_rounds);
+
+        let manifest = if composer_type == ComposerType::Standard as u32 {
+            vec![
+                wire(1, W1, false),
+                wire(2, W2, false),
+                wire(3, W3, false),
+                z_perm,
+                PolynomialDescriptor::selector("q_1", false, Q1),
+                PolynomialDescriptor::selector("q_2", false, Q2),
+                PolynomialDescriptor::selector("q_3", false, Q3),
+                PolynomialDescriptor::selector("q_m", false, QM),
+                PolynomialDescriptor::selector("q_c", false, QC),
+                PolynomialDescriptor::permutation("sigma_1", Sigma1),
+                PolynomialDescriptor::permutation("sigma_2", Sigma2),
+                PolynomialDescriptor::permutation("sigma_3", Sigma3),
+            ]
+        } else if composer_type == ComposerType::Turbo as u32 {
+            vec![
+                wire(1, W1, true),
+                wire(2, W2, true),
+                wire(3, W3, true),
+                wire(4, W4, true),
+                z_perm,
+                PolynomialDescriptor::selector("q_1", false, Q1),
+                PolynomialDescriptor::selector("q_2", false, Q2),
+                PolynomialDescriptor::selector("q_3", false, Q3),
+                PolynomialDescriptor::selector("q_4", false, Q4),
+                PolynomialDescriptor::selector("q_5", false, Q5),
+                PolynomialDescriptor::selector("q_m", false, QM),
+                PolynomialDescriptor::selector("q_c", false, QC),
+                PolynomialDescriptor::new(
+                    "Q_ARITHMETIC_SELECTOR".to_string(),
+                    "q_arith".to_string(),
+                    false,
+                    PolynomialSource::Selector,
+                    QArithmetic,
+                ),
+                PolynomialDescriptor::selector("q_fixed_base", false, QFixedBase),
+                PolynomialDescriptor::selector("q_range", false, QRange),
+                PolynomialDescriptor::selector("q_logic", false, QLogic),
+                PolynomialDescriptor::permutation("sigma_1", Sigma1),
+                PolynomialDescriptor::permutation("sigma_2", Sigma2),
+                PolynomialDescriptor::permutation("sigma_3", Sigma3),
+                PolynomialDescriptor::permutation("sigma_4", Sigma4),
+            ]
+        } else if composer_type == ComposerType::Plookup as u32 {
+            vec![
+                wire(1, W1, true),
+                wire(2, W2, true),
+                wire(3, W3, true),
+                wire(4, W4, true),
+                // the sorted list is built, committed and queued for its fft in the second round, and
+                // its lagrange form feeds the lookup grand product in the third
+                PolynomialDescriptor::witness(
+                    "S",
+                    "s",
+                    S,
+                    &[Second, Third],
+                    &[Second, Fifth, Sixth],
+                ),
+                z_perm,
+                PolynomialDescriptor::witness(
+                    "Z_LOOKUP",
+                    "z_lookup",
+                    ZLookup,
+                    &[Third],
+                    monomial_rounds,
+                ),
+                PolynomialDescriptor::selector("q_1", false, Q1),
+                PolynomialDescriptor::selector("q_2", false, Q2),
+                PolynomialDescriptor::selector("q_3", false, Q3),
+                PolynomialDescriptor::selector("q_4", false, Q4),
+                PolynomialDescriptor::selector("q_m", false, QM),
+                PolynomialDescriptor::selector("q_c", false, QC),
+                PolynomialDescriptor::new(
+                    "Q_ARITHMETIC".to_string(),
+                    "q_arith".to_string(),
+                    false,
+                    PolynomialSource::Selector,
+                    QArithmetic,
+                ),
+                PolynomialDescriptor::selector("q_sort", false, QSort),
+                PolynomialDescriptor::selector("q_elliptic", false, QElliptic),
+                PolynomialDescriptor::selector("q_aux", false, QAux),
+                PolynomialDescriptor::permutation("sigma_1", Sigma1),
+                PolynomialDescriptor::permutation("sigma_2", Sigma2),
+                PolynomialDescriptor::permutation("sigma_3", Sigma3),
+                PolynomialDescriptor::permutation("sigma_4", Sigma4),
+                PolynomialDescriptor::new(
+                    "TABLE_1".to_string(),
+                    "table_value_1".to_string(),
+                    true,
+                    PolynomialSource::Selector,
+                    Table1,
+                ),
+                PolynomialDescriptor::new(
+                    "TABLE_2".to_string(),
+                    "table_value_2".to_string(),
+                    true,
+                    PolynomialSource::Selector,
+                    Table2,
+                ),
+                PolynomialDescriptor::new(
+                    "TABLE_3".to_string(),
+                    "table_value_3".to_string(),
+                    true,
+                    PolynomialSource::Selector,
+                    Table3,
+                ),
+                PolynomialDescriptor::new(
+                    "TABLE_4".to_string(),
+                    "table_value_4".to_string(),
+                    true,
+                    PolynomialSource::Selector,
+                    Table4,
+                ),
+                PolynomialDescriptor::selector("table_type", false, TableType),
+                PolynomialDescriptor::permutation("id_1", Id1),
+                PolynomialDescriptor::permutation("id_2", Id2),
+                PolynomialDescriptor::permutation("id_3", Id3),
+                PolynomialDescriptor::permutation("id_4", Id4),
+            ]
+        } else {
+            vec![]
+        };
+        Self { manifest }
+    }
+
     pub(crate) fn len(&self) -> usize {
-        todo!()
+        self.manifest.len()
+    }
+    /// The descriptor of the polynomial at `index`, if the composer uses it. The manifest is in
+    /// barretenberg's order, witnesses first, so this goes by the descriptors' own indices.
+    pub(crate) fn get(&self, index: PolynomialIndex) -> Option<&PolynomialDescriptor> {
+        self.manifest
+            .iter()
+            .find(|descriptor| descriptor.index == index)
+    }
+
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, PolynomialDescriptor> {
+        self.manifest.iter()
     }
-    pub(crate) fn get(&self, index: PolynomialIndex) -> &PolynomialDescriptor {
-        &self.manifest[index as usize]
+
+    /// Polynomial store labels of every witness form whose last consumer is `round`. Once `round` is
+    /// done the prover can drop these.
+    pub(crate) fn get_labels_last_consumed_in(&self, round: ProverRound) -> Vec<String> {
+        let mut labels = Vec::new();
+        for descriptor in &self.manifest {
+            if descriptor.source != PolynomialSource::Witness {
+                continue;
+            }
+            for form in [
+                PolynomialForm::Lagrange,
+                PolynomialForm::Monomial,
+                PolynomialForm::CosetFft,
+            ] {
+                if descriptor.last_consumer_round(form) == Some(round) {
+                    labels.push(form.label(&descriptor.polynomial_label));
+                }
+            }
+        }
+        labels
     }
 }
 
@@ -53,7 +337,8 @@ impl Index<PolynomialIndex> for PolynomialManifest {
     type Output = PolynomialDescriptor;
 
     fn index(&self, index: PolynomialIndex) -> &Self::Output {
-        self.manifest.get(index as usize).unwrap()
+        self.get(index)
+            .unwrap_or_else(|| panic!("{:?} is not in the polynomial manifest", index))
     }
 }
 
@@ -154,9 +439,129 @@ impl From<usize> for PolynomialIndex {
             34 => PolynomialIndex::ZLookup,
             35 => PolynomialIndex::LagrangeFirst,
             36 => PolynomialIndex::LagrangeLast,
-            // 37 => PolynomialIndex::SUBGROUP_GENERATOR,
-            38 => PolynomialIndex::MaxNumPolynomials,
+            // barretenberg has SUBGROUP_GENERATOR here
+            37 => PolynomialIndex::MaxNumPolynomials,
             _ => panic!("Invalid polynomial index"),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ark_bn254::Fr;
+
+    use super::{PolynomialForm, PolynomialIndex, PolynomialManifest, ProverRound};
+    use crate::{
+        plonk::composer::composer_base::ComposerType, polynomials::Polynomial,
+        proof_system::polynomial_store::PolynomialStore,
+    };
+
+    #[test]
+    fn standard_manifest_consumer_rounds() {
+        let manifest = PolynomialManifest::new(ComposerType::Standard as u32);
+        assert_eq!(manifest.len(), 12);
+
+        assert_eq!(
+            manifest.get_labels_last_consumed_in(ProverRound::Third),
+            vec![
+                "w_1_lagrange",
+                "w_2_lagrange",
+                "w_3_lagrange",
+                "z_perm_lagrange"
+            ]
+        );
+        assert_eq!(
+            manifest.get_labels_last_consumed_in(ProverRound::Fourth),
+            vec!["w_1_fft", "w_2_fft", "w_3_fft", "z_perm_fft"]
+        );
+        assert_eq!(
+            manifest.get_labels_last_consumed_in(ProverRound::Sixth),
+            vec!["w_1", "w_2", "w_3", "z_perm"]
+        );
+        // selectors belong to the key, so they never show up
+        assert!(manifest
+            .get_labels_last_consumed_in(ProverRound::First)
+            .is_empty());
+    }
+
+    #[test]
+    fn every_index_finds_its_own_descriptor() {
+        for composer_type in [
+            ComposerType::Standard,
+            ComposerType::Turbo,
+            ComposerType::Plookup,
+        ] {
+            let manifest = PolynomialManifest::new(composer_type as u32);
+            let mut found = 0;
+            for i in 0..=PolynomialIndex::MaxNumPolynomials as usize {
+                let index = PolynomialIndex::from(i);
+                assert_eq!(index as usize, i);
+                if let Some(descriptor) = manifest.get(index) {
+                    assert_eq!(descriptor.index, index);
+                    assert_eq!(
+                        manifest[index].polynomial_label,
+                        descriptor.polynomial_label
+                    );
+                    found += 1;
+                }
+            }
+            assert_eq!(found, manifest.len(), "{:?}", composer_type);
+            for descriptor in manifest.iter() {
+                assert_eq!(
+                    manifest[descriptor.index].polynomial_label,
+                    descriptor.polynomial_label
+                );
+            }
+        }
+        let standard = PolynomialManifest::new(ComposerType::Standard as u32);
+        assert_eq!(standard[PolynomialIndex::Q1].polynomial_label, "q_1");
+        assert_eq!(standard[PolynomialIndex::W1].polynomial_label, "w_1");
+        assert!(standard.get(PolynomialIndex::W4).is_none());
+    }
+
+    #[test]
+    fn freeing_consumed_polynomials_bounds_peak_memory() {
+        let n = 16;
+        let manifest = PolynomialManifest::new(ComposerType::Standard as u32);
+        let mut store = PolynomialStore::<Fr>::new();
+        store
+            .put("q_1_fft".to_string(), Polynomial::new(4 * n))
+            .unwrap();
+        let wires = ["w_1", "w_2", "w_3", "z_perm"];
+        let free = |store: &mut PolynomialStore<Fr>, round| {
+            for label in manifest.get_labels_last_consumed_in(round) {
+                if store.contains(&label) {
+                    store.remove(label).unwrap();
+                }
+            }
+        };
+
+        // lagrange and monomial forms of every witness, then the quotient round's ffts
+        for wire in wires {
+            store
+                .put(PolynomialForm::Lagrange.label(wire), Polynomial::new(n))
+                .unwrap();
+            store
+                .put(PolynomialForm::Monomial.label(wire), Polynomial::new(n))
+                .unwrap();
+        }
+        free(&mut store, ProverRound::Third);
+        for wire in wires {
+            store
+                .put(PolynomialForm::CosetFft.label(wire), Polynomial::new(4 * n))
+                .unwrap();
+        }
+        free(&mut store, ProverRound::Fourth);
+        free(&mut store, ProverRound::Sixth);
+
+        let fr_bytes = std::mem::size_of::<Fr>();
+        // only the selector survives the proof
+        assert_eq!(store.stats().polynomials.len(), 1);
+        assert_eq!(store.stats().resident_bytes, 4 * n * fr_bytes);
+        // at most: the selector, the monomials and the ffts. the lagrange forms were gone by then
+        assert_eq!(
+            store.get_peak_size_in_bytes(),
+            (4 * n + wires.len() * (n + 4 * n)) * fr_bytes
+        );
+    }
+}
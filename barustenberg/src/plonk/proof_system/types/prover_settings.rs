@@ -2,7 +2,7 @@ use crate::transcript::{BarretenHasher, Keccak256, PedersenBlake3s, PlookupPeder
 
 // TODO bevy_reflect? or what
 // todo at least inline it all
-pub(crate) trait Settings<H: BarretenHasher> {
+pub(crate) trait Settings<H: BarretenHasher>: Send + Sync {
     #[inline]
     fn requires_shifted_wire(wire_shift_settings: u64, wire_index: u64) -> bool {
         ((wire_shift_settings >> wire_index) & 1u64) == 1u64
@@ -18,6 +18,7 @@ pub(crate) trait Settings<H: BarretenHasher> {
     fn hasher(&self) -> &H;
 }
 
+#[derive(Default)]
 pub(crate) struct StandardSettings<H: BarretenHasher> {
     hasher: H,
 }
@@ -72,6 +73,7 @@ impl<H: BarretenHasher> Settings<H> for StandardSettings<H> {
     }
 }
 
+#[derive(Default)]
 pub(crate) struct TurboSettings {}
 
 impl TurboSettings {
@@ -119,6 +121,7 @@ impl Settings<PedersenBlake3s> for TurboSettings {
     }
 }
 
+#[derive(Default)]
 pub(crate) struct UltraSettings {}
 
 impl Settings<PlookupPedersenBlake3s> for UltraSettings {
@@ -152,7 +155,7 @@ impl Settings<PlookupPedersenBlake3s> for UltraSettings {
     }
     #[inline]
     fn is_plookup(&self) -> bool {
-        false
+        true
     }
     #[inline]
     fn hasher(&self) -> &PlookupPedersenBlake3s {
@@ -160,6 +163,7 @@ impl Settings<PlookupPedersenBlake3s> for UltraSettings {
     }
 }
 
+#[derive(Default)]
 pub(crate) struct UltraToStandardSettings {}
 
 impl Settings<PedersenBlake3s> for UltraToStandardSettings {
@@ -193,7 +197,7 @@ impl Settings<PedersenBlake3s> for UltraToStandardSettings {
     }
     #[inline]
     fn is_plookup(&self) -> bool {
-        false
+        true
     }
     #[inline]
     fn hasher(&self) -> &PedersenBlake3s {
@@ -201,6 +205,7 @@ impl Settings<PedersenBlake3s> for UltraToStandardSettings {
     }
 }
 
+#[derive(Default)]
 pub(crate) struct UltraWithKeccakSettings {}
 
 impl Settings<Keccak256> for UltraWithKeccakSettings {
@@ -234,7 +239,7 @@ impl Settings<Keccak256> for UltraWithKeccakSettings {
     }
     #[inline]
     fn is_plookup(&self) -> bool {
-        false
+        true
     }
     #[inline]
     fn hasher(&self) -> &Keccak256 {
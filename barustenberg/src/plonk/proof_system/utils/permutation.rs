@@ -1,21 +1,106 @@
+use anyhow::Result;
 use ark_ff::{FftField, Field};
 
 use crate::{
+    common::thread::parallel_for_chunks_mut,
     ecc::{
-        conditionally_subtract_from_double_modulus, coset_generator,
-        curves::external_coset_generator, tag_coset_generator,
+        conditionally_subtract_from_double_modulus,
+        curves::{coset_generators, external_coset_generator, tag_coset_generator, CurveConfig},
     },
     numeric::bitop::Msb,
-    plonk::proof_system::types::prover_settings::Settings,
+    plonk::proof_system::{proving_key::ProvingKey, types::prover_settings::Settings},
     polynomials::{evaluation_domain::EvaluationDomain, Polynomial},
     transcript::BarretenHasher,
 };
 
+/// Where a single wire cell points to in a permutation.
+///
+/// For tagged cells `subgroup_index` holds the tag rather than a row, and the cell maps into the tag coset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct PermutationSubgroupElement {
-    subgroup_index: u32,
-    column_index: u8,
-    is_public_input: bool,
-    is_tag: bool,
+    pub(crate) subgroup_index: u32,
+    pub(crate) column_index: u8,
+    pub(crate) is_public_input: bool,
+    pub(crate) is_tag: bool,
+}
+
+impl PermutationSubgroupElement {
+    pub(crate) fn new(subgroup_index: u32, column_index: u8) -> Self {
+        Self {
+            subgroup_index,
+            column_index,
+            is_public_input: false,
+            is_tag: false,
+        }
+    }
+}
+
+/// The σ permutation over all wire cells, indexed by `[column][row]`. Generalized permutations also carry an
+/// explicit id permutation, which differs from the identity only at tagged cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PermutationMapping {
+    pub(crate) sigmas: Vec<Vec<PermutationSubgroupElement>>,
+    pub(crate) ids: Vec<Vec<PermutationSubgroupElement>>,
+}
+
+impl PermutationMapping {
+    /// A mapping where every cell points to itself. `ids` is only populated if `generalized` is set.
+    pub(crate) fn identity(program_width: usize, circuit_size: usize, generalized: bool) -> Self {
+        let identity = (0..program_width)
+            .map(|column| {
+                (0..circuit_size)
+                    .map(|row| PermutationSubgroupElement::new(row as u32, column as u8))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        Self {
+            ids: if generalized {
+                identity.clone()
+            } else {
+                vec![]
+            },
+            sigmas: identity,
+        }
+    }
+}
+
+/// Turns each column of `mapping` into `{label}_{column + 1}_lagrange`, plus its monomial form `{label}_{column + 1}`
+/// and coset FFT `{label}_{column + 1}_fft`, and stores them in the proving key.
+pub(crate) fn compute_permutation_polynomials_from_mapping<
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+>(
+    label: &str,
+    mapping: &[Vec<PermutationSubgroupElement>],
+    key: &mut ProvingKey<'_, C>,
+) -> Result<()> {
+    let n = key.circuit_size;
+    for (column, column_mapping) in mapping.iter().enumerate() {
+        let mut lagrange = Polynomial::new(n);
+        compute_permutation_lagrange_base_single_helper::<H, C::Fr, S>(
+            &mut lagrange,
+            column_mapping,
+            &key.small_domain,
+        );
+
+        let mut monomial = lagrange.clone();
+        key.small_domain.ifft_inplace(&mut monomial);
+
+        let mut fft = Polynomial::new(4 * n + 4);
+        key.large_domain
+            .coset_fft(&monomial.coefficients, &mut fft.coefficients[..4 * n]);
+        for i in 0..4 {
+            fft[4 * n + i] = fft[i];
+        }
+
+        let prefix = format!("{}_{}", label, column + 1);
+        key.polynomial_store
+            .put(format!("{}_lagrange", prefix), lagrange)?;
+        key.polynomial_store.put(format!("{}_fft", prefix), fft)?;
+        key.polynomial_store.put(prefix, monomial)?;
+    }
+    Ok(())
 }
 
 pub(crate) fn compute_permutation_lagrange_base_single<
@@ -61,31 +146,40 @@ pub(crate) fn compute_permutation_lagrange_base_single_helper<
         panic!("Permutation polynomial size is insufficient to store permutations.");
     }
 
-    let roots = small_domain.get_round_roots()[small_domain.log2_size - 2];
+    let roots = &small_domain.get_round_roots()[small_domain.log2_size - 2];
     let root_size = small_domain.size >> 1;
 
     let log2_root_size = root_size.get_msb();
 
-    for i in 0..small_domain.size {
-        let raw_idx = permutation[i].subgroup_index as usize;
-        let negative_idx = raw_idx >= root_size;
-        let idx = raw_idx - ((negative_idx as usize) << log2_root_size);
-
-        output.coefficients[i] =
-            conditionally_subtract_from_double_modulus(&roots[idx], negative_idx as u64);
-
-        if permutation[i].is_public_input {
-            // TODO: Replace with correct external_coset_generator function
-            output.coefficients[i] *= external_coset_generator::<Fr>();
-        } else if permutation[i].is_tag {
-            // TODO: Replace with correct tag_coset_generator function
-            output.coefficients[i] *= tag_coset_generator::<Fr>();
-        } else {
-            let column_index = permutation[i].column_index;
-            if column_index > 0 {
-                // TODO: Replace with correct coset_generator function
-                output.coefficients[i] *= coset_generator::<Fr>(column_index - 1);
+    let coset_generators = coset_generators::<Fr>();
+    let external_coset_generator = external_coset_generator::<Fr>();
+    let tag_coset_generator = tag_coset_generator::<Fr>();
+
+    parallel_for_chunks_mut(
+        &mut output.coefficients[..small_domain.size],
+        small_domain.thread_size,
+        |j, chunk| {
+            let offset = j * small_domain.thread_size;
+            for (k, output_i) in chunk.iter_mut().enumerate() {
+                let permutation_i = &permutation[offset + k];
+                let raw_idx = permutation_i.subgroup_index as usize;
+                let negative_idx = raw_idx >= root_size;
+                let idx = raw_idx - ((negative_idx as usize) << log2_root_size);
+
+                *output_i =
+                    conditionally_subtract_from_double_modulus(&roots[idx], negative_idx as u64);
+
+                if permutation_i.is_public_input {
+                    *output_i *= external_coset_generator;
+                } else if permutation_i.is_tag {
+                    *output_i *= tag_coset_generator;
+                } else {
+                    let column_index = permutation_i.column_index as usize;
+                    if column_index > 0 {
+                        *output_i *= coset_generators[column_index - 1];
+                    }
+                }
             }
-        }
-    }
+        },
+    );
 }
@@ -1,29 +1,106 @@
 use std::{collections::HashMap, sync::Arc};
 
-use ark_bn254::{G1Affine, G2Affine};
-use ark_ff::{FftField, Field};
+use anyhow::Result;
+use ark_ff::Zero;
 
 use crate::{
+    ecc::{curves::CurveConfig, PippengerRuntimeState},
+    plonk::composer::composer_base::ComposerType,
     polynomials::evaluation_domain::EvaluationDomain,
     srs::reference_string::VerifierReferenceString,
 };
 
-use super::types::PolynomialManifest;
+use super::{
+    proving_key::ProvingKey,
+    types::{polynomial_manifest::PolynomialSource, PolynomialManifest},
+};
 
-pub(crate) struct VerificationKey<'a, Fr: Field + FftField> {
-    composer_type: u32,
-    circuit_size: usize,
+/// The verifier's view of a circuit over `C`. Commitments are to its G1 and the reference string holds `[τ]₂`.
+#[derive(Clone)]
+pub(crate) struct VerificationKey<'a, C: CurveConfig> {
+    pub(crate) composer_type: u32,
+    pub(crate) circuit_size: usize,
     log_circuit_size: usize,
-    num_inputs: usize,
-    pub(crate) domain: EvaluationDomain<'a, Fr>,
-    reference_string: Arc<dyn VerifierReferenceString<G2Affine>>,
-    commitments: HashMap<String, G1Affine>,
+    pub(crate) num_inputs: usize,
+    pub(crate) domain: EvaluationDomain<'a, C::Fr>,
+    pub(crate) reference_string: Arc<dyn VerifierReferenceString<C::G2Affine>>,
+    /// Commitments to the selector and permutation polynomials, by their commitment labels.
+    pub(crate) commitments: HashMap<String, C::G1Affine>,
     pub(crate) polynomial_manifest: PolynomialManifest,
     /// This is a member variable so as to avoid recomputing it in the different places of the verifier algorithm.
     /// Note that recomputing would also have added constraints to the recursive verifier circuit.
     /// ʓ^n (ʓ being the 'evaluation challenge')
-    pub(crate) z_pow_n: Fr,
+    pub(crate) z_pow_n: C::Fr,
     contains_recursive_proof: bool,
     recursive_proof_public_input_indices: Vec<u32>,
     pub(crate) program_width: usize,
 }
+
+impl<'a, C: CurveConfig> VerificationKey<'a, C> {
+    /// A key for a circuit of `circuit_size` rows, a power of two, with `num_inputs` public inputs. It has
+    /// no commitments yet.
+    pub(crate) fn new(
+        circuit_size: usize,
+        num_inputs: usize,
+        crs: Arc<dyn VerifierReferenceString<C::G2Affine>>,
+        composer_type: u32,
+    ) -> Self {
+        let program_width = if composer_type == ComposerType::Standard as u32 {
+            3
+        } else {
+            4
+        };
+        Self {
+            composer_type,
+            circuit_size,
+            log_circuit_size: circuit_size.trailing_zeros() as usize,
+            num_inputs,
+            domain: EvaluationDomain::new(circuit_size, None),
+            reference_string: crs,
+            commitments: HashMap::new(),
+            polynomial_manifest: PolynomialManifest::new(composer_type),
+            z_pow_n: C::Fr::zero(),
+            contains_recursive_proof: false,
+            recursive_proof_public_input_indices: vec![],
+            program_width,
+        }
+    }
+
+    /// The key for the circuit of `proving_key`: commitments to each of its selector and permutation
+    /// polynomials, made with the proving key's reference string.
+    pub(crate) fn from_proving_key(
+        proving_key: &ProvingKey<'a, C>,
+        crs: Arc<dyn VerifierReferenceString<C::G2Affine>>,
+    ) -> Result<Self> {
+        let n = proving_key.circuit_size;
+        let mut key = Self::new(
+            n,
+            proving_key.num_public_inputs,
+            crs,
+            proving_key.composer_type,
+        );
+        let monomial_points = proving_key
+            .reference_string
+            .write()
+            .unwrap()
+            .get_monomial_points();
+        for descriptor in proving_key.polynomial_manifest.iter() {
+            if descriptor.source == PolynomialSource::Witness {
+                continue;
+            }
+            let polynomial = proving_key
+                .polynomial_store
+                .get(&descriptor.polynomial_label)?;
+            let mut coefficients = polynomial.read().unwrap().coefficients.clone();
+            let commitment = PippengerRuntimeState::<C::Fr, C::G1Affine>::new(n).pippenger_unsafe(
+                &mut coefficients,
+                &monomial_points,
+                n,
+            );
+            key.commitments
+                .insert(descriptor.commitment_label.clone(), commitment);
+        }
+        key.polynomial_manifest = proving_key.polynomial_manifest.clone();
+        Ok(key)
+    }
+}
@@ -1,14 +1,40 @@
-use crate::transcript::{BarretenHasher, Manifest};
+use crate::plonk::composer::composer_base::ComposerType;
+use crate::transcript::{trace::write_trace, BarretenHasher, Manifest, Transcript};
 
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use crate::ecc::{
+    curves::{CurveConfig, PairingCurveConfig},
+    PippengerRuntimeState,
+};
 
 use super::{
-    commitment_scheme::CommitmentScheme,
+    commitment_scheme::{CommitmentScheme, KateCommitmentScheme},
     types::{prover_settings::Settings, Proof},
+    widgets::{
+        random_widgets::{
+            permutation_widget::VerifierPermutationWidget, plookup_widget::VerifierPlookupWidget,
+        },
+        transition_widgets::{
+            arithmetic_widget::VerifierArithmeticWidget, transition_widget::GenericVerifierWidget,
+            turbo_arithmetic_widget::VerifierTurboArithmeticWidget,
+            turbo_fixed_base_widget::VerifierTurboFixedBaseWidget,
+            turbo_logic_widget::VerifierTurboLogicWidget,
+            turbo_range_widget::VerifierTurboRangeWidget,
+            ultra_arithmetic_widget::VerifierUltraArithmeticWidget,
+            ultra_aux_widget::VerifierUltraAuxWidget,
+            ultra_elliptic_widget::VerifierUltraEllipticWidget,
+            ultra_sort_widget::VerifierUltraSortWidget,
+        },
+    },
 };
 
+use anyhow::{anyhow, ensure, Result};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{Field, Zero};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use tracing::warn;
+
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::verification_key::VerificationKey;
@@ -16,41 +42,41 @@ use super::verification_key::VerificationKey;
 #[cfg(test)]
 mod test;
 
-pub(crate) trait VerifierBase<'a, Fr: Field + FftField, H: BarretenHasher, PS: Settings<H>> {
-    fn new(verifier_key: Option<Arc<VerificationKey<'a, Fr>>>, manifest: Manifest) -> Self;
+pub(crate) trait VerifierBase<'a, C: CurveConfig, H: BarretenHasher, PS: Settings<H>> {
+    fn new(verifier_key: Option<Arc<VerificationKey<'a, C>>>, manifest: Manifest) -> Self;
     fn validate_commitments(&self) -> bool;
     fn validate_scalars(&self) -> bool;
     fn verify_proof(&self, proof: &Proof) -> bool;
 }
 
-pub(crate) struct Verifier<
-    'a,
-    Fq: Field,
-    Fr: Field + FftField,
-    G1Affine: AffineRepr,
-    H: BarretenHasher,
-    PS: Settings<H>,
-> {
+pub(crate) struct Verifier<'a, C: CurveConfig, H: BarretenHasher, PS: Settings<H>> {
     settings: PS,
-    key: Option<Arc<VerificationKey<'a, Fr>>>,
+    key: Option<Arc<VerificationKey<'a, C>>>,
     manifest: Manifest,
-    kate_g1_elements: HashMap<String, G1Affine>,
-    kate_fr_elements: HashMap<String, Fr>,
-    commitment_scheme: Box<dyn CommitmentScheme<Fq, Fr, G1Affine, H>>,
+    kate_g1_elements: HashMap<String, C::G1Affine>,
+    kate_fr_elements: HashMap<String, C::Fr>,
+    commitment_scheme: Box<dyn CommitmentScheme<C, H>>,
+    /// Where [`verify`](Self::verify) writes the transcript's trace, if anywhere.
+    trace_path: Option<PathBuf>,
 }
 
 impl<
         'a,
-        Fq: Field,
-        Fr: Field + FftField,
-        G1Affine: AffineRepr,
-        H: BarretenHasher,
-        PS: Settings<H>,
-    > VerifierBase<'a, Fr, H, PS> for Verifier<'a, Fq, Fr, G1Affine, H, PS>
+        C: PairingCurveConfig,
+        H: BarretenHasher + Default + 'static,
+        PS: Settings<H> + Default + 'static,
+    > VerifierBase<'a, C, H, PS> for Verifier<'a, C, H, PS>
 {
-    fn new(_verifier_key: Option<Arc<VerificationKey<'a, Fr>>>, _manifest: Manifest) -> Self {
-        // Implement constructor logic here.
-        todo!("Verifier::new")
+    fn new(verifier_key: Option<Arc<VerificationKey<'a, C>>>, manifest: Manifest) -> Self {
+        Self {
+            settings: PS::default(),
+            key: verifier_key,
+            manifest,
+            kate_g1_elements: HashMap::new(),
+            kate_fr_elements: HashMap::new(),
+            commitment_scheme: Box::new(KateCommitmentScheme::<H, PS>::default()),
+            trace_path: None,
+        }
     }
 
     fn validate_commitments(&self) -> bool {
@@ -63,8 +89,245 @@ impl<
         todo!("Verifier::validate_scalars")
     }
 
-    fn verify_proof(&self, _proof: &Proof) -> bool {
-        // Implement verify_proof logic here.
-        todo!("Verifier::verify_proof")
+    /// Replays the prover's transcript, reconstructs the quotient evaluation and checks the batched Kate
+    /// openings at `z` and `z·ω` with a pairing.
+    fn verify_proof(&self, proof: &Proof) -> bool {
+        match self.verify(proof) {
+            Ok(verified) => verified,
+            Err(err) => {
+                warn!("rejecting Plonk proof: {}", err);
+                false
+            }
+        }
+    }
+}
+
+impl<'a, C: PairingCurveConfig, H: BarretenHasher, PS: Settings<H>> Verifier<'a, C, H, PS> {
+    /// There are no verifier settings yet to pick the widgets from, so the key does: width 3 checks the
+    /// standard arithmetisation with the widgets of
+    /// [`new_standard_prover`](super::prover::Prover::new_standard_prover), and width 4 the turbo one
+    /// with those of [`new_turbo_prover`](super::prover::Prover::new_turbo_prover), or the ultra one with
+    /// those of [`new_ultra_prover`](super::prover::Prover::new_ultra_prover) if the key is a plookup
+    /// composer's.
+    fn verify(&self, proof: &Proof) -> Result<bool> {
+        let proof_size: usize = self
+            .manifest
+            .round_manifests
+            .iter()
+            .flat_map(|round| &round.elements)
+            .filter(|element| !element.derived_by_verifier)
+            .map(|element| element.num_bytes)
+            .sum();
+        ensure!(
+            proof.proof_data.len() == proof_size,
+            "proof of {} bytes, the manifest expects {}",
+            proof.proof_data.len(),
+            proof_size
+        );
+
+        let mut transcript = self.transcript(proof);
+        let verified = self.verify_transcript(&mut transcript);
+        // a rejected proof is when the trace is wanted
+        if let Some(path) = &self.trace_path {
+            write_trace(path, &transcript.take_trace())?;
+        }
+        verified
+    }
+
+    /// The checks of [`verify`](Self::verify), on the transcript it parsed from the proof.
+    fn verify_transcript(&self, transcript: &mut Transcript<H, C>) -> Result<bool> {
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| anyhow!("verifying needs a verification key"))?;
+        ensure!(
+            key.program_width == 3 || key.program_width == 4,
+            "only the standard, turbo and ultra arithmetisations are verified, the key has width {}",
+            key.program_width
+        );
+        transcript.add_element(
+            "circuit_size",
+            (key.circuit_size as u32).to_be_bytes().to_vec(),
+        );
+        transcript.add_element(
+            "public_input_size",
+            (key.num_inputs as u32).to_be_bytes().to_vec(),
+        );
+        transcript.apply_fiat_shamir("init")?;
+        transcript.apply_fiat_shamir("eta")?;
+        transcript.apply_fiat_shamir("beta")?;
+        transcript.apply_fiat_shamir("alpha")?;
+        transcript.apply_fiat_shamir("z")?;
+
+        let alpha = transcript.get_challenge_field_element("alpha", None);
+        let zeta = transcript.get_challenge_field_element("z", None);
+
+        // the widgets read ʓ^n from the key, so evaluate against a copy holding this proof's
+        let mut key = key.as_ref().clone();
+        key.z_pow_n = zeta.pow([key.circuit_size as u64]);
+        let key = Arc::new(key);
+
+        // t(ʓ) = [quotient numerator](ʓ) / Z_H*(ʓ)
+        let mut quotient_numerator_eval = C::Fr::zero();
+        let is_plookup = key.composer_type == ComposerType::Plookup as u32;
+        let alpha_base =
+            VerifierPermutationWidget::<H, C, 4>::compute_quotient_evaluation_contribution(
+                &key,
+                alpha,
+                transcript,
+                &mut quotient_numerator_eval,
+                is_plookup,
+            )?;
+        let mut rng: Box<dyn RngCore + Send> = Box::new(StdRng::from_entropy());
+        if is_plookup {
+            let alpha_base =
+                VerifierPlookupWidget::<H, C, 4>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                );
+            let alpha_base =
+                VerifierUltraArithmeticWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                    &mut rng,
+                );
+            let alpha_base =
+                VerifierUltraSortWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                    &mut rng,
+                );
+            let alpha_base =
+                VerifierUltraEllipticWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                    &mut rng,
+                );
+            VerifierUltraAuxWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                &key,
+                alpha_base,
+                transcript,
+                &mut quotient_numerator_eval,
+                &mut rng,
+            );
+        } else if key.program_width == 3 {
+            VerifierArithmeticWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                &key,
+                alpha_base,
+                transcript,
+                &mut quotient_numerator_eval,
+                &mut rng,
+            );
+        } else {
+            let alpha_base =
+                VerifierTurboArithmeticWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                    &mut rng,
+                );
+            let alpha_base =
+                VerifierTurboFixedBaseWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                    &mut rng,
+                );
+            let alpha_base =
+                VerifierTurboRangeWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                    &key,
+                    alpha_base,
+                    transcript,
+                    &mut quotient_numerator_eval,
+                    &mut rng,
+                );
+            VerifierTurboLogicWidget::<H, C, PS>::compute_quotient_evaluation_contribution(
+                &key,
+                alpha_base,
+                transcript,
+                &mut quotient_numerator_eval,
+                &mut rng,
+            );
+        }
+        let vanishing_eval = key.domain.evaluate_pseudo_vanishing_polynomial(
+            &zeta,
+            self.settings.num_roots_cut_out_of_vanishing_polynomial(),
+        )?;
+        let t_eval = quotient_numerator_eval
+            * vanishing_eval
+                .inverse()
+                .ok_or_else(|| anyhow!("Z_H*(z) is zero"))?;
+        transcript.add_field_element("t", &t_eval);
+
+        transcript.apply_fiat_shamir("nu")?;
+        transcript.apply_fiat_shamir("separator")?;
+        let separator = transcript.get_challenge_field_element("separator", None);
+
+        let mut kate_g1_elements = self.kate_g1_elements.clone();
+        let mut kate_fr_elements = self.kate_fr_elements.clone();
+        self.commitment_scheme.batch_verify(
+            transcript,
+            &mut kate_g1_elements,
+            &mut kate_fr_elements,
+            Some(&key),
+        )?;
+
+        // [F] + u[F'] - [batch evaluation] + ʓ[W_ʓ] + ʓωu[W_ʓω] = τ([W_ʓ] + u[W_ʓω])
+        let pi_z = transcript.get_group_element("PI_Z")?;
+        let pi_z_omega = transcript.get_group_element("PI_Z_OMEGA")?;
+        kate_g1_elements.insert("PI_Z_OMEGA".to_string(), pi_z_omega);
+        kate_fr_elements.insert("PI_Z_OMEGA".to_string(), zeta * key.domain.root * separator);
+        kate_g1_elements.insert("PI_Z".to_string(), pi_z);
+        kate_fr_elements.insert("PI_Z".to_string(), zeta);
+
+        let (points, mut scalars): (Vec<_>, Vec<_>) = kate_g1_elements
+            .iter()
+            .map(|(label, point)| (*point, kate_fr_elements[label]))
+            .unzip();
+        let num_points = points.len();
+        let p0 = PippengerRuntimeState::<C::Fr, C::G1Affine>::new(num_points).pippenger_unsafe(
+            &mut scalars,
+            &points,
+            num_points,
+        );
+        let p1 = (-(pi_z_omega * separator + pi_z)).into_affine();
+
+        Ok(C::Pairing::multi_pairing(
+            [p0, p1],
+            [C::G2Affine::generator(), key.reference_string.get_g2x()],
+        )
+        .is_zero())
+    }
+}
+
+impl<'a, C: CurveConfig, H: BarretenHasher, PS: Settings<H>> Verifier<'a, C, H, PS> {
+    /// The verifier's transcript of `proof`, parsed by the manifest the prover wrote it against, and traced if
+    /// [`trace_to`](Self::trace_to) was called.
+    pub(crate) fn transcript(&self, proof: &Proof) -> Transcript<H, C> {
+        let mut transcript = Transcript::new_from_transcript(
+            &proof.proof_data,
+            self.manifest.clone(),
+            self.settings.num_challenge_bytes(),
+        );
+        if self.trace_path.is_some() {
+            transcript.enable_trace();
+        }
+        transcript
+    }
+
+    /// Trace the transcript of every proof verified from now on and write each trace to `path`, to be compared
+    /// with the prover's by [`diff_trace_files`](crate::transcript::trace::diff_trace_files).
+    pub(crate) fn trace_to(&mut self, path: impl Into<PathBuf>) {
+        self.trace_path = Some(path.into());
     }
 }
@@ -1,2 +1,4 @@
 pub(crate) mod random_widgets;
+#[cfg(test)]
+mod test_utils;
 pub(crate) mod transition_widgets;
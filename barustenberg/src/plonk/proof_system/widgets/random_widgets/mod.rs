@@ -1,2 +1,3 @@
 pub(crate) mod permutation_widget;
+pub(crate) mod plookup_widget;
 pub(crate) mod random_widget;
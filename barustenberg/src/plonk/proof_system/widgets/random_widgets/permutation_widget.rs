@@ -1,60 +1,60 @@
-use crate::ecc::curves::coset_generator;
+use crate::common::thread::{parallel_for_chunks_mut, parallel_for_chunks_mut_pair};
+use crate::ecc::batch::batch_invert;
+use crate::ecc::curves::{coset_generator, CurveConfig};
 use crate::plonk::proof_system::proving_key::ProvingKey;
 use crate::plonk::proof_system::public_inputs::compute_public_input_delta;
 use crate::plonk::proof_system::verification_key::VerificationKey;
 use crate::plonk::proof_system::widgets::random_widgets::random_widget::ProverRandomWidget;
-use crate::proof_system::work_queue::WorkQueue;
+use crate::polynomials::{evaluation_domain::EvaluationDomain, Polynomial};
+use crate::proof_system::work_queue::{Work, WorkItem, WorkQueue};
 use crate::transcript::{BarretenHasher, Transcript};
 use std::marker::PhantomData;
-use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use anyhow::Result;
+use ark_ff::{FftField, Field, One, UniformRand};
 
 pub(crate) struct VerifierPermutationWidget<
     H: BarretenHasher,
-    F: Field,
-    Group: AffineRepr,
+    C: CurveConfig,
     const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
 > {
-    transcript: Transcript<H, F, Group>,
-    phantom: PhantomData<(F, Group)>,
+    transcript: Transcript<H, C>,
+    phantom: PhantomData<C>,
 }
 
-impl<H, F, G1Affine, const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize>
-    VerifierPermutationWidget<H, F, G1Affine, NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL>
+impl<H, C, const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize>
+    VerifierPermutationWidget<H, C, NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL>
 where
     H: BarretenHasher,
-    F: Field + FftField,
-    G1Affine: AffineRepr,
+    C: CurveConfig,
 {
     pub(crate) fn new() -> Self {
         Self {
-            transcript: Transcript::<H, F, G1Affine>::default(),
+            transcript: Transcript::<H, C>::default(),
             phantom: PhantomData,
         }
     }
 
     pub(crate) fn compute_quotient_evaluation_contribution(
-        key: &Arc<VerificationKey<'_, F>>,
-        alpha: F,
-        transcript: &Transcript<H, F, G1Affine>,
-        quotient_numerator_eval: &mut F,
+        key: &Arc<VerificationKey<'_, C>>,
+        alpha: C::Fr,
+        transcript: &Transcript<H, C>,
+        quotient_numerator_eval: &mut C::Fr,
         idpolys: bool,
-    ) -> F {
-        let alpha_squared: F = alpha.square();
+    ) -> Result<C::Fr> {
+        let alpha_squared: C::Fr = alpha.square();
         let alpha_cubed = alpha_squared * alpha;
         // a.k.a. zeta or ʓ
-        let z: F = transcript.get_challenge_field_element("z", None);
-        let beta: F = transcript.get_challenge_field_element("beta", Some(0));
-        let gamma: F = transcript.get_challenge_field_element("beta", Some(1));
-        let z_beta: F = z * beta;
+        let z: C::Fr = transcript.get_challenge_field_element("z", None);
+        let beta: C::Fr = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma: C::Fr = transcript.get_challenge_field_element("beta", Some(1));
+        let z_beta: C::Fr = z * beta;
 
         // We need wire polynomials' and sigma polynomials' evaluations at zeta which we fetch from the transcript.
         // Fetch a_eval, b_eval, c_eval, sigma1_eval, sigma2_eval
-        let mut wire_evaluations = Vec::<F>::new();
-        let mut sigma_evaluations = Vec::<F>::new();
+        let mut wire_evaluations = Vec::<C::Fr>::new();
+        let mut sigma_evaluations = Vec::<C::Fr>::new();
 
         for i in 0..key.program_width {
             let index = (i + 1).to_string();
@@ -78,10 +78,10 @@ where
         //                               X.ω^{-i + 1} - 1
         //
         // ʓ^n - 1
-        let mut numerator = key.z_pow_n - F::one();
+        let mut numerator = key.z_pow_n - C::Fr::one();
         numerator *= key.domain.domain_inverse;
         // [ʓ^n - 1] / [n.(ʓ - 1)] =: L_1(ʓ)
-        let l_start: F = numerator / (z - F::one());
+        let l_start: C::Fr = numerator / (z - C::Fr::one());
 
         // Compute ω^{num_roots_cut_out_of_vanishing_polynomial + 1}
         let mut l_end_root = if NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL & 1 != 0 {
@@ -93,9 +93,9 @@ where
             l_end_root *= key.domain.root.square();
         }
         // [ʓ^n - 1] / [n.(ʓ.ω^{k+1} - 1)] =: L_{n-k}(ʓ)
-        let l_end: F = numerator / ((z * l_end_root) - F::one());
+        let l_end: C::Fr = numerator / ((z * l_end_root) - C::Fr::one());
 
-        let z_1_shifted_eval: F = transcript.get_field_element("z_perm_omega");
+        let z_1_shifted_eval: C::Fr = transcript.get_field_element("z_perm_omega");
 
         // Recall that the full quotient numerator is the polynomial
         // t(X) =
@@ -113,9 +113,9 @@ where
         //
         // sigma_contribution = (a_eval + β.sigma1_eval + γ)(b_eval + β.sigma2_eval + γ)(c_eval + γ).z(ʓ.ω).α
         //
-        let mut sigma_contribution = F::one();
+        let mut sigma_contribution = C::Fr::one();
         let mut t0;
-        let mut t1: F;
+        let mut t1: C::Fr;
         for i in 0..key.program_width - 1 {
             t0 = sigma_evaluations[i] * beta;
             t1 = wire_evaluations[i] + gamma;
@@ -133,15 +133,15 @@ where
         //
         // (See the separate paper which alters the 'public inputs' component of the plonk protocol)
         let public_inputs = transcript.get_field_element_vector("public_inputs");
-        let public_input_delta: F =
-            compute_public_input_delta(&public_inputs, beta, gamma, key.domain.root);
+        let public_input_delta: C::Fr =
+            compute_public_input_delta(&public_inputs, beta, gamma, key.domain.root)?;
 
         t1 = (z_1_shifted_eval - public_input_delta) * l_end * alpha_squared;
         // Part 3: compute starting lagrange polynomial term, i.e.
         //
         // L_1(ʓ).α^3
         //
-        let t2: F = l_start * alpha_cubed;
+        let t2: C::Fr = l_start * alpha_cubed;
 
         // Combine parts 1, 2, 3.
         //  quotient_numerator_eval =
@@ -161,7 +161,7 @@ where
         //
         // - α.(a_eval + β.sigma1_eval + γ)(b_eval + β.sigma2_eval + γ).β.z(ʓ.ω)
         //
-        sigma_contribution = F::one();
+        sigma_contribution = C::Fr::one();
         for i in 0..key.program_width - 1 {
             t0 = sigma_evaluations[i] * beta;
             t0 += wire_evaluations[i];
@@ -191,7 +191,7 @@ where
         *quotient_numerator_eval +=
             sigma_last_multiplicand * sigma_evaluations[key.program_width - 1];
 
-        let z_eval: F = transcript.get_field_element("z_perm");
+        let z_eval: C::Fr = transcript.get_field_element("z_perm");
         if idpolys {
             // Part 5.1: If idpolys = true, it indicates that we are not using the identity polynomials to
             // represent identity permutations. In that case, we need to use the pre-defined values for
@@ -203,9 +203,9 @@ where
             // ].z(X)
             //
 
-            let mut id_contribution = F::one();
+            let mut id_contribution = C::Fr::one();
             for (i, eval_i) in wire_evaluations.iter().enumerate().take(key.program_width) {
-                let id_evaluation: F =
+                let id_evaluation: C::Fr =
                     transcript.get_field_element(format!("id_{}", i + 1).as_str());
                 t0 = id_evaluation * beta;
                 t0 += eval_i;
@@ -238,12 +238,12 @@ where
             //   + α^3.L_1(ʓ)
             // ].z(ʓ)
             //
-            let mut z_contribution = F::one();
+            let mut z_contribution = C::Fr::one();
             for (i, eval_i) in wire_evaluations.iter().enumerate().take(key.program_width) {
                 let coset_generator = if i == 0 {
-                    F::one()
+                    C::Fr::one()
                 } else {
-                    coset_generator(i - 1)
+                    C::coset_generator(i - 1)
                 };
                 t0 = z_beta * coset_generator;
                 t0 += eval_i;
@@ -257,109 +257,637 @@ where
             // add up part 5.2 to the  quotient_numerator_eval term
             *quotient_numerator_eval += z_1_multiplicand * z_eval;
         }
-        alpha_squared.square()
+        Ok(alpha_squared.square())
     }
 
     pub(crate) fn append_scalar_multiplication_inputs(
-        alpha_base: F,
-        transcript: &Transcript<H, F, G1Affine>,
-    ) -> F {
-        let alpha_step: F = transcript.get_challenge_field_element("alpha", None);
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+    ) -> C::Fr {
+        let alpha_step: C::Fr = transcript.get_challenge_field_element("alpha", None);
         alpha_base * alpha_step.square() * alpha_step
     }
 }
 
+/// Computes the permutation grand product in Lagrange form over `small_domain`:
+///
+/// z_0 = 1, z_{i+1} = z_i · ∏_j (w_{i,j} + β.id_{i,j} + γ) / (w_{i,j} + β.σ_{i,j} + γ)
+///
+/// When `ids` is `None` the identity permutation is represented by k_j.ω^i, with k_0 = 1 and
+/// k_j the j-th coset generator.
+pub(crate) fn compute_permutation_grand_product<F: Field + FftField>(
+    wires: &[&[F]],
+    sigmas: &[&[F]],
+    ids: Option<&[&[F]]>,
+    beta: F,
+    gamma: F,
+    small_domain: &EvaluationDomain<'_, F>,
+) -> Vec<F> {
+    let n = small_domain.size;
+    let program_width = wires.len();
+    assert_eq!(sigmas.len(), program_width);
+
+    let mut column_multipliers = vec![F::one(); program_width];
+    for (k, multiplier) in column_multipliers.iter_mut().enumerate().skip(1) {
+        *multiplier = coset_generator(k - 1);
+    }
+
+    // numerators[i] = ∏_j (w_{i,j} + β.id_{i,j} + γ), denominators[i] = ∏_j (w_{i,j} + β.σ_{i,j} + γ)
+    let mut numerators = vec![F::one(); n];
+    let mut denominators = vec![F::one(); n];
+    parallel_for_chunks_mut_pair(
+        &mut numerators,
+        &mut denominators,
+        small_domain.thread_size,
+        |j, numerator_chunk, denominator_chunk| {
+            let start = j * small_domain.thread_size;
+            let mut cur_root_times_beta = small_domain.root.pow([start as u64]) * beta;
+            for (k, (numerator, denominator)) in numerator_chunk
+                .iter_mut()
+                .zip(denominator_chunk.iter_mut())
+                .enumerate()
+            {
+                let i = start + k;
+                for column in 0..program_width {
+                    let wire_plus_gamma = wires[column][i] + gamma;
+                    let id_times_beta = match ids {
+                        Some(ids) => ids[column][i] * beta,
+                        None => column_multipliers[column] * cur_root_times_beta,
+                    };
+                    *numerator *= id_times_beta + wire_plus_gamma;
+                    *denominator *= sigmas[column][i] * beta + wire_plus_gamma;
+                }
+                cur_root_times_beta *= small_domain.root;
+            }
+        },
+    );
+
+    batch_invert(&mut denominators).expect("permutation denominators are nonzero");
+
+    let mut z = vec![F::zero(); n];
+    z[0] = F::one();
+    for i in 0..n - 1 {
+        z[i + 1] = z[i] * numerators[i] * denominators[i];
+    }
+    z
+}
+
 pub(crate) struct ProverPermutationWidget<
     'a,
-    Fr: Field + FftField,
     Hash: BarretenHasher,
-    G1Affine: AffineRepr,
+    C: CurveConfig,
     const PROGRAM_WIDTH: usize,
     const IDPOLYS: bool,
     const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
 > {
-    pub(crate) key: Rc<ProvingKey<'a, Fr, G1Affine>>,
-    phantom: PhantomData<(Hash, Fr, G1Affine)>,
+    pub(crate) key: Arc<RwLock<ProvingKey<'a, C>>>,
+    phantom: PhantomData<(Hash, C)>,
 }
 
 impl<
         'a,
-        Fr: Field + FftField,
         Hash: BarretenHasher,
-        G1Affine: AffineRepr,
+        C: CurveConfig,
         const PROGRAM_WIDTH: usize,
         const IDPOLYS: bool,
         const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
-    > ProverRandomWidget<'a, Hash, Fr, G1Affine>
+    > ProverRandomWidget<'a, Hash, C>
     for ProverPermutationWidget<
         'a,
-        Fr,
         Hash,
-        G1Affine,
+        C,
         PROGRAM_WIDTH,
         IDPOLYS,
         NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL,
     >
 {
+    /// Computes z_perm in round 3, once β and γ are known: the grand product is built in Lagrange
+    /// form, blinded, converted to monomial form and queued for commitment as "Z_PERM" and for a
+    /// coset FFT.
     fn compute_round_commitments(
         &self,
-        _transcript: &mut Transcript<Hash, Fr, G1Affine>,
-        _size: usize,
-        _work_queue: &mut WorkQueue<'a, Hash, Fr, G1Affine>,
-    ) {
-        todo!()
+        transcript: &mut Transcript<Hash, C>,
+        round_number: usize,
+        work_queue: &mut WorkQueue<'a, Hash, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> Result<()> {
+        if round_number != 3 {
+            return Ok(());
+        }
+
+        let beta: C::Fr = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma: C::Fr = transcript.get_challenge_field_element("beta", Some(1));
+
+        let mut key = self.key.write().unwrap();
+        let n = key.circuit_size;
+
+        let mut z_perm_lagrange = {
+            let get_all = |prefix: &str| {
+                (1..=PROGRAM_WIDTH)
+                    .map(|i| {
+                        key.polynomial_store
+                            .get(&format!("{}_{}_lagrange", prefix, i))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            };
+            let wires = get_all("w")?;
+            let sigmas = get_all("sigma")?;
+            let ids = if IDPOLYS { get_all("id")? } else { vec![] };
+
+            let wires = wires.iter().map(|p| p.read().unwrap()).collect::<Vec<_>>();
+            let sigmas = sigmas.iter().map(|p| p.read().unwrap()).collect::<Vec<_>>();
+            let ids = ids.iter().map(|p| p.read().unwrap()).collect::<Vec<_>>();
+
+            let wires = wires
+                .iter()
+                .map(|p| &p.coefficients[..n])
+                .collect::<Vec<_>>();
+            let sigmas = sigmas
+                .iter()
+                .map(|p| &p.coefficients[..n])
+                .collect::<Vec<_>>();
+            let ids = ids.iter().map(|p| &p.coefficients[..n]).collect::<Vec<_>>();
+
+            compute_permutation_grand_product(
+                &wires,
+                &sigmas,
+                if IDPOLYS { Some(ids.as_slice()) } else { None },
+                beta,
+                gamma,
+                &key.small_domain,
+            )
+        };
+
+        // Blind z_perm in the rows cut out of the vanishing polynomial. The relation at row n-k-1
+        // still reaches z_perm[n-k], so we start blinding right after it.
+        let z_randomness = 3;
+        assert!(z_randomness < NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL);
+        for k in 0..z_randomness {
+            z_perm_lagrange[n - NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL + 1 + k] =
+                C::Fr::rand(rng);
+        }
+
+        let mut z_perm = Polynomial::from_coefficients(z_perm_lagrange.clone(), n);
+        key.small_domain.ifft_inplace(&mut z_perm);
+        let z_perm = Arc::new(RwLock::new(z_perm));
+
+        key.polynomial_store.put(
+            "z_perm_lagrange".to_string(),
+            Polynomial::from_coefficients(z_perm_lagrange, n),
+        )?;
+        key.polynomial_store
+            .put("z_perm".to_string(), z_perm.read().unwrap().clone())?;
+
+        work_queue.add_to_queue(WorkItem {
+            work: Work::ScalarMultiplication {
+                constant: C::Fr::from(n as u64),
+                mul_scalars: z_perm,
+            },
+            tag: "Z_PERM".to_string(),
+        });
+        work_queue.add_to_queue(WorkItem {
+            work: Work::Fft { index: 0 },
+            tag: "z_perm".to_string(),
+        });
+        Ok(())
     }
 
+    /// Computes the permutation identity over the 4n coset and adds it to the quotient parts:
+    ///
+    ///     α_base.[ ∏_j (w_j(X) + β.id_j(X) + γ).z(X) - ∏_j (w_j(X) + β.σ_j(X) + γ).z(X.ω) ]
+    ///   + α_base.α.(z(X.ω) - ∆_PI).L_{n-k}(X)
+    ///   + α_base.α^2.(z(X) - 1).L_1(X)
     fn compute_quotient_contribution(
         &self,
-        _alpha_base: Fr,
-        _transcript: &Transcript<Hash, Fr, G1Affine>,
-    ) -> Fr {
-        todo!()
+        alpha_base: C::Fr,
+        transcript: &Transcript<Hash, C>,
+    ) -> Result<C::Fr> {
+        let key = self.key.read().unwrap();
+        let n = key.circuit_size;
+
+        let alpha: C::Fr = transcript.get_challenge_field_element("alpha", None);
+        let beta: C::Fr = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma: C::Fr = transcript.get_challenge_field_element("beta", Some(1));
+        let alpha_squared = alpha_base * alpha;
+        let alpha_cubed = alpha_squared * alpha;
+
+        let public_inputs = transcript.get_field_element_vector("public_inputs");
+        let public_input_delta =
+            compute_public_input_delta(&public_inputs, beta, gamma, key.small_domain.root)?;
+
+        let get = |label: String| key.polynomial_store.get(&label);
+        let wire_ffts = (1..=PROGRAM_WIDTH)
+            .map(|i| get(format!("w_{}_fft", i)))
+            .collect::<Result<Vec<_>>>()?;
+        let sigma_ffts = (1..=PROGRAM_WIDTH)
+            .map(|i| get(format!("sigma_{}_fft", i)))
+            .collect::<Result<Vec<_>>>()?;
+        let id_ffts = if IDPOLYS {
+            (1..=PROGRAM_WIDTH)
+                .map(|i| get(format!("id_{}_fft", i)))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            vec![]
+        };
+        let z_perm_fft = get("z_perm_fft".to_string())?;
+        let l_start = get("lagrange_1_fft".to_string())?;
+
+        let wire_ffts = wire_ffts
+            .iter()
+            .map(|p| p.read().unwrap())
+            .collect::<Vec<_>>();
+        let sigma_ffts = sigma_ffts
+            .iter()
+            .map(|p| p.read().unwrap())
+            .collect::<Vec<_>>();
+        let id_ffts = id_ffts
+            .iter()
+            .map(|p| p.read().unwrap())
+            .collect::<Vec<_>>();
+        let z_perm_fft = z_perm_fft.read().unwrap();
+        let l_start = l_start.read().unwrap();
+
+        let mut column_multipliers = vec![C::Fr::one(); PROGRAM_WIDTH];
+        for (k, multiplier) in column_multipliers.iter_mut().enumerate().skip(1) {
+            *multiplier = C::coset_generator(k - 1);
+        }
+
+        let large_root = key.large_domain.root;
+        let fft_coset_generator = key.small_domain.generator;
+        let block_mask = key.large_domain.size - 1;
+        // L_{n-k}(X) = L_1(X.ω^{k+1}), and a shift by ω on the small domain is a shift by 4 on the
+        // large one.
+        let l_end_shift = 4 * (NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL + 1);
+        let num_parts = key.large_domain.size >> key.small_domain.log2_size;
+        let chunk_size = key.small_domain.thread_size;
+
+        for (part_idx, quotient_part) in key
+            .quotient_polynomial_parts
+            .iter()
+            .take(num_parts)
+            .enumerate()
+        {
+            let mut quotient_part = quotient_part.write().unwrap();
+            parallel_for_chunks_mut(
+                &mut quotient_part.coefficients[..n],
+                chunk_size,
+                |j, chunk| {
+                    let start = part_idx * n + j * chunk_size;
+                    // X = g.ω_{4n}^i, where g is the coset generator
+                    let mut cur_root_times_beta =
+                        large_root.pow([start as u64]) * fft_coset_generator * beta;
+                    for (k, quotient_term) in chunk.iter_mut().enumerate() {
+                        let i = start + k;
+                        let shifted_i = (i + 4) & block_mask;
+
+                        let mut numerator = C::Fr::one();
+                        let mut denominator = C::Fr::one();
+                        for column in 0..PROGRAM_WIDTH {
+                            let wire_plus_gamma = wire_ffts[column][i] + gamma;
+                            let id_times_beta = if IDPOLYS {
+                                id_ffts[column][i] * beta
+                            } else {
+                                column_multipliers[column] * cur_root_times_beta
+                            };
+                            numerator *= id_times_beta + wire_plus_gamma;
+                            denominator *= sigma_ffts[column][i] * beta + wire_plus_gamma;
+                        }
+                        numerator *= z_perm_fft[i];
+                        denominator *= z_perm_fft[shifted_i];
+                        let permutation_term = (numerator - denominator) * alpha_base;
+
+                        let l_end = l_start[(i + l_end_shift) & block_mask];
+                        let public_input_term =
+                            (z_perm_fft[shifted_i] - public_input_delta) * l_end * alpha_squared;
+
+                        let start_term = (z_perm_fft[i] - C::Fr::one()) * l_start[i] * alpha_cubed;
+
+                        *quotient_term += permutation_term + public_input_term + start_term;
+                        cur_root_times_beta *= large_root;
+                    }
+                },
+            );
+        }
+
+        Ok(alpha_cubed * alpha)
     }
 }
 
 impl<
         'a,
-        Fr: Field + FftField,
-        G1Affine: AffineRepr,
         Hash: BarretenHasher,
+        C: CurveConfig,
         const PROGRAM_WIDTH: usize,
         const IDPOLYS: bool,
         const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
     >
     ProverPermutationWidget<
         'a,
-        Fr,
         Hash,
-        G1Affine,
+        C,
         PROGRAM_WIDTH,
         IDPOLYS,
         NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL,
     >
 {
-    pub(crate) fn new(proving_key: Rc<ProvingKey<'a, Fr, G1Affine>>) -> Self {
+    pub(crate) fn new(proving_key: Arc<RwLock<ProvingKey<'a, C>>>) -> Self {
         Self {
             key: proving_key,
             phantom: PhantomData,
         }
     }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecc::curves::{tag_coset_generator, Bn254};
+    use crate::plonk::composer::manifest::turbo_manifest;
+    use crate::polynomials::polynomial_arithmetic::evaluate;
+    use crate::transcript::Keccak256;
+    use ark_bn254::Fr;
+    use ark_ff::{One, UniformRand, Zero};
+    use rand::{rngs::StdRng, SeedableRng};
 
-    pub(crate) fn compute_round_commitments(
-        &mut self,
-        _transcript: &mut Transcript<Hash, Fr, G1Affine>,
-        _round_number: usize,
-        _queue: &mut WorkQueue<'a, Hash, Fr, G1Affine>,
-    ) {
-        // ...
+    const N: usize = 8;
+    const WIDTH: usize = 3;
+
+    fn domain(thread_size: usize) -> EvaluationDomain<'static, Fr> {
+        EvaluationDomain {
+            size: N,
+            thread_size,
+            num_threads: N / thread_size,
+            root: Fr::get_root_of_unity(N as u64).unwrap(),
+            ..Default::default()
+        }
     }
 
-    pub(crate) fn compute_quotient_contribution(
-        &self,
-        _alpha_base: Fr,
-        _transcript: &Transcript<Hash, Fr, G1Affine>,
-    ) -> Fr {
-        // ...
-        todo!("ProverPermutationWidget::compute_quotient_contribution")
+    /// σ_j(ω^i) = k_j.ω^i, i.e. every cell only maps to itself.
+    fn identity_sigmas(width: usize) -> Vec<Vec<Fr>> {
+        let root = Fr::get_root_of_unity(N as u64).unwrap();
+        (0..width)
+            .map(|j| {
+                let k = if j == 0 {
+                    Fr::one()
+                } else {
+                    coset_generator(j - 1)
+                };
+                (0..N).map(|i| k * root.pow([i as u64])).collect()
+            })
+            .collect()
+    }
+
+    fn grand_product(
+        wires: &[Vec<Fr>],
+        sigmas: &[Vec<Fr>],
+        ids: Option<&[Vec<Fr>]>,
+        thread_size: usize,
+    ) -> Vec<Fr> {
+        let wires = wires.iter().map(|w| w.as_slice()).collect::<Vec<_>>();
+        let sigmas = sigmas.iter().map(|s| s.as_slice()).collect::<Vec<_>>();
+        let ids = ids.map(|ids| ids.iter().map(|s| s.as_slice()).collect::<Vec<_>>());
+        compute_permutation_grand_product(
+            &wires,
+            &sigmas,
+            ids.as_deref(),
+            Fr::from(3u64),
+            Fr::from(7u64),
+            &domain(thread_size),
+        )
+    }
+
+    fn random_wires(rng: &mut StdRng, width: usize) -> Vec<Vec<Fr>> {
+        (0..width)
+            .map(|_| (0..N).map(|_| Fr::rand(rng)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn identity_permutation_has_trivial_grand_product() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let z = grand_product(
+            &random_wires(&mut rng, WIDTH),
+            &identity_sigmas(WIDTH),
+            None,
+            N,
+        );
+        assert!(z.iter().all(|z_i| z_i.is_one()));
+    }
+
+    #[test]
+    fn copy_constraint_balances_only_when_values_match() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut wires = random_wires(&mut rng, WIDTH);
+        // swap the permutation of cells (w_1, row 1) and (w_2, row 3)
+        let mut sigmas = identity_sigmas(WIDTH);
+        let tmp = sigmas[0][1];
+        sigmas[0][1] = sigmas[1][3];
+        sigmas[1][3] = tmp;
+
+        wires[1][3] = wires[0][1];
+        let z = grand_product(&wires, &sigmas, None, N);
+        assert!(!z[2].is_one());
+        assert!(z[4..].iter().all(|z_i| z_i.is_one()));
+
+        wires[1][3] += Fr::one();
+        let z = grand_product(&wires, &sigmas, None, N);
+        assert!(!z[N - 1].is_one());
+    }
+
+    #[test]
+    fn id_polynomials_match_implicit_identity() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let wires = random_wires(&mut rng, WIDTH);
+        let sigmas = random_wires(&mut rng, WIDTH);
+        let ids = identity_sigmas(WIDTH);
+
+        let expected = grand_product(&wires, &sigmas, None, N);
+        assert_eq!(grand_product(&wires, &sigmas, Some(&ids), N), expected);
+        assert_eq!(grand_product(&wires, &sigmas, None, 2), expected);
+    }
+
+    #[test]
+    fn tagged_sets_balance_only_when_they_are_permutations() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let root = Fr::get_root_of_unity(N as u64).unwrap();
+        let tagged = |tag: u64| tag_coset_generator::<Fr>() * root.pow([tag]);
+        let (a, b) = (1, 2);
+
+        // Rows 0..4 of w_1 are tagged a and rows 0..4 of w_2 are tagged b, with τ(a) = b and τ(b) = a.
+        // Every cell is alone in its cycle, so id sends it to its own tag and σ to its partner's.
+        let mut ids = identity_sigmas(WIDTH);
+        let mut sigmas = identity_sigmas(WIDTH);
+        for row in 0..4 {
+            ids[0][row] = tagged(a);
+            sigmas[0][row] = tagged(b);
+            ids[1][row] = tagged(b);
+            sigmas[1][row] = tagged(a);
+        }
+
+        let mut wires = random_wires(&mut rng, WIDTH);
+        for (row, source) in [3, 0, 2, 1].into_iter().enumerate() {
+            wires[1][row] = wires[0][source];
+        }
+        let z = grand_product(&wires, &sigmas, Some(&ids), N);
+        assert!(z[4..].iter().all(|z_i| z_i.is_one()));
+
+        wires[1][2] = wires[0][3];
+        let z = grand_product(&wires, &sigmas, Some(&ids), N);
+        assert!(!z[4].is_one());
+    }
+
+    /// Evaluations of `monomial` over the large coset, as the work queue leaves them in the store.
+    fn large_coset_fft(key: &ProvingKey<'_, Bn254>, monomial: &Polynomial<Fr>) -> Polynomial<Fr> {
+        let mut fft = vec![Fr::zero(); 4 * N];
+        key.large_domain
+            .coset_fft(&monomial.coefficients[..N], &mut fft);
+        Polynomial::from_coefficients(fft, 4 * N)
+    }
+
+    /// A key over N rows holding `wires` and `sigmas` in Lagrange, monomial and coset FFT form.
+    fn key_with(wires: &[Vec<Fr>], sigmas: &[Vec<Fr>]) -> ProvingKey<'static, Bn254> {
+        let mut key = ProvingKey {
+            circuit_size: N,
+            small_domain: EvaluationDomain::new(N, None),
+            large_domain: EvaluationDomain::new(4 * N, None),
+            ..Default::default()
+        };
+        key.init();
+        for (prefix, columns) in [("w", wires), ("sigma", sigmas)] {
+            for (j, column) in columns.iter().enumerate() {
+                let label = format!("{}_{}", prefix, j + 1);
+                let lagrange = Polynomial::from_coefficients(column.clone(), N);
+                let mut monomial = lagrange.clone();
+                key.small_domain.ifft_inplace(&mut monomial);
+                let fft = large_coset_fft(&key, &monomial);
+                let store = &mut key.polynomial_store;
+                store.put(format!("{}_lagrange", label), lagrange).unwrap();
+                store.put(format!("{}_fft", label), fft).unwrap();
+                store.put(label, monomial).unwrap();
+            }
+        }
+        key
+    }
+
+    /// Whether the width-4 widget's contribution to the quotient, once divided by the vanishing polynomial,
+    /// agrees with the permutation identity at a random point. It only does when the identity holds on every
+    /// row the vanishing polynomial keeps.
+    fn width_4_quotient_is_a_polynomial(wires: &[Vec<Fr>], sigmas: &[Vec<Fr>]) -> bool {
+        const K: usize = 4;
+        let mut rng = StdRng::seed_from_u64(1);
+        let key = Arc::new(RwLock::new(key_with(wires, sigmas)));
+        let widget = ProverPermutationWidget::<'_, Keccak256, Bn254, 4, false, K>::new(key.clone());
+
+        let mut transcript = Transcript::<Keccak256, Bn254>::new(Some(turbo_manifest(0)), 16);
+        transcript
+            .mock_inputs_prior_to_challenge("alpha", N)
+            .unwrap();
+        transcript.apply_fiat_shamir("alpha").unwrap();
+        let alpha = transcript.get_challenge_field_element("alpha", None);
+        let beta = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma = transcript.get_challenge_field_element("beta", Some(1));
+
+        let mut queue = WorkQueue::new(Some(key.clone()), None);
+        let mut prover_rng: Box<dyn rand::RngCore + Send> = Box::new(StdRng::seed_from_u64(2));
+        widget
+            .compute_round_commitments(&mut transcript, 3, &mut queue, &mut prover_rng)
+            .unwrap();
+
+        // stand in for the queue's coset FFT of z_perm and the prover's L_1, and for the terms of widgets
+        // that ran before this one
+        let mut previous = vec![];
+        {
+            let mut key = key.write().unwrap();
+            let z_perm = key.polynomial_store.get(&"z_perm".to_string()).unwrap();
+            let z_perm_fft = large_coset_fft(&key, &z_perm.read().unwrap());
+            let mut l_start = Polynomial::new(N);
+            l_start[0] = Fr::one();
+            key.small_domain.ifft_inplace(&mut l_start);
+            let l_start_fft = large_coset_fft(&key, &l_start);
+            let store = &mut key.polynomial_store;
+            store.put("z_perm_fft".to_string(), z_perm_fft).unwrap();
+            store
+                .put("lagrange_1_fft".to_string(), l_start_fft)
+                .unwrap();
+            for part in key.quotient_polynomial_parts.iter() {
+                for coefficient in part.write().unwrap().coefficients[..N].iter_mut() {
+                    *coefficient = Fr::rand(&mut rng);
+                    previous.push(*coefficient);
+                }
+            }
+        }
+
+        let alpha_base = alpha;
+        let next_alpha_base = widget
+            .compute_quotient_contribution(alpha_base, &transcript)
+            .unwrap();
+        assert_eq!(next_alpha_base, alpha_base * alpha.pow([3]));
+
+        let key = key.read().unwrap();
+        let small_root = key.small_domain.root;
+        let mut quotient: Vec<Fr> = key
+            .quotient_polynomial_parts
+            .iter()
+            .flat_map(|part| part.read().unwrap().coefficients[..N].to_vec())
+            .zip(previous)
+            .map(|(total, previous)| total - previous)
+            .collect();
+
+        // (X^n - 1) without the factors for the last K rows, which the identity need not hold on
+        let vanishing = |x: Fr| {
+            (N - K..N).fold(x.pow([N as u64]) - Fr::one(), |acc, row| {
+                acc / (x - small_root.pow([row as u64]))
+            })
+        };
+        let mut x = key.large_domain.generator;
+        for term in quotient.iter_mut() {
+            *term /= vanishing(x);
+            x *= key.large_domain.root;
+        }
+        key.large_domain.coset_ifft(&mut quotient);
+
+        let zeta = Fr::rand(&mut rng);
+        let monomial = |label: String| key.polynomial_store.get(&label).unwrap();
+        let at = |label: String, point: Fr| {
+            evaluate(&monomial(label).read().unwrap().coefficients, &point, N)
+        };
+        let l_1 = |x: Fr| (x.pow([N as u64]) - Fr::one()) / (Fr::from(N as u64) * (x - Fr::one()));
+
+        let mut numerator = at("z_perm".to_string(), zeta);
+        let mut denominator = at("z_perm".to_string(), zeta * small_root);
+        for j in 0..4 {
+            let k_j = if j == 0 {
+                Fr::one()
+            } else {
+                coset_generator(j - 1)
+            };
+            let wire = at(format!("w_{}", j + 1), zeta);
+            numerator *= wire + beta * k_j * zeta + gamma;
+            denominator *= wire + beta * at(format!("sigma_{}", j + 1), zeta) + gamma;
+        }
+        let z_omega = at("z_perm".to_string(), zeta * small_root);
+        let l_end = l_1(zeta * small_root.pow([K as u64 + 1]));
+        let expected = (numerator - denominator) * alpha_base
+            + (z_omega - Fr::one()) * l_end * alpha_base * alpha
+            + (at("z_perm".to_string(), zeta) - Fr::one()) * l_1(zeta) * alpha_base * alpha * alpha;
+
+        evaluate(&quotient, &zeta, 4 * N) * vanishing(zeta) == expected
+    }
+
+    #[test]
+    fn width_4_quotient_adds_the_permutation_identity() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut wires = random_wires(&mut rng, 4);
+        // swap the permutation of cells (w_1, row 1) and (w_4, row 2)
+        let mut sigmas = identity_sigmas(4);
+        let tmp = sigmas[0][1];
+        sigmas[0][1] = sigmas[3][2];
+        sigmas[3][2] = tmp;
+
+        wires[3][2] = wires[0][1];
+        assert!(width_4_quotient_is_a_polynomial(&wires, &sigmas));
+
+        wires[3][2] += Fr::one();
+        assert!(!width_4_quotient_is_a_polynomial(&wires, &sigmas));
     }
 }
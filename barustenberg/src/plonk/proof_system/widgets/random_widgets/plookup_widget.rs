@@ -0,0 +1,801 @@
+use crate::common::thread::{parallel_for_chunks_mut, parallel_for_chunks_mut_pair};
+use crate::ecc::{batch::batch_invert, curves::CurveConfig};
+use crate::plonk::proof_system::proving_key::ProvingKey;
+use crate::plonk::proof_system::verification_key::VerificationKey;
+use crate::plonk::proof_system::widgets::random_widgets::random_widget::ProverRandomWidget;
+use crate::polynomials::{evaluation_domain::EvaluationDomain, Polynomial};
+use crate::proof_system::work_queue::{Work, WorkItem, WorkQueue};
+use crate::transcript::{BarretenHasher, Transcript};
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, ensure, Result};
+use ark_ff::{FftField, Field, One, UniformRand, Zero};
+use std::collections::HashMap;
+
+/// Number of blinding values we put in the rows cut out of the vanishing polynomial.
+const NUM_BLINDING_VALUES: usize = 3;
+
+/// Labels of the polynomials that go into the lookup values f and the table values t, in the order
+/// [`LookupColumns`] reads them.
+const LOOKUP_LABELS: [&str; 12] = [
+    "w_1",
+    "w_2",
+    "w_3",
+    "q_2",
+    "q_m",
+    "q_c",
+    "q_3",
+    "table_type",
+    "table_value_1",
+    "table_value_2",
+    "table_value_3",
+    "table_value_4",
+];
+
+/// Compresses several columns into one value per row with powers of η: `c_1 + η.c_2 + η².c_3 + ...`
+pub(crate) fn compress_columns<F: Field>(columns: &[&[F]], eta: F) -> Vec<F> {
+    let mut result = vec![F::zero(); columns[0].len()];
+    for column in columns.iter().rev() {
+        for (value, entry) in result.iter_mut().zip(column.iter()) {
+            *value = *value * eta + entry;
+        }
+    }
+    result
+}
+
+/// The columns a lookup row reads, as slices over the rows of the circuit, in the order of
+/// [`LOOKUP_LABELS`].
+struct LookupColumns<'a, F> {
+    wires: [&'a [F]; 3],
+    step_sizes: [&'a [F]; 3],
+    table_index: &'a [F],
+    lookup_selector: &'a [F],
+    tables: [&'a [F]; 4],
+}
+
+impl<'a, F: Field> LookupColumns<'a, F> {
+    fn new(columns: &[&'a [F]]) -> Self {
+        Self {
+            wires: [columns[0], columns[1], columns[2]],
+            step_sizes: [columns[3], columns[4], columns[5]],
+            table_index: columns[6],
+            lookup_selector: columns[7],
+            tables: [columns[8], columns[9], columns[10], columns[11]],
+        }
+    }
+
+    /// `q_lookup.f` at `i`, where `shifted_i` is the index of the next row and
+    ///
+    ///     f = w_1 + q_2.w_1(ωX) + η.(w_2 + q_m.w_2(ωX)) + η².(w_3 + q_c.w_3(ωX)) + η³.q_3
+    ///
+    /// The step sizes let a row look up the difference between neighbouring accumulators, which is how
+    /// a value gets split into table-sized slices, and q_3 holds the index of the table.
+    fn lookup(&self, i: usize, shifted_i: usize, eta: F) -> F {
+        let mut f = self.table_index[i];
+        for j in (0..3).rev() {
+            f = f * eta + self.wires[j][i] + self.step_sizes[j][i] * self.wires[j][shifted_i];
+        }
+        f * self.lookup_selector[i]
+    }
+
+    /// `t = table_1 + η.table_2 + η².table_3 + η³.table_4` at `i`.
+    fn table(&self, i: usize, eta: F) -> F {
+        self.tables
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, table| acc * eta + table[i])
+    }
+}
+
+/// The evaluations of the lookup polynomials at one point X and at X.ω.
+pub(crate) struct LookupEvaluations<F> {
+    pub(crate) z: F,
+    pub(crate) z_omega: F,
+    pub(crate) lookup: F,
+    pub(crate) table: F,
+    pub(crate) table_omega: F,
+    pub(crate) sorted: F,
+    pub(crate) sorted_omega: F,
+}
+
+impl<F: Field> LookupEvaluations<F> {
+    /// The grand product identity
+    ///
+    ///     z(X).(1 + β)(γ + q_lookup.f)(γ(1 + β) + t + β.t(ωX)) - z(ωX).γ(1 + β).(γ(1 + β) + s + β.s(ωX))
+    ///
+    /// See [`compute_lookup_grand_product`] for the extra γ(1 + β) in the second term.
+    pub(crate) fn grand_product_identity(&self, beta: F, gamma: F) -> F {
+        let one_plus_beta = F::one() + beta;
+        let gamma_beta = gamma * one_plus_beta;
+        let numerator = self.z
+            * one_plus_beta
+            * (gamma + self.lookup)
+            * (gamma_beta + self.table + beta * self.table_omega);
+        let denominator =
+            self.z_omega * gamma_beta * (gamma_beta + self.sorted + beta * self.sorted_omega);
+        numerator - denominator
+    }
+}
+
+/// Computes the lookup grand product in Lagrange form over `small_domain`:
+///
+///     z_0 = 1, z_{i+1} = z_i . (1 + β)(γ + f_i)(γ(1 + β) + t_i + β.t_{i+1}) / γ(1 + β).(γ(1 + β) + s_i + β.s_{i+1})
+///
+/// Here f_i is the (compressed) value row i looks up, zero for rows that don't, t is the table padded with
+/// zeros at the start and s the sorted union of the lookups and the table, padded the same way. Over m
+/// rows the zero padding leaves the numerator with m more factors of γ(1 + β) than the denominator has, so
+/// dividing each row by γ(1 + β) brings the product back to 1 exactly when every lookup is in the table.
+pub(crate) fn compute_lookup_grand_product<F: Field + FftField>(
+    lookups: &[F],
+    table: &[F],
+    sorted: &[F],
+    beta: F,
+    gamma: F,
+    small_domain: &EvaluationDomain<'_, F>,
+) -> Result<Vec<F>> {
+    let n = small_domain.size;
+    let one_plus_beta = F::one() + beta;
+    let gamma_beta = gamma * one_plus_beta;
+
+    let mut numerators = vec![F::one(); n];
+    let mut denominators = vec![F::one(); n];
+    parallel_for_chunks_mut_pair(
+        &mut numerators,
+        &mut denominators,
+        small_domain.thread_size,
+        |j, numerator_chunk, denominator_chunk| {
+            let start = j * small_domain.thread_size;
+            for (k, (numerator, denominator)) in numerator_chunk
+                .iter_mut()
+                .zip(denominator_chunk.iter_mut())
+                .enumerate()
+            {
+                let i = start + k;
+                let next = (i + 1) % n;
+                *numerator = one_plus_beta
+                    * (gamma + lookups[i])
+                    * (gamma_beta + table[i] + beta * table[next]);
+                *denominator = gamma_beta * (gamma_beta + sorted[i] + beta * sorted[next]);
+            }
+        },
+    );
+
+    batch_invert(&mut denominators)?;
+
+    let mut z = vec![F::zero(); n];
+    z[0] = F::one();
+    for i in 0..n - 1 {
+        z[i + 1] = z[i] * numerators[i] * denominators[i];
+    }
+    Ok(z)
+}
+
+/// Sorts the values the witness looks up into `table`, the compressed table over the rows the grand
+/// product reaches: every lookup goes right after the table row it matches, and as many of the table's
+/// leading zero rows are dropped as there are lookups, so the sorted list ends on the table's last row.
+/// A lookup of zero is indistinguishable from a row that looks nothing up, so those are left out.
+pub(crate) fn compute_sorted_list<F: Field>(lookups: &[F], table: &[F]) -> Result<Vec<F>> {
+    let position: HashMap<F, usize> = table
+        .iter()
+        .enumerate()
+        .map(|(row, value)| (*value, row))
+        .collect();
+    let mut multiplicities = vec![0; table.len()];
+    let mut num_lookups = 0;
+    for value in lookups.iter().filter(|value| !value.is_zero()) {
+        let row = position
+            .get(value)
+            .ok_or_else(|| anyhow!("a lookup is in none of the tables"))?;
+        multiplicities[*row] += 1;
+        num_lookups += 1;
+    }
+
+    let padding = table.iter().take_while(|value| value.is_zero()).count();
+    ensure!(
+        num_lookups <= padding,
+        "{} lookups do not fit in the {} rows the tables leave free",
+        num_lookups,
+        padding
+    );
+    Ok(table
+        .iter()
+        .zip(multiplicities)
+        .skip(num_lookups)
+        .flat_map(|(value, multiplicity)| std::iter::repeat(*value).take(multiplicity + 1))
+        .collect())
+}
+
+/// The compressed value each row looks up and the compressed table, row by row.
+type LookupsAndTable<F> = (Vec<F>, Vec<F>);
+
+pub(crate) struct ProverPlookupWidget<
+    'a,
+    Hash: BarretenHasher,
+    C: CurveConfig,
+    const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
+> {
+    pub(crate) key: Arc<RwLock<ProvingKey<'a, C>>>,
+    phantom: PhantomData<(Hash, C)>,
+}
+
+impl<
+        'a,
+        Hash: BarretenHasher,
+        C: CurveConfig,
+        const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
+    > ProverPlookupWidget<'a, Hash, C, NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL>
+{
+    pub(crate) fn new(proving_key: Arc<RwLock<ProvingKey<'a, C>>>) -> Self {
+        Self {
+            key: proving_key,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Blinds `values` in the rows cut out of the vanishing polynomial, stores its Lagrange and monomial
+    /// forms under `label`, and queues its commitment and coset FFT.
+    fn blind_and_commit(
+        key: &mut ProvingKey<'a, C>,
+        label: &str,
+        mut values: Vec<C::Fr>,
+        work_queue: &mut WorkQueue<'a, Hash, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> Result<()> {
+        let n = key.circuit_size;
+        assert!(NUM_BLINDING_VALUES < NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL);
+        for k in 0..NUM_BLINDING_VALUES {
+            values[n - NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL + 1 + k] = C::Fr::rand(rng);
+        }
+
+        let mut monomial = Polynomial::from_coefficients(values.clone(), n);
+        key.small_domain.ifft_inplace(&mut monomial);
+        let monomial = Arc::new(RwLock::new(monomial));
+
+        key.polynomial_store.put(
+            format!("{}_lagrange", label),
+            Polynomial::from_coefficients(values, n),
+        )?;
+        key.polynomial_store
+            .put(label.to_string(), monomial.read().unwrap().clone())?;
+
+        work_queue.add_to_queue(WorkItem {
+            work: Work::ScalarMultiplication {
+                constant: C::Fr::from(n as u64),
+                mul_scalars: monomial,
+            },
+            tag: label.to_uppercase(),
+        });
+        work_queue.add_to_queue(WorkItem {
+            work: Work::Fft { index: 0 },
+            tag: label.to_string(),
+        });
+        Ok(())
+    }
+
+    /// The value each row looks up, zero for rows that don't, and the table, both compressed with η.
+    fn compressed_lookups_and_table(
+        key: &ProvingKey<'a, C>,
+        eta: C::Fr,
+    ) -> Result<LookupsAndTable<C::Fr>> {
+        let n = key.circuit_size;
+        let polynomials = LOOKUP_LABELS
+            .iter()
+            .map(|label| key.polynomial_store.get(&format!("{}_lagrange", label)))
+            .collect::<Result<Vec<_>>>()?;
+        let polynomials = polynomials
+            .iter()
+            .map(|p| p.read().unwrap())
+            .collect::<Vec<_>>();
+        let values = polynomials
+            .iter()
+            .map(|p| &p.coefficients[..n])
+            .collect::<Vec<_>>();
+
+        let columns = LookupColumns::new(&values);
+        let lookups = (0..n)
+            .map(|i| columns.lookup(i, (i + 1) % n, eta))
+            .collect();
+        Ok((lookups, compress_columns(&columns.tables, eta)))
+    }
+
+    /// Builds the sorted list s in round 2, once η is known, from the values the witness looks up and the
+    /// tables.
+    fn compute_sorted_list_commitment(
+        &self,
+        transcript: &Transcript<Hash, C>,
+        work_queue: &mut WorkQueue<'a, Hash, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> Result<()> {
+        let eta: C::Fr = transcript.get_challenge_field_element("eta", None);
+
+        let mut key = self.key.write().unwrap();
+        let n = key.circuit_size;
+        // the grand product relation holds on the rows before m and reaches row m
+        let m = n - NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL;
+
+        let (lookups, table) = Self::compressed_lookups_and_table(&key, eta)?;
+        let mut s_lagrange = compute_sorted_list(&lookups[..m], &table[..=m])?;
+        s_lagrange.resize(n, C::Fr::zero());
+
+        Self::blind_and_commit(&mut key, "s", s_lagrange, work_queue, rng)
+    }
+
+    /// Computes z_lookup in round 3, once β and γ are known.
+    fn compute_grand_product_commitment(
+        &self,
+        transcript: &Transcript<Hash, C>,
+        work_queue: &mut WorkQueue<'a, Hash, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> Result<()> {
+        let eta: C::Fr = transcript.get_challenge_field_element("eta", None);
+        let beta: C::Fr = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma: C::Fr = transcript.get_challenge_field_element("beta", Some(1));
+
+        let mut key = self.key.write().unwrap();
+        let n = key.circuit_size;
+
+        let z_lookup_lagrange = {
+            let (lookups, table) = Self::compressed_lookups_and_table(&key, eta)?;
+            let sorted = key.polynomial_store.get(&"s_lagrange".to_string())?;
+            let sorted = sorted.read().unwrap();
+            compute_lookup_grand_product(
+                &lookups,
+                &table,
+                &sorted.coefficients[..n],
+                beta,
+                gamma,
+                &key.small_domain,
+            )?
+        };
+
+        Self::blind_and_commit(&mut key, "z_lookup", z_lookup_lagrange, work_queue, rng)
+    }
+}
+
+impl<
+        'a,
+        Hash: BarretenHasher,
+        C: CurveConfig,
+        const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
+    > ProverRandomWidget<'a, Hash, C>
+    for ProverPlookupWidget<'a, Hash, C, NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL>
+{
+    /// Commits to the sorted list s in round 2, right after η, and to the lookup grand product z_lookup in
+    /// round 3, right after β and γ.
+    fn compute_round_commitments(
+        &self,
+        transcript: &mut Transcript<Hash, C>,
+        round_number: usize,
+        work_queue: &mut WorkQueue<'a, Hash, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> Result<()> {
+        match round_number {
+            2 => self.compute_sorted_list_commitment(transcript, work_queue, rng),
+            3 => self.compute_grand_product_commitment(transcript, work_queue, rng),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds the lookup identities over the 4n coset to the quotient parts:
+    ///
+    ///     α_base.[ the grand product identity of [`LookupEvaluations`] ]
+    ///   + α_base.α.(z_lookup(X.ω) - 1).L_{n-k}(X)
+    ///   + α_base.α^2.(z_lookup(X) - 1).L_1(X)
+    fn compute_quotient_contribution(
+        &self,
+        alpha_base: C::Fr,
+        transcript: &Transcript<Hash, C>,
+    ) -> Result<C::Fr> {
+        let key = self.key.read().unwrap();
+        let n = key.circuit_size;
+
+        let alpha: C::Fr = transcript.get_challenge_field_element("alpha", None);
+        let eta: C::Fr = transcript.get_challenge_field_element("eta", None);
+        let beta: C::Fr = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma: C::Fr = transcript.get_challenge_field_element("beta", Some(1));
+        let alpha_squared = alpha_base * alpha;
+        let alpha_cubed = alpha_squared * alpha;
+
+        let polynomials = LOOKUP_LABELS
+            .iter()
+            .chain(&["s", "z_lookup", "lagrange_1"])
+            .map(|label| key.polynomial_store.get(&format!("{}_fft", label)))
+            .collect::<Result<Vec<_>>>()?;
+        let polynomials = polynomials
+            .iter()
+            .map(|p| p.read().unwrap())
+            .collect::<Vec<_>>();
+        let values = polynomials
+            .iter()
+            .map(|p| p.coefficients.as_slice())
+            .collect::<Vec<_>>();
+        let columns = LookupColumns::new(&values);
+        let [sorted, z_lookup, l_start] = [
+            values[LOOKUP_LABELS.len()],
+            values[LOOKUP_LABELS.len() + 1],
+            values[LOOKUP_LABELS.len() + 2],
+        ];
+
+        let block_mask = key.large_domain.size - 1;
+        // L_{n-k}(X) = L_1(X.ω^{k+1}), and a shift by ω on the small domain is a shift by 4 on the
+        // large one.
+        let l_end_shift = 4 * (NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL + 1);
+        let num_parts = key.large_domain.size >> key.small_domain.log2_size;
+        let chunk_size = key.small_domain.thread_size;
+
+        for (part_idx, quotient_part) in key
+            .quotient_polynomial_parts
+            .iter()
+            .take(num_parts)
+            .enumerate()
+        {
+            let mut quotient_part = quotient_part.write().unwrap();
+            parallel_for_chunks_mut(
+                &mut quotient_part.coefficients[..n],
+                chunk_size,
+                |j, chunk| {
+                    let start = part_idx * n + j * chunk_size;
+                    for (k, quotient_term) in chunk.iter_mut().enumerate() {
+                        let i = start + k;
+                        let shifted_i = (i + 4) & block_mask;
+
+                        let evaluations = LookupEvaluations {
+                            z: z_lookup[i],
+                            z_omega: z_lookup[shifted_i],
+                            lookup: columns.lookup(i, shifted_i, eta),
+                            table: columns.table(i, eta),
+                            table_omega: columns.table(shifted_i, eta),
+                            sorted: sorted[i],
+                            sorted_omega: sorted[shifted_i],
+                        };
+                        let grand_product_term =
+                            evaluations.grand_product_identity(beta, gamma) * alpha_base;
+
+                        let l_end = l_start[(i + l_end_shift) & block_mask];
+                        let end_term = (z_lookup[shifted_i] - C::Fr::one()) * l_end * alpha_squared;
+                        let start_term = (z_lookup[i] - C::Fr::one()) * l_start[i] * alpha_cubed;
+
+                        *quotient_term += grand_product_term + end_term + start_term;
+                    }
+                },
+            );
+        }
+
+        Ok(alpha_cubed * alpha)
+    }
+}
+
+/// Checks the lookup identities at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierPlookupWidget<
+    H: BarretenHasher,
+    C: CurveConfig,
+    const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize,
+> {
+    phantom: PhantomData<(H, C)>,
+}
+
+impl<H, C, const NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL: usize>
+    VerifierPlookupWidget<H, C, NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+{
+    /// Adds the same three terms as the prover's quotient contribution, evaluated at ʓ.
+    pub(crate) fn compute_quotient_evaluation_contribution(
+        key: &Arc<VerificationKey<'_, C>>,
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+        quotient_numerator_eval: &mut C::Fr,
+    ) -> C::Fr {
+        let alpha: C::Fr = transcript.get_challenge_field_element("alpha", None);
+        let eta: C::Fr = transcript.get_challenge_field_element("eta", None);
+        let beta: C::Fr = transcript.get_challenge_field_element("beta", Some(0));
+        let gamma: C::Fr = transcript.get_challenge_field_element("beta", Some(1));
+        let z: C::Fr = transcript.get_challenge_field_element("z", None);
+        let alpha_squared = alpha_base * alpha;
+        let alpha_cubed = alpha_squared * alpha;
+
+        // L_1(ʓ) = (ʓ^n - 1) / n.(ʓ - 1) and L_{n-k}(ʓ) = (ʓ^n - 1) / n.(ʓ.ω^{k+1} - 1)
+        let numerator = (key.z_pow_n - C::Fr::one()) * key.domain.domain_inverse;
+        let l_start = numerator / (z - C::Fr::one());
+        let l_end_root = key
+            .domain
+            .root
+            .pow([(NUM_ROOTS_CUT_OUT_OF_VANISHING_POLYNOMIAL + 1) as u64]);
+        let l_end = numerator / (z * l_end_root - C::Fr::one());
+
+        let lookup_evaluations = Self::opened_evaluations(transcript, eta);
+        let z_lookup = lookup_evaluations.z;
+        let z_lookup_omega = lookup_evaluations.z_omega;
+
+        *quotient_numerator_eval +=
+            lookup_evaluations.grand_product_identity(beta, gamma) * alpha_base;
+        *quotient_numerator_eval += (z_lookup_omega - C::Fr::one()) * l_end * alpha_squared;
+        *quotient_numerator_eval += (z_lookup - C::Fr::one()) * l_start * alpha_cubed;
+
+        alpha_cubed * alpha
+    }
+
+    /// The lookup polynomials at ʓ and ʓ.ω from the openings in the transcript. Only the wires and the tables
+    /// are read at the next row, so theirs are the only shifted openings the proof carries.
+    fn opened_evaluations(transcript: &Transcript<H, C>, eta: C::Fr) -> LookupEvaluations<C::Fr> {
+        // The lookup columns at ʓ and ʓ.ω, each as a single row so that LookupColumns can read them.
+        let evaluations = LOOKUP_LABELS
+            .iter()
+            .map(|label| {
+                let shifted = if label.starts_with("w_") || label.starts_with("table_value_") {
+                    transcript.get_field_element(&format!("{}_omega", label))
+                } else {
+                    C::Fr::zero()
+                };
+                [transcript.get_field_element(label), shifted]
+            })
+            .collect::<Vec<_>>();
+        let values = evaluations.iter().map(|e| e.as_slice()).collect::<Vec<_>>();
+        let columns = LookupColumns::new(&values);
+
+        LookupEvaluations {
+            z: transcript.get_field_element("z_lookup"),
+            z_omega: transcript.get_field_element("z_lookup_omega"),
+            lookup: columns.lookup(0, 1, eta),
+            table: columns.table(0, eta),
+            table_omega: columns.table(1, eta),
+            sorted: transcript.get_field_element("s"),
+            sorted_omega: transcript.get_field_element("s_omega"),
+        }
+    }
+
+    pub(crate) fn append_scalar_multiplication_inputs(
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+    ) -> C::Fr {
+        let alpha_step: C::Fr = transcript.get_challenge_field_element("alpha", None);
+        alpha_base * alpha_step.square() * alpha_step
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecc::curves::{Bn254, PairingCurveConfig};
+    use crate::plonk::composer::{composer_base::ComposerType, manifest::ultra_manifest};
+    use crate::polynomials::polynomial_arithmetic::evaluate;
+    use crate::srs::io::test::mock_srs;
+    use crate::transcript::Keccak256;
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const N: usize = 16;
+    /// Rows whose grand product relation is enforced, i.e. all but the ones cut out of the vanishing
+    /// polynomial.
+    const M: usize = N - 4;
+
+    fn domain() -> EvaluationDomain<'static, Fr> {
+        EvaluationDomain {
+            size: N,
+            thread_size: 4,
+            num_threads: N / 4,
+            root: Fr::get_root_of_unity(N as u64).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    /// Lays out `lookups` in rows 1, 3, 5, ... and pads `table` and the sorted union of both with zeros
+    /// at the start, so they end in row m.
+    fn trace(lookups: &[u64], table: &[u64]) -> (Vec<Fr>, Vec<Fr>, Vec<Fr>) {
+        let mut lookup_values = vec![Fr::zero(); N];
+        for (k, value) in lookups.iter().enumerate() {
+            lookup_values[2 * k + 1] = Fr::from(*value);
+        }
+
+        let pad = |values: &[u64]| {
+            let mut padded = vec![Fr::zero(); N];
+            for (k, value) in values.iter().enumerate() {
+                padded[M + 1 - values.len() + k] = Fr::from(*value);
+            }
+            padded
+        };
+
+        // sort by the position in the table; lookups of values outside the table go at the end
+        let mut sorted = table.to_vec();
+        for value in lookups {
+            match table.iter().position(|t| t == value) {
+                Some(position) => {
+                    let insert_at = sorted.iter().rposition(|s| s == &table[position]).unwrap();
+                    sorted.insert(insert_at + 1, *value);
+                }
+                None => sorted.push(*value),
+            }
+        }
+
+        (lookup_values, pad(table), pad(&sorted))
+    }
+
+    fn grand_product(lookups: &[Fr], table: &[Fr], sorted: &[Fr]) -> Vec<Fr> {
+        compute_lookup_grand_product(
+            lookups,
+            table,
+            sorted,
+            Fr::from(3u64),
+            Fr::from(7u64),
+            &domain(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn lookups_in_the_table_balance() {
+        let (lookups, table, sorted) = trace(&[20, 40, 20], &[10, 20, 30, 40]);
+        let z = grand_product(&lookups, &table, &sorted);
+        assert!(z[M].is_one());
+
+        // and every row satisfies the identity the quotient checks
+        for i in 0..M {
+            let evaluations = LookupEvaluations {
+                z: z[i],
+                z_omega: z[i + 1],
+                lookup: lookups[i],
+                table: table[i],
+                table_omega: table[i + 1],
+                sorted: sorted[i],
+                sorted_omega: sorted[i + 1],
+            };
+            assert!(evaluations
+                .grand_product_identity(Fr::from(3u64), Fr::from(7u64))
+                .is_zero());
+        }
+    }
+
+    #[test]
+    fn sorted_list_follows_the_table() {
+        let (lookups, table, sorted) = trace(&[20, 40, 20, 10], &[10, 20, 30, 40]);
+        assert_eq!(
+            compute_sorted_list(&lookups[..M], &table[..=M]).unwrap(),
+            sorted[..=M]
+        );
+
+        let (lookups, table, _) = trace(&[20, 25], &[10, 20, 30, 40]);
+        assert!(compute_sorted_list(&lookups[..M], &table[..=M]).is_err());
+
+        // the tables leave room for M + 1 - 4 lookups before the sorted list runs out of rows
+        let (_, table, _) = trace(&[], &[10, 20, 30, 40]);
+        let lookups = vec![Fr::from(30u64); M - 2];
+        assert!(compute_sorted_list(&lookups, &table[..=M]).is_err());
+    }
+
+    #[test]
+    fn lookups_outside_the_table_do_not_balance() {
+        let (lookups, table, sorted) = trace(&[20, 25], &[10, 20, 30, 40]);
+        let z = grand_product(&lookups, &table, &sorted);
+        assert!(!z[M].is_one());
+
+        // nor does a sorted list that isn't sorted by the table
+        let (lookups, table, mut sorted) = trace(&[20, 40], &[10, 20, 30, 40]);
+        sorted.swap(M - 1, M - 3);
+        let z = grand_product(&lookups, &table, &sorted);
+        assert!(!z[M].is_one());
+    }
+
+    #[test]
+    fn lookup_values_follow_accumulator_steps() {
+        let eta = Fr::from(5u64);
+        let column = |values: [u64; 2]| values.map(Fr::from);
+        // w_1 accumulates 7 → 2 with step size -4, so the row looks up 7 - 4·2 = -1 in table 9
+        let columns = [
+            column([7, 2]),
+            column([3, 0]),
+            column([1, 0]),
+            [-Fr::from(4u64), Fr::zero()],
+            column([0, 0]),
+            column([0, 0]),
+            column([9, 0]),
+            column([1, 0]),
+            column([0, 0]),
+            column([0, 0]),
+            column([0, 0]),
+            column([0, 0]),
+        ];
+        let values = columns.iter().map(|c| c.as_slice()).collect::<Vec<_>>();
+        let expected =
+            -Fr::one() + eta * Fr::from(3u64) + eta.square() + eta.square() * eta * Fr::from(9u64);
+        assert_eq!(LookupColumns::new(&values).lookup(0, 1, eta), expected);
+        assert!(LookupColumns::new(&values).lookup(1, 0, eta).is_zero());
+
+        let compressed = compress_columns(&[&column([1, 2]), &column([3, 4])], eta);
+        assert_eq!(compressed, vec![Fr::from(16u64), Fr::from(22u64)]);
+    }
+
+    /// The prover's contribution to the quotient numerator over the 4n coset, interpolated and evaluated
+    /// at ʓ, is what the verifier adds from the openings at ʓ and ʓ.ω.
+    #[test]
+    fn verifier_contribution_matches_the_prover_at_zeta() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut key = ProvingKey::<Bn254> {
+            circuit_size: N,
+            small_domain: EvaluationDomain::new(N, None),
+            large_domain: EvaluationDomain::new(4 * N, None),
+            ..Default::default()
+        };
+        key.init();
+        let large_coset_fft = |key: &ProvingKey<'_, Bn254>, monomial: &[Fr]| {
+            let mut fft = vec![Fr::zero(); 4 * N];
+            key.large_domain.coset_fft(monomial, &mut fft);
+            Polynomial::from_coefficients(fft, 4 * N)
+        };
+
+        // low degree polynomials keep the numerator within the 4n coset
+        let mut monomials = HashMap::new();
+        for label in LOOKUP_LABELS.iter().chain(&["s", "z_lookup"]) {
+            let mut monomial = vec![Fr::zero(); N];
+            for coefficient in monomial.iter_mut().take(3) {
+                *coefficient = Fr::rand(&mut rng);
+            }
+            let fft = large_coset_fft(&key, &monomial);
+            key.polynomial_store
+                .put(format!("{}_fft", label), fft)
+                .unwrap();
+            monomials.insert(label.to_string(), monomial);
+        }
+        let mut l_start = Polynomial::new(N);
+        l_start[0] = Fr::one();
+        key.small_domain.ifft_inplace(&mut l_start);
+        let l_start_fft = large_coset_fft(&key, &l_start.coefficients);
+        key.polynomial_store
+            .put("lagrange_1_fft".to_string(), l_start_fft)
+            .unwrap();
+
+        let mut transcript = Transcript::<Keccak256, Bn254>::new(Some(ultra_manifest(0)), 16);
+        transcript.mock_inputs_prior_to_challenge("nu", N).unwrap();
+        let alpha: Fr = transcript.get_challenge_field_element("alpha", None);
+        let zeta: Fr = transcript.get_challenge_field_element("z", None);
+        let omega = key.small_domain.root;
+        for (label, monomial) in &monomials {
+            let at = |point: Fr| evaluate(monomial, &point, N);
+            transcript.add_field_element(label, &at(zeta));
+            transcript.add_field_element(&format!("{}_omega", label), &at(zeta * omega));
+        }
+
+        let key = Arc::new(RwLock::new(key));
+        let alpha_base = alpha.square();
+        let next_alpha_base = ProverPlookupWidget::<'_, Keccak256, Bn254, 4>::new(key.clone())
+            .compute_quotient_contribution(alpha_base, &transcript)
+            .unwrap();
+
+        let key = key.read().unwrap();
+        let mut numerator: Vec<Fr> = key
+            .quotient_polynomial_parts
+            .iter()
+            .flat_map(|part| part.read().unwrap().coefficients[..N].to_vec())
+            .collect();
+        key.large_domain.coset_ifft(&mut numerator);
+
+        let mut verification_key = VerificationKey::<Bn254>::new(
+            N,
+            0,
+            Arc::new(mock_srs::<<Bn254 as PairingCurveConfig>::Pairing>(N)),
+            ComposerType::Plookup as u32,
+        );
+        verification_key.z_pow_n = zeta.pow([N as u64]);
+        let verification_key = Arc::new(verification_key);
+        let verifier_contribution = |transcript: &Transcript<Keccak256, Bn254>| {
+            let mut quotient_numerator_eval = Fr::zero();
+            let next_alpha_base = VerifierPlookupWidget::<Keccak256, Bn254, 4>::compute_quotient_evaluation_contribution(
+                &verification_key,
+                alpha_base,
+                transcript,
+                &mut quotient_numerator_eval,
+            );
+            (quotient_numerator_eval, next_alpha_base)
+        };
+
+        let (expected, verifier_next_alpha_base) = verifier_contribution(&transcript);
+        assert_eq!(evaluate(&numerator, &zeta, 4 * N), expected);
+        assert_eq!(verifier_next_alpha_base, next_alpha_base);
+
+        // a sorted list opened at ʓ.ω to anything else no longer agrees
+        transcript.add_field_element("s_omega", &Fr::rand(&mut rng));
+        assert_ne!(verifier_contribution(&transcript).0, expected);
+    }
+}
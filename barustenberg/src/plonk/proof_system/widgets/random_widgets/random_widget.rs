@@ -1,28 +1,25 @@
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use anyhow::Result;
 
 use crate::{
+    ecc::curves::CurveConfig,
     proof_system::work_queue::WorkQueue,
     transcript::{BarretenHasher, Transcript},
 };
 
-pub(crate) trait ProverRandomWidget<
-    'a,
-    H: BarretenHasher,
-    Fr: Field + FftField,
-    G1Affine: AffineRepr,
->
+pub(crate) trait ProverRandomWidget<'a, H: BarretenHasher, C: CurveConfig>:
+    Send + Sync
 {
     fn compute_round_commitments(
         &self,
-        _transcript: &mut Transcript<H, Fr, G1Affine>,
+        _transcript: &mut Transcript<H, C>,
         _size: usize,
-        _work_queue: &mut WorkQueue<'a, H, Fr, G1Affine>,
-    );
+        _work_queue: &mut WorkQueue<'a, H, C>,
+        _rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> Result<()>;
 
     fn compute_quotient_contribution(
         &self,
-        _alpha_base: Fr,
-        _transcript: &Transcript<H, Fr, G1Affine>,
-    ) -> Fr;
+        _alpha_base: C::Fr,
+        _transcript: &Transcript<H, C>,
+    ) -> Result<C::Fr>;
 }
@@ -0,0 +1,106 @@
+//! What the widget tests share. Each transition kernel is checked on openings at a single point, the way the
+//! verifier evaluates it, so the tests only differ in the gates they lay out.
+
+use std::collections::HashMap;
+
+use ark_bn254::Fr;
+use ark_ff::{Field, One, UniformRand};
+use generic_array::ArrayLength;
+use rand::{rngs::StdRng, SeedableRng};
+use typenum::{U1, U2, U4, U7};
+
+use crate::{
+    plonk::proof_system::types::{
+        polynomial_manifest::PolynomialIndex, prover_settings::StandardSettings,
+    },
+    transcript::Keccak256,
+};
+
+use super::transition_widgets::{
+    arithmetic_widget::ArithmeticKernel,
+    containers::{ChallengeArray, CoefficientArray, PolyArray},
+    getters::EvaluationGetterImpl,
+    transition_widget::KernelBase,
+    turbo_arithmetic_widget::TurboArithmeticKernel,
+    turbo_fixed_base_widget::TurboFixedBaseKernel,
+    turbo_logic_widget::TurboLogicKernel,
+    turbo_range_widget::TurboRangeKernel,
+    ultra_arithmetic_widget::UltraArithmeticKernel,
+    ultra_aux_widget::UltraAuxKernel,
+    ultra_elliptic_widget::UltraEllipticKernel,
+    ultra_sort_widget::UltraSortKernel,
+};
+
+pub(crate) type TestSettings = StandardSettings<Keccak256>;
+type Getter<N> = EvaluationGetterImpl<Keccak256, Fr, TestSettings, N>;
+
+/// Challenges whose α powers are 1, α, α², ..., one per relation of the kernel.
+pub(crate) fn challenges<N: ArrayLength<Fr>>(alpha: u64) -> ChallengeArray<Fr, N> {
+    let mut challenges = ChallengeArray::<Fr, N>::default();
+    let mut power = Fr::one();
+    for alpha_power in challenges.alpha_powers.iter_mut() {
+        *alpha_power = power;
+        power *= Fr::from(alpha);
+    }
+    challenges
+}
+
+/// The relations of kernel `K` on `polynomials`, each scaled by its α power and summed.
+pub(crate) fn evaluate<K, N>(polynomials: &PolyArray<Fr>, challenges: &ChallengeArray<Fr, N>) -> Fr
+where
+    K: KernelBase<Keccak256, TestSettings, Fr, N>,
+    N: ArrayLength<Fr>,
+{
+    let mut linear_terms = CoefficientArray::default();
+    K::compute_linear_terms::<_, Getter<N>>(polynomials, challenges, &mut linear_terms, Some(0));
+    let mut result = K::sum_linear_terms::<_, Getter<N>>(polynomials, challenges, &linear_terms, 0);
+    K::compute_non_linear_terms::<_, Getter<N>>(polynomials, challenges, &mut result, 0);
+    result
+}
+
+/// The first Grumpkin (`y² = x³ - 17`) point with x coordinate at least `x`.
+pub(crate) fn grumpkin_point_from(mut x: Fr) -> (Fr, Fr) {
+    loop {
+        if let Some(y) = (x.square() * x - Fr::from(17u64)).sqrt() {
+            return (x, y);
+        }
+        x += Fr::one();
+    }
+}
+
+/// Runs kernel `K`'s Kate scalar update on random openings and checks it adds nothing: every selector is
+/// opened, so the batched opening already covers it with its own ν power.
+fn check_kate_scalars_are_untouched<K, N>()
+where
+    K: KernelBase<Keccak256, TestSettings, Fr, N>,
+    N: ArrayLength<Fr>,
+{
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut polynomials = PolyArray::default();
+    for index in 0..PolynomialIndex::MaxNumPolynomials as usize {
+        polynomials.0[index] = (Fr::rand(&mut rng), Fr::rand(&mut rng));
+    }
+    let mut challenges = challenges::<N>(7);
+    for element in challenges.elements.iter_mut() {
+        *element = Fr::rand(&mut rng);
+    }
+
+    let mut linear_terms = CoefficientArray::default();
+    K::compute_linear_terms::<_, Getter<N>>(&polynomials, &challenges, &mut linear_terms, Some(0));
+    let mut scalars = HashMap::from([("Q_M".to_string(), Fr::one())]);
+    K::update_kate_opening_scalars(&linear_terms, &mut scalars, &challenges);
+    assert_eq!(scalars, HashMap::from([("Q_M".to_string(), Fr::one())]));
+}
+
+#[test]
+fn kernels_leave_the_kate_scalars_alone() {
+    check_kate_scalars_are_untouched::<ArithmeticKernel<_, _, _>, U1>();
+    check_kate_scalars_are_untouched::<TurboArithmeticKernel<_, _, _>, U2>();
+    check_kate_scalars_are_untouched::<TurboFixedBaseKernel<_, _, _>, U7>();
+    check_kate_scalars_are_untouched::<TurboLogicKernel<_, _, _>, U4>();
+    check_kate_scalars_are_untouched::<TurboRangeKernel<_, _, _>, U4>();
+    check_kate_scalars_are_untouched::<UltraArithmeticKernel<_, _, _>, U2>();
+    check_kate_scalars_are_untouched::<UltraAuxKernel<_, _, _>, U4>();
+    check_kate_scalars_are_untouched::<UltraEllipticKernel<_, _, _>, U2>();
+    check_kate_scalars_are_untouched::<UltraSortKernel<_, _, _>, U4>();
+}
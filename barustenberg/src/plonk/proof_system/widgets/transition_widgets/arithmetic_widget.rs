@@ -1,14 +1,11 @@
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use ark_ff::Field;
 use typenum::U1;
 
 use crate::{
-    plonk::proof_system::{
-        proving_key::ProvingKey,
-        types::{
-            polynomial_manifest::{EvaluationType, PolynomialIndex},
-            prover_settings::Settings,
-        },
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
     },
     transcript::BarretenHasher,
 };
@@ -16,13 +13,12 @@ use crate::{
 use std::{
     collections::{HashMap, HashSet},
     marker::PhantomData,
-    sync::Arc,
 };
 
 use super::{
     containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
-    getters::BaseGetter,
-    transition_widget::KernelBase,
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
 };
 
 pub(crate) struct ArithmeticKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
@@ -36,11 +32,6 @@ where
     // TODO see all these U1s they should be a named variable but they are not :( inherent associate type problem
     pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
     pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
-
-    pub(crate) fn get_required_polynomial_ids() -> &'static HashSet<PolynomialIndex> {
-        // ...
-        todo!("ArithmeticKernel::get_required_polynomial_ids")
-    }
 }
 
 impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U1>
@@ -60,12 +51,14 @@ impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U1>
         ])
     }
 
+    #[inline]
     fn quotient_required_challenges() -> u8 {
-        todo!()
+        Self::QUOTIENT_REQUIRED_CHALLENGES
     }
 
+    #[inline]
     fn update_required_challenges() -> u8 {
-        todo!()
+        Self::UPDATE_REQUIRED_CHALLENGES
     }
 
     #[inline]
@@ -101,98 +94,156 @@ impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U1>
         linear_terms[3.into()] = w_3;
     }
 
+    /// Multiplies the linear terms by their selectors, adds q_c and scales the sum by α.
+    #[inline]
     fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U1>>(
-        _polynomials: &PC,
-        _challenges: &ChallengeArray<F, U1>,
-        _linear_terms: &CoefficientArray<F>,
-        _index: usize,
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U1>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
     ) -> F {
-        /*
-                /**
-         * @brief Scale and sum the linear terms for the final equation.
-         *
-         * @details Multiplies the linear terms by selector values and scale the whole sum by alpha before returning
-         *
-         * @param polynomials Container with polynomials or their simulation
-         * @param challenges A structure with various challenges
-         * @param linear_terms Precomuputed linear terms to be scaled and summed
-         * @param i The index at which selector/witness values are sampled
-         * @return Field Scaled sum of values
-         */
-        inline static Field sum_linear_terms(PolyContainer& polynomials,
-                                             const challenge_array& challenges,
-                                             coefficient_array& linear_terms,
-                                             const size_t i = 0)
-        {
-            const Field& alpha = challenges.alpha_powers[0];
-            const Field& q_1 =
-                Getters::template get_value<EvaluationType::NON_SHIFTED, PolynomialIndex::Q_1>(polynomials, i);
-            const Field& q_2 =
-                Getters::template get_value<EvaluationType::NON_SHIFTED, PolynomialIndex::Q_2>(polynomials, i);
-            const Field& q_3 =
-                Getters::template get_value<EvaluationType::NON_SHIFTED, PolynomialIndex::Q_3>(polynomials, i);
-            const Field& q_m =
-                Getters::template get_value<EvaluationType::NON_SHIFTED, PolynomialIndex::Q_M>(polynomials, i);
-            const Field& q_c =
-                Getters::template get_value<EvaluationType::NON_SHIFTED, PolynomialIndex::Q_C>(polynomials, i);
-
-            Field result = linear_terms[0] * q_m;
-            result += (linear_terms[1] * q_1);
-            result += (linear_terms[2] * q_2);
-            result += (linear_terms[3] * q_3);
-            result += q_c;
-            result *= alpha;
-            return result;
-        }
-             */
-        todo!()
+        let alpha = challenges.alpha_powers[0];
+        let index = Some(index);
+        let q_1 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::Q1,
+            index,
+        );
+        let q_2 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::Q2,
+            index,
+        );
+        let q_3 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::Q3,
+            index,
+        );
+        let q_m = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QM,
+            index,
+        );
+        let q_c = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QC,
+            index,
+        );
+
+        let mut result = linear_terms[0.into()] * q_m;
+        result += linear_terms[1.into()] * q_1;
+        result += linear_terms[2.into()] * q_2;
+        result += linear_terms[3.into()] * q_3;
+        result += q_c;
+        result * alpha
     }
 
-    /// Not being used in arithmetic_widget because there are none
+    /// The standard arithmetic relation is linear in the selectors, so there is nothing to add here.
+    #[inline]
     fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U1>>(
         _polynomials: &PC,
         _challenges: &ChallengeArray<F, U1>,
         _quotient_term: &mut F,
         _index: usize,
     ) {
-        unimplemented!(
-            "ArithmeticKernel::compute_non_linear_terms- there are no non-linear terms..."
-        )
     }
 
-    /// Compute the scaled values of openings
-    ///
-    /// # Arguments
-    /// - `linear_terms` - The original computed linear terms of the product and wires
-    /// - `scalars` - A map where we put the values
-    /// - `challenges` - Challenges where we get the alpha
+    /// Adds nothing: every selector is opened, so the batched opening already covers them with their
+    /// own ν powers.
     fn update_kate_opening_scalars(
-        linear_terms: &CoefficientArray<F>,
-        scalars: &mut HashMap<String, F>,
-        challenges: &ChallengeArray<F, U1>,
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U1>,
     ) {
-        let alpha: F = challenges.alpha_powers[0];
-        scalars.insert(
-            "Q_M".to_string(),
-            *scalars.get("Q_M").unwrap() + linear_terms[0.into()] * alpha,
-        );
-        scalars.insert(
-            "Q_1".to_string(),
-            *scalars.get("Q_1").unwrap() + linear_terms[1.into()] * alpha,
-        );
-        scalars.insert(
-            "Q_2".to_string(),
-            *scalars.get("Q_2").unwrap() + linear_terms[2.into()] * alpha,
+    }
+}
+
+/// Computes the contribution of the standard arithmetic gate
+/// `q_m·w_1·w_2 + q_1·w_1 + q_2·w_2 + q_3·w_3 + q_c` to the quotient polynomial over the 4n coset.
+pub(crate) type ProverArithmeticWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U1, ArithmeticKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the standard arithmetic gate at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierArithmeticWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U1>,
+        U1,
+        S,
+        ArithmeticKernel<H, C::Fr, S>,
+    > for VerifierArithmeticWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    type Kernel = ArithmeticKernel<Keccak256, Fr, TestSettings>;
+
+    /// Openings for the gate `q_m·w_1·w_2 + q_1·w_1 + q_2·w_2 - q_3·w_3 + q_c`, so the output
+    /// selector can be given as a positive value.
+    fn openings(selectors: [u64; 5], wires: [u64; 3]) -> PolyArray<Fr> {
+        let mut polynomials = PolyArray::default();
+        let [q_m, q_1, q_2, q_3, q_c] = selectors;
+        polynomials[PolynomialIndex::QM].0 = Fr::from(q_m);
+        polynomials[PolynomialIndex::Q1].0 = Fr::from(q_1);
+        polynomials[PolynomialIndex::Q2].0 = Fr::from(q_2);
+        polynomials[PolynomialIndex::Q3].0 = -Fr::from(q_3);
+        polynomials[PolynomialIndex::QC].0 = Fr::from(q_c);
+        polynomials[PolynomialIndex::W1].0 = Fr::from(wires[0]);
+        polynomials[PolynomialIndex::W2].0 = Fr::from(wires[1]);
+        polynomials[PolynomialIndex::W3].0 = Fr::from(wires[2]);
+        polynomials
+    }
+
+    #[test]
+    fn satisfied_gates_vanish() {
+        let challenges = challenges(7);
+        // 3·5 = 15
+        assert!(
+            evaluate::<Kernel, _>(&openings([1, 0, 0, 1, 0], [3, 5, 15]), &challenges).is_zero()
         );
-        scalars.insert(
-            "Q_3".to_string(),
-            *scalars.get("Q_3").unwrap() + linear_terms[3.into()] * alpha,
+        // 3 + 5 + 2 = 10
+        assert!(
+            evaluate::<Kernel, _>(&openings([0, 1, 1, 1, 2], [3, 5, 10]), &challenges).is_zero()
         );
-        scalars.insert("Q_C".to_string(), *scalars.get("Q_C").unwrap() + alpha);
     }
-}
 
-pub(crate) struct ProverArithmeticWidget<'a, Fr: Field + FftField, G1Affine: AffineRepr, H, S> {
-    key: Arc<ProvingKey<'a, Fr, G1Affine>>,
-    phantom: PhantomData<(H, S)>,
+    #[test]
+    fn unsatisfied_gates_are_scaled_by_alpha() {
+        // 3·5 ≠ 16, off by one
+        let polynomials = openings([1, 0, 0, 1, 0], [3, 5, 16]);
+        let mut challenges = challenges(7);
+        assert_eq!(evaluate::<Kernel, _>(&polynomials, &challenges), -Fr::one());
+        challenges.alpha_powers[0] = Fr::from(7u64);
+        assert_eq!(
+            evaluate::<Kernel, _>(&polynomials, &challenges),
+            -Fr::from(7u64)
+        );
+    }
 }
@@ -1,9 +1,8 @@
 use generic_array::GenericArray;
 use once_cell::sync::Lazy;
 use std::{
-    cell::RefCell,
     ops::{Index, IndexMut},
-    rc::Rc,
+    sync::{Arc, RwLock},
 };
 use typenum::Unsigned;
 
@@ -75,7 +74,7 @@ impl<F: Field> Default for PolyArray<F> {
 impl<F: Field> PolyContainer<F> for PolyArray<F> {}
 
 pub(crate) struct PolyPtrMap<F: Field> {
-    pub(crate) coefficients: HashMap<PolynomialIndex, Rc<RefCell<Polynomial<F>>>>,
+    pub(crate) coefficients: HashMap<PolynomialIndex, Arc<RwLock<Polynomial<F>>>>,
     pub(crate) block_mask: usize,
     pub(crate) index_shift: usize,
 }
@@ -93,7 +92,7 @@ impl<F: Field> PolyPtrMap<F> {
 impl<F: Field> PolyContainer<F> for PolyPtrMap<F> {}
 
 impl<F: Field> Index<PolynomialIndex> for PolyPtrMap<F> {
-    type Output = Rc<RefCell<Polynomial<F>>>;
+    type Output = Arc<RwLock<Polynomial<F>>>;
     fn index(&self, index: PolynomialIndex) -> &Self::Output {
         &self.coefficients[&index]
     }
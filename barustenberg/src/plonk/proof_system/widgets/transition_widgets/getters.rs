@@ -8,10 +8,10 @@
 
 use std::{collections::HashSet, marker::PhantomData};
 
-use ark_ec::AffineRepr;
 use ark_ff::{FftField, Field};
 
 use crate::{
+    ecc::curves::CurveConfig,
     plonk::proof_system::{
         proving_key::ProvingKey,
         types::{
@@ -53,11 +53,11 @@ pub(crate) trait BaseGetter<
     ///
     /// # Returns
     /// A structure with an array of challenge values and powers of α
-    fn get_challenges<G1Affine: AffineRepr>(
-        transcript: &Transcript<H, F, G1Affine>,
+    fn get_challenges<C: CurveConfig<Fr = F>>(
+        transcript: &Transcript<H, C>,
         alpha_base: F,
         required_challenges: u8,
-        rng: &mut Box<dyn rand::RngCore>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
     ) -> ChallengeArray<F, NWidgetRelations> {
         let mut result: ChallengeArray<F, _> = ChallengeArray::default();
         let mut add_challenge = |label: &str, tag: usize, required: bool, index: usize| {
@@ -66,11 +66,8 @@ pub(crate) trait BaseGetter<
                 assert!(index < transcript.get_num_challenges(label));
                 result.elements[tag] = transcript.get_challenge_field_element(label, Some(index));
             } else {
-                let mut random_bytes = vec![0u8; std::mem::size_of::<F>()];
-                // TODO should you really have an unwrap here?
-                rng.fill_bytes(&mut random_bytes);
-                result.elements[tag] = F::from_random_bytes(random_bytes.as_ref())
-                    .expect("random deserialization didn't work");
+                // a challenge the transcript doesn't derive, such as eta for the standard composer
+                result.elements[tag] = F::rand(rng);
             }
         };
         add_challenge(
@@ -146,16 +143,16 @@ where
     S: Settings<H>,
     NWidgetRelations: generic_array::ArrayLength<F>,
 {
+    /// Openings are single values, so the row `index` kernels pass along is ignored.
     fn get_value(
         polynomials: &PolyArray<F>,
         evaluation_type: EvaluationType,
         id: PolynomialIndex,
-        index: Option<usize>,
+        _index: Option<usize>,
     ) -> F {
-        assert!(index.is_none());
         match evaluation_type {
-            EvaluationType::NonShifted => polynomials[id].1,
-            EvaluationType::Shifted => polynomials[id].0,
+            EvaluationType::NonShifted => polynomials[id].0,
+            EvaluationType::Shifted => polynomials[id].1,
         }
     }
 }
@@ -228,15 +225,14 @@ pub(crate) trait EvaluationGetter<
     /// # Returns
     ///
     /// `PolyArray`
-    fn get_polynomial_evaluations<G1Affine: AffineRepr>(
+    fn get_polynomial_evaluations<C: CurveConfig<Fr = F>>(
         polynomial_manifest: &PolynomialManifest,
-        transcript: &Transcript<H, F, G1Affine>,
+        transcript: &Transcript<H, C>,
     ) -> PolyArray<F> {
         let mut result: PolyArray<F> = Default::default();
-        for i in 0..polynomial_manifest.len() {
-            let info = &polynomial_manifest[i.into()];
+        for info in polynomial_manifest.iter() {
             let label = info.polynomial_label.clone();
-            result[i.into()].0 = transcript.get_field_element(&label);
+            result[info.index].0 = transcript.get_field_element(&label);
 
             if info.requires_shifted_evaluation {
                 result[info.index].1 = transcript.get_field_element(&(label + "_omega"));
@@ -248,52 +244,49 @@ pub(crate) trait EvaluationGetter<
     }
 }
 
-pub(crate) struct FFTGetterImpl<H, F, G1Affine, S, NWidgetRelations>
+pub(crate) struct FFTGetterImpl<H, C, S, NWidgetRelations>
 where
-    F: Field + FftField,
+    C: CurveConfig,
     H: BarretenHasher,
     S: Settings<H>,
-    G1Affine: AffineRepr,
-    NWidgetRelations: generic_array::ArrayLength<F>,
+    NWidgetRelations: generic_array::ArrayLength<C::Fr>,
 {
-    phantom: PhantomData<(F, H, S, G1Affine, NWidgetRelations)>,
+    phantom: PhantomData<(C, H, S, NWidgetRelations)>,
 }
 
-impl<H, F, G1Affine, S, NWidgetRelations> BaseGetter<H, F, S, PolyPtrMap<F>, NWidgetRelations>
-    for FFTGetterImpl<H, F, G1Affine, S, NWidgetRelations>
+impl<H, C, S, NWidgetRelations> BaseGetter<H, C::Fr, S, PolyPtrMap<C::Fr>, NWidgetRelations>
+    for FFTGetterImpl<H, C, S, NWidgetRelations>
 where
-    F: Field + FftField,
+    C: CurveConfig,
     H: BarretenHasher,
     S: Settings<H>,
-    G1Affine: AffineRepr,
-    NWidgetRelations: generic_array::ArrayLength<F>,
+    NWidgetRelations: generic_array::ArrayLength<C::Fr>,
 {
     fn get_value(
-        polynomials: &PolyPtrMap<F>,
+        polynomials: &PolyPtrMap<C::Fr>,
         evaluation_type: EvaluationType,
         id: PolynomialIndex,
         index: Option<usize>,
-    ) -> F {
+    ) -> C::Fr {
         // TODO ew
         let index = index.unwrap();
         let poly = &polynomials.coefficients.get(&id).unwrap();
         if evaluation_type == EvaluationType::Shifted {
             let shifted_index = (index + polynomials.index_shift) & polynomials.block_mask;
-            poly.borrow()[shifted_index]
+            poly.read().unwrap()[shifted_index]
         } else {
-            poly.borrow()[index]
+            poly.read().unwrap()[index]
         }
     }
 }
 
-impl<'a, H, F, G1Affine, S, NWidgetRelations> FFTGetter<'a, H, F, G1Affine, S, NWidgetRelations>
-    for FFTGetterImpl<H, F, G1Affine, S, NWidgetRelations>
+impl<'a, H, C, S, NWidgetRelations> FFTGetter<'a, H, C, S, NWidgetRelations>
+    for FFTGetterImpl<H, C, S, NWidgetRelations>
 where
-    F: Field + FftField,
+    C: CurveConfig,
     H: BarretenHasher,
     S: Settings<H>,
-    G1Affine: AffineRepr,
-    NWidgetRelations: generic_array::ArrayLength<F>,
+    NWidgetRelations: generic_array::ArrayLength<C::Fr>,
 {
 }
 
@@ -302,19 +295,17 @@ where
 pub(crate) trait FFTGetter<
     'a,
     H,
-    F,
-    G1Affine: AffineRepr,
+    C: CurveConfig,
     S,
-    NWidgetRelations: generic_array::ArrayLength<F>,
->: BaseGetter<H, F, S, PolyPtrMap<F>, NWidgetRelations> where
-    F: Field + FftField,
+    NWidgetRelations: generic_array::ArrayLength<C::Fr>,
+>: BaseGetter<H, C::Fr, S, PolyPtrMap<C::Fr>, NWidgetRelations> where
     H: BarretenHasher,
     S: Settings<H>,
 {
     fn get_polynomials(
-        key: &ProvingKey<'a, F, G1Affine>,
+        key: &ProvingKey<'a, C>,
         required_polynomial_ids: &HashSet<PolynomialIndex>,
-    ) -> PolyPtrMap<F> {
+    ) -> PolyPtrMap<C::Fr> {
         let mut result = PolyPtrMap::new();
         let label_suffix = "_fft";
 
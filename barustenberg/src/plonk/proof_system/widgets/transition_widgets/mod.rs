@@ -2,3 +2,11 @@ pub(crate) mod arithmetic_widget;
 pub(crate) mod containers;
 pub(crate) mod getters;
 pub(crate) mod transition_widget;
+pub(crate) mod turbo_arithmetic_widget;
+pub(crate) mod turbo_fixed_base_widget;
+pub(crate) mod turbo_logic_widget;
+pub(crate) mod turbo_range_widget;
+pub(crate) mod ultra_arithmetic_widget;
+pub(crate) mod ultra_aux_widget;
+pub(crate) mod ultra_elliptic_widget;
+pub(crate) mod ultra_sort_widget;
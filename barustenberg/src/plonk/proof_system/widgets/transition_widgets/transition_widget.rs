@@ -1,15 +1,14 @@
 use std::{
     collections::{HashMap, HashSet},
     marker::PhantomData,
-    rc::Rc,
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 
-use ark_bn254::G1Affine;
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
+use ark_ff::Field;
 
 use crate::{
+    common::thread::parallel_for_chunks_mut,
+    ecc::curves::CurveConfig,
     plonk::proof_system::{
         proving_key::ProvingKey,
         types::{polynomial_manifest::PolynomialIndex, prover_settings::Settings},
@@ -20,7 +19,7 @@ use crate::{
 
 use super::{
     containers::{ChallengeArray, CoefficientArray, PolyArray, PolyContainer, PolyPtrMap},
-    getters::{BaseGetter, EvaluationGetter, EvaluationGetterImpl, FFTGetter, FFTGetterImpl},
+    getters::{BaseGetter, EvaluationGetter, FFTGetter, FFTGetterImpl},
 };
 
 pub(crate) trait KernelBase<
@@ -64,123 +63,149 @@ pub(crate) trait KernelBase<
     );
 }
 
-pub(crate) trait TransitionWidgetBase<
-    'a,
-    H: BarretenHasher,
-    F: Field + FftField,
-    G1Affine: AffineRepr,
->
+pub(crate) trait TransitionWidgetBase<'a, H: BarretenHasher, C: CurveConfig>:
+    Send + Sync
 {
     fn compute_quotient_contribution(
         &self,
-        alpha_base: F,
-        transcript: &Transcript<H, F, G1Affine>,
-        rng: &mut Box<dyn rand::RngCore>,
-    ) -> F;
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> C::Fr;
 }
 
 pub(crate) struct TransitionWidget<
     'a,
     H: BarretenHasher,
-    F: Field + FftField,
-    G1Affine: AffineRepr,
+    C: CurveConfig,
     S: Settings<H>,
     NIndependentRelations,
     KB,
 > where
-    NIndependentRelations: generic_array::ArrayLength<F>,
-    KB: KernelBase<H, S, F, NIndependentRelations>,
+    NIndependentRelations: generic_array::ArrayLength<C::Fr>,
+    KB: KernelBase<H, S, C::Fr, NIndependentRelations>,
+{
+    key: Arc<RwLock<ProvingKey<'a, C>>>,
+    phantom: PhantomData<fn() -> (H, C, S, NIndependentRelations, KB)>,
+}
+
+impl<'a, H, C, S, NIndependentRelations, KB>
+    TransitionWidget<'a, H, C, S, NIndependentRelations, KB>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+    NIndependentRelations: generic_array::ArrayLength<C::Fr>,
+    KB: KernelBase<H, S, C::Fr, NIndependentRelations>,
 {
-    key: Rc<ProvingKey<'a, F, G1Affine>>,
-    phantom: PhantomData<(H, F, S, NIndependentRelations, KB)>,
+    pub(crate) fn new(key: Arc<RwLock<ProvingKey<'a, C>>>) -> Self {
+        Self {
+            key,
+            phantom: PhantomData,
+        }
+    }
 }
 impl<
         'a,
         H: BarretenHasher,
-        F: Field + FftField,
-        G1Affine: AffineRepr,
+        C: CurveConfig,
         S: Settings<H>,
-        NIndependentRelations: generic_array::ArrayLength<F>,
+        NIndependentRelations: generic_array::ArrayLength<C::Fr>,
         KB,
-    > TransitionWidgetBase<'a, H, F, G1Affine>
-    for TransitionWidget<'a, H, F, G1Affine, S, NIndependentRelations, KB>
+    > TransitionWidgetBase<'a, H, C> for TransitionWidget<'a, H, C, S, NIndependentRelations, KB>
 where
-    KB: KernelBase<H, S, F, NIndependentRelations>,
+    KB: KernelBase<H, S, C::Fr, NIndependentRelations>,
 {
     // other methods and trait implementations
     fn compute_quotient_contribution(
         &self,
-        alpha_base: F,
-        transcript: &Transcript<H, F, G1Affine>,
-        rng: &mut Box<dyn rand::RngCore>,
-    ) -> F {
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> C::Fr {
+        let key = self.key.read().unwrap();
         let required_polynomial_ids = KB::get_required_polynomial_ids();
-        let polynomials =
-            FFTGetterImpl::<H, F, G1Affine, S, NIndependentRelations>::get_polynomials(
-                &self.key,
-                &required_polynomial_ids,
-            );
+        let polynomials = FFTGetterImpl::<H, C, S, NIndependentRelations>::get_polynomials(
+            &key,
+            &required_polynomial_ids,
+        );
 
-        let challenges =
-            FFTGetterImpl::<H, F, G1Affine, S, NIndependentRelations>::get_challenges::<G1Affine>(
-                transcript,
-                alpha_base,
-                KB::quotient_required_challenges(),
-                rng,
-            );
+        let challenges = FFTGetterImpl::<H, C, S, NIndependentRelations>::get_challenges(
+            transcript,
+            alpha_base,
+            KB::quotient_required_challenges(),
+            rng,
+        );
+
+        // the quotient is split into parts of size n, so we walk the large domain one part at a time and
+        // split each part up across threads.
+        let circuit_size = key.circuit_size;
+        let num_parts = key.large_domain.size >> key.small_domain.log2_size;
+        let chunk_size = key.small_domain.thread_size;
+        for (part_idx, quotient_part) in key
+            .quotient_polynomial_parts
+            .iter()
+            .take(num_parts)
+            .enumerate()
+        {
+            let mut quotient_part = quotient_part.write().unwrap();
+            parallel_for_chunks_mut(
+                &mut quotient_part.coefficients[..circuit_size],
+                chunk_size,
+                |j, chunk| {
+                    let offset = part_idx * circuit_size + j * chunk_size;
+                    for (k, quotient_term) in chunk.iter_mut().enumerate() {
+                        let i = offset + k;
+                        let mut linear_terms = CoefficientArray::default();
+                        KB::compute_linear_terms::<
+                            PolyPtrMap<C::Fr>,
+                            FFTGetterImpl<H, C, S, NIndependentRelations>,
+                        >(
+                            &polynomials, &challenges, &mut linear_terms, Some(i)
+                        );
+                        let sum_of_linear_terms = KB::sum_linear_terms::<
+                            PolyPtrMap<C::Fr>,
+                            FFTGetterImpl<H, C, S, NIndependentRelations>,
+                        >(
+                            &polynomials, &challenges, &linear_terms, i
+                        );
 
-        let mut quotient_term;
-
-        // TODO: hidden missing multithreading here
-        for i in 0..self.key.large_domain.size {
-            let mut linear_terms = CoefficientArray::default();
-            KB::compute_linear_terms::<
-                PolyPtrMap<F>,
-                FFTGetterImpl<H, F, G1Affine, S, NIndependentRelations>,
-            >(&polynomials, &challenges, &mut linear_terms, Some(i));
-            let sum_of_linear_terms = KB::sum_linear_terms::<
-                PolyPtrMap<F>,
-                FFTGetterImpl<H, F, G1Affine, S, NIndependentRelations>,
-            >(&polynomials, &challenges, &linear_terms, i);
-
-            quotient_term = self.key.quotient_polynomial_parts
-                [i >> self.key.small_domain.log2_size]
-                .borrow()[i & (self.key.circuit_size - 1)];
-            quotient_term += sum_of_linear_terms;
-            KB::compute_non_linear_terms::<
-                PolyPtrMap<F>,
-                FFTGetterImpl<H, F, G1Affine, S, NIndependentRelations>,
-            >(&polynomials, &challenges, &mut quotient_term, i);
+                        *quotient_term += sum_of_linear_terms;
+                        KB::compute_non_linear_terms::<
+                            PolyPtrMap<C::Fr>,
+                            FFTGetterImpl<H, C, S, NIndependentRelations>,
+                        >(&polynomials, &challenges, quotient_term, i);
+                    }
+                },
+            );
         }
 
-        FFTGetterImpl::<H, F, G1Affine, S, NIndependentRelations>::update_alpha(&challenges)
+        FFTGetterImpl::<H, C, S, NIndependentRelations>::update_alpha(&challenges)
     }
 }
 
 pub(crate) trait GenericVerifierWidget<
     'a,
-    F: Field + FftField,
+    C: CurveConfig,
     H: BarretenHasher,
-    G: EvaluationGetter<H, F, S, NIndependentRelations>,
+    G: EvaluationGetter<H, C::Fr, S, NIndependentRelations>,
     NIndependentRelations,
     S: Settings<H>,
     KB,
 > where
-    NIndependentRelations: generic_array::ArrayLength<F>,
-    KB: KernelBase<H, S, F, NIndependentRelations>,
+    NIndependentRelations: generic_array::ArrayLength<C::Fr>,
+    KB: KernelBase<H, S, C::Fr, NIndependentRelations>,
 {
-    fn compute_quotient_evaluation_contribution<G1Affine: AffineRepr>(
-        key: &Arc<VerificationKey<'a, F>>,
-        alpha_base: F,
-        transcript: &Transcript<H, F, G1Affine>,
-        quotient_numerator_eval: &mut F,
-        rng: &mut Box<dyn rand::RngCore>,
-    ) -> F {
-        let polynomial_evaluations = G::get_polynomial_evaluations::<G1Affine>(
-            &key.as_ref().polynomial_manifest,
-            transcript,
-        );
-        let challenges = G::get_challenges::<G1Affine>(
+    fn compute_quotient_evaluation_contribution(
+        key: &Arc<VerificationKey<'a, C>>,
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+        quotient_numerator_eval: &mut C::Fr,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> C::Fr {
+        let polynomial_evaluations =
+            G::get_polynomial_evaluations(&key.as_ref().polynomial_manifest, transcript);
+        let challenges = G::get_challenges(
             transcript,
             alpha_base,
             KB::quotient_required_challenges(),
@@ -188,24 +213,19 @@ pub(crate) trait GenericVerifierWidget<
         );
 
         let mut linear_terms = CoefficientArray::default();
-        KB::compute_linear_terms::<
-            PolyArray<F>,
-            EvaluationGetterImpl<H, F, S, NIndependentRelations>,
-        >(
+        KB::compute_linear_terms::<PolyArray<C::Fr>, G>(
             &polynomial_evaluations,
             &challenges,
             &mut linear_terms,
             Some(0),
         );
-        *quotient_numerator_eval +=
-            KB::sum_linear_terms::<
-                PolyArray<F>,
-                EvaluationGetterImpl<H, F, S, NIndependentRelations>,
-            >(&polynomial_evaluations, &challenges, &linear_terms, 0);
-        KB::compute_non_linear_terms::<
-            PolyArray<F>,
-            EvaluationGetterImpl<H, F, S, NIndependentRelations>,
-        >(
+        *quotient_numerator_eval += KB::sum_linear_terms::<PolyArray<C::Fr>, G>(
+            &polynomial_evaluations,
+            &challenges,
+            &linear_terms,
+            0,
+        );
+        KB::compute_non_linear_terms::<PolyArray<C::Fr>, G>(
             &polynomial_evaluations,
             &challenges,
             quotient_numerator_eval,
@@ -215,20 +235,33 @@ pub(crate) trait GenericVerifierWidget<
         G::update_alpha(&challenges)
     }
 
+    /// Adds the scalars this widget contributes to commitments in the batched Kate opening, computed
+    /// from the openings in the transcript, beyond the ν powers every opened polynomial already gets.
     fn append_scalar_multiplication_inputs(
-        _key: &Arc<VerificationKey<'_, F>>,
-        alpha_base: F,
-        transcript: &Transcript<H, F, G1Affine>,
-        _scalar_mult_inputs: &mut HashMap<String, F>,
-        rng: &mut Box<dyn rand::RngCore>,
-    ) -> F {
-        let challenges = G::get_challenges::<G1Affine>(
+        key: &Arc<VerificationKey<'a, C>>,
+        alpha_base: C::Fr,
+        transcript: &Transcript<H, C>,
+        scalar_mult_inputs: &mut HashMap<String, C::Fr>,
+        rng: &mut Box<dyn rand::RngCore + Send>,
+    ) -> C::Fr {
+        let polynomial_evaluations =
+            G::get_polynomial_evaluations(&key.as_ref().polynomial_manifest, transcript);
+        let challenges = G::get_challenges(
             transcript,
             alpha_base,
             KB::quotient_required_challenges() | KB::update_required_challenges(),
             rng,
         );
 
+        let mut linear_terms = CoefficientArray::default();
+        KB::compute_linear_terms::<PolyArray<C::Fr>, G>(
+            &polynomial_evaluations,
+            &challenges,
+            &mut linear_terms,
+            Some(0),
+        );
+        KB::update_kate_opening_scalars(&linear_terms, scalar_mult_inputs, &challenges);
+
         G::update_alpha(&challenges)
     }
 }
@@ -0,0 +1,262 @@
+use ark_ff::Field;
+use typenum::U2;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+};
+
+/// The width-4 arithmetic gate, switched on by q_arith:
+/// - `q_arith·(q_m·w_1·w_2 + q_1·w_1 + q_2·w_2 + q_3·w_3 + q_4·w_4 + q_c)`
+/// - `q_arith·q_5·(δ·(δ - 1)·(2δ - 7) + 6·w_1)` with `δ = w_3 - 4·w_4`
+///
+/// The second relation extracts the high bit of the quad δ into w_1, since `-δ·(δ - 1)·(2δ - 7) / 6`
+/// maps 0, 1, 2, 3 to 0, 0, 1, 1. It only says something when δ is known to be a quad, e.g. because the
+/// same wires are range constrained.
+pub(crate) struct TurboArithmeticKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> TurboArithmeticKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    const SELECTORS: [PolynomialIndex; 7] = [
+        PolynomialIndex::QM,
+        PolynomialIndex::Q1,
+        PolynomialIndex::Q2,
+        PolynomialIndex::Q3,
+        PolynomialIndex::Q4,
+        PolynomialIndex::QC,
+        PolynomialIndex::Q5,
+    ];
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U2>
+    for TurboArithmeticKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::Q1,
+            PolynomialIndex::Q2,
+            PolynomialIndex::Q3,
+            PolynomialIndex::Q4,
+            PolynomialIndex::Q5,
+            PolynomialIndex::QM,
+            PolynomialIndex::QC,
+            PolynomialIndex::QArithmetic,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Computes the coefficient of each selector, already scaled by q_arith and the relation's power of α.
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U2>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let index = Some(index.unwrap_or_default());
+        let w_1 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W1,
+            index,
+        );
+        let w_2 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W2,
+            index,
+        );
+        let w_3 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W3,
+            index,
+        );
+        let w_4 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W4,
+            index,
+        );
+        let q_arith = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QArithmetic,
+            index,
+        );
+
+        let delta = w_3 - w_4 * F::from(4u64);
+        let bit_extraction =
+            (delta.square() - delta) * (delta.double() - F::from(7u64)) + w_1 * F::from(6u64);
+
+        let scale = q_arith * challenges.alpha_powers[0];
+        linear_terms[0.into()] = w_1 * w_2 * scale;
+        linear_terms[1.into()] = w_1 * scale;
+        linear_terms[2.into()] = w_2 * scale;
+        linear_terms[3.into()] = w_3 * scale;
+        linear_terms[4.into()] = w_4 * scale;
+        linear_terms[5.into()] = scale;
+        linear_terms[6.into()] = bit_extraction * q_arith * challenges.alpha_powers[1];
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U2>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let mut result = F::zero();
+        for (i, selector) in Self::SELECTORS.into_iter().enumerate() {
+            let q_i = G::get_value(
+                polynomials,
+                EvaluationType::NonShifted,
+                selector,
+                Some(index),
+            );
+            result += linear_terms[i.into()] * q_i;
+        }
+        result
+    }
+
+    /// Both relations are linear in the selectors once q_arith is folded in, so there is nothing to add here.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U2>,
+        _quotient_term: &mut F,
+        _index: usize,
+    ) {
+    }
+
+    /// The selectors are opened, so the batched opening already covers them with their own ν powers.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U2>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the width-4 arithmetic gate to the quotient polynomial over the 4n coset.
+pub(crate) type ProverTurboArithmeticWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U2, TurboArithmeticKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the width-4 arithmetic gate at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierTurboArithmeticWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U2>,
+        U2,
+        S,
+        TurboArithmeticKernel<H, C::Fr, S>,
+    > for VerifierTurboArithmeticWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    type Kernel = TurboArithmeticKernel<Keccak256, Fr, TestSettings>;
+
+    /// Openings for a gate with selectors `[q_m, q_1, q_2, q_3, q_4, q_c, q_5]` and q_arith = 1. The
+    /// selectors are given as field elements so they can be negative.
+    fn openings(selectors: [Fr; 7], wires: [u64; 4]) -> PolyArray<Fr> {
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QArithmetic].0 = Fr::one();
+        for (selector, value) in Kernel::SELECTORS.into_iter().zip(selectors) {
+            polynomials[selector].0 = value;
+        }
+        polynomials[PolynomialIndex::W1].0 = Fr::from(wires[0]);
+        polynomials[PolynomialIndex::W2].0 = Fr::from(wires[1]);
+        polynomials[PolynomialIndex::W3].0 = Fr::from(wires[2]);
+        polynomials[PolynomialIndex::W4].0 = Fr::from(wires[3]);
+        polynomials
+    }
+
+    #[test]
+    fn big_add_and_mul_gates_vanish() {
+        let (one, zero) = (Fr::one(), Fr::zero());
+        let challenges = challenges(7);
+        // 2 + 3 + 5 - 10 = 0
+        let big_add = openings([zero, one, one, one, -one, zero, zero], [2, 3, 5, 10]);
+        assert!(evaluate::<Kernel, _>(&big_add, &challenges).is_zero());
+        // 3·4 + 1 - 13 = 0
+        let mul_add = openings([one, zero, zero, -one, zero, one, zero], [3, 4, 13, 0]);
+        assert!(evaluate::<Kernel, _>(&mul_add, &challenges).is_zero());
+
+        let wrong = openings([one, zero, zero, -one, zero, one, zero], [3, 4, 14, 0]);
+        assert_eq!(evaluate::<Kernel, _>(&wrong, &challenges), -one);
+    }
+
+    #[test]
+    fn bit_extraction_takes_the_high_bit_of_the_quad() {
+        let zero = Fr::zero();
+        let q_5 = [zero, zero, zero, zero, zero, zero, Fr::one()];
+        let challenges = challenges(7);
+        for quad in 0..4 {
+            // w_3 = 4·w_4 + quad, with the quad's high bit in w_1
+            let high_bit = quad >> 1;
+            let gate = openings(q_5, [high_bit, 0, 4 * 5 + quad, 5]);
+            assert!(evaluate::<Kernel, _>(&gate, &challenges).is_zero());
+
+            let flipped = openings(q_5, [1 - high_bit, 0, 4 * 5 + quad, 5]);
+            assert!(!evaluate::<Kernel, _>(&flipped, &challenges).is_zero());
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use ark_ff::Field;
+use typenum::U7;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+};
+
+/// The `b` in Grumpkin's `y² = x³ + b`. Grumpkin's base field is our scalar field, so its points can be
+/// added inside the circuit.
+fn grumpkin_b<F: Field>() -> F {
+    -F::from(17u64)
+}
+
+/// One step of a fixed-base scalar multiplication ladder over Grumpkin. Each row holds the point `x_α`
+/// being added in w_1, the accumulated point `(x, y)` in w_2 and w_3 and the accumulated scalar in w_4; the
+/// next row holds the sums. The step's wNAF digit `δ = w_4(ωX) - 4·w_4(X)` must be ±1 or ±3, and the
+/// selectors encode the two ladder points `P` and `3P` of the row:
+/// - `x_α = q_1·δ² + q_2` picks the x coordinate by |δ|
+/// - `y_α = δ·(q_3·x_α + q_4)` picks the y coordinate and flips it for negative digits
+///
+/// The addition identities use `y_α² = x_α³ + b` so that every term stays within degree 5. The first row
+/// of a ladder is marked by q_5 and starts the accumulators at the scalar 1 and the point `(q_m, q_c)`.
+pub(crate) struct TurboFixedBaseKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> TurboFixedBaseKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    const SELECTORS: [PolynomialIndex; 6] = [
+        PolynomialIndex::Q1,
+        PolynomialIndex::Q2,
+        PolynomialIndex::Q3,
+        PolynomialIndex::Q4,
+        PolynomialIndex::QM,
+        PolynomialIndex::QC,
+    ];
+}
+
+/// The wire values of a ladder row that the fixed-base relations are built from.
+struct LadderStep<F: Field> {
+    x_alpha: F,
+    x_1: F,
+    y_1: F,
+    x_3: F,
+    y_3: F,
+    scalar: F,
+    delta: F,
+    q_ecc: F,
+    q_5: F,
+}
+
+impl<F: Field> LadderStep<F> {
+    fn new<H, S, PC, G>(polynomials: &PC, index: Option<usize>) -> Self
+    where
+        H: BarretenHasher,
+        S: Settings<H>,
+        PC: PolyContainer<F>,
+        G: BaseGetter<H, F, S, PC, U7>,
+    {
+        let get = |evaluation_type, id| G::get_value(polynomials, evaluation_type, id, index);
+        let scalar = get(EvaluationType::NonShifted, PolynomialIndex::W4);
+        let scalar_omega = get(EvaluationType::Shifted, PolynomialIndex::W4);
+        Self {
+            x_alpha: get(EvaluationType::NonShifted, PolynomialIndex::W1),
+            x_1: get(EvaluationType::NonShifted, PolynomialIndex::W2),
+            y_1: get(EvaluationType::NonShifted, PolynomialIndex::W3),
+            x_3: get(EvaluationType::Shifted, PolynomialIndex::W2),
+            y_3: get(EvaluationType::Shifted, PolynomialIndex::W3),
+            scalar,
+            delta: scalar_omega - scalar * F::from(4u64),
+            q_ecc: get(EvaluationType::NonShifted, PolynomialIndex::QFixedBase),
+            q_5: get(EvaluationType::NonShifted, PolynomialIndex::Q5),
+        }
+    }
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U7>
+    for TurboFixedBaseKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::Q1,
+            PolynomialIndex::Q2,
+            PolynomialIndex::Q3,
+            PolynomialIndex::Q4,
+            PolynomialIndex::Q5,
+            PolynomialIndex::QM,
+            PolynomialIndex::QC,
+            PolynomialIndex::QFixedBase,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Computes the coefficients of the selectors that enter the relations linearly: q_1 and q_2 through
+    /// `x_α`, q_3 and q_4 through `y_α`, and q_m and q_c through the ladder initialisation.
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U7>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U7>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let step = LadderStep::new::<H, S, PC, G>(polynomials, Some(index.unwrap_or_default()));
+        let alpha = &challenges.alpha_powers;
+
+        // y_α appears as 2·y_1·y_α in the x identity and as -y_α·(x_1 - x_3) in the y identity
+        let y_alpha_coefficient =
+            (step.y_1.double() * alpha[2] - (step.x_1 - step.x_3) * alpha[3]) * step.q_ecc;
+
+        linear_terms[0.into()] = step.delta.square() * step.q_ecc * alpha[1];
+        linear_terms[1.into()] = step.q_ecc * alpha[1];
+        linear_terms[2.into()] = y_alpha_coefficient * step.delta * step.x_alpha;
+        linear_terms[3.into()] = y_alpha_coefficient * step.delta;
+        linear_terms[4.into()] = -(step.q_5 * alpha[5]);
+        linear_terms[5.into()] = -(step.q_5 * alpha[6]);
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U7>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U7>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let mut result = F::zero();
+        for (i, selector) in Self::SELECTORS.into_iter().enumerate() {
+            let q_i = G::get_value(
+                polynomials,
+                EvaluationType::NonShifted,
+                selector,
+                Some(index),
+            );
+            result += linear_terms[i.into()] * q_i;
+        }
+        result
+    }
+
+    /// Adds everything that does not multiply one of the linearised selectors: the digit check, the rest
+    /// of the addition identities and the initial accumulator values.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U7>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U7>,
+        quotient_term: &mut F,
+        index: usize,
+    ) {
+        let LadderStep {
+            x_alpha,
+            x_1,
+            y_1,
+            x_3,
+            y_3,
+            scalar,
+            delta,
+            q_ecc,
+            q_5,
+        } = LadderStep::new::<H, S, PC, G>(polynomials, Some(index));
+        let alpha = &challenges.alpha_powers;
+
+        let delta_squared = delta.square();
+        let digit_identity =
+            (delta_squared - F::one()) * (delta_squared - F::from(9u64)) * alpha[0];
+        let x_alpha_identity = -(x_alpha * alpha[1]);
+        let x_identity = ((x_3 + x_1 + x_alpha) * (x_alpha - x_1).square()
+            - x_alpha.square() * x_alpha
+            - grumpkin_b::<F>()
+            - y_1.square())
+            * alpha[2];
+        let y_identity = ((y_3 + y_1) * (x_alpha - x_1) + y_1 * (x_1 - x_3)) * alpha[3];
+        *quotient_term += (digit_identity + x_alpha_identity + x_identity + y_identity) * q_ecc;
+
+        let init_identity = (scalar - F::one()) * alpha[4] + x_1 * alpha[5] + y_1 * alpha[6];
+        *quotient_term += init_identity * q_5;
+    }
+
+    /// The selectors are opened, so the batched opening already covers them with their own ν powers.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U7>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the fixed-base ladder to the quotient polynomial over the 4n coset.
+pub(crate) type ProverTurboFixedBaseWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U7, TurboFixedBaseKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the fixed-base ladder at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierTurboFixedBaseWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U7>,
+        U7,
+        S,
+        TurboFixedBaseKernel<H, C::Fr, S>,
+    > for VerifierTurboFixedBaseWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, grumpkin_point_from, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    type Kernel = TurboFixedBaseKernel<Keccak256, Fr, TestSettings>;
+
+    fn add(p: (Fr, Fr), q: (Fr, Fr)) -> (Fr, Fr) {
+        let lambda = if p == q {
+            p.0.square() * Fr::from(3u64) / p.1.double()
+        } else {
+            (q.1 - p.1) / (q.0 - p.0)
+        };
+        let x = lambda.square() - p.0 - q.0;
+        (x, lambda * (p.0 - x) - p.1)
+    }
+
+    /// A ladder row that adds `digit·P` to the accumulator `(acc, scalar)`.
+    fn openings(point: (Fr, Fr), acc: (Fr, Fr), scalar: u64, digit: i64) -> PolyArray<Fr> {
+        let triple = add(add(point, point), point);
+        let chosen = if digit.abs() == 1 { point } else { triple };
+        let signed = if digit < 0 {
+            (chosen.0, -chosen.1)
+        } else {
+            chosen
+        };
+        let next = add(acc, signed);
+
+        let q_1 = (triple.0 - point.0) / Fr::from(8u64);
+        let q_2 = point.0 - q_1;
+        let q_3 = (point.1 - triple.1 / Fr::from(3u64)) / (point.0 - triple.0);
+        let q_4 = point.1 - q_3 * point.0;
+
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QFixedBase].0 = Fr::one();
+        polynomials[PolynomialIndex::Q1].0 = q_1;
+        polynomials[PolynomialIndex::Q2].0 = q_2;
+        polynomials[PolynomialIndex::Q3].0 = q_3;
+        polynomials[PolynomialIndex::Q4].0 = q_4;
+        polynomials[PolynomialIndex::W1].0 = chosen.0;
+        polynomials[PolynomialIndex::W2] = (acc.0, next.0);
+        polynomials[PolynomialIndex::W3] = (acc.1, next.1);
+        let digit = if digit < 0 {
+            -Fr::from(digit.unsigned_abs())
+        } else {
+            Fr::from(digit.unsigned_abs())
+        };
+        polynomials[PolynomialIndex::W4] = (Fr::from(scalar), Fr::from(4 * scalar) + digit);
+        polynomials
+    }
+
+    #[test]
+    fn ladder_steps_vanish_for_every_digit() {
+        let point = grumpkin_point_from(Fr::from(1u64));
+        let acc = grumpkin_point_from(Fr::from(1000u64));
+        let challenges = challenges(7);
+        for digit in [-3, -1, 1, 3] {
+            assert!(evaluate::<Kernel, _>(&openings(point, acc, 5, digit), &challenges).is_zero());
+        }
+    }
+
+    #[test]
+    fn wrong_digits_and_sums_are_caught() {
+        let point = grumpkin_point_from(Fr::from(1u64));
+        let acc = grumpkin_point_from(Fr::from(1000u64));
+        let challenges = challenges(7);
+
+        // a digit of 2 is not in the wNAF alphabet
+        let mut polynomials = openings(point, acc, 5, 1);
+        polynomials[PolynomialIndex::W4].1 += Fr::one();
+        assert!(!evaluate::<Kernel, _>(&polynomials, &challenges).is_zero());
+
+        // adding P while claiming 3P
+        let mut polynomials = openings(point, acc, 5, 3);
+        polynomials[PolynomialIndex::W1].0 = point.0;
+        assert!(!evaluate::<Kernel, _>(&polynomials, &challenges).is_zero());
+
+        // landing on the negation of the sum
+        let mut polynomials = openings(point, acc, 5, -1);
+        polynomials[PolynomialIndex::W3].1 = -polynomials[PolynomialIndex::W3].1;
+        assert!(!evaluate::<Kernel, _>(&polynomials, &challenges).is_zero());
+    }
+
+    #[test]
+    fn ladder_starts_at_the_offset_point() {
+        let point = grumpkin_point_from(Fr::from(1u64));
+        let offset = grumpkin_point_from(Fr::from(1000u64));
+        let challenges = challenges(7);
+
+        let mut polynomials = openings(point, offset, 1, 3);
+        polynomials[PolynomialIndex::Q5].0 = Fr::one();
+        polynomials[PolynomialIndex::QM].0 = offset.0;
+        polynomials[PolynomialIndex::QC].0 = offset.1;
+        assert!(evaluate::<Kernel, _>(&polynomials, &challenges).is_zero());
+
+        polynomials[PolynomialIndex::QC].0 = -offset.1;
+        assert!(!evaluate::<Kernel, _>(&polynomials, &challenges).is_zero());
+    }
+}
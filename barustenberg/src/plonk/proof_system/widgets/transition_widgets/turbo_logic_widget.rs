@@ -0,0 +1,292 @@
+use ark_ff::Field;
+use typenum::U4;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+    turbo_range_widget::quad_range_check,
+};
+
+/// AND or XOR of two 32-bit-style values, two bits at a time. Each row carries the base-4 accumulators
+/// `a` in w_1, `b` in w_2 and the output `c` in w_4, and moving to the next row appends one quad to each:
+/// `δ_a = w_1(ωX) - 4·w_1(X)` and likewise for `b` and `c`. The product `δ_a·δ_b` sits in w_3, which keeps
+/// the output identity down to degree 3 in the wires. q_c picks the operation: 1 for AND, -1 for XOR.
+pub(crate) struct TurboLogicKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> TurboLogicKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+
+    /// `6·(x & y)` for quads x and y, written in terms of their product `p` and sum `s`:
+    /// `83p - 81ps + 18ps² + 45p² - 18p²s + 4p³`. Interpolated over all ten unordered pairs of quads.
+    #[inline]
+    fn six_times_quad_and(product: F, sum: F) -> F {
+        let product_squared = product.square();
+        let mut result = product * F::from(83u64);
+        result -= product * sum * F::from(81u64);
+        result += product * sum.square() * F::from(18u64);
+        result += product_squared * F::from(45u64);
+        result -= product_squared * sum * F::from(18u64);
+        result += product_squared * product * F::from(4u64);
+        result
+    }
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U4>
+    for TurboLogicKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::QC,
+            PolynomialIndex::QLogic,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Computes the four logic identities under their powers of α. Their sum is the coefficient of q_logic:
+    /// - `δ_a` is a quad
+    /// - `δ_b` is a quad
+    /// - `w_3 = δ_a·δ_b`
+    /// - `12·δ_c = (3·q_c - 1)·6·(δ_a & δ_b) + 6·(1 - q_c)·(δ_a + δ_b)`
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U4>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let index = Some(index.unwrap_or_default());
+        let w_1 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W1,
+            index,
+        );
+        let w_2 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W2,
+            index,
+        );
+        let w_3 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W3,
+            index,
+        );
+        let w_4 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W4,
+            index,
+        );
+        let w_1_omega = G::get_value(
+            polynomials,
+            EvaluationType::Shifted,
+            PolynomialIndex::W1,
+            index,
+        );
+        let w_2_omega = G::get_value(
+            polynomials,
+            EvaluationType::Shifted,
+            PolynomialIndex::W2,
+            index,
+        );
+        let w_4_omega = G::get_value(
+            polynomials,
+            EvaluationType::Shifted,
+            PolynomialIndex::W4,
+            index,
+        );
+        let q_c = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QC,
+            index,
+        );
+
+        let four = F::from(4u64);
+        let delta_a = w_1_omega - w_1 * four;
+        let delta_b = w_2_omega - w_2 * four;
+        let delta_c = w_4_omega - w_4 * four;
+        let delta_sum = delta_a + delta_b;
+
+        let mut output_identity = delta_c * F::from(12u64);
+        output_identity -=
+            Self::six_times_quad_and(w_3, delta_sum) * (q_c * F::from(3u64) - F::one());
+        output_identity -= delta_sum * F::from(6u64) * (F::one() - q_c);
+
+        let alpha = &challenges.alpha_powers;
+        linear_terms[0.into()] = quad_range_check(delta_a) * alpha[0]
+            + quad_range_check(delta_b) * alpha[1]
+            + (delta_a * delta_b - w_3) * alpha[2]
+            + output_identity * alpha[3];
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let q_logic = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QLogic,
+            Some(index),
+        );
+        linear_terms[0.into()] * q_logic
+    }
+
+    /// The logic relation is linear in q_logic, so there is nothing to add here.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        _quotient_term: &mut F,
+        _index: usize,
+    ) {
+    }
+
+    /// q_logic is opened, so the batched opening already covers it with its own ν power.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U4>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the quad-wise AND/XOR gate to the quotient polynomial over the 4n coset.
+pub(crate) type ProverTurboLogicWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U4, TurboLogicKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the quad-wise AND/XOR gate at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierTurboLogicWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U4>,
+        U4,
+        S,
+        TurboLogicKernel<H, C::Fr, S>,
+    > for VerifierTurboLogicWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::Zero;
+
+    type Kernel = TurboLogicKernel<Keccak256, Fr, TestSettings>;
+
+    /// A logic gate row with accumulators `a`, `b` and `c`, stepping to the next row by appending the quads
+    /// `x`, `y` and `z`.
+    fn openings(is_xor: bool, accumulators: [u64; 3], quads: [u64; 3]) -> PolyArray<Fr> {
+        let [a, b, c] = accumulators;
+        let [x, y, z] = quads;
+
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QLogic].0 = Fr::from(1u64);
+        polynomials[PolynomialIndex::QC].0 = if is_xor {
+            -Fr::from(1u64)
+        } else {
+            Fr::from(1u64)
+        };
+        polynomials[PolynomialIndex::W1] = (Fr::from(a), Fr::from(4 * a + x));
+        polynomials[PolynomialIndex::W2] = (Fr::from(b), Fr::from(4 * b + y));
+        polynomials[PolynomialIndex::W3].0 = Fr::from(x * y);
+        polynomials[PolynomialIndex::W4] = (Fr::from(c), Fr::from(4 * c + z));
+        polynomials
+    }
+
+    #[test]
+    fn every_pair_of_quads_and_and_xor_correctly() {
+        let challenges = challenges(7);
+        for x in 0..4 {
+            for y in 0..4 {
+                let and = openings(false, [9, 6, 2], [x, y, x & y]);
+                let xor = openings(true, [9, 6, 11], [x, y, x ^ y]);
+                assert!(evaluate::<Kernel, _>(&and, &challenges).is_zero());
+                assert!(evaluate::<Kernel, _>(&xor, &challenges).is_zero());
+
+                let wrong_and = openings(false, [9, 6, 2], [x, y, x ^ y]);
+                let wrong_xor = openings(true, [9, 6, 11], [x, y, x & y]);
+                assert_eq!(
+                    evaluate::<Kernel, _>(&wrong_and, &challenges).is_zero(),
+                    x & y == x ^ y
+                );
+                assert_eq!(
+                    evaluate::<Kernel, _>(&wrong_xor, &challenges).is_zero(),
+                    x & y == x ^ y
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inputs_must_be_quads_and_the_product_must_match() {
+        let challenges = challenges(7);
+        // 4 is not a quad
+        assert!(
+            !evaluate::<Kernel, _>(&openings(false, [0, 0, 0], [4, 1, 0]), &challenges).is_zero()
+        );
+
+        let mut polynomials = openings(false, [0, 0, 0], [3, 2, 2]);
+        polynomials[PolynomialIndex::W3].0 = Fr::from(5u64);
+        assert!(!evaluate::<Kernel, _>(&polynomials, &challenges).is_zero());
+    }
+}
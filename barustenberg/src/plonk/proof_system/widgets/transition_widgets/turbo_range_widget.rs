@@ -0,0 +1,244 @@
+use ark_ff::Field;
+use typenum::U4;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+};
+
+/// `δ·(δ - 1)·(δ - 2)·(δ - 3)`, which vanishes exactly when δ is a base-4 digit.
+#[inline]
+pub(crate) fn quad_range_check<F: Field>(delta: F) -> F {
+    let delta_squared = delta.square();
+    (delta_squared - delta) * (delta_squared - delta * F::from(5u64) + F::from(6u64))
+}
+
+/// Range constraint over a base-4 accumulator that runs through the wires of a row in the order
+/// `w_4, w_3, w_2, w_1` and on into `w_4` of the next row. Every step `4·a_i + δ_i = a_{i+1}` must add
+/// a quad `δ_i ∈ {0, 1, 2, 3}`, so each row range constrains four more quads of the accumulated value.
+pub(crate) struct TurboRangeKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> TurboRangeKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U4>
+    for TurboRangeKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::QRange,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Sums the four quad checks, each under its own power of α. The result is the coefficient of q_range.
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U4>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let index = Some(index.unwrap_or_default());
+        let w_1 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W1,
+            index,
+        );
+        let w_2 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W2,
+            index,
+        );
+        let w_3 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W3,
+            index,
+        );
+        let w_4 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W4,
+            index,
+        );
+        let w_4_omega = G::get_value(
+            polynomials,
+            EvaluationType::Shifted,
+            PolynomialIndex::W4,
+            index,
+        );
+
+        let four = F::from(4u64);
+        let deltas = [
+            w_3 - w_4 * four,
+            w_2 - w_3 * four,
+            w_1 - w_2 * four,
+            w_4_omega - w_1 * four,
+        ];
+
+        let mut result = F::zero();
+        for (delta, alpha) in deltas.into_iter().zip(challenges.alpha_powers.iter()) {
+            result += quad_range_check(delta) * alpha;
+        }
+        linear_terms[0.into()] = result;
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let q_range = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QRange,
+            Some(index),
+        );
+        linear_terms[0.into()] * q_range
+    }
+
+    /// The range relation is linear in q_range, so there is nothing to add here.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        _quotient_term: &mut F,
+        _index: usize,
+    ) {
+    }
+
+    /// q_range is opened, so the batched opening already covers it with its own ν power.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U4>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the base-4 range constraint to the quotient polynomial over the 4n coset.
+pub(crate) type ProverTurboRangeWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U4, TurboRangeKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the base-4 range constraint at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierTurboRangeWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U4>,
+        U4,
+        S,
+        TurboRangeKernel<H, C::Fr, S>,
+    > for VerifierTurboRangeWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::Zero;
+
+    type Kernel = TurboRangeKernel<Keccak256, Fr, TestSettings>;
+
+    /// A range gate row whose accumulator starts at `start` and then takes the four quads in `quads`.
+    fn openings(start: u64, quads: [u64; 4]) -> PolyArray<Fr> {
+        let mut accumulators = [start; 5];
+        for (i, quad) in quads.into_iter().enumerate() {
+            accumulators[i + 1] = 4 * accumulators[i] + quad;
+        }
+
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QRange].0 = Fr::from(1u64);
+        polynomials[PolynomialIndex::W4].0 = Fr::from(accumulators[0]);
+        polynomials[PolynomialIndex::W3].0 = Fr::from(accumulators[1]);
+        polynomials[PolynomialIndex::W2].0 = Fr::from(accumulators[2]);
+        polynomials[PolynomialIndex::W1].0 = Fr::from(accumulators[3]);
+        polynomials[PolynomialIndex::W4].1 = Fr::from(accumulators[4]);
+        polynomials
+    }
+
+    #[test]
+    fn base_four_accumulators_vanish() {
+        let challenges = challenges(7);
+        assert!(evaluate::<Kernel, _>(&openings(0, [3, 1, 0, 2]), &challenges).is_zero());
+        assert!(evaluate::<Kernel, _>(&openings(1234, [0, 3, 3, 1]), &challenges).is_zero());
+    }
+
+    #[test]
+    fn out_of_range_quads_are_caught_under_their_alpha_power() {
+        // the last step adds 4, which the fourth check catches as 4·3·2·1 = 24 under α³
+        assert_eq!(
+            evaluate::<Kernel, _>(&openings(5, [1, 2, 3, 4]), &challenges(7)),
+            Fr::from(24u64 * 7 * 7 * 7)
+        );
+        // and so is an accumulator that was tampered with mid row
+        let mut polynomials = openings(5, [1, 2, 3, 0]);
+        polynomials[PolynomialIndex::W2].0 -= Fr::from(1u64);
+        assert!(!evaluate::<Kernel, _>(&polynomials, &challenges(7)).is_zero());
+    }
+
+    #[test]
+    fn gates_without_the_selector_are_ignored() {
+        let mut polynomials = openings(5, [1, 2, 3, 4]);
+        polynomials[PolynomialIndex::QRange].0 = Fr::zero();
+        assert!(evaluate::<Kernel, _>(&polynomials, &challenges(7)).is_zero());
+    }
+}
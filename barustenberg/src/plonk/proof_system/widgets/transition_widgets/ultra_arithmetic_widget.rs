@@ -0,0 +1,266 @@
+use ark_ff::Field;
+use typenum::U2;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+};
+
+/// The Ultra arithmetic gate, whose mode is the value of q_arith:
+/// - `q_arith·(-½(q_arith - 3)·q_m·w_1·w_2 + q_1·w_1 + q_2·w_2 + q_3·w_3 + q_4·w_4 + q_c + (q_arith - 1)·w_4(ωX))`
+/// - `q_arith·(q_arith - 1)·(q_arith - 2)·(w_1 + w_4 - w_1(ωX) + q_m)`
+///
+/// With q_arith = 1 this is the width-4 arithmetic gate. Higher modes add w_4 of the next row, once for 2 and
+/// twice for 3, and scale the multiplication by ½ and 0. Mode 3 uses the second relation for another addition
+/// into w_1 of the next row, with q_m as its constant.
+pub(crate) struct UltraArithmeticKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> UltraArithmeticKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U2>
+    for UltraArithmeticKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::Q1,
+            PolynomialIndex::Q2,
+            PolynomialIndex::Q3,
+            PolynomialIndex::Q4,
+            PolynomialIndex::QM,
+            PolynomialIndex::QC,
+            PolynomialIndex::QArithmetic,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// q_arith scales everything, including itself, so the gate is computed whole in
+    /// [`compute_non_linear_terms`](Self::compute_non_linear_terms).
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U2>,
+        _linear_terms: &mut CoefficientArray<F>,
+        _index: Option<usize>,
+    ) {
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U2>,
+        _linear_terms: &CoefficientArray<F>,
+        _index: usize,
+    ) -> F {
+        F::zero()
+    }
+
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U2>,
+        quotient_term: &mut F,
+        index: usize,
+    ) {
+        let value = |evaluation_type, polynomial| {
+            G::get_value(polynomials, evaluation_type, polynomial, Some(index))
+        };
+        let w_1 = value(EvaluationType::NonShifted, PolynomialIndex::W1);
+        let w_2 = value(EvaluationType::NonShifted, PolynomialIndex::W2);
+        let w_3 = value(EvaluationType::NonShifted, PolynomialIndex::W3);
+        let w_4 = value(EvaluationType::NonShifted, PolynomialIndex::W4);
+        let w_1_omega = value(EvaluationType::Shifted, PolynomialIndex::W1);
+        let w_4_omega = value(EvaluationType::Shifted, PolynomialIndex::W4);
+        let q_1 = value(EvaluationType::NonShifted, PolynomialIndex::Q1);
+        let q_2 = value(EvaluationType::NonShifted, PolynomialIndex::Q2);
+        let q_3 = value(EvaluationType::NonShifted, PolynomialIndex::Q3);
+        let q_4 = value(EvaluationType::NonShifted, PolynomialIndex::Q4);
+        let q_m = value(EvaluationType::NonShifted, PolynomialIndex::QM);
+        let q_c = value(EvaluationType::NonShifted, PolynomialIndex::QC);
+        let q_arith = value(EvaluationType::NonShifted, PolynomialIndex::QArithmetic);
+
+        let one = F::one();
+        let two = one.double();
+        let three = two + one;
+        let neg_half = -two.inverse().unwrap();
+
+        let gate = (q_arith - three) * q_m * w_1 * w_2 * neg_half
+            + q_1 * w_1
+            + q_2 * w_2
+            + q_3 * w_3
+            + q_4 * w_4
+            + q_c
+            + (q_arith - one) * w_4_omega;
+        let extra_addition = (q_arith - one) * (q_arith - two) * (w_1 + w_4 - w_1_omega + q_m);
+
+        *quotient_term += q_arith
+            * (gate * challenges.alpha_powers[0] + extra_addition * challenges.alpha_powers[1]);
+    }
+
+    /// The selectors are opened, so the batched opening already covers them with their own ν powers.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U2>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the Ultra arithmetic gate to the quotient polynomial over the 4n coset.
+pub(crate) type ProverUltraArithmeticWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U2, UltraArithmeticKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the Ultra arithmetic gate at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierUltraArithmeticWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U2>,
+        U2,
+        S,
+        UltraArithmeticKernel<H, C::Fr, S>,
+    > for VerifierUltraArithmeticWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    type Kernel = UltraArithmeticKernel<Keccak256, Fr, TestSettings>;
+
+    /// Openings for a gate with selectors `[q_m, q_1, q_2, q_3, q_4, q_c]` in mode `q_arith`, on the
+    /// wires of this row followed by w_1 and w_4 of the next.
+    fn openings(
+        q_arith: u64,
+        selectors: [Fr; 6],
+        wires: [u64; 4],
+        next: [u64; 2],
+    ) -> PolyArray<Fr> {
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QArithmetic].0 = Fr::from(q_arith);
+        for (selector, value) in [
+            PolynomialIndex::QM,
+            PolynomialIndex::Q1,
+            PolynomialIndex::Q2,
+            PolynomialIndex::Q3,
+            PolynomialIndex::Q4,
+            PolynomialIndex::QC,
+        ]
+        .into_iter()
+        .zip(selectors)
+        {
+            polynomials[selector].0 = value;
+        }
+        for (wire, value) in [
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ]
+        .into_iter()
+        .zip(wires)
+        {
+            polynomials[wire].0 = Fr::from(value);
+        }
+        polynomials[PolynomialIndex::W1].1 = Fr::from(next[0]);
+        polynomials[PolynomialIndex::W4].1 = Fr::from(next[1]);
+        polynomials
+    }
+
+    #[test]
+    fn width_four_gates_vanish() {
+        let (one, zero) = (Fr::one(), Fr::zero());
+        let challenges = challenges(7);
+        // 3·4 + 5 - 17 = 0
+        let gate = openings(1, [one, zero, zero, one, -one, zero], [3, 4, 5, 17], [0, 0]);
+        assert!(evaluate::<Kernel, _>(&gate, &challenges).is_zero());
+
+        let wrong = openings(1, [one, zero, zero, one, -one, zero], [3, 4, 5, 18], [0, 0]);
+        assert_eq!(evaluate::<Kernel, _>(&wrong, &challenges), -one);
+    }
+
+    #[test]
+    fn higher_modes_reach_into_the_next_row() {
+        let (one, zero) = (Fr::one(), Fr::zero());
+        let challenges = challenges(7);
+        // mode 2 halves the multiplication: ½·2·3·4 + 5 - 23 + 6 = 0, with 6 in the next row's w_4
+        let two = Fr::from(2u64);
+        let gate = openings(2, [two, zero, zero, one, -one, zero], [3, 4, 5, 23], [0, 6]);
+        assert!(evaluate::<Kernel, _>(&gate, &challenges).is_zero());
+        let gate = openings(2, [two, zero, zero, one, -one, zero], [3, 4, 5, 23], [0, 7]);
+        assert!(!evaluate::<Kernel, _>(&gate, &challenges).is_zero());
+
+        // mode 3 drops it: -2 - 3 - 5 + 2·5 = 0 with 5 in the next row's w_4, and 2 + 0 + 4 = 6 in its
+        // w_1 with q_m = 4
+        let q_m = Fr::from(4u64);
+        let gate = openings(3, [q_m, -one, -one, -one, zero, zero], [2, 3, 5, 0], [6, 5]);
+        assert!(evaluate::<Kernel, _>(&gate, &challenges).is_zero());
+        let gate = openings(3, [q_m, -one, -one, -one, zero, zero], [2, 3, 5, 0], [7, 5]);
+        assert_eq!(
+            evaluate::<Kernel, _>(&gate, &challenges),
+            -Fr::from(3u64 * 2 * 7)
+        );
+    }
+
+    #[test]
+    fn gates_without_the_selector_are_ignored() {
+        let one = Fr::one();
+        let gate = openings(0, [one, one, one, one, one, one], [3, 4, 5, 6], [7, 8]);
+        assert!(evaluate::<Kernel, _>(&gate, &challenges(7)).is_zero());
+    }
+}
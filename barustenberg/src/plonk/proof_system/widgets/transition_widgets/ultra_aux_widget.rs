@@ -0,0 +1,454 @@
+use ark_ff::Field;
+use typenum::U4;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{
+        ChallengeArray, ChallengeIndex, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA,
+        CHALLENGE_BIT_ETA,
+    },
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+};
+
+/// Width of a bigfield limb.
+const LIMB_SHIFT: u128 = 1 << 68;
+/// Width of the sublimbs a limb is range constrained through.
+const SUBLIMB_SHIFT: u64 = 1 << 14;
+
+/// The auxiliary gates of the Ultra arithmetisation, all behind q_aux and told apart by which of the other
+/// selectors are set:
+/// - bigfield limb products (q_2 with one of q_3, q_4 or q_m), which check the cross products of two
+///   68-bit limb pairs that non-native multiplication is built from
+/// - limb accumulators (q_3 with q_4 or q_m), which recompose a limb from five 14-bit sublimbs
+/// - ROM consistency (q_1 and q_2) over the list of records sorted by index: indices go up by 0 or 1,
+///   and adjacent records at the same index are equal
+/// - RAM timestamps (q_1 and q_4), which put the timestamp delta of accesses to the same index in w_3
+/// - memory records (q_1 and q_m), which check `w_4 = η·w_1 + η²·w_2 + η³·w_3 + q_c`
+/// - RAM consistency (q_arith) over the list of accesses sorted by index and timestamp: access types are
+///   boolean, indices go up by 0 or 1, and a read returns the last value written to its index
+///
+/// Sub-relations that share a gate are combined with powers of α, so the widget takes four of them.
+pub(crate) struct UltraAuxKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> UltraAuxKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 =
+        (CHALLENGE_BIT_ALPHA | CHALLENGE_BIT_ETA) as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 =
+        (CHALLENGE_BIT_ALPHA | CHALLENGE_BIT_ETA) as u8;
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U4>
+    for UltraAuxKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::Q1,
+            PolynomialIndex::Q2,
+            PolynomialIndex::Q3,
+            PolynomialIndex::Q4,
+            PolynomialIndex::QM,
+            PolynomialIndex::QC,
+            PolynomialIndex::QArithmetic,
+            PolynomialIndex::QAux,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Computes the whole auxiliary identity, which is the coefficient of q_aux.
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U4>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let index = Some(index.unwrap_or_default());
+        let get = |evaluation_type, id| G::get_value(polynomials, evaluation_type, id, index);
+        let w_1 = get(EvaluationType::NonShifted, PolynomialIndex::W1);
+        let w_2 = get(EvaluationType::NonShifted, PolynomialIndex::W2);
+        let w_3 = get(EvaluationType::NonShifted, PolynomialIndex::W3);
+        let w_4 = get(EvaluationType::NonShifted, PolynomialIndex::W4);
+        let w_1_omega = get(EvaluationType::Shifted, PolynomialIndex::W1);
+        let w_2_omega = get(EvaluationType::Shifted, PolynomialIndex::W2);
+        let w_3_omega = get(EvaluationType::Shifted, PolynomialIndex::W3);
+        let w_4_omega = get(EvaluationType::Shifted, PolynomialIndex::W4);
+        let q_1 = get(EvaluationType::NonShifted, PolynomialIndex::Q1);
+        let q_2 = get(EvaluationType::NonShifted, PolynomialIndex::Q2);
+        let q_3 = get(EvaluationType::NonShifted, PolynomialIndex::Q3);
+        let q_4 = get(EvaluationType::NonShifted, PolynomialIndex::Q4);
+        let q_m = get(EvaluationType::NonShifted, PolynomialIndex::QM);
+        let q_c = get(EvaluationType::NonShifted, PolynomialIndex::QC);
+        let q_arith = get(EvaluationType::NonShifted, PolynomialIndex::QArithmetic);
+
+        let alpha = challenges.elements[ChallengeIndex::Alpha as usize];
+        let eta = challenges.elements[ChallengeIndex::Eta as usize];
+        let limb_shift = F::from(LIMB_SHIFT);
+        let sublimb_shift = F::from(SUBLIMB_SHIFT);
+
+        // bigfield limb products
+        let limb_subproduct = w_1 * w_2_omega + w_1_omega * w_2;
+        let non_native_field_gate_2 =
+            ((w_1 * w_4 + w_2 * w_3 - w_3_omega) * limb_shift - w_4_omega + limb_subproduct) * q_4;
+        let limb_subproduct = limb_subproduct * limb_shift + w_1_omega * w_2_omega;
+        let non_native_field_gate_1 = (limb_subproduct - (w_3 + w_4)) * q_3;
+        let non_native_field_gate_3 = (limb_subproduct + w_4 - (w_3_omega + w_4_omega)) * q_m;
+        let non_native_field_identity =
+            (non_native_field_gate_1 + non_native_field_gate_2 + non_native_field_gate_3) * q_2;
+
+        // limb accumulators, recomposed by Horner's rule from the most significant sublimb down
+        let recompose = |sublimbs: [F; 5]| {
+            sublimbs
+                .into_iter()
+                .fold(F::zero(), |acc, sublimb| acc * sublimb_shift + sublimb)
+        };
+        let limb_accumulator_1 = (recompose([w_2_omega, w_1_omega, w_3, w_2, w_1]) - w_4) * q_4;
+        let limb_accumulator_2 =
+            (recompose([w_3_omega, w_2_omega, w_1_omega, w_4, w_3]) - w_4_omega) * q_m;
+        let limb_accumulator_identity = (limb_accumulator_1 + limb_accumulator_2) * q_3;
+
+        // memory records compress (index, value or timestamp, value) into η·w_1 + η²·w_2 + η³·w_3
+        let partial_record_check = ((w_3 * eta + w_2) * eta + w_1) * eta + q_c;
+        let memory_record_check = partial_record_check - w_4;
+
+        // ROM consistency
+        let index_delta = w_1_omega - w_1;
+        let record_delta = w_4_omega - w_4;
+        let index_is_monotonically_increasing = index_delta.square() - index_delta;
+        let adjacent_values_match_if_adjacent_indices_match =
+            (F::one() - index_delta) * record_delta;
+        let rom_consistency_check_identity = (adjacent_values_match_if_adjacent_indices_match
+            * alpha
+            + index_is_monotonically_increasing)
+            * alpha
+            + memory_record_check;
+
+        // RAM consistency, where the access type is what the record holds on top of its compressed wires
+        let access_type = w_4 - partial_record_check;
+        let access_check = access_type.square() - access_type;
+        let next_gate_access_type =
+            w_4_omega - ((w_3_omega * eta + w_2_omega) * eta + w_1_omega) * eta;
+        let value_delta = w_3_omega - w_3;
+        let adjacent_values_match_if_adjacent_indices_match_and_next_access_is_a_read_operation =
+            (F::one() - index_delta) * value_delta * (F::one() - next_gate_access_type);
+        let next_gate_access_type_is_boolean =
+            next_gate_access_type.square() - next_gate_access_type;
+        let ram_consistency_check_identity = ((access_check * alpha
+            + next_gate_access_type_is_boolean)
+            * alpha
+            + adjacent_values_match_if_adjacent_indices_match_and_next_access_is_a_read_operation)
+            * alpha
+            + index_is_monotonically_increasing;
+
+        // RAM timestamps
+        let timestamp_delta = w_2_omega - w_2;
+        let ram_timestamp_check_identity = (F::one() - index_delta) * timestamp_delta - w_3;
+
+        let memory_identity = (rom_consistency_check_identity * q_2
+            + ram_timestamp_check_identity * q_4
+            + memory_record_check * q_m)
+            * q_1
+            + ram_consistency_check_identity * q_arith;
+
+        linear_terms[0.into()] =
+            (memory_identity + non_native_field_identity + limb_accumulator_identity)
+                * challenges.alpha_powers[0];
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let q_aux = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QAux,
+            Some(index),
+        );
+        linear_terms[0.into()] * q_aux
+    }
+
+    /// The auxiliary relation is linear in q_aux, so there is nothing to add here.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        _quotient_term: &mut F,
+        _index: usize,
+    ) {
+    }
+
+    /// q_aux is opened, so the batched opening already covers it with its own ν power.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U4>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the auxiliary gates to the quotient polynomial over the 4n coset.
+pub(crate) type ProverUltraAuxWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U4, UltraAuxKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the auxiliary gates at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierUltraAuxWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U4>,
+        U4,
+        S,
+        UltraAuxKernel<H, C::Fr, S>,
+    > for VerifierUltraAuxWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{self, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    type Kernel = UltraAuxKernel<Keccak256, Fr, TestSettings>;
+
+    const ETA: u64 = 11;
+
+    /// An auxiliary gate with the selectors in `selectors` set to one, and `row` and `next` in the wires of
+    /// its row and the next.
+    fn openings(selectors: &[PolynomialIndex], row: [Fr; 4], next: [Fr; 4]) -> PolyArray<Fr> {
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QAux].0 = Fr::one();
+        for selector in selectors {
+            polynomials[*selector].0 = Fr::one();
+        }
+        let wires = [
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ];
+        for (i, wire) in wires.into_iter().enumerate() {
+            polynomials[wire] = (row[i], next[i]);
+        }
+        polynomials
+    }
+
+    /// The kernel raises α itself, so it also needs α and η among the challenge elements.
+    fn challenges(alpha: u64) -> ChallengeArray<Fr, U4> {
+        let mut challenges = test_utils::challenges(alpha);
+        challenges.elements[ChallengeIndex::Alpha as usize] = Fr::from(alpha);
+        challenges.elements[ChallengeIndex::Eta as usize] = Fr::from(ETA);
+        challenges
+    }
+
+    fn record(wires: [u64; 3], access: u64) -> Fr {
+        let eta = Fr::from(ETA);
+        Fr::from(wires[0]) * eta
+            + Fr::from(wires[1]) * eta.square()
+            + Fr::from(wires[2]) * eta.square() * eta
+            + Fr::from(access)
+    }
+
+    fn memory_row(wires: [u64; 3], access: u64) -> [Fr; 4] {
+        [
+            Fr::from(wires[0]),
+            Fr::from(wires[1]),
+            Fr::from(wires[2]),
+            record(wires, access),
+        ]
+    }
+
+    #[test]
+    fn bigfield_limb_products_vanish_only_when_correct() {
+        use PolynomialIndex::*;
+        let [a_0, b_0, a_1, b_1] = [3u64, 5, 7, 11].map(Fr::from);
+        let limb_shift = Fr::from(LIMB_SHIFT);
+        let cross = a_0 * b_1 + a_1 * b_0;
+        let product = cross * limb_shift + a_1 * b_1;
+
+        // gate 1: the limb product lands in w_3 + w_4
+        let gate_1 = openings(
+            &[Q2, Q3],
+            [a_0, b_0, product - Fr::from(2u64), Fr::from(2u64)],
+            [a_1, b_1, Fr::zero(), Fr::zero()],
+        );
+        // gate 2: the cross terms of (w_1, w_2)·(w_4, w_3) carried up a limb, plus the cross product
+        let [c, d] = [13u64, 17].map(Fr::from);
+        let gate_2 = openings(
+            &[Q2, Q4],
+            [a_0, b_0, c, d],
+            [a_1, b_1, a_0 * d + b_0 * c, cross],
+        );
+        // gate 3: the limb product plus w_4 carries into w_3 and w_4 of the next row
+        let gate_3 = openings(&[Q2, QM], [a_0, b_0, Fr::zero(), d], [a_1, b_1, product, d]);
+
+        for mut gate in [gate_1, gate_2, gate_3] {
+            assert!(evaluate::<Kernel, _>(&gate, &challenges(5)).is_zero());
+            gate[W1].0 += Fr::one();
+            assert!(!evaluate::<Kernel, _>(&gate, &challenges(5)).is_zero());
+        }
+    }
+
+    #[test]
+    fn limb_accumulators_recompose_sublimbs() {
+        use PolynomialIndex::*;
+        let shift = Fr::from(SUBLIMB_SHIFT);
+        let sublimbs = [1u64, 2, 3, 4, 5].map(Fr::from);
+        let limb = sublimbs
+            .iter()
+            .rev()
+            .fold(Fr::zero(), |acc, sublimb| acc * shift + sublimb);
+
+        // accumulator 1 reads w_1, w_2, w_3, w_1(ω), w_2(ω) into w_4
+        let [s_0, s_1, s_2, s_3, s_4] = sublimbs;
+        let mut low = openings(
+            &[Q3, Q4],
+            [s_0, s_1, s_2, limb],
+            [s_3, s_4, Fr::zero(), Fr::zero()],
+        );
+        assert!(evaluate::<Kernel, _>(&low, &challenges(5)).is_zero());
+        low[W2].1 += Fr::one();
+        assert!(!evaluate::<Kernel, _>(&low, &challenges(5)).is_zero());
+
+        // accumulator 2 reads w_3, w_4, w_1(ω), w_2(ω), w_3(ω) into w_4(ω)
+        let mut high = openings(
+            &[Q3, QM],
+            [Fr::zero(), Fr::zero(), s_0, s_1],
+            [s_2, s_3, s_4, limb],
+        );
+        assert!(evaluate::<Kernel, _>(&high, &challenges(5)).is_zero());
+        high[W4].1 -= Fr::one();
+        assert!(!evaluate::<Kernel, _>(&high, &challenges(5)).is_zero());
+    }
+
+    #[test]
+    fn rom_records_are_consistent() {
+        use PolynomialIndex::*;
+        let rom = [Q1, Q2];
+        let same_index = openings(&rom, memory_row([4, 20, 30], 0), memory_row([4, 20, 30], 0));
+        let next_index = openings(&rom, memory_row([4, 20, 30], 0), memory_row([5, 1, 2], 0));
+        assert!(evaluate::<Kernel, _>(&same_index, &challenges(5)).is_zero());
+        assert!(evaluate::<Kernel, _>(&next_index, &challenges(5)).is_zero());
+
+        // two different values at one index
+        let forked = openings(&rom, memory_row([4, 20, 30], 0), memory_row([4, 21, 30], 0));
+        assert!(!evaluate::<Kernel, _>(&forked, &challenges(5)).is_zero());
+        // an index that skips
+        let skipped = openings(&rom, memory_row([4, 20, 30], 0), memory_row([6, 20, 30], 0));
+        assert!(!evaluate::<Kernel, _>(&skipped, &challenges(5)).is_zero());
+        // a record that does not compress its wires
+        let mut forged = same_index;
+        forged[W4].0 += Fr::one();
+        forged[W4].1 += Fr::one();
+        assert!(!evaluate::<Kernel, _>(&forged, &challenges(5)).is_zero());
+    }
+
+    #[test]
+    fn memory_records_compress_their_wires() {
+        use PolynomialIndex::*;
+        let mut gate = openings(&[Q1, QM], memory_row([1, 2, 3], 0), [Fr::zero(); 4]);
+        assert!(evaluate::<Kernel, _>(&gate, &challenges(5)).is_zero());
+        gate[QC].0 = Fr::one();
+        assert!(!evaluate::<Kernel, _>(&gate, &challenges(5)).is_zero());
+        gate[W4].0 += Fr::one();
+        assert!(evaluate::<Kernel, _>(&gate, &challenges(5)).is_zero());
+    }
+
+    #[test]
+    fn ram_timestamps_are_carried_in_w_3() {
+        use PolynomialIndex::*;
+        let timestamps = |index_omega: u64, delta: u64| {
+            openings(
+                &[Q1, Q4],
+                [Fr::from(4u64), Fr::from(10u64), Fr::from(delta), Fr::zero()],
+                [
+                    Fr::from(index_omega),
+                    Fr::from(17u64),
+                    Fr::zero(),
+                    Fr::zero(),
+                ],
+            )
+        };
+        assert!(evaluate::<Kernel, _>(&timestamps(4, 7), &challenges(5)).is_zero());
+        assert!(!evaluate::<Kernel, _>(&timestamps(4, 6), &challenges(5)).is_zero());
+        // the delta is only meaningful between accesses to the same index
+        assert!(evaluate::<Kernel, _>(&timestamps(5, 0), &challenges(5)).is_zero());
+    }
+
+    #[test]
+    fn ram_reads_return_the_last_write() {
+        use PolynomialIndex::*;
+        let ram = [QArithmetic];
+        // a write of 30 at index 4, then a read of 30 and a write of 31
+        let read = openings(&ram, memory_row([4, 1, 30], 1), memory_row([4, 2, 30], 0));
+        let write = openings(&ram, memory_row([4, 1, 30], 1), memory_row([4, 2, 31], 1));
+        let next_index = openings(&ram, memory_row([4, 1, 30], 0), memory_row([5, 0, 7], 0));
+        assert!(evaluate::<Kernel, _>(&read, &challenges(5)).is_zero());
+        assert!(evaluate::<Kernel, _>(&write, &challenges(5)).is_zero());
+        assert!(evaluate::<Kernel, _>(&next_index, &challenges(5)).is_zero());
+
+        // a read of a value that was never written
+        let stale = openings(&ram, memory_row([4, 1, 30], 1), memory_row([4, 2, 31], 0));
+        assert!(!evaluate::<Kernel, _>(&stale, &challenges(5)).is_zero());
+        // an access type that is not a bit
+        let bad_access = openings(&ram, memory_row([4, 1, 30], 2), memory_row([4, 2, 30], 0));
+        assert!(!evaluate::<Kernel, _>(&bad_access, &challenges(5)).is_zero());
+        let bad_next_access = openings(&ram, memory_row([4, 1, 30], 1), memory_row([4, 2, 31], 2));
+        assert!(!evaluate::<Kernel, _>(&bad_next_access, &challenges(5)).is_zero());
+        // an index that goes down
+        let unsorted = openings(&ram, memory_row([4, 1, 30], 1), memory_row([3, 2, 30], 1));
+        assert!(!evaluate::<Kernel, _>(&unsorted, &challenges(5)).is_zero());
+    }
+}
@@ -0,0 +1,278 @@
+use ark_ff::Field;
+use typenum::U2;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+};
+
+/// Short Weierstrass point addition `(x_1, y_1) + (β·x_2, ±y_2) = (x_3, y_3)` over two rows, laid out as
+/// by an `EccAddGate`: `(x_1, y_1)` sit in w_2 and w_3 of the gate's row, while `x_2`, `x_3`, `y_3` and
+/// `y_2` sit in w_1 to w_4 of the next one. The selectors carry the gate's coefficients, with the sign in
+/// q_1, the endomorphism coefficient β in q_3 and β² in q_4, so the same gate adds, subtracts and applies
+/// the GLV endomorphism `(x, y) ↦ (β·x, y)`. β must be 1 or a cube root of unity, as the x identity uses
+/// `β³ = 1` to keep within degree 5.
+pub(crate) struct UltraEllipticKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> UltraEllipticKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U2>
+    for UltraEllipticKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::Q1,
+            PolynomialIndex::Q3,
+            PolynomialIndex::Q4,
+            PolynomialIndex::QElliptic,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Sums the x and y addition identities under α⁰ and α¹. The result is the coefficient of q_elliptic.
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U2>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let index = Some(index.unwrap_or_default());
+        let get = |evaluation_type, id| G::get_value(polynomials, evaluation_type, id, index);
+        let x_1 = get(EvaluationType::NonShifted, PolynomialIndex::W2);
+        let y_1 = get(EvaluationType::NonShifted, PolynomialIndex::W3);
+        let x_2 = get(EvaluationType::Shifted, PolynomialIndex::W1);
+        let x_3 = get(EvaluationType::Shifted, PolynomialIndex::W2);
+        let y_3 = get(EvaluationType::Shifted, PolynomialIndex::W3);
+        let y_2 = get(EvaluationType::Shifted, PolynomialIndex::W4);
+        let q_sign = get(EvaluationType::NonShifted, PolynomialIndex::Q1);
+        let q_beta = get(EvaluationType::NonShifted, PolynomialIndex::Q3);
+        let q_beta_sqr = get(EvaluationType::NonShifted, PolynomialIndex::Q4);
+
+        // (x_3 + x_1 + β·x_2)·(β·x_2 - x_1)² - (±y_2 - y_1)², expanded by powers of β
+        let beta_term = -x_2 * x_1 * (x_3.double() + x_1) * q_beta;
+        let beta_sqr_term = x_2.square() * (x_3 - x_1) * q_beta_sqr;
+        let sign_term = (y_2 * y_1).double() * q_sign;
+        let leftovers =
+            x_2.square() * x_2 + x_1.square() * (x_3 + x_1) - y_2.square() - y_1.square();
+        let x_identity = beta_term + beta_sqr_term + sign_term + leftovers;
+
+        // (y_3 + y_1)·(β·x_2 - x_1) - (±y_2 - y_1)·(x_1 - x_3)
+        let y_identity = x_2 * (y_3 + y_1) * q_beta - y_2 * (x_1 - x_3) * q_sign
+            + y_1 * (x_1 - x_3)
+            - x_1 * (y_3 + y_1);
+
+        linear_terms[0.into()] =
+            x_identity * challenges.alpha_powers[0] + y_identity * challenges.alpha_powers[1];
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U2>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let q_elliptic = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QElliptic,
+            Some(index),
+        );
+        linear_terms[0.into()] * q_elliptic
+    }
+
+    /// The elliptic relation is linear in q_elliptic, so there is nothing to add here.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U2>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U2>,
+        _quotient_term: &mut F,
+        _index: usize,
+    ) {
+    }
+
+    /// q_elliptic is opened, so the batched opening already covers it with its own ν power.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U2>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the elliptic addition gate to the quotient polynomial over the 4n coset.
+pub(crate) type ProverUltraEllipticWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U2, UltraEllipticKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the elliptic addition gate at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierUltraEllipticWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U2>,
+        U2,
+        S,
+        UltraEllipticKernel<H, C::Fr, S>,
+    > for VerifierUltraEllipticWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, grumpkin_point_from, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        proof_system::arithmetization::gate_data::EccAddGate,
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::{One, Zero};
+
+    type Kernel = UltraEllipticKernel<Keccak256, Fr, TestSettings>;
+
+    fn add(p: (Fr, Fr), q: (Fr, Fr)) -> (Fr, Fr) {
+        let lambda = (q.1 - p.1) / (q.0 - p.0);
+        let x = lambda.square() - p.0 - q.0;
+        (x, lambda * (p.0 - x) - p.1)
+    }
+
+    /// A primitive cube root of unity, `(-1 + √-3) / 2`.
+    fn cube_root_of_unity() -> Fr {
+        ((-Fr::from(3u64)).sqrt().unwrap() - Fr::one()) / Fr::from(2u64)
+    }
+
+    fn gate(endomorphism_coefficient: Fr, sign_coefficient: Fr) -> EccAddGate<Fr> {
+        EccAddGate {
+            x1: 0,
+            y1: 1,
+            x2: 2,
+            y2: 3,
+            x3: 4,
+            y3: 5,
+            endomorphism_coefficient,
+            sign_coefficient,
+        }
+    }
+
+    /// The two rows of `gate` adding `p` and `q` into `r`.
+    fn openings(gate: &EccAddGate<Fr>, p: (Fr, Fr), q: (Fr, Fr), r: (Fr, Fr)) -> PolyArray<Fr> {
+        let [q_sign, q_beta, q_beta_sqr] = gate.selectors();
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QElliptic].0 = Fr::one();
+        polynomials[PolynomialIndex::Q1].0 = q_sign;
+        polynomials[PolynomialIndex::Q3].0 = q_beta;
+        polynomials[PolynomialIndex::Q4].0 = q_beta_sqr;
+        polynomials[PolynomialIndex::W2].0 = p.0;
+        polynomials[PolynomialIndex::W3].0 = p.1;
+        polynomials[PolynomialIndex::W1].1 = q.0;
+        polynomials[PolynomialIndex::W4].1 = q.1;
+        polynomials[PolynomialIndex::W2].1 = r.0;
+        polynomials[PolynomialIndex::W3].1 = r.1;
+        polynomials
+    }
+
+    #[test]
+    fn additions_and_subtractions_vanish() {
+        let p = grumpkin_point_from(Fr::from(5u64));
+        let q = grumpkin_point_from(Fr::from(1000u64));
+
+        let addition = gate(Fr::one(), Fr::one());
+        assert!(
+            evaluate::<Kernel, _>(&openings(&addition, p, q, add(p, q)), &challenges(9)).is_zero()
+        );
+
+        let subtraction = gate(Fr::one(), -Fr::one());
+        let sum = add(p, (q.0, -q.1));
+        assert!(
+            evaluate::<Kernel, _>(&openings(&subtraction, p, q, sum), &challenges(9)).is_zero()
+        );
+    }
+
+    #[test]
+    fn endomorphism_scaled_additions_vanish() {
+        let beta = cube_root_of_unity();
+        assert!(beta != Fr::one() && (beta.square() * beta).is_one());
+
+        let p = grumpkin_point_from(Fr::from(5u64));
+        let q = grumpkin_point_from(Fr::from(1000u64));
+        for sign in [Fr::one(), -Fr::one()] {
+            let endomorphism = gate(beta, sign);
+            let sum = add(p, (beta * q.0, sign * q.1));
+            assert!(
+                evaluate::<Kernel, _>(&openings(&endomorphism, p, q, sum), &challenges(9))
+                    .is_zero()
+            );
+        }
+    }
+
+    #[test]
+    fn wrong_sums_are_caught_by_each_identity() {
+        let p = grumpkin_point_from(Fr::from(5u64));
+        let q = grumpkin_point_from(Fr::from(1000u64));
+        let r = add(p, q);
+        let addition = gate(Fr::one(), Fr::one());
+
+        // the x identity has no α, so a wrong y_3 alone shows up only under α
+        let wrong_y = openings(&addition, p, q, (r.0, r.1 + Fr::one()));
+        let with_alpha = evaluate::<Kernel, _>(&wrong_y, &challenges(9));
+        let without_alpha = evaluate::<Kernel, _>(&wrong_y, &challenges(0));
+        assert!(!with_alpha.is_zero() && without_alpha.is_zero());
+
+        let wrong_x = openings(&addition, p, q, (r.0 + Fr::one(), r.1));
+        assert!(!evaluate::<Kernel, _>(&wrong_x, &challenges(0)).is_zero());
+
+        // adding with the wrong sign is not the same gate
+        let subtraction = gate(Fr::one(), -Fr::one());
+        assert!(!evaluate::<Kernel, _>(&openings(&subtraction, p, q, r), &challenges(9)).is_zero());
+    }
+}
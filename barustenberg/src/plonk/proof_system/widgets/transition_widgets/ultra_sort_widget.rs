@@ -0,0 +1,231 @@
+use ark_ff::Field;
+use typenum::U4;
+
+use crate::{
+    ecc::curves::CurveConfig,
+    plonk::proof_system::types::{
+        polynomial_manifest::{EvaluationType, PolynomialIndex},
+        prover_settings::Settings,
+    },
+    transcript::BarretenHasher,
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use super::{
+    containers::{ChallengeArray, CoefficientArray, PolyContainer, CHALLENGE_BIT_ALPHA},
+    getters::{BaseGetter, EvaluationGetterImpl},
+    transition_widget::{GenericVerifierWidget, KernelBase, TransitionWidget},
+    turbo_range_widget::quad_range_check,
+};
+
+/// Delta range constraint over a sorted list that runs through the wires of a row in the order
+/// `w_1, w_2, w_3, w_4` and on into `w_1` of the next row. Every adjacent difference must lie in
+/// `{0, 1, 2, 3}`, so a sorted list that starts at 0, ends at a bound `b` and is a permutation of the values
+/// being checked (through the tagged copy constraints) range constrains them all to `[0, b]`.
+pub(crate) struct UltraSortKernel<H: BarretenHasher, F: Field, S: Settings<H>> {
+    _marker: PhantomData<(H, F, S)>,
+}
+
+impl<H: BarretenHasher, F, S: Settings<H>> UltraSortKernel<H, F, S>
+where
+    F: Field,
+{
+    pub(crate) const QUOTIENT_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+    pub(crate) const UPDATE_REQUIRED_CHALLENGES: u8 = CHALLENGE_BIT_ALPHA as u8;
+}
+
+impl<H: BarretenHasher, F: Field, S: Settings<H>> KernelBase<H, S, F, U4>
+    for UltraSortKernel<H, F, S>
+{
+    #[inline]
+    fn get_required_polynomial_ids() -> HashSet<PolynomialIndex> {
+        HashSet::from([
+            PolynomialIndex::QSort,
+            PolynomialIndex::W1,
+            PolynomialIndex::W2,
+            PolynomialIndex::W3,
+            PolynomialIndex::W4,
+        ])
+    }
+
+    #[inline]
+    fn quotient_required_challenges() -> u8 {
+        Self::QUOTIENT_REQUIRED_CHALLENGES
+    }
+
+    #[inline]
+    fn update_required_challenges() -> u8 {
+        Self::UPDATE_REQUIRED_CHALLENGES
+    }
+
+    /// Sums the four difference checks, each under its own power of α. The result is the coefficient of q_sort.
+    #[inline]
+    fn compute_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        challenges: &ChallengeArray<F, U4>,
+        linear_terms: &mut CoefficientArray<F>,
+        index: Option<usize>,
+    ) {
+        let index = Some(index.unwrap_or_default());
+        let w_1 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W1,
+            index,
+        );
+        let w_2 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W2,
+            index,
+        );
+        let w_3 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W3,
+            index,
+        );
+        let w_4 = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::W4,
+            index,
+        );
+        let w_1_omega = G::get_value(
+            polynomials,
+            EvaluationType::Shifted,
+            PolynomialIndex::W1,
+            index,
+        );
+
+        let deltas = [w_2 - w_1, w_3 - w_2, w_4 - w_3, w_1_omega - w_4];
+
+        let mut result = F::zero();
+        for (delta, alpha) in deltas.into_iter().zip(challenges.alpha_powers.iter()) {
+            result += quad_range_check(delta) * alpha;
+        }
+        linear_terms[0.into()] = result;
+    }
+
+    #[inline]
+    fn sum_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        linear_terms: &CoefficientArray<F>,
+        index: usize,
+    ) -> F {
+        let q_sort = G::get_value(
+            polynomials,
+            EvaluationType::NonShifted,
+            PolynomialIndex::QSort,
+            Some(index),
+        );
+        linear_terms[0.into()] * q_sort
+    }
+
+    /// The sort relation is linear in q_sort, so there is nothing to add here.
+    #[inline]
+    fn compute_non_linear_terms<PC: PolyContainer<F>, G: BaseGetter<H, F, S, PC, U4>>(
+        _polynomials: &PC,
+        _challenges: &ChallengeArray<F, U4>,
+        _quotient_term: &mut F,
+        _index: usize,
+    ) {
+    }
+
+    /// q_sort is opened, so the batched opening already covers it with its own ν power.
+    fn update_kate_opening_scalars(
+        _linear_terms: &CoefficientArray<F>,
+        _scalars: &mut HashMap<String, F>,
+        _challenges: &ChallengeArray<F, U4>,
+    ) {
+    }
+}
+
+/// Computes the contribution of the delta range constraint to the quotient polynomial over the 4n coset.
+pub(crate) type ProverUltraSortWidget<'a, H, C, S> =
+    TransitionWidget<'a, H, C, S, U4, UltraSortKernel<H, <C as CurveConfig>::Fr, S>>;
+
+/// Evaluates the delta range constraint at the challenge ʓ from the openings in the transcript.
+pub(crate) struct VerifierUltraSortWidget<H: BarretenHasher, C: CurveConfig, S: Settings<H>> {
+    phantom: PhantomData<(H, C, S)>,
+}
+
+impl<'a, H, C, S>
+    GenericVerifierWidget<
+        'a,
+        C,
+        H,
+        EvaluationGetterImpl<H, C::Fr, S, U4>,
+        U4,
+        S,
+        UltraSortKernel<H, C::Fr, S>,
+    > for VerifierUltraSortWidget<H, C, S>
+where
+    H: BarretenHasher,
+    C: CurveConfig,
+    S: Settings<H>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        plonk::proof_system::widgets::{
+            test_utils::{challenges, evaluate, TestSettings},
+            transition_widgets::containers::PolyArray,
+        },
+        transcript::Keccak256,
+    };
+    use ark_bn254::Fr;
+    use ark_ff::Zero;
+
+    type Kernel = UltraSortKernel<Keccak256, Fr, TestSettings>;
+
+    /// A sort gate row holding `values` followed by `next` in w_1 of the next row.
+    fn openings(values: [u64; 4], next: u64) -> PolyArray<Fr> {
+        let mut polynomials = PolyArray::default();
+        polynomials[PolynomialIndex::QSort].0 = Fr::from(1u64);
+        polynomials[PolynomialIndex::W1].0 = Fr::from(values[0]);
+        polynomials[PolynomialIndex::W2].0 = Fr::from(values[1]);
+        polynomials[PolynomialIndex::W3].0 = Fr::from(values[2]);
+        polynomials[PolynomialIndex::W4].0 = Fr::from(values[3]);
+        polynomials[PolynomialIndex::W1].1 = Fr::from(next);
+        polynomials
+    }
+
+    #[test]
+    fn sorted_lists_with_small_steps_vanish() {
+        let challenges = challenges(7);
+        assert!(evaluate::<Kernel, _>(&openings([0, 0, 1, 4], 7), &challenges).is_zero());
+        assert!(evaluate::<Kernel, _>(&openings([10, 12, 12, 13], 13), &challenges).is_zero());
+    }
+
+    #[test]
+    fn large_steps_are_caught_under_their_alpha_power() {
+        // 0 → 1 → 5: the second step is 4, caught as 4·3·2·1 = 24 under α
+        assert_eq!(
+            evaluate::<Kernel, _>(&openings([0, 1, 5, 5], 5), &challenges(7)),
+            Fr::from(24u64 * 7)
+        );
+        // the step into the next row is checked under α³
+        assert_eq!(
+            evaluate::<Kernel, _>(&openings([0, 1, 2, 3], 7), &challenges(7)),
+            Fr::from(24u64 * 7 * 7 * 7)
+        );
+        // and a list that goes down is not sorted
+        assert!(!evaluate::<Kernel, _>(&openings([3, 2, 2, 2], 2), &challenges(7)).is_zero());
+    }
+
+    #[test]
+    fn gates_without_the_selector_are_ignored() {
+        let mut polynomials = openings([0, 9, 0, 9], 0);
+        polynomials[PolynomialIndex::QSort].0 = Fr::zero();
+        assert!(evaluate::<Kernel, _>(&polynomials, &challenges(7)).is_zero());
+    }
+}
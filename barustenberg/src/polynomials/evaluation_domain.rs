@@ -1,11 +1,11 @@
 use ark_ff::{FftField, Field};
 
 use crate::numeric::bitop::Msb;
-use std::vec::Vec;
+use std::{marker::PhantomData, vec::Vec};
 
 pub(crate) const MIN_GROUP_PER_THREAD: usize = 4;
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub(crate) struct EvaluationDomain<'a, F: Field + FftField> {
     /// n, always a power of 2
     pub(crate) size: usize,
@@ -27,20 +27,19 @@ pub(crate) struct EvaluationDomain<'a, F: Field + FftField> {
     pub(crate) generator: F,
     pub(crate) generator_inverse: F,
     pub(crate) four_inverse: F,
-    /// An entry for each of the log(n) rounds: each entry is a pointer to
-    /// the subset of the roots of unity required for that fft round.
-    /// E.g. round_roots[0] = [1, ω^(n/2 - 1)],
-    ///      round_roots[1] = [1, ω^(n/4 - 1), ω^(n/2 - 1), ω^(3n/4 - 1)]
+    /// An entry for each of the log(n) - 1 rounds after the first: the subset of the roots of unity required
+    /// for that fft round, i.e. the first m = 2^(i + 1) powers of the 2m'th root of unity.
+    /// E.g. round_roots[0] = [1, ω^(n/4)],
+    ///      round_roots[1] = [1, ω^(n/8), ω^(n/4), ω^(3n/8)]
     ///      ...
-    pub(crate) round_roots: &'a [&'a [F]],
-    pub(crate) inverse_round_roots: &'a [&'a [F]],
+    /// Empty until `compute_lookup_table` is called.
+    pub(crate) round_roots: Vec<Vec<F>>,
+    pub(crate) inverse_round_roots: Vec<Vec<F>>,
+    pub(crate) phantom: PhantomData<&'a F>,
 }
 
 fn compute_num_threads(size: usize) -> usize {
-    #[cfg(feature = "multithreading")]
     let num_threads = crate::common::max_threads::compute_num_threads();
-    #[cfg(not(feature = "multithreading"))]
-    let num_threads = 1;
     if size <= num_threads * MIN_GROUP_PER_THREAD {
         1
     } else {
@@ -100,63 +99,77 @@ fn compute_lookup_table_single<Fr: Field>(
     }
 }
 impl<'a, F: Field + FftField> EvaluationDomain<'a, F> {
-    pub(crate) fn new(domain_size: usize, _target_generator_size: Option<usize>) -> Self {
-        // TODO: implement constructor logic
-
+    pub(crate) fn new(domain_size: usize, target_generator_size: Option<usize>) -> Self {
+        if domain_size == 0 {
+            return Self::default();
+        }
         let size = domain_size;
         let num_threads = compute_num_threads(size);
         let thread_size = size / num_threads;
-        let _log2_size = size.get_msb();
-        let _log2_thread_size = thread_size.get_msb();
-        let _log2_num_threads = num_threads.get_msb();
-        // let root = F::get_root_of_unity(log2_size);
-        // let domain = F::new(size, 0,0,0).to_montgomery_form();
-        // let domain_inverse = domain.inverse().unwrap();
-        // let generator = F::coset_generator(0);
-        // let generator_inverse = generator.inverse().unwrap();
-        // let four_inverse = F::from(4).inverse().unwrap();
-        // let roots = None;
+        let log2_size = size.get_msb();
+        let log2_thread_size = thread_size.get_msb();
+        let log2_num_threads = num_threads.get_msb();
+        assert_eq!(1 << log2_size, size, "domain size must be a power of two");
+        assert_eq!(1 << log2_thread_size, thread_size);
+        assert_eq!(1 << log2_num_threads, num_threads);
 
-        todo!("fix ");
-        // assert!((1UL << log2_size) == size || (size == 0));
-        // assert!((1UL << log2_thread_size) == thread_size || (size == 0));
-        // assert!((1UL << log2_num_threads) == num_threads || (size == 0));
+        let root = F::get_root_of_unity(size as u64)
+            .unwrap_or_else(|| panic!("the field has no root of unity of order {}", size));
+        let domain = F::from(size as u64);
+        let generator = F::GENERATOR;
 
-        // EvaluationDomain { size: size,
-        //     num_threads,
-        //     thread_size,
-        //     log2_size,
-        //     log2_thread_size,
-        //     log2_num_threads,
-        //     // TODO original was generator_size(target_generator_size ? target_generator_size : domain_size)- check me
-        //     generator_size: if target_generator_size == 0 { size } else { target_generator_size },
-        //     root,
-        //     root_inverse: root.inverse().unwrap(),
-        //     domain,
-        //     domain_inverse,
-        //     generator,
-        //     generator_inverse,
-        //     four_inverse,
-        //     roots,
-        //     round_roots: None,
-        //     inverse_round_roots: None,
-        // }
+        EvaluationDomain {
+            size,
+            num_threads,
+            thread_size,
+            log2_size,
+            log2_thread_size,
+            log2_num_threads,
+            generator_size: target_generator_size.unwrap_or(size),
+            root,
+            root_inverse: root.inverse().unwrap(),
+            domain,
+            domain_inverse: domain.inverse().unwrap(),
+            generator,
+            generator_inverse: generator.inverse().unwrap(),
+            four_inverse: F::from(4u64).inverse().unwrap(),
+            round_roots: Vec::new(),
+            inverse_round_roots: Vec::new(),
+            phantom: PhantomData,
+        }
     }
 
+    /// Precompute the per-round roots of unity the FFTs read, for both the forward and the inverse transform.
     pub(crate) fn compute_lookup_table(&mut self) {
-        // TODO: implement compute_lookup_table logic
+        self.round_roots = Self::round_roots_of(&self.root, self.size);
+        self.inverse_round_roots = Self::round_roots_of(&self.root_inverse, self.size);
+    }
+
+    fn round_roots_of(input_root: &F, size: usize) -> Vec<Vec<F>> {
+        if size < 4 {
+            // the only round of a size 2 fft multiplies by 1
+            return Vec::new();
+        }
+        let mut roots = vec![F::zero(); size];
+        let mut offsets = Vec::new();
+        compute_lookup_table_single(input_root, size, &mut roots, &mut offsets);
+        offsets
+            .iter()
+            .enumerate()
+            .map(|(i, &offset)| roots[offset..offset + (1 << (i + 1))].to_vec())
+            .collect()
     }
 
     pub(crate) fn compute_generator_table(&mut self, _target_generator_size: usize) {
         // TODO: implement compute_generator_table logic
     }
 
-    pub(crate) fn get_round_roots(&self) -> &[&[F]] {
-        self.round_roots
+    pub(crate) fn get_round_roots(&self) -> &[Vec<F>] {
+        &self.round_roots
     }
 
-    pub(crate) fn get_inverse_round_roots(&self) -> &[&[F]] {
-        self.inverse_round_roots
+    pub(crate) fn get_inverse_round_roots(&self) -> &[Vec<F>] {
+        &self.inverse_round_roots
     }
 }
 
@@ -1,4 +1,5 @@
 pub(crate) mod evaluation_domain;
 pub(crate) mod polynomial;
 pub(crate) mod polynomial_arithmetic;
+pub(crate) mod univariate;
 pub(crate) use polynomial::Polynomial;
@@ -41,6 +41,14 @@ impl<F: Field> Polynomial<F> {
         }
     }
     #[inline]
+    pub(crate) fn from_coefficients(coefficients: Vec<F>, size: usize) -> Self {
+        Self {
+            size,
+            coefficients,
+            phantom: PhantomData,
+        }
+    }
+    #[inline]
     pub(crate) fn size(&self) -> usize {
         self.size
     }
@@ -52,6 +60,30 @@ impl<F: Field> Polynomial<F> {
     pub(crate) fn resize(&mut self, new_len: usize, val: F) {
         self.coefficients.resize(new_len, val)
     }
+
+    /// Evaluates the multilinear extension of the coefficients, read as values on the boolean hypercube with
+    /// variable `l` in bit `l` of the index. With `shift` set the coefficients are first moved down by one, as
+    /// if the polynomial had been divided by X.
+    pub(crate) fn evaluate_mle(&self, point: &[F], shift: bool) -> F {
+        let n = 1 << point.len();
+        let offset = shift as usize;
+        let mut tmp: Vec<F> = (0..n)
+            .map(|i| {
+                self.coefficients
+                    .get(i + offset)
+                    .copied()
+                    .unwrap_or_else(F::zero)
+            })
+            .collect();
+        let mut m = n;
+        for u in point {
+            m >>= 1;
+            for i in 0..m {
+                tmp[i] = tmp[2 * i] + *u * (tmp[2 * i + 1] - tmp[2 * i]);
+            }
+        }
+        tmp[0]
+    }
 }
 
 impl<F: Field> AddAssign for Polynomial<F> {
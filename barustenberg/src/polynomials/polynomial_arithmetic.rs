@@ -1,7 +1,14 @@
 use anyhow::ensure;
 use ark_ff::{FftField, Field};
 
-use crate::{common::max_threads::compute_num_threads, numeric::bitop::Msb};
+use crate::{
+    common::{
+        max_threads::compute_num_threads,
+        thread::{parallel_for_chunks_mut, parallel_for_chunks_mut_pair, parallel_map},
+    },
+    ecc::batch::batch_invert,
+    numeric::bitop::Msb,
+};
 
 #[inline]
 fn reverse_bits(x: u32, bit_length: u32) -> u32 {
@@ -97,18 +104,86 @@ fn fft_inner_serial<Fr: Copy + Default + Add<Output = Fr> + Sub<Output = Fr> + M
     }
 }
 
+#[inline]
+fn butterfly<Fr: Field>(lo: &mut [Fr], hi: &mut [Fr], round_roots: &[Fr]) {
+    for ((a, b), root) in lo.iter_mut().zip(hi.iter_mut()).zip(round_roots) {
+        let temp = *root * *b;
+        *b = *a - temp;
+        *a += temp;
+    }
+}
+
+/// One radix-2 FFT round: for every block of `2m` elements, butterfly `data[k + j]` with
+/// `data[k + j + m]` using the j'th root of this round.
+/// Work is handed out in pieces of `chunk_size`; when a block is bigger than that we split
+/// the two halves of the block instead, so late rounds still use every thread.
+fn fft_round<Fr: Field>(data: &mut [Fr], m: usize, round_roots: &[Fr], chunk_size: usize) {
+    if 2 * m <= chunk_size {
+        parallel_for_chunks_mut(data, chunk_size, |_, chunk| {
+            for block in chunk.chunks_mut(2 * m) {
+                let (lo, hi) = block.split_at_mut(m);
+                butterfly(lo, hi, round_roots);
+            }
+        });
+    } else {
+        let half_chunk_size = chunk_size >> 1;
+        for block in data.chunks_mut(2 * m) {
+            let (lo, hi) = block.split_at_mut(m);
+            parallel_for_chunks_mut_pair(lo, hi, half_chunk_size, |j, lo, hi| {
+                butterfly(lo, hi, &round_roots[j * half_chunk_size..]);
+            });
+        }
+    }
+}
+
 impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
     /// modifies target[..generator_size]
     fn scale_by_generator(
         &self,
-        _coeffs: &mut [Fr],
-        _target: &mut [Fr],
-        _generator_start: Fr,
-        _generator_shift: Fr,
+        coeffs: &mut [Fr],
+        target: &mut [Fr],
+        generator_start: Fr,
+        generator_shift: Fr,
+        generator_size: usize,
+    ) {
+        let generator_size_per_thread = std::cmp::max(generator_size / self.num_threads, 1);
+        let coeffs = &*coeffs;
+        parallel_for_chunks_mut(
+            &mut target[..generator_size],
+            generator_size_per_thread,
+            |j, chunk| {
+                let offset = j * generator_size_per_thread;
+                let thread_shift = generator_shift.pow([offset as u64]);
+                let mut work_generator = generator_start * thread_shift;
+                for (i, target_i) in chunk.iter_mut().enumerate() {
+                    *target_i = coeffs[offset + i] * work_generator;
+                    work_generator *= generator_shift;
+                }
+            },
+        );
+    }
+
+    /// Multiplies `coeffs[i]` by `generator_start.generator_shift^i` for every i < `generator_size`.
+    fn scale_by_generator_inplace(
+        &self,
+        coeffs: &mut [Fr],
+        generator_start: Fr,
+        generator_shift: Fr,
         generator_size: usize,
     ) {
-        let _generator_size_per_thread = generator_size / self.num_threads;
-        todo!("parallelism");
+        let generator_size_per_thread = std::cmp::max(generator_size / self.num_threads, 1);
+        parallel_for_chunks_mut(
+            &mut coeffs[..generator_size],
+            generator_size_per_thread,
+            |j, chunk| {
+                let offset = j * generator_size_per_thread;
+                let mut work_generator = generator_start * generator_shift.pow([offset as u64]);
+                for coeff in chunk {
+                    *coeff *= work_generator;
+                    work_generator *= generator_shift;
+                }
+            },
+        );
     }
 
     /// Compute multiplicative subgroup (g.X)^n.
@@ -126,7 +201,7 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         let subgroup_size = 1 << log2_subgroup_size;
 
         // Step 1: get primitive 4th root of unity
-        let subgroup_root = Fr::get_root_of_unity(log2_subgroup_size as u64)
+        let subgroup_root = Fr::get_root_of_unity(subgroup_size as u64)
             .ok_or_else(|| anyhow::anyhow!("Failed to find root of unity"))?;
 
         // Step 2: compute the cofactor term g^n
@@ -143,12 +218,11 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         Ok(())
     }
 
-    // TODO readd pragma omp parallel
     pub(crate) fn fft_inner_parallel_vec_inplace(
         &self,
         coeffs: &mut [&mut [Fr]],
         _fr: &Fr,
-        root_table: &[&[Fr]],
+        root_table: &[Vec<Fr>],
     ) {
         //let scratch_space = Self::get_scratch_space(self.size); // Implement the get_scratch_space function
 
@@ -163,26 +237,22 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
 
         // First FFT round is a special case - no need to multiply by root table, because all entries are 1.
         // We also combine the bit reversal step into the first round, to avoid a redundant round of copying data
-        for j in 0..self.num_threads {
-            let mut temp_1;
-            let mut temp_2;
-            for i in (j * self.thread_size..(j + 1) * self.thread_size).step_by(2) {
-                //let next_index_1 = reverse_bits((i + 2) as u32, self.log2_size as u32) as usize;
-                //let next_index_2 = reverse_bits((i + 3) as u32, self.log2_size as u32) as usize;
-                // TODO builtin prefetch stuff here
-                let swap_index_1 = reverse_bits(i as u32, self.log2_size as u32) as usize;
-                let swap_index_2 = reverse_bits((i + 1) as u32, self.log2_size as u32) as usize;
-
-                let poly_idx_1 = swap_index_1 >> log2_poly_size;
-                let elem_idx_1 = swap_index_1 & poly_mask;
-                let poly_idx_2 = swap_index_2 >> log2_poly_size;
-                let elem_idx_2 = swap_index_2 & poly_mask;
-
-                temp_1 = coeffs[poly_idx_1][elem_idx_1];
-                temp_2 = coeffs[poly_idx_2][elem_idx_2];
-                scratch_space[i + 1] = temp_1 - temp_2;
-                scratch_space[i] = temp_1 + temp_2;
-            }
+        {
+            let coeffs = &*coeffs;
+            parallel_for_chunks_mut(&mut scratch_space, self.thread_size, |j, chunk| {
+                let offset = j * self.thread_size;
+                for (k, pair) in chunk.chunks_mut(2).enumerate() {
+                    let i = offset + 2 * k;
+                    // TODO builtin prefetch stuff here
+                    let swap_index_1 = reverse_bits(i as u32, self.log2_size as u32) as usize;
+                    let swap_index_2 = reverse_bits((i + 1) as u32, self.log2_size as u32) as usize;
+
+                    let temp_1 = coeffs[swap_index_1 >> log2_poly_size][swap_index_1 & poly_mask];
+                    let temp_2 = coeffs[swap_index_2 >> log2_poly_size][swap_index_2 & poly_mask];
+                    pair[1] = temp_1 - temp_2;
+                    pair[0] = temp_1 + temp_2;
+                }
+            });
         }
 
         // hard code exception for when the domain size is tiny - we won't execute the next loop, so need to manually
@@ -190,122 +260,74 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         if self.size <= 2 {
             coeffs[0][0] = scratch_space[0];
             coeffs[0][1] = scratch_space[1];
+            return;
         }
-        // Outer FFT loop - iterates over the FFT rounds
-        for m in (2..=self.size).step_by(2) {
-            for j in 0..self.num_threads {
-                let mut temp: Fr;
-
-                // Ok! So, what's going on here? This is the inner loop of the FFT algorithm, and we want to break it
-                // out into multiple independent threads. For `num_threads`, each thread will evaluation `domain.size /
-                // num_threads` of the polynomial. The actual iteration length will be half of this, because we leverage
-                // the fact that \omega^{n/2} = -\omega (where \omega is a root of unity)
-
-                // Here, `start` and `end` are used as our iterator limits, so that we can use our iterator `i` to
-                // directly access the roots of unity lookup table
-                let start = j * (self.thread_size >> 1);
-                let end = (j + 1) * (self.thread_size >> 1);
-
-                // For all but the last round of our FFT, the roots of unity that we need, will be a subset of our
-                // lookup table. e.g. for a size 2^n FFT, the 2^n'th roots create a multiplicative subgroup of order 2^n
-                //      the 1st round will use the roots from the multiplicative subgroup of order 2 : the 2'th roots of
-                //      unity the 2nd round will use the roots from the multiplicative subgroup of order 4 : the 4'th
-                //      roots of unity
-                // i.e. each successive FFT round will double the set of roots that we need to index.
-                // We have already laid out the `root_table` container so that each FFT round's roots are linearly
-                // ordered in memory. For all FFT rounds, the number of elements we're iterating over is greater than
-                // the size of our lookup table. We need to access this table in a cyclical fasion - i.e. for a subgroup
-                // of size x, the first x iterations will index the subgroup elements in order, then for the next x
-                // iterations, we loop back to the start.
-
-                // We could implement the algorithm by having 2 nested loops (where the inner loop iterates over the
-                // root table), but we want to flatten this out - as for the first few rounds, the inner loop will be
-                // tiny and we'll have quite a bit of unneccesary branch checks For each iteration of our flattened
-                // loop, indexed by `i`, the element of the root table we need to access will be `i % (current round
-                // subgroup size)` Given that each round subgroup size is `m`, which is a power of 2, we can index the
-                // root table with a very cheap `i & (m - 1)` Which is why we have this odd `block_mask` variable
-
-                let block_mask = m - 1;
-
-                // The next problem to tackle, is we now need to efficiently index the polynomial element in
-                // `scratch_space` in our flattened loop If we used nested loops, the outer loop (e.g. `y`) iterates
-                // from 0 to 'domain size', in steps of 2 * m, with the inner loop (e.g. `z`) iterating from 0 to m. We
-                // have our inner loop indexer with `i & (m - 1)`. We need to add to this our outer loop indexer, which
-                // is equivalent to taking our indexer `i`, masking out the bits used in the 'inner loop', and doubling
-                // the result. i.e. polynomial indexer = (i & (m - 1)) + ((i & ~(m - 1)) >> 1) To simplify this, we
-                // cache index_mask = ~block_mask, meaning that our indexer is just `((i & index_mask) << 1 + (i &
-                // block_mask)`
-
-                let index_mask = !block_mask;
-
-                // `round_roots` fetches the pointer to this round's lookup table. We use `numeric::get_msb(m) - 1` as
-                // our indexer, because we don't store the precomputed root values for the 1st round (because they're
-                // all 1).
-
-                let round_roots = root_table[((m.trailing_zeros() - 1) as usize)];
-
-                // Finally, we want to treat the final round differently from the others,
-                // so that we can reduce out of our 'coarse' reduction and store the output in `coeffs` instead of
-                // `scratch_space`
-
-                if m != (self.size >> 1) {
-                    for i in start..end {
-                        let k1 = (i & index_mask) << 1;
-                        let j1 = i & block_mask;
-                        temp = round_roots[j1] * scratch_space[k1 + j1 + m];
-                        scratch_space[k1 + j1 + m] = scratch_space[k1 + j1] - temp;
-                        scratch_space[k1 + j1] += temp;
-                    }
-                } else {
-                    for i in start..end {
-                        let k1 = (i & index_mask) << 1;
-                        let j1 = i & block_mask;
-
-                        let poly_idx_1 = (k1 + j1) >> log2_poly_size;
-                        let elem_idx_1 = (k1 + j1) & poly_mask;
-                        let poly_idx_2 = (k1 + j1 + m) >> log2_poly_size;
-                        let elem_idx_2 = (k1 + j1 + m) & poly_mask;
-
-                        temp = round_roots[j1] * scratch_space[k1 + j1 + m];
-                        coeffs[poly_idx_2][elem_idx_2] = scratch_space[k1 + j1] - temp;
-                        coeffs[poly_idx_1][elem_idx_1] = scratch_space[k1 + j1] + temp;
-                    }
+
+        // Outer FFT loop - iterates over the FFT rounds. Round `m` combines pairs of size-m sub-FFTs, using the
+        // 2m'th roots of unity. `round_roots` fetches this round's lookup table; we use `get_msb(m) - 1` as the
+        // index because we don't store the precomputed roots for the 1st round (they're all 1).
+        //
+        // Each round is split into independent blocks of butterflies (see `fft_round`), which is what lets us
+        // hand them out across threads.
+        let last_round = self.size >> 1;
+        let mut m = 2;
+        while m < last_round {
+            fft_round(
+                &mut scratch_space,
+                m,
+                &root_table[m.get_msb() - 1],
+                self.thread_size,
+            );
+            m <<= 1;
+        }
+
+        // We treat the final round differently from the others, so that we can store the output in `coeffs`
+        // instead of `scratch_space`. In the last round there is a single block, so element `i` of the output
+        // only depends on scratch_space[i] and its partner i +/- m.
+        let m = last_round;
+        let round_roots = &root_table[m.get_msb() - 1];
+        let scratch_space = &scratch_space;
+        for (poly_idx, poly) in coeffs.iter_mut().enumerate() {
+            parallel_for_chunks_mut(&mut poly[..poly_size], self.thread_size, |j, chunk| {
+                let offset = (poly_idx << log2_poly_size) + j * self.thread_size;
+                for (k, coeff) in chunk.iter_mut().enumerate() {
+                    let i = offset + k;
+                    *coeff = if i < m {
+                        scratch_space[i] + round_roots[i] * scratch_space[i + m]
+                    } else {
+                        scratch_space[i - m] - round_roots[i - m] * scratch_space[i]
+                    };
                 }
-            }
+            });
         }
     }
 
-    // TODO readd pragma omp parallel
     pub(crate) fn fft_inner_parallel(
         &self,
         coeffs: &mut [Fr],
         target: &mut [Fr],
         _fr: &Fr,
-        root_table: &[&[Fr]],
+        root_table: &[Vec<Fr>],
     ) {
-        // TODO parallelize
         // First FFT round is a special case - no need to multiply by root table, because all entries are 1.
         // We also combine the bit reversal step into the first round, to avoid a redundant round of copying data
-        (0..self.num_threads).for_each(|j| {
-            let mut temp_1;
-            let mut temp_2;
-            let thread_start = j * self.thread_size;
-            let thread_end = (j + 1) * self.thread_size;
-            for i in (thread_start..thread_end).step_by(2) {
-                //let next_index_1 = reverse_bits((i + 2) as u32, self.log2_size as u32) as usize;
-                //let next_index_2 = reverse_bits((i + 3) as u32, self.log2_size as u32) as usize;
-
-                // TODO builtin prefetch :|
-
-                let swap_index_1 = reverse_bits(i as u32, self.log2_size as u32) as usize;
-                let swap_index_2 = reverse_bits((i + 1) as u32, self.log2_size as u32) as usize;
-
-                temp_1 = coeffs[swap_index_1];
-                temp_2 = coeffs[swap_index_2];
-                target[i + 1] = temp_1 - temp_2;
-                target[i] = temp_1 + temp_2;
-            }
-        });
+        {
+            let coeffs = &*coeffs;
+            parallel_for_chunks_mut(&mut target[..self.size], self.thread_size, |j, chunk| {
+                let offset = j * self.thread_size;
+                for (k, pair) in chunk.chunks_mut(2).enumerate() {
+                    let i = offset + 2 * k;
+                    // TODO builtin prefetch :|
+                    let swap_index_1 = reverse_bits(i as u32, self.log2_size as u32) as usize;
+                    let swap_index_2 = reverse_bits((i + 1) as u32, self.log2_size as u32) as usize;
+
+                    let temp_1 = coeffs[swap_index_1];
+                    let temp_2 = coeffs[swap_index_2];
+                    pair[1] = temp_1 - temp_2;
+                    pair[0] = temp_1 + temp_2;
+                }
+            });
+        }
 
         // hard code exception for when the domain size is tiny - we won't execute the next loop, so need to manually
         // reduce + copy
@@ -315,31 +337,24 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         }
 
         // outer FFT loop
-        // TODO this is super incorrect
-        for m in (2..self.size).step_by(2) {
-            (0..self.num_threads).for_each(|j| {
-                let mut temp;
-
-                let start = j * (self.thread_size >> 1);
-                let end = (j + 1) * (self.thread_size >> 1);
-
-                let block_mask = m - 1;
-                let index_mask = !block_mask;
-
-                let round_roots = &root_table[m.get_msb() - 1];
-
-                for i in start..end {
-                    let k1 = (i & index_mask) << 1;
-                    let j1 = i & block_mask;
-                    temp = round_roots[j1] * target[k1 + j1 + m];
-                    target[k1 + j1 + m] = target[k1 + j1] - temp;
-                    target[k1 + j1] += temp;
-                }
-            });
+        let mut m = 2;
+        while m < self.size {
+            fft_round(
+                &mut target[..self.size],
+                m,
+                &root_table[m.get_msb() - 1],
+                self.thread_size,
+            );
+            m <<= 1;
         }
     }
 
-    fn partial_fft_serial_inner(&self, coeffs: &mut [Fr], target: &mut [Fr], root_table: &[&[Fr]]) {
+    fn partial_fft_serial_inner(
+        &self,
+        coeffs: &mut [Fr],
+        target: &mut [Fr],
+        root_table: &[Vec<Fr>],
+    ) {
         let n = self.size >> 2;
         let full_mask = self.size - 1;
         let m = self.size >> 1;
@@ -367,7 +382,7 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
     pub(crate) fn partial_fft_parallel_inner(
         &self,
         coeffs: &mut [Fr],
-        root_table: &[&[Fr]],
+        root_table: &[Vec<Fr>],
         constant: Fr,
         is_coset: bool,
     ) {
@@ -426,7 +441,7 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         self.partial_fft_parallel_inner(coeffs, self.get_round_roots(), constant, is_coset);
     }
 
-    fn fft_inplace(&self, coeffs: &mut [Fr]) {
+    pub(crate) fn fft_inplace(&self, coeffs: &mut [Fr]) {
         self.fft_inner_parallel_vec_inplace(&mut [coeffs], &self.root, self.get_round_roots());
     }
 
@@ -446,19 +461,29 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
             &self.root_inverse,
             self.get_inverse_round_roots(),
         );
-        todo!("parallelize")
-        // for i in 0..self.size {
-        //     coeffs[i] *= self.domain_inverse;
-        // }
+        parallel_for_chunks_mut(
+            &mut coeffs.coefficients[..self.size],
+            self.thread_size,
+            |_, chunk| {
+                for coeff in chunk {
+                    *coeff *= self.domain_inverse;
+                }
+            },
+        );
     }
 
     pub(crate) fn ifft(&self, coeffs: &mut [Fr], target: &mut [Fr]) {
-        self.fft_inner_parallel(coeffs, target, &self.root_inverse, self.get_round_roots());
-        // TODO parallelize me
-        todo!("parallelize here")
-        // for i in 0..self.size {
-        //     target[i] *= self.domain_inverse;
-        // }
+        self.fft_inner_parallel(
+            coeffs,
+            target,
+            &self.root_inverse,
+            self.get_inverse_round_roots(),
+        );
+        parallel_for_chunks_mut(&mut target[..self.size], self.thread_size, |_, chunk| {
+            for coeff in chunk {
+                *coeff *= self.domain_inverse;
+            }
+        });
     }
 
     fn ifft_vec_inplace(&self, _coeffs: &mut [&mut [Fr]]) {
@@ -468,11 +493,40 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         todo!();
     }
 
-    pub(crate) fn coset_ifft(&self, _coeffs: &mut [Fr]) {
-        todo!()
+    /// Interpolates the evaluations of a polynomial over the coset g.H back into its coefficients.
+    pub(crate) fn coset_ifft(&self, coeffs: &mut [Fr]) {
+        self.fft_inner_parallel_vec_inplace(
+            &mut [coeffs],
+            &self.root_inverse,
+            self.get_inverse_round_roots(),
+        );
+        // the ifft gives the coefficients of p(gX), so the i'th one also needs dividing by g^i
+        self.scale_by_generator_inplace(
+            coeffs,
+            self.domain_inverse,
+            self.generator_inverse,
+            self.size,
+        );
     }
-    pub(crate) fn coset_ifft_vec(&self, _coeffs: &[&mut [&mut Fr]]) {
-        todo!()
+    /// [`coset_ifft`](Self::coset_ifft) of a polynomial whose evaluations are split into equal consecutive parts.
+    pub(crate) fn coset_ifft_vec(&self, coeffs: &mut [&mut [Fr]]) {
+        self.fft_inner_parallel_vec_inplace(
+            coeffs,
+            &self.root_inverse,
+            self.get_inverse_round_roots(),
+        );
+        let poly_size = self.size / coeffs.len();
+        let part_shift = self.generator_inverse.pow([poly_size as u64]);
+        let mut generator_start = self.domain_inverse;
+        for poly in coeffs.iter_mut() {
+            self.scale_by_generator_inplace(
+                poly,
+                generator_start,
+                self.generator_inverse,
+                poly_size,
+            );
+            generator_start *= part_shift;
+        }
     }
 
     fn fft_with_constant(&self, coeffs: &mut [Fr], target: &mut [Fr], value: Fr) {
@@ -553,26 +607,111 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         }
         Ok(())
     }
-    pub(crate) fn coset_fft_inplace(&self, _coeffs: &mut [Fr]) {
-        unimplemented!()
+    /// Evaluates the polynomial over the coset g.H, where g is the domain's generator, by evaluating p(gX) over H.
+    pub(crate) fn coset_fft_inplace(&self, coeffs: &mut [Fr]) {
+        self.scale_by_generator_inplace(coeffs, Fr::one(), self.generator, self.generator_size);
+        self.fft_inplace(coeffs);
     }
     fn coset_fft_vec_inplace(&self, _coeffs: &mut [&mut [Fr]]) {
         unimplemented!()
     }
-    pub(crate) fn coset_fft(&self, _coeffs: &[Fr], _target: &mut [Fr]) {
-        unimplemented!()
+    /// Writes the evaluations of `coeffs` over the coset g.H to `target`. `coeffs` may be shorter than the
+    /// domain, in which case the missing coefficients are zero.
+    pub(crate) fn coset_fft(&self, coeffs: &[Fr], target: &mut [Fr]) {
+        copy_polynomial(
+            coeffs,
+            target,
+            std::cmp::min(coeffs.len(), self.size),
+            self.size,
+        );
+        self.coset_fft_inplace(target);
     }
-    pub(crate) fn coset_fft_with_generator_shift(&self, _coeffs: &mut [Fr], _constant: Fr) {
-        unimplemented!()
+
+    /// Evaluates the polynomial over the coset (g.constant).H. With `constant` a power of a root of unity of a
+    /// domain k times larger, this gives every k'th evaluation over that domain's coset.
+    pub(crate) fn coset_fft_with_generator_shift(&self, coeffs: &mut [Fr], constant: Fr) {
+        self.scale_by_generator_inplace(
+            coeffs,
+            Fr::one(),
+            self.generator * constant,
+            self.generator_size,
+        );
+        self.fft_inplace(coeffs);
     }
 
+    /// Divides the evaluations of a polynomial over `target`'s coset by those of the pseudo vanishing polynomial
+    ///
+    ///                 X^n - 1
+    /// Z_H*(X) = -------------------------------
+    ///           (X - ω^{n-1})...(X - ω^{n-k})
+    ///
+    /// where n is the size of this domain and k = `num_roots_cut_out_of_vanishing_poly`: the identities need not
+    /// hold on the last k rows. The evaluations are split into equal consecutive parts, as the quotient is.
+    ///
+    /// Like in `compute_lagrange_polynomial_fft`, X_i^n only takes |target| / n values over the coset, so
+    /// only those are inverted.
     pub(crate) fn divide_by_pseudo_vanishing_polynomial(
         &self,
-        _coeffs: &[&mut [&mut Fr]],
-        _target: &EvaluationDomain<'a, Fr>,
-        _num_roots_cut_out_of_vanishing_poly: usize,
-    ) {
-        unimplemented!()
+        coeffs: &mut [&mut [Fr]],
+        target: &EvaluationDomain<'a, Fr>,
+        num_roots_cut_out_of_vanishing_poly: usize,
+    ) -> anyhow::Result<()> {
+        // 1 / (X_i^n - 1)
+        let log2_subgroup_size = target.log2_size - self.log2_size;
+        let subgroup_size = 1usize << log2_subgroup_size;
+        let mut subgroup_roots = vec![Fr::zero(); subgroup_size];
+        self.compute_multiplicative_subgroup(log2_subgroup_size, &mut subgroup_roots)?;
+        for root in &mut subgroup_roots {
+            *root -= Fr::one();
+        }
+        batch_invert(&mut subgroup_roots)?;
+
+        // ω^{-1}, ..., ω^{-k}, i.e. ω^{n-1}, ..., ω^{n-k}
+        let mut roots_cut = Vec::with_capacity(num_roots_cut_out_of_vanishing_poly);
+        let mut root = self.root_inverse;
+        for _ in 0..num_roots_cut_out_of_vanishing_poly {
+            roots_cut.push(root);
+            root *= self.root_inverse;
+        }
+
+        let poly_size = target.size / coeffs.len();
+        let chunk_size = std::cmp::min(target.thread_size, poly_size);
+        let subgroup_mask = subgroup_size - 1;
+        for (poly_idx, poly) in coeffs.iter_mut().enumerate() {
+            parallel_for_chunks_mut(&mut poly[..poly_size], chunk_size, |j, chunk| {
+                let offset = poly_idx * poly_size + j * chunk_size;
+                let mut x = target.generator * target.root.pow([offset as u64]);
+                for (k, coeff) in chunk.iter_mut().enumerate() {
+                    let mut factor = subgroup_roots[(offset + k) & subgroup_mask];
+                    for root in &roots_cut {
+                        factor *= x - root;
+                    }
+                    *coeff *= factor;
+                    x *= target.root;
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Z_H*(`z`), the pseudo vanishing polynomial of
+    /// [`divide_by_pseudo_vanishing_polynomial`](Self::divide_by_pseudo_vanishing_polynomial), at a point
+    /// outside the domain.
+    pub(crate) fn evaluate_pseudo_vanishing_polynomial(
+        &self,
+        z: &Fr,
+        num_roots_cut_out_of_vanishing_poly: usize,
+    ) -> anyhow::Result<Fr> {
+        let mut denominator = Fr::one();
+        let mut root = self.root_inverse;
+        for _ in 0..num_roots_cut_out_of_vanishing_poly {
+            denominator *= *z - root;
+            root *= self.root_inverse;
+        }
+        let denominator = denominator
+            .inverse()
+            .ok_or_else(|| anyhow::anyhow!("{} is one of the roots cut out of Z_H", z))?;
+        Ok((z.pow([self.size as u64]) - Fr::one()) * denominator)
     }
 
     /// Compute evaluations of lagrange polynomial L_1(X) on the specified domain.
@@ -621,32 +760,21 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         let multiplicand = target_domain.root; // kn'th root of unity w'
 
         // First compute X_i - 1, i = 0,...,kn-1
-        for j in 0..target_domain.num_threads {
-            let root_shift = multiplicand.pow([(j * target_domain.thread_size) as u64]);
-            let mut work_root = self.generator * root_shift; // g.(w')^{j*thread_size}
-            let offset = j * target_domain.thread_size;
-            for i in offset..offset + target_domain.thread_size {
-                l_1_coefficients[i] = work_root - Fr::one(); // (w')^{j*thread_size + i}.g - 1
-                work_root *= multiplicand; // (w')^{j*thread_size + i + 1}
-            }
-        }
+        parallel_for_chunks_mut(
+            &mut l_1_coefficients.coefficients[..target_domain.size],
+            target_domain.thread_size,
+            |j, chunk| {
+                let root_shift = multiplicand.pow([(j * target_domain.thread_size) as u64]);
+                let mut work_root = self.generator * root_shift; // g.(w')^{j*thread_size}
+                for coeff in chunk {
+                    *coeff = work_root - Fr::one(); // (w')^{j*thread_size + i}.g - 1
+                    work_root *= multiplicand; // (w')^{j*thread_size + i + 1}
+                }
+            },
+        );
 
         // Compute 1/(X_i - 1) using Montgomery batch inversion
-        // Note: This is a placeholder, replace with actual batch invert function.
-        // TODO add batch invert
-        // invert them all
-        let result: Result<(), anyhow::Error> = l_1_coefficients
-            .coefficients
-            .iter_mut()
-            .map(|x| {
-                let inverse = x
-                    .inverse()
-                    .ok_or_else(|| anyhow::anyhow!("Failed to find inverse"))?;
-                *x = inverse;
-                Ok(())
-            })
-            .collect();
-        result?;
+        batch_invert(&mut l_1_coefficients.coefficients[..target_domain.size])?;
 
         // Step 2: Compute numerator (1/n)*(X_i^n - 1)
         // First compute X_i^n (which forms a multiplicative subgroup of order k)
@@ -665,12 +793,16 @@ impl<'a, Fr: Field + FftField> EvaluationDomain<'a, Fr> {
         // Step 3: Construct L_1(X_i) by multiplying the 1/denominator evaluations in
         // l_1_coefficients by the numerator evaluations in subgroup_roots
         let subgroup_mask = subgroup_size - 1;
-        for i in 0..target_domain.num_threads {
-            for j in 0..target_domain.thread_size {
-                let eval_idx = i * target_domain.thread_size + j;
-                l_1_coefficients[eval_idx] *= subgroup_roots[eval_idx & subgroup_mask];
-            }
-        }
+        parallel_for_chunks_mut(
+            &mut l_1_coefficients.coefficients[..target_domain.size],
+            target_domain.thread_size,
+            |i, chunk| {
+                for (j, coeff) in chunk.iter_mut().enumerate() {
+                    let eval_idx = i * target_domain.thread_size + j;
+                    *coeff *= subgroup_roots[eval_idx & subgroup_mask];
+                }
+            },
+        );
         Ok(())
     }
 }
@@ -748,19 +880,8 @@ pub(crate) fn compute_efficient_interpolation<Fr: Field>(
         }
     }
 
-    // TODO make this a batch_invert
     // invert them all
-    let result: Result<(), anyhow::Error> = roots_and_denominators
-        .iter_mut()
-        .map(|x| {
-            let inverse = x
-                .inverse()
-                .ok_or_else(|| anyhow::anyhow!("Failed to find inverse"))?;
-            *x = inverse;
-            Ok(())
-        })
-        .collect();
-    result?;
+    batch_invert(&mut roots_and_denominators)?;
 
     let mut z;
     let mut multiplier;
@@ -781,29 +902,189 @@ pub(crate) fn compute_efficient_interpolation<Fr: Field>(
 }
 
 pub(crate) fn evaluate<F: Field>(coeffs: &[F], z: &F, n: usize) -> F {
-    // let num_threads = compute_num_threads();
     let num_threads = compute_num_threads();
     let range_per_thread = n / num_threads;
     let leftovers = n - (range_per_thread * num_threads);
-    let mut evaluations = vec![F::default(); num_threads];
-    for (j, eval_j) in evaluations.iter_mut().enumerate().take(num_threads) {
+    let evaluations = parallel_map(num_threads, |j| {
         let mut z_acc = z.pow([(j * range_per_thread) as u64]);
         let offset = j * range_per_thread;
-        *eval_j = F::default();
         let end = if j == num_threads - 1 {
             offset + range_per_thread + leftovers
         } else {
             offset + range_per_thread
         };
+        let mut eval_j = F::zero();
         for coeffs_i in coeffs.iter().take(end).skip(offset) {
             let work_var = z_acc * coeffs_i;
-            *eval_j += work_var;
+            eval_j += work_var;
             z_acc *= z;
         }
-    }
-    let mut r = F::default();
+        eval_j
+    });
+    let mut r = F::zero();
     for evaluation in evaluations {
         r += evaluation;
     }
     r
 }
+
+/// Fixes the lowest variable of the multilinear polynomial with values `src` on the boolean hypercube to `u`,
+/// halving the number of values.
+pub(crate) fn partially_evaluate_mle<F: Field>(src: &[F], u: F) -> Vec<F> {
+    (0..src.len() / 2)
+        .map(|j| src[2 * j] + u * (src[2 * j + 1] - src[2 * j]))
+        .collect()
+}
+
+/// Divides `coeffs` in place by `(X - root)`, which must be a root of the polynomial. The top coefficient of
+/// the result is left at zero.
+pub(crate) fn factor_root<F: Field>(coeffs: &mut [F], root: &F) {
+    // synthetic division from the top down: q_{i - 1} = c_i + root * q_i, and what is left over at the
+    // bottom is the remainder
+    let mut carry = F::zero();
+    for coeff in coeffs.iter_mut().rev() {
+        let c = *coeff;
+        *coeff = carry;
+        carry = c + carry * root;
+    }
+    debug_assert!(carry.is_zero(), "root does not divide the polynomial");
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bn254::Fr;
+    use ark_ff::{FftField, Field, One, UniformRand, Zero};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{evaluate, factor_root};
+    use crate::{
+        common::thread::test::run_serial_and_parallel,
+        polynomials::evaluation_domain::EvaluationDomain,
+    };
+
+    /// `round_roots[i]` holds the first `m = 2^(i + 1)` powers of the 2m'th root of unity.
+    fn round_roots(size: usize, root: Fr) -> Vec<Vec<Fr>> {
+        let mut tables = Vec::new();
+        let mut m = 2;
+        while m < size {
+            let round_root = root.pow([(size / (2 * m)) as u64]);
+            let mut table = vec![Fr::one(); m];
+            for j in 1..m {
+                table[j] = table[j - 1] * round_root;
+            }
+            tables.push(table);
+            m <<= 1;
+        }
+        tables
+    }
+
+    fn naive_evaluations(coeffs: &[Fr], root: Fr) -> Vec<Fr> {
+        (0..coeffs.len())
+            .map(|i| {
+                let x = root.pow([i as u64]);
+                coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fft_serial_and_parallel_agree() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let log2_size = 8;
+        let size = 1 << log2_size;
+        let num_threads = 4;
+        let root = Fr::get_root_of_unity(size as u64).unwrap();
+        let domain = EvaluationDomain {
+            size,
+            num_threads,
+            thread_size: size / num_threads,
+            log2_size,
+            root,
+            round_roots: round_roots(size, root),
+            ..Default::default()
+        };
+        let coeffs: Vec<Fr> = (0..size).map(|_| Fr::rand(&mut rng)).collect();
+        let expected = naive_evaluations(&coeffs, root);
+
+        let (serial, parallel) = run_serial_and_parallel(num_threads, || {
+            let mut input = coeffs.clone();
+            let mut target = vec![Fr::zero(); size];
+            domain.fft(&mut input, &mut target);
+            target
+        });
+        assert_eq!(serial, expected);
+        assert_eq!(serial, parallel);
+
+        let (serial, parallel) = run_serial_and_parallel(num_threads, || {
+            let mut input = coeffs.clone();
+            domain.fft_inplace(&mut input);
+            input
+        });
+        assert_eq!(serial, expected);
+        assert_eq!(serial, parallel);
+    }
+
+    #[test]
+    fn lookup_table_matches_round_roots() {
+        let mut domain = EvaluationDomain::<Fr>::new(64, None);
+        domain.compute_lookup_table();
+        assert_eq!(domain.round_roots, round_roots(64, domain.root));
+        assert_eq!(
+            domain.inverse_round_roots,
+            round_roots(64, domain.root_inverse)
+        );
+    }
+
+    #[test]
+    fn coset_fft_evaluates_over_the_coset() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let size = 32;
+        let mut domain = EvaluationDomain::<Fr>::new(size, None);
+        domain.compute_lookup_table();
+        // fewer coefficients than the domain, as when a circuit polynomial is evaluated over the large domain
+        let coeffs: Vec<Fr> = (0..size / 4).map(|_| Fr::rand(&mut rng)).collect();
+
+        let mut evaluations = vec![Fr::zero(); size];
+        domain.coset_fft(&coeffs, &mut evaluations);
+        for (i, evaluation) in evaluations.iter().enumerate() {
+            let x = domain.generator * domain.root.pow([i as u64]);
+            assert_eq!(*evaluation, evaluate(&coeffs, &x, coeffs.len()));
+        }
+
+        domain.coset_ifft(&mut evaluations);
+        assert_eq!(evaluations[..coeffs.len()], coeffs[..]);
+        assert!(evaluations[coeffs.len()..].iter().all(|c| c.is_zero()));
+    }
+
+    #[test]
+    fn evaluate_serial_and_parallel_agree() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = 1001;
+        let coeffs: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let z = Fr::rand(&mut rng);
+        let expected = coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * z + c);
+
+        let (serial, parallel) = run_serial_and_parallel(4, || evaluate(&coeffs, &z, n));
+        assert_eq!(serial, expected);
+        assert_eq!(parallel, expected);
+    }
+
+    #[test]
+    fn factor_root_divides_out_the_linear_factor() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let n = 17;
+        let mut coeffs: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+        let root = Fr::rand(&mut rng);
+        let remainder = evaluate(&coeffs, &root, n);
+        coeffs[0] -= remainder;
+        let original = coeffs.clone();
+
+        factor_root(&mut coeffs, &root);
+        assert!(coeffs[n - 1].is_zero());
+        let x = Fr::rand(&mut rng);
+        assert_eq!(
+            evaluate(&coeffs, &x, n) * (x - root),
+            evaluate(&original, &x, n)
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub};
+
+use ark_ff::Field;
+
+/// A univariate polynomial of degree less than `LENGTH`, held as its values at `0, 1, ..., LENGTH - 1`.
+///
+/// Sumcheck round polynomials live in this form: the prover only ever computes them on these points, and the
+/// verifier needs their value at 0, 1 and one random challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Univariate<F: Field, const LENGTH: usize> {
+    pub(crate) evaluations: [F; LENGTH],
+}
+
+impl<F: Field, const LENGTH: usize> Default for Univariate<F, LENGTH> {
+    fn default() -> Self {
+        Self {
+            evaluations: [F::zero(); LENGTH],
+        }
+    }
+}
+
+impl<F: Field, const LENGTH: usize> Univariate<F, LENGTH> {
+    pub(crate) fn new(evaluations: [F; LENGTH]) -> Self {
+        Self { evaluations }
+    }
+
+    /// The linear polynomial through `(0, lo)` and `(1, hi)`, on all `LENGTH` points.
+    pub(crate) fn from_edge(lo: F, hi: F) -> Self {
+        let delta = hi - lo;
+        let mut evaluations = [lo; LENGTH];
+        for i in 1..LENGTH {
+            evaluations[i] = evaluations[i - 1] + delta;
+        }
+        Self { evaluations }
+    }
+
+    #[inline]
+    pub(crate) fn value_at(&self, i: usize) -> F {
+        self.evaluations[i]
+    }
+
+    /// Evaluates the polynomial at `u` by barycentric interpolation over `0, 1, ..., LENGTH - 1`.
+    pub(crate) fn evaluate(&self, u: F) -> F {
+        let points: Vec<F> = (0..LENGTH).map(|i| F::from(i as u64)).collect();
+        if let Some(i) = points.iter().position(|x| *x == u) {
+            return self.evaluations[i];
+        }
+
+        // ℓ(u) · Σ_i y_i · w_i / (u - i), with ℓ(X) = ∏_i (X - i) and w_i = 1 / ∏_{j != i} (i - j)
+        let mut numerator = F::one();
+        for x in &points {
+            numerator *= u - x;
+        }
+        let mut result = F::zero();
+        for (i, x_i) in points.iter().enumerate() {
+            let mut denominator = u - x_i;
+            for (j, x_j) in points.iter().enumerate() {
+                if i != j {
+                    denominator *= *x_i - x_j;
+                }
+            }
+            result += self.evaluations[i] * denominator.inverse().unwrap();
+        }
+        result * numerator
+    }
+
+    /// The same polynomial on the points `0, 1, ..., EXTENDED_LENGTH - 1`.
+    pub(crate) fn extend_to<const EXTENDED_LENGTH: usize>(&self) -> Univariate<F, EXTENDED_LENGTH> {
+        assert!(EXTENDED_LENGTH >= LENGTH);
+        let mut result = Univariate::<F, EXTENDED_LENGTH>::default();
+        result.evaluations[..LENGTH].copy_from_slice(&self.evaluations);
+        for i in LENGTH..EXTENDED_LENGTH {
+            result.evaluations[i] = self.evaluate(F::from(i as u64));
+        }
+        result
+    }
+}
+
+impl<F: Field, const LENGTH: usize> AddAssign for Univariate<F, LENGTH> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (lhs, rhs) in self.evaluations.iter_mut().zip(rhs.evaluations) {
+            *lhs += rhs;
+        }
+    }
+}
+
+impl<F: Field, const LENGTH: usize> Add for Univariate<F, LENGTH> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<F: Field, const LENGTH: usize> Sub for Univariate<F, LENGTH> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        for (lhs, rhs) in self.evaluations.iter_mut().zip(rhs.evaluations) {
+            *lhs -= rhs;
+        }
+        self
+    }
+}
+
+/// Pointwise product. The caller is responsible for `LENGTH` being large enough to hold the product's degree.
+impl<F: Field, const LENGTH: usize> Mul for Univariate<F, LENGTH> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: Self) -> Self {
+        for (lhs, rhs) in self.evaluations.iter_mut().zip(rhs.evaluations) {
+            *lhs *= rhs;
+        }
+        self
+    }
+}
+
+impl<F: Field, const LENGTH: usize> MulAssign<F> for Univariate<F, LENGTH> {
+    fn mul_assign(&mut self, rhs: F) {
+        for lhs in self.evaluations.iter_mut() {
+            *lhs *= rhs;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// 3 + 2X + 5X²
+    fn quadratic(x: Fr) -> Fr {
+        Fr::from(3u64) + Fr::from(2u64) * x + Fr::from(5u64) * x * x
+    }
+
+    #[test]
+    fn evaluate_and_extend_interpolate_the_points() {
+        let univariate = Univariate::<Fr, 3>::new([
+            quadratic(Fr::from(0u64)),
+            quadratic(Fr::from(1u64)),
+            quadratic(Fr::from(2u64)),
+        ]);
+        let mut rng = StdRng::seed_from_u64(0);
+        let u = Fr::rand(&mut rng);
+        assert_eq!(univariate.evaluate(u), quadratic(u));
+        assert_eq!(
+            univariate.evaluate(Fr::from(1u64)),
+            quadratic(Fr::from(1u64))
+        );
+
+        let extended = univariate.extend_to::<6>();
+        for i in 0..6u64 {
+            assert_eq!(extended.value_at(i as usize), quadratic(Fr::from(i)));
+        }
+        assert_eq!(extended.evaluate(u), quadratic(u));
+    }
+
+    #[test]
+    fn edges_extend_linearly() {
+        let edge = Univariate::<Fr, 4>::from_edge(Fr::from(7u64), Fr::from(10u64));
+        assert_eq!(edge.evaluations, [7u64, 10, 13, 16].map(Fr::from));
+        let product = edge * edge;
+        assert_eq!(product.evaluate(Fr::from(5u64)), Fr::from(22u64 * 22));
+    }
+}
@@ -34,14 +34,14 @@ pub(crate) mod gate_data {
     use ark_ff::Field;
     use serde::{Deserialize, Serialize};
 
-    struct AddTriple<Fr: Field> {
-        a: u32,
-        b: u32,
-        c: u32,
-        a_scaling: Fr,
-        b_scaling: Fr,
-        c_scaling: Fr,
-        const_scaling: Fr,
+    pub(crate) struct AddTriple<Fr: Field> {
+        pub(crate) a: u32,
+        pub(crate) b: u32,
+        pub(crate) c: u32,
+        pub(crate) a_scaling: Fr,
+        pub(crate) b_scaling: Fr,
+        pub(crate) c_scaling: Fr,
+        pub(crate) const_scaling: Fr,
     }
 
     struct AddQuad<Fr: Field> {
@@ -69,25 +69,25 @@ pub(crate) mod gate_data {
         const_scaling: Fr,
     }
 
-    struct MulTriple<Fr: Field> {
-        a: u32,
-        b: u32,
-        c: u32,
-        mul_scaling: Fr,
-        c_scaling: Fr,
-        const_scaling: Fr,
+    pub(crate) struct MulTriple<Fr: Field> {
+        pub(crate) a: u32,
+        pub(crate) b: u32,
+        pub(crate) c: u32,
+        pub(crate) mul_scaling: Fr,
+        pub(crate) c_scaling: Fr,
+        pub(crate) const_scaling: Fr,
     }
 
     #[derive(PartialEq, Eq, Serialize, Deserialize)]
-    struct PolyTriple<Fr: Field> {
-        a: u32,
-        b: u32,
-        c: u32,
-        q_m: Fr,
-        q_l: Fr,
-        q_r: Fr,
-        q_o: Fr,
-        q_c: Fr,
+    pub(crate) struct PolyTriple<Fr: Field> {
+        pub(crate) a: u32,
+        pub(crate) b: u32,
+        pub(crate) c: u32,
+        pub(crate) q_m: Fr,
+        pub(crate) q_l: Fr,
+        pub(crate) q_r: Fr,
+        pub(crate) q_o: Fr,
+        pub(crate) q_c: Fr,
     }
 
     struct FixedGroupAddQuad<Fr: Field> {
@@ -114,14 +114,27 @@ pub(crate) mod gate_data {
         out: Vec<u32>,
     }
 
-    struct EccAddGate<Fr: Field> {
-        x1: u32,
-        y1: u32,
-        x2: u32,
-        y2: u32,
-        x3: u32,
-        y3: u32,
-        endomorphism_coefficient: Fr,
-        sign_coefficient: Fr,
+    /// Adds `(x1, y1)` and `(β·x2, ±y2)` into `(x3, y3)`, where β is `endomorphism_coefficient` (a cube root
+    /// of unity, or 1) and ± is `sign_coefficient`.
+    pub(crate) struct EccAddGate<Fr: Field> {
+        pub(crate) x1: u32,
+        pub(crate) y1: u32,
+        pub(crate) x2: u32,
+        pub(crate) y2: u32,
+        pub(crate) x3: u32,
+        pub(crate) y3: u32,
+        pub(crate) endomorphism_coefficient: Fr,
+        pub(crate) sign_coefficient: Fr,
+    }
+
+    impl<Fr: Field> EccAddGate<Fr> {
+        /// The selectors `[q_1, q_3, q_4]` of the elliptic gate: the sign, β and β².
+        pub(crate) fn selectors(&self) -> [Fr; 3] {
+            [
+                self.sign_coefficient,
+                self.endomorphism_coefficient,
+                self.endomorphism_coefficient.square(),
+            ]
+        }
     }
 }
@@ -0,0 +1,254 @@
+use ark_ff::Field;
+use typenum::{U3, U5};
+
+use crate::plonk::composer::composer_base::{CycleNode, WireType};
+
+use super::arithmetization::{
+    gate_data::{AddTriple, MulTriple, PolyTriple},
+    Arithmetization, Standard,
+};
+
+/// Circuit data for the Standard arithmetization, independent of the proving system that consumes it.
+///
+/// Every gate is `q_m·a·b + q_1·a + q_2·b + q_3·c + q_c = 0` over the three wires, which hold indices into
+/// `variables`. Public inputs take up the first rows of the execution trace, ahead of the gates.
+#[derive(Debug, Clone)]
+pub(crate) struct StandardCircuitConstructor<F: Field> {
+    pub(crate) variables: Vec<F>,
+    /// the variable that each variable was merged into by `assert_equal`
+    real_variable_index: Vec<u32>,
+    pub(crate) public_inputs: Vec<u32>,
+    pub(crate) w_l: Vec<u32>,
+    pub(crate) w_r: Vec<u32>,
+    pub(crate) w_o: Vec<u32>,
+    pub(crate) q_m: Vec<F>,
+    pub(crate) q_1: Vec<F>,
+    pub(crate) q_2: Vec<F>,
+    pub(crate) q_3: Vec<F>,
+    pub(crate) q_c: Vec<F>,
+    pub(crate) num_gates: usize,
+    pub(crate) zero_idx: u32,
+    pub(crate) failed: bool,
+    pub(crate) err: Option<String>,
+}
+
+impl<F: Field> Arithmetization for StandardCircuitConstructor<F> {
+    type NumWires = U3;
+    type NumSelectors = U5;
+}
+
+impl<F: Field> Standard for StandardCircuitConstructor<F> {}
+
+impl<F: Field> Default for StandardCircuitConstructor<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Field> StandardCircuitConstructor<F> {
+    /// An empty circuit, apart from a variable constrained to zero that unused wires can point at.
+    pub(crate) fn new() -> Self {
+        let mut circuit = Self {
+            variables: vec![],
+            real_variable_index: vec![],
+            public_inputs: vec![],
+            w_l: vec![],
+            w_r: vec![],
+            w_o: vec![],
+            q_m: vec![],
+            q_1: vec![],
+            q_2: vec![],
+            q_3: vec![],
+            q_c: vec![],
+            num_gates: 0,
+            zero_idx: 0,
+            failed: false,
+            err: None,
+        };
+        circuit.zero_idx = circuit.add_variable(F::zero());
+        circuit.create_poly_gate(&PolyTriple {
+            a: circuit.zero_idx,
+            b: circuit.zero_idx,
+            c: circuit.zero_idx,
+            q_m: F::zero(),
+            q_l: F::one(),
+            q_r: F::zero(),
+            q_o: F::zero(),
+            q_c: F::zero(),
+        });
+        circuit
+    }
+
+    pub(crate) fn add_variable(&mut self, value: F) -> u32 {
+        let index = self.variables.len() as u32;
+        self.variables.push(value);
+        self.real_variable_index.push(index);
+        index
+    }
+
+    pub(crate) fn add_public_variable(&mut self, value: F) -> u32 {
+        let index = self.add_variable(value);
+        self.public_inputs.push(index);
+        index
+    }
+
+    #[inline]
+    pub(crate) fn get_variable(&self, index: u32) -> F {
+        self.variables[self.real_variable_index[index as usize] as usize]
+    }
+
+    /// Values of the public inputs, in the order they sit in the trace.
+    pub(crate) fn get_public_inputs(&self) -> Vec<F> {
+        self.public_inputs
+            .iter()
+            .map(|&index| self.get_variable(index))
+            .collect()
+    }
+
+    /// Constrain `a` and `b` to be equal by merging their equivalence classes.
+    pub(crate) fn assert_equal(&mut self, a: u32, b: u32, msg: Option<&str>) {
+        if self.get_variable(a) != self.get_variable(b) && !self.failed {
+            self.failed = true;
+            self.err = Some(msg.unwrap_or("assert_equal").to_string());
+        }
+        let a_real = self.real_variable_index[a as usize];
+        let b_real = self.real_variable_index[b as usize];
+        for real in self.real_variable_index.iter_mut() {
+            if *real == b_real {
+                *real = a_real;
+            }
+        }
+    }
+
+    /// `a_scaling·a + b_scaling·b + c_scaling·c + const_scaling = 0`
+    pub(crate) fn create_add_gate(&mut self, gate: &AddTriple<F>) {
+        self.create_poly_gate(&PolyTriple {
+            a: gate.a,
+            b: gate.b,
+            c: gate.c,
+            q_m: F::zero(),
+            q_l: gate.a_scaling,
+            q_r: gate.b_scaling,
+            q_o: gate.c_scaling,
+            q_c: gate.const_scaling,
+        });
+    }
+
+    /// `mul_scaling·a·b + c_scaling·c + const_scaling = 0`
+    pub(crate) fn create_mul_gate(&mut self, gate: &MulTriple<F>) {
+        self.create_poly_gate(&PolyTriple {
+            a: gate.a,
+            b: gate.b,
+            c: gate.c,
+            q_m: gate.mul_scaling,
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_o: gate.c_scaling,
+            q_c: gate.const_scaling,
+        });
+    }
+
+    pub(crate) fn create_poly_gate(&mut self, gate: &PolyTriple<F>) {
+        self.w_l.push(gate.a);
+        self.w_r.push(gate.b);
+        self.w_o.push(gate.c);
+        self.q_m.push(gate.q_m);
+        self.q_1.push(gate.q_l);
+        self.q_2.push(gate.q_r);
+        self.q_3.push(gate.q_o);
+        self.q_c.push(gate.q_c);
+        self.num_gates += 1;
+    }
+
+    /// Whether every gate holds on the current variable values. Copy constraints hold by construction.
+    pub(crate) fn check_circuit(&self) -> bool {
+        (0..self.num_gates).all(|i| {
+            let a = self.get_variable(self.w_l[i]);
+            let b = self.get_variable(self.w_r[i]);
+            let c = self.get_variable(self.w_o[i]);
+            (self.q_m[i] * a * b
+                + self.q_1[i] * a
+                + self.q_2[i] * b
+                + self.q_3[i] * c
+                + self.q_c[i])
+                .is_zero()
+        })
+    }
+
+    /// Rows taken up by public inputs and gates.
+    pub(crate) fn get_total_num_rows(&self) -> usize {
+        self.public_inputs.len() + self.num_gates
+    }
+
+    /// Collect, for every real variable, the wire cells that hold it. Public input `i` sits in both the left and
+    /// the right wire of row `i`, left first, and the gates follow after the public inputs.
+    pub(crate) fn compute_wire_copy_cycles(&self) -> Vec<Vec<CycleNode>> {
+        let num_public_inputs = self.public_inputs.len();
+        let mut copy_cycles = vec![Vec::new(); self.variables.len()];
+
+        for (i, &public_input) in self.public_inputs.iter().enumerate() {
+            let real_index = self.real_variable_index[public_input as usize] as usize;
+            copy_cycles[real_index].push(CycleNode::new(i as u32, WireType::Left));
+            copy_cycles[real_index].push(CycleNode::new(i as u32, WireType::Right));
+        }
+
+        let wires = [
+            (&self.w_l, WireType::Left),
+            (&self.w_r, WireType::Right),
+            (&self.w_o, WireType::Output),
+        ];
+        for i in 0..self.num_gates {
+            for (wire, wire_type) in wires.iter() {
+                let real_index = self.real_variable_index[wire[i] as usize] as usize;
+                copy_cycles[real_index]
+                    .push(CycleNode::new((i + num_public_inputs) as u32, *wire_type));
+            }
+        }
+        copy_cycles
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn assert_equal_merges_cycles() {
+        let mut circuit = StandardCircuitConstructor::<Fr>::new();
+        let a = circuit.add_public_variable(Fr::from(3u64));
+        let b = circuit.add_variable(Fr::from(3u64));
+        let c = circuit.add_variable(Fr::from(9u64));
+        circuit.create_mul_gate(&MulTriple {
+            a,
+            b,
+            c,
+            mul_scaling: Fr::from(1u64),
+            c_scaling: -Fr::from(1u64),
+            const_scaling: Fr::from(0u64),
+        });
+        assert!(circuit.check_circuit());
+
+        let cycles = circuit.compute_wire_copy_cycles();
+        assert_eq!(cycles[b as usize].len(), 1);
+        circuit.assert_equal(a, b, None);
+        assert!(!circuit.failed);
+
+        let cycles = circuit.compute_wire_copy_cycles();
+        // both public input cells, then the two gate cells of a and b; the zero gate is at row 1
+        assert_eq!(
+            cycles[a as usize],
+            vec![
+                CycleNode::new(0, WireType::Left),
+                CycleNode::new(0, WireType::Right),
+                CycleNode::new(2, WireType::Left),
+                CycleNode::new(2, WireType::Right),
+            ]
+        );
+        assert!(cycles[b as usize].is_empty());
+
+        circuit.assert_equal(a, c, Some("3 != 9"));
+        assert!(circuit.failed);
+        assert_eq!(circuit.err.as_deref(), Some("3 != 9"));
+    }
+}
@@ -1,3 +1,4 @@
 pub(crate) mod arithmetization;
+pub(crate) mod circuit_constructor;
 pub(crate) mod polynomial_store;
 pub(crate) mod work_queue;
@@ -1,51 +1,271 @@
 use crate::polynomials::Polynomial;
 use anyhow::{anyhow, Result};
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use memmap2::{Mmap, MmapMut};
 use std::{
-    cell::RefCell,
     collections::HashMap,
     fmt::{self, Display, Formatter},
+    fs::File,
     marker::PhantomData,
-    rc::Rc,
+    sync::{Arc, Mutex, RwLock},
 };
+use tracing::info;
 
-#[derive(Debug, Clone, Default)]
+/// A polynomial that has been written out to an (unlinked) temp file to free up memory.
+#[derive(Debug)]
+struct SpilledPolynomial {
+    file: File,
+    /// `Polynomial::size`, which can differ from the number of coefficients
+    size: usize,
+}
+
+#[derive(Debug)]
+enum StoreEntry<Fr: Field> {
+    Resident(Arc<RwLock<Polynomial<Fr>>>),
+    Spilled(SpilledPolynomial),
+}
+
+#[derive(Debug)]
+struct TrackedEntry<Fr: Field> {
+    entry: StoreEntry<Fr>,
+    /// size of the coefficients in memory. Recorded when the polynomial comes in, so that we never have
+    /// to lock a polynomial someone else may be holding just to do the bookkeeping.
+    num_bytes: usize,
+    /// value of the store's clock the last time this entry was put or fetched
+    last_used: u64,
+}
+
+/// Per-polynomial entry of [`PolynomialStoreStats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PolynomialStats {
+    pub(crate) name: String,
+    pub(crate) num_bytes: usize,
+    pub(crate) resident: bool,
+}
+
+/// Snapshot of how much a [`PolynomialStore`] is holding and where.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PolynomialStoreStats {
+    pub(crate) resident_bytes: usize,
+    pub(crate) spilled_bytes: usize,
+    /// largest `resident_bytes` seen since the store was created
+    pub(crate) peak_resident_bytes: usize,
+    pub(crate) num_spills: usize,
+    pub(crate) num_page_ins: usize,
+    /// sorted by name
+    pub(crate) polynomials: Vec<PolynomialStats>,
+}
+
+#[derive(Debug, Default)]
+struct StoreState<Fr: Field> {
+    polynomial_map: HashMap<String, TrackedEntry<Fr>>,
+    clock: u64,
+    resident_bytes: usize,
+    peak_resident_bytes: usize,
+    num_spills: usize,
+    num_page_ins: usize,
+}
+
+/// Holds the named polynomials of a proving key.
+///
+/// By default everything stays in memory. With a memory budget set, the least recently used
+/// polynomials are spilled to memory-mapped temp files whenever the resident ones add up to more
+/// than the budget, and are paged back in on `get`. A polynomial is only spilled while nobody
+/// outside the store holds on to it, so the budget is a target rather than a hard limit.
+#[derive(Debug, Default)]
 pub(crate) struct PolynomialStore<Fr: Field> {
-    polynomial_map: HashMap<String, Rc<RefCell<Polynomial<Fr>>>>,
+    state: Mutex<StoreState<Fr>>,
+    memory_budget: Option<usize>,
     phantom: PhantomData<Fr>,
 }
 
+fn polynomial_bytes<Fr: Field>(polynomial: &Polynomial<Fr>) -> usize {
+    polynomial.coefficients.len() * std::mem::size_of::<Fr>()
+}
+
+impl SpilledPolynomial {
+    fn write<Fr: Field>(polynomial: &Polynomial<Fr>) -> Result<Self> {
+        let file_size = polynomial.coefficients.uncompressed_size();
+        let file = tempfile::tempfile()?;
+        file.set_len(file_size as u64)?;
+        // SAFETY: the file was just created by us and is unlinked, so nothing else can touch it
+        // while it is mapped.
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        polynomial
+            .coefficients
+            .serialize_uncompressed(&mut mmap[..])
+            .map_err(|e| anyhow!("failed to spill polynomial: {e}"))?;
+        mmap.flush()?;
+        Ok(Self {
+            file,
+            size: polynomial.size(),
+        })
+    }
+
+    fn read<Fr: Field>(&self) -> Result<Polynomial<Fr>> {
+        // SAFETY: as above, we are the only ones with access to the file.
+        let mmap = unsafe { Mmap::map(&self.file)? };
+        // we wrote these ourselves, no need to check them again
+        let coefficients = Vec::<Fr>::deserialize_uncompressed_unchecked(&mmap[..])
+            .map_err(|e| anyhow!("failed to page in polynomial: {e}"))?;
+        Ok(Polynomial::from_coefficients(coefficients, self.size))
+    }
+}
+
+impl<Fr: Field> StoreState<Fr> {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn add_resident_bytes(&mut self, num_bytes: usize) {
+        self.resident_bytes += num_bytes;
+        self.peak_resident_bytes = self.peak_resident_bytes.max(self.resident_bytes);
+    }
+
+    fn remove_entry(&mut self, key: &str) -> Option<StoreEntry<Fr>> {
+        let tracked = self.polynomial_map.remove(key)?;
+        if let StoreEntry::Resident(_) = &tracked.entry {
+            self.resident_bytes -= tracked.num_bytes;
+        }
+        Some(tracked.entry)
+    }
+
+    fn insert(&mut self, name: String, polynomial: Polynomial<Fr>) {
+        self.remove_entry(&name);
+        let num_bytes = polynomial_bytes(&polynomial);
+        self.add_resident_bytes(num_bytes);
+        let last_used = self.tick();
+        self.polynomial_map.insert(
+            name,
+            TrackedEntry {
+                entry: StoreEntry::Resident(Arc::new(RwLock::new(polynomial))),
+                num_bytes,
+                last_used,
+            },
+        );
+    }
+
+    /// Spill least recently used polynomials until we fit in `budget`. `keep` is never spilled, and
+    /// neither is anything with outstanding references.
+    fn enforce_budget(&mut self, budget: usize, keep: &str) -> Result<()> {
+        while self.resident_bytes > budget {
+            let victim = self
+                .polynomial_map
+                .iter()
+                .filter(|(name, tracked)| name.as_str() != keep && tracked.is_spillable())
+                .min_by_key(|(_, tracked)| tracked.last_used)
+                .map(|(name, _)| name.clone());
+            let victim = match victim {
+                Some(victim) => victim,
+                None => break,
+            };
+
+            let tracked = self.polynomial_map.get_mut(&victim).unwrap();
+            let spilled = match &tracked.entry {
+                // nobody else holds this polynomial, so this lock is uncontended
+                StoreEntry::Resident(polynomial) => {
+                    SpilledPolynomial::write(&polynomial.read().unwrap())?
+                }
+                StoreEntry::Spilled(_) => unreachable!("only resident entries are spilled"),
+            };
+            tracked.entry = StoreEntry::Spilled(spilled);
+            self.resident_bytes -= tracked.num_bytes;
+            self.num_spills += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<Fr: Field> TrackedEntry<Fr> {
+    fn is_spillable(&self) -> bool {
+        match &self.entry {
+            StoreEntry::Resident(polynomial) => Arc::strong_count(polynomial) == 1,
+            StoreEntry::Spilled(_) => false,
+        }
+    }
+}
+
 impl<Fr: Field> PolynomialStore<Fr> {
     pub(crate) fn new() -> Self {
         Self {
-            polynomial_map: HashMap::new(),
+            state: Mutex::new(StoreState::default()),
+            memory_budget: None,
             phantom: PhantomData,
         }
     }
 
+    /// A store that tries to keep at most `memory_budget` bytes of polynomials in memory.
+    pub(crate) fn with_memory_budget(memory_budget: usize) -> Self {
+        Self {
+            memory_budget: Some(memory_budget),
+            ..Self::new()
+        }
+    }
+
+    /// Change (or with `None`, remove) the memory budget. Takes effect on the next `put` or `get`.
+    pub(crate) fn set_memory_budget(&mut self, memory_budget: Option<usize>) {
+        self.memory_budget = memory_budget;
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, StoreState<Fr>> {
+        self.state.lock().unwrap()
+    }
+
     /// Transfer ownership of a polynomial to the PolynomialStore
     ///
     /// # Arguments
     /// - `name` - string ID of the polynomial
     /// - `polynomial` - the polynomial to be stored
-    pub(crate) fn put(&mut self, name: String, polynomial: Polynomial<Fr>) {
-        self.polynomial_map
-            .insert(name, Rc::new(RefCell::new(polynomial)));
+    ///
+    /// # Returns
+    /// - an error if going over the memory budget and spilling to disk failed. The polynomial is stored either
+    ///   way.
+    pub(crate) fn put(&mut self, name: String, polynomial: Polynomial<Fr>) -> Result<()> {
+        let mut state = self.state();
+        state.insert(name.clone(), polynomial);
+        if let Some(budget) = self.memory_budget {
+            state.enforce_budget(budget, &name)?;
+        }
+        Ok(())
     }
 
     /// Get a reference to a polynomial in the PolynomialStore; will throw exception if the
-    /// key does not exist in the map
+    /// key does not exist in the map. Spilled polynomials are paged back in.
     ///
     /// # Arguments
     /// - `key` - string ID of the polynomial
     ///
     /// # Returns
     /// - `Result<Polynomial>` - a reference to the polynomial associated with the given key
-    pub(crate) fn get(&self, key: &String) -> Result<Rc<RefCell<Polynomial<Fr>>>> {
-        self.polynomial_map
-            .get(key)
-            .ok_or_else(|| anyhow!("didn't find polynomial..."))
-            .map(|a| a.clone())
+    pub(crate) fn get(&self, key: &String) -> Result<Arc<RwLock<Polynomial<Fr>>>> {
+        let mut state = self.state();
+        let last_used = state.tick();
+        let tracked = state
+            .polynomial_map
+            .get_mut(key)
+            .ok_or_else(|| anyhow!("didn't find polynomial..."))?;
+        tracked.last_used = last_used;
+
+        let (polynomial, paged_in_bytes) = match &tracked.entry {
+            StoreEntry::Resident(polynomial) => (polynomial.clone(), None),
+            StoreEntry::Spilled(spilled) => {
+                let polynomial = spilled.read()?;
+                tracked.num_bytes = polynomial_bytes(&polynomial);
+                let polynomial = Arc::new(RwLock::new(polynomial));
+                tracked.entry = StoreEntry::Resident(polynomial.clone());
+                (polynomial, Some(tracked.num_bytes))
+            }
+        };
+        if let Some(num_bytes) = paged_in_bytes {
+            state.add_resident_bytes(num_bytes);
+            state.num_page_ins += 1;
+            if let Some(budget) = self.memory_budget {
+                state.enforce_budget(budget, key)?;
+            }
+        }
+        Ok(polynomial)
     }
 
     /// Erase a polynomial from the PolynomialStore; will throw exception if the key does not exist
@@ -56,51 +276,110 @@ impl<Fr: Field> PolynomialStore<Fr> {
     /// # Returns
     /// - `Result<Polynomial>` - the polynomial associated with the given key
     pub(crate) fn remove(&mut self, key: String) -> Result<Polynomial<Fr>> {
-        let wrapped_poly = self
-            .polynomial_map
-            .remove(&key)
+        let entry = self
+            .state()
+            .remove_entry(&key)
             .ok_or_else(|| anyhow!("didn't find polynomial..."))?;
-        let poly = Rc::try_unwrap(wrapped_poly).map_err(|_| anyhow!("unwrapping rc failed"))?;
-        Ok(poly.into_inner())
+        match entry {
+            StoreEntry::Resident(wrapped_poly) => {
+                let poly =
+                    Arc::try_unwrap(wrapped_poly).map_err(|_| anyhow!("unwrapping arc failed"))?;
+                poly.into_inner()
+                    .map_err(|_| anyhow!("polynomial lock was poisoned"))
+            }
+            StoreEntry::Spilled(spilled) => spilled.read(),
+        }
     }
 
-    /// Get the current size (bytes) of all polynomials in the PolynomialStore
+    /// Get the current size (bytes) of all polynomials in the PolynomialStore, spilled or not
     ///
     /// # Returns
     /// - `usize` - the size
     fn get_size_in_bytes(&self) -> usize {
-        let mut size_in_bytes: usize = 0;
-        for (_, entry) in self.polynomial_map.iter() {
-            size_in_bytes += entry.borrow().size() * std::mem::size_of::<Fr>();
-        }
-        size_in_bytes
+        let stats = self.stats();
+        stats.resident_bytes + stats.spilled_bytes
+    }
+
+    /// The most bytes of polynomials we've held in memory at once since the store was created.
+    pub(crate) fn get_peak_size_in_bytes(&self) -> usize {
+        self.state().peak_resident_bytes
     }
 
-    pub(crate) fn insert(&mut self, key: &String, poly: Polynomial<Fr>) {
-        self.polynomial_map
-            .insert(key.to_string(), Rc::new(RefCell::new(poly)));
+    pub(crate) fn insert(&mut self, key: &String, poly: Polynomial<Fr>) -> Result<()> {
+        self.put(key.to_string(), poly)
     }
 
-    fn contains(&self, key: &String) -> bool {
-        self.polynomial_map.contains_key(key)
+    pub(crate) fn contains(&self, key: &String) -> bool {
+        self.state().polynomial_map.contains_key(key)
     }
 
     fn len(&self) -> usize {
-        self.polynomial_map.len()
+        self.state().polynomial_map.len()
+    }
+
+    pub(crate) fn stats(&self) -> PolynomialStoreStats {
+        let state = self.state();
+        let mut polynomials: Vec<PolynomialStats> = state
+            .polynomial_map
+            .iter()
+            .map(|(name, tracked)| PolynomialStats {
+                name: name.clone(),
+                num_bytes: tracked.num_bytes,
+                resident: matches!(tracked.entry, StoreEntry::Resident(_)),
+            })
+            .collect();
+        polynomials.sort_by(|a, b| a.name.cmp(&b.name));
+        let spilled_bytes = polynomials
+            .iter()
+            .filter(|p| !p.resident)
+            .map(|p| p.num_bytes)
+            .sum();
+
+        PolynomialStoreStats {
+            resident_bytes: state.resident_bytes,
+            spilled_bytes,
+            peak_resident_bytes: state.peak_resident_bytes,
+            num_spills: state.num_spills,
+            num_page_ins: state.num_page_ins,
+            polynomials,
+        }
+    }
+
+    /// Log the contents of the store, see the `Display` impl.
+    pub(crate) fn print(&self) {
+        info!("{}", self);
     }
     // TODO: "allow for const range based for loop"
 }
 
 impl<Fr: Field> Display for PolynomialStore<Fr> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let size_in_mb = (self.get_size_in_bytes() / 1_000_000) as f32;
-        write!(f, "PolynomialStore contents total size: {} MB", size_in_mb)?;
-        for (key, entry) in self.polynomial_map.iter() {
-            let entry_bytes = entry.borrow().size() * std::mem::size_of::<Fr>();
-            write!(
+        let stats = self.stats();
+        writeln!(
+            f,
+            "PolynomialStore contents total size: {} MB ({} MB in memory, {} MB on disk)",
+            (stats.resident_bytes + stats.spilled_bytes) / 1_000_000,
+            stats.resident_bytes / 1_000_000,
+            stats.spilled_bytes / 1_000_000,
+        )?;
+        writeln!(
+            f,
+            "PolynomialStore: peak {} MB in memory, {} spills, {} page-ins",
+            stats.peak_resident_bytes / 1_000_000,
+            stats.num_spills,
+            stats.num_page_ins,
+        )?;
+        for polynomial in stats.polynomials {
+            writeln!(
                 f,
-                "PolynomialStore: {} -> {} bytes, {:?}",
-                key, entry_bytes, entry
+                "PolynomialStore: {} -> {} bytes{}",
+                polynomial.name,
+                polynomial.num_bytes,
+                if polynomial.resident {
+                    ""
+                } else {
+                    " (on disk)"
+                }
             )?;
         }
         Ok(())
@@ -109,8 +388,107 @@ impl<Fr: Field> Display for PolynomialStore<Fr> {
 
 #[cfg(test)]
 mod test {
+    use ark_bn254::Fr;
+    use ark_ff::UniformRand;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::sync::Arc;
+
+    use super::PolynomialStore;
+    use crate::polynomials::Polynomial;
+
+    fn random_polynomial(size: usize, rng: &mut StdRng) -> Polynomial<Fr> {
+        let mut polynomial = Polynomial::new(size);
+        for coeff in polynomial.coefficients.iter_mut() {
+            *coeff = Fr::rand(rng);
+        }
+        polynomial
+    }
+
     #[test]
     fn test_todo() {
         todo!("check out polynomial_store.test.cpp")
     }
+
+    #[test]
+    fn spills_least_recently_used_and_pages_back_in() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let size = 64;
+        let poly_bytes = size * std::mem::size_of::<Fr>();
+        // room for two polynomials
+        let mut store = PolynomialStore::with_memory_budget(2 * poly_bytes);
+
+        let polys: Vec<Polynomial<Fr>> =
+            (0..3).map(|_| random_polynomial(size, &mut rng)).collect();
+        store.put("a".to_string(), polys[0].clone()).unwrap();
+        store.put("b".to_string(), polys[1].clone()).unwrap();
+        let b = Arc::downgrade(&store.get(&"b".to_string()).unwrap());
+        // touch "a" so that "b" is the least recently used
+        let a = Arc::downgrade(&store.get(&"a".to_string()).unwrap());
+        store.put("c".to_string(), polys[2].clone()).unwrap();
+
+        let stats = store.stats();
+        assert_eq!(stats.num_spills, 1);
+        assert_eq!(stats.resident_bytes, 2 * poly_bytes);
+        assert_eq!(stats.spilled_bytes, poly_bytes);
+        let b_stats = stats.polynomials.iter().find(|p| p.name == "b").unwrap();
+        assert!(!b_stats.resident);
+        assert_eq!(b_stats.num_bytes, poly_bytes);
+        // the store let go of its copy in memory
+        assert!(b.upgrade().is_none());
+        assert!(a.upgrade().is_some());
+
+        // paging "b" back in pushes out "a", the least recently used now
+        assert_eq!(
+            *store.get(&"b".to_string()).unwrap().read().unwrap(),
+            polys[1]
+        );
+        let stats = store.stats();
+        assert_eq!(stats.num_page_ins, 1);
+        assert_eq!(stats.num_spills, 2);
+        // we only ever go over budget by the polynomial that was just put or paged in
+        assert_eq!(stats.peak_resident_bytes, 3 * poly_bytes);
+        assert_eq!(stats.resident_bytes, 2 * poly_bytes);
+        assert!(
+            !stats
+                .polynomials
+                .iter()
+                .find(|p| p.name == "a")
+                .unwrap()
+                .resident
+        );
+        assert!(a.upgrade().is_none());
+
+        assert_eq!(store.remove("a".to_string()).unwrap(), polys[0]);
+        assert_eq!(store.remove("c".to_string()).unwrap(), polys[2]);
+        assert_eq!(store.get_size_in_bytes(), poly_bytes);
+    }
+
+    #[test]
+    fn does_not_spill_polynomials_in_use() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let size = 64;
+        let poly_bytes = size * std::mem::size_of::<Fr>();
+        let mut store = PolynomialStore::with_memory_budget(poly_bytes);
+
+        store
+            .put("a".to_string(), random_polynomial(size, &mut rng))
+            .unwrap();
+        let a = store.get(&"a".to_string()).unwrap();
+        store
+            .put("b".to_string(), random_polynomial(size, &mut rng))
+            .unwrap();
+
+        // "a" is still borrowed and "b" is the newest, so we go over budget rather than spill
+        let stats = store.stats();
+        assert_eq!(stats.num_spills, 0);
+        assert_eq!(stats.resident_bytes, 2 * poly_bytes);
+
+        drop(a);
+        store
+            .put("c".to_string(), random_polynomial(size, &mut rng))
+            .unwrap();
+        assert_eq!(store.stats().num_spills, 2);
+        assert!(store.contains(&"a".to_string()));
+        assert_eq!(store.len(), 3);
+    }
 }
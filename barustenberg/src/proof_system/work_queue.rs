@@ -1,16 +1,21 @@
-use ark_ec::AffineRepr;
-use ark_ff::{FftField, Field};
-use std::cell::RefCell;
-use std::rc::Rc;
+use ark_ff::{Field, Zero};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 
-use crate::ecc::PippengerRuntimeState;
+use crate::common::thread::parallel_map;
+use crate::ecc::{curves::CurveConfig, fields::to_usize, PippengerRuntimeState};
 use crate::plonk::proof_system::proving_key::ProvingKey;
 use crate::polynomials::Polynomial;
 use crate::transcript::{BarretenHasher, Transcript};
 
-#[derive(PartialEq, Eq, Clone)]
+/// A polynomial shared between the work queue and whoever reads its results.
+type SharedPolynomial<F> = Arc<RwLock<Polynomial<F>>>;
+
+#[derive(Clone)]
 pub(crate) enum Work<Fr: Field> {
     Fft {
         index: usize,
@@ -22,14 +27,14 @@ pub(crate) enum Work<Fr: Field> {
     Ifft,
     ScalarMultiplication {
         constant: Fr,
-        mul_scalars: Rc<RefCell<Polynomial<Fr>>>,
+        mul_scalars: Arc<RwLock<Polynomial<Fr>>>,
     },
 }
 
 pub(crate) struct WorkItemInfo {
-    num_scalar_multiplications: usize,
-    num_ffts: usize,
-    num_iffts: usize,
+    pub(crate) num_scalar_multiplications: usize,
+    pub(crate) num_ffts: usize,
+    pub(crate) num_iffts: usize,
 }
 
 pub(crate) enum WorkItemConstant<Fr: Field> {
@@ -49,38 +54,66 @@ pub(crate) struct WorkItem<Fr: Field> {
 }
 
 pub(crate) struct QueuedFftInputs<Fr: Field> {
-    data: Rc<RefCell<Polynomial<Fr>>>,
-    shift_factor: Fr,
+    pub(crate) data: Arc<RwLock<Polynomial<Fr>>>,
+    pub(crate) shift_factor: Fr,
 }
 
-pub(crate) struct WorkQueue<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr> {
-    key: Rc<RefCell<ProvingKey<'a, Fr, G1Affine>>>,
-    transcript: Rc<RefCell<Transcript<H, Fr, G1Affine>>>,
-    work_items: Vec<WorkItem<Fr>>,
+/// Something outside the queue that can take work off our hands, e.g. a host application sending MSMs
+/// to another process.
+///
+/// `execute` is called at the start of `process_queue`. It can pick up any of the queued scalar
+/// multiplications, small FFTs and IFFTs through the `get_*_data` methods and hand results back through
+/// the matching `put_*_data` method. Whatever it doesn't put back gets computed by the queue itself.
+pub(crate) trait WorkExecutor<'a, H: BarretenHasher, C: CurveConfig>: Send + Sync {
+    fn execute(&self, queue: &WorkQueue<'a, H, C>) -> Result<()>;
 }
 
-/// super fucked up...
-unsafe fn field_element_to_usize<F: Field>(element: F) -> usize {
-    // pretending to be this: static_cast<size_t>(static_cast<uint256_t>(item.constant));
-    // first turn it into a u256 (by memtransmute into a slice!)
-    let u256_bytes: [u8; 32] = std::mem::transmute_copy(&element);
-    std::mem::transmute_copy(&u256_bytes)
+pub(crate) struct WorkQueue<'a, H: BarretenHasher, C: CurveConfig> {
+    key: Arc<RwLock<ProvingKey<'a, C>>>,
+    transcript: Arc<RwLock<Transcript<H, C>>>,
+    work_items: Vec<WorkItem<C::Fr>>,
+    executor: Option<Arc<dyn WorkExecutor<'a, H, C> + 'a>>,
+    /// indices into `work_items` whose results have already been put back by the executor
+    completed_items: RwLock<HashSet<usize>>,
 }
 
-impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
-    WorkQueue<'a, H, Fr, G1Affine>
-{
+impl<'a, H: BarretenHasher, C: CurveConfig> WorkQueue<'a, H, C> {
     pub(crate) fn new(
-        prover_key: Option<Rc<RefCell<ProvingKey<'a, Fr, G1Affine>>>>,
-        prover_transcript: Option<Rc<RefCell<Transcript<H, Fr, G1Affine>>>>,
+        prover_key: Option<Arc<RwLock<ProvingKey<'a, C>>>>,
+        prover_transcript: Option<Arc<RwLock<Transcript<H, C>>>>,
     ) -> Self {
         WorkQueue {
             key: prover_key.unwrap_or_default(),
             transcript: prover_transcript.unwrap_or_default(),
             work_items: Vec::new(),
+            executor: None,
+            completed_items: RwLock::new(HashSet::new()),
         }
     }
 
+    /// Hand queued work to `executor` before we process it ourselves. See [`WorkExecutor`].
+    pub(crate) fn set_executor(&mut self, executor: Arc<dyn WorkExecutor<'a, H, C> + 'a>) {
+        self.executor = Some(executor);
+    }
+
+    /// Position in `work_items` of the `work_item_number`'th item matching `predicate`.
+    fn find_item(
+        &self,
+        work_item_number: usize,
+        predicate: impl Fn(&Work<C::Fr>) -> bool,
+    ) -> Option<usize> {
+        self.work_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| predicate(&item.work))
+            .nth(work_item_number)
+            .map(|(idx, _)| idx)
+    }
+
+    fn mark_completed(&self, idx: usize) {
+        self.completed_items.write().unwrap().insert(idx);
+    }
+
     pub(crate) fn get_queued_work_item_info(&self) -> WorkItemInfo {
         let mut num_scalar_multiplications = 0;
         let mut num_ffts = 0;
@@ -104,7 +137,7 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
     pub(crate) fn get_scalar_multiplication_data(
         &self,
         work_item_number: usize,
-    ) -> Option<Rc<RefCell<Polynomial<Fr>>>> {
+    ) -> Option<SharedPolynomial<C::Fr>> {
         let mut count: usize = 0;
         for item in self.work_items.iter() {
             if let Work::ScalarMultiplication { mul_scalars, .. } = item.work.clone() {
@@ -122,7 +155,8 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
         for item in self.work_items.iter() {
             if let Work::ScalarMultiplication { constant, .. } = item.work {
                 if count == work_item_number {
-                    return unsafe { field_element_to_usize(constant) };
+                    return to_usize(&constant)
+                        .expect("scalar multiplication sizes fit in a usize");
                 };
                 count += 1;
             }
@@ -133,7 +167,7 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
     pub(crate) fn get_ifft_data(
         &self,
         work_item_number: usize,
-    ) -> Result<Option<Rc<RefCell<Polynomial<Fr>>>>> {
+    ) -> Result<Option<SharedPolynomial<C::Fr>>> {
         let mut count: usize = 0;
         for item in self.work_items.iter() {
             if let Work::Ifft = item.work {
@@ -141,7 +175,8 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
                     //todo!("look at this code");
                     return Ok(Some(
                         self.key
-                            .borrow()
+                            .read()
+                            .unwrap()
                             .polynomial_store
                             .get(&format!("{}_lagrange", item.tag))
                             .unwrap(),
@@ -153,34 +188,34 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
         Ok(None)
     }
 
-    pub(crate) fn put_ifft_data(&mut self, result: &mut [Fr], work_item_number: usize) {
-        for (ix, item) in self.work_items.iter().enumerate() {
-            if let Work::Ifft = item.work {
-                if ix == work_item_number {
-                    // barretenberg::polynomial wire(key->circuit_size);
-                    // memcpy((void*)wire.get_coefficients(), result, key->circuit_size * sizeof(barretenberg::fr));
-                    // key->polynomial_store.put(item.tag, std::move(wire));
-                    let wire = Polynomial::new(self.key.borrow().circuit_size);
-                    result.copy_from_slice(wire.coefficients.as_slice());
-                    (*self.key)
-                        .borrow_mut()
-                        .polynomial_store
-                        .put(item.tag.clone(), wire);
-                    return;
-                }
-            }
+    pub(crate) fn put_ifft_data(&self, result: &[C::Fr], work_item_number: usize) -> Result<()> {
+        if let Some(idx) = self.find_item(work_item_number, |work| matches!(work, Work::Ifft)) {
+            // barretenberg::polynomial wire(key->circuit_size);
+            // memcpy((void*)wire.get_coefficients(), result, key->circuit_size * sizeof(barretenberg::fr));
+            // key->polynomial_store.put(item.tag, std::move(wire));
+            let n = self.key.read().unwrap().circuit_size;
+            let mut wire = Polynomial::new(n);
+            wire.coefficients.copy_from_slice(&result[..n]);
+            self.key
+                .write()
+                .unwrap()
+                .polynomial_store
+                .put(self.work_items[idx].tag.clone(), wire)?;
+            self.mark_completed(idx);
         }
+        Ok(())
     }
 
-    pub(crate) fn get_fft_data(&self, work_item_number: usize) -> Option<QueuedFftInputs<Fr>> {
+    pub(crate) fn get_fft_data(&self, work_item_number: usize) -> Option<QueuedFftInputs<C::Fr>> {
         let mut count = 0;
         for item in &self.work_items {
             if let Work::SmallFft { index, .. } = item.work {
                 if count == work_item_number {
-                    let wire = self.key.borrow().polynomial_store.get(&item.tag).unwrap();
+                    let key = self.key.read().unwrap();
+                    let wire = key.polynomial_store.get(&item.tag).unwrap();
                     return Some(QueuedFftInputs {
                         data: wire,
-                        shift_factor: self.key.borrow().large_domain.root.pow([index as u64]),
+                        shift_factor: key.large_domain.root.pow([index as u64]),
                     });
                 }
                 count += 1;
@@ -189,59 +224,54 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
         None
     }
 
-    pub(crate) fn put_fft_data(&self, result: Vec<Fr>, work_item_number: usize) {
-        let mut count = 0;
-        for item in &self.work_items {
+    pub(crate) fn put_fft_data(&self, result: &[C::Fr], work_item_number: usize) -> Result<()> {
+        if let Some(idx) = self.find_item(work_item_number, |work| {
+            matches!(work, Work::SmallFft { .. })
+        }) {
+            let item = &self.work_items[idx];
             if let Work::SmallFft { index, .. } = item.work {
-                if count == work_item_number {
-                    let n = self.key.borrow().circuit_size;
-                    let mut wire_fft = Polynomial::new(4 * n + 4);
-
-                    for i in 0..n {
-                        wire_fft[4 * i + index] = result[i];
-                    }
-                    wire_fft[4 * n + index] = result[0];
-
-                    (*self.key)
-                        .borrow_mut()
-                        .polynomial_store
-                        .insert(&format!("{}_fft", item.tag), wire_fft);
-                    return;
-                }
-                count += 1;
+                self.insert_small_fft_result(&item.tag, index, result)?;
+                self.mark_completed(idx);
             }
         }
+        Ok(())
     }
 
     pub(crate) fn put_scalar_multiplication_data(
         &self,
-        result: G1Affine,
+        result: C::G1Affine,
         work_item_number: usize,
     ) -> Result<()> {
-        for (idx, item) in self.work_items.iter().enumerate() {
-            if let Work::ScalarMultiplication { .. } = &item.work {
-                if idx == work_item_number {
-                    (*self.transcript)
-                        .borrow_mut()
-                        .add_group_element(&item.tag, &result);
-                    return Ok(());
-                }
-            }
+        if let Some(idx) = self.find_item(work_item_number, |work| {
+            matches!(work, Work::ScalarMultiplication { .. })
+        }) {
+            self.transcript
+                .write()
+                .unwrap()
+                .add_group_element(&self.work_items[idx].tag, &result);
+            self.mark_completed(idx);
         }
         Ok(())
     }
 
+    /// Adds `proof` to the transcript under `tag`, for executors whose results are opening proofs rather than
+    /// commitments to queued polynomials.
+    pub(crate) fn put_opening_proof_data(&self, tag: &str, proof: Vec<u8>) {
+        self.transcript.write().unwrap().add_element(tag, proof);
+    }
+
     pub(crate) fn flush_queue(&mut self) {
         self.work_items = vec![];
+        self.completed_items.write().unwrap().clear();
     }
-    pub(crate) fn add_to_queue(&mut self, work_item: WorkItem<Fr>) {
+    pub(crate) fn add_to_queue(&mut self, work_item: WorkItem<C::Fr>) {
         #[cfg(target_arch = "wasm32")]
         // #[cfg(debug_assertions)]
         todo!("unimplemented");
         // if let WorkType::FFT = item.work_type {
         //     let large_root = &self.key.large_domain.root;
         //     let coset_shifts = [
-        //         Fr(1),
+        //         C::Fr(1),
         //         *large_root,
         //         large_root.sqr(),
         //         large_root.sqr() * *large_root,
@@ -262,127 +292,472 @@ impl<'a, H: BarretenHasher, Fr: Field + FftField, G1Affine: AffineRepr>
         self.work_items.push(work_item);
     }
 
-    pub(crate) fn process_queue(&mut self) -> Result<()> {
-        for item in &self.work_items {
-            match &item.work {
-                Work::ScalarMultiplication {
-                    constant,
-                    mul_scalars,
-                } => {
-                    let msm_size = unsafe { field_element_to_usize(*constant) };
-
-                    assert!(
-                        msm_size
-                            <= (*(*self.key).borrow().reference_string)
-                                .borrow()
-                                .get_monomial_size()
-                    );
-
-                    let srs_points = (*self.key.borrow().reference_string)
-                        .borrow_mut()
-                        .get_monomial_points();
-
-                    let mut runtime_state: PippengerRuntimeState<Fr, G1Affine> =
-                        PippengerRuntimeState::new(msm_size);
-                    let result = G1Affine::from(
-                        runtime_state
-                            .pippenger_unsafe(
-                                (*mul_scalars).borrow_mut().coefficients.as_mut_slice(),
-                                &srs_points,
-                                msm_size,
-                            )
-                            .into(),
-                    );
-
-                    (*self.transcript)
-                        .borrow_mut()
-                        .add_group_element(&item.tag, &result);
-                }
-                Work::SmallFft { index, constant } => {
-                    let n = self.key.borrow().circuit_size;
-                    let wire = self.key.borrow().polynomial_store.get(&item.tag).unwrap();
-                    self.key
-                        .borrow()
-                        .small_domain
-                        .coset_fft_with_generator_shift(
-                            (*wire).borrow_mut().coefficients.as_mut_slice(),
-                            *constant,
-                        );
-
-                    if *index != 0 {
-                        let old_wire_fft = self
-                            .key
-                            .borrow()
-                            .polynomial_store
-                            .get(&(item.tag.clone() + "_fft"))
-                            .unwrap()
-                            .clone();
-                        for i in 0..n {
-                            (*old_wire_fft).borrow_mut()[4 * i + index] = wire.borrow()[i];
-                        }
-                        (*old_wire_fft).borrow_mut()[4 * n + index] = wire.borrow()[0];
-                    } else {
-                        let mut wire_fft = Polynomial::new(4 * n + 4);
-                        for i in 0..n {
-                            wire_fft[4 * i + index] = wire.borrow()[i];
-                        }
-
-                        (*self.key)
-                            .borrow_mut()
-                            .polynomial_store
-                            .insert(&format!("{}_fft", item.tag.clone()), wire_fft);
-                    }
-                }
-                Work::Fft { .. } => {
-                    let mut wire_fft = self
-                        .key
-                        .borrow()
-                        .polynomial_store
-                        .get(&item.tag)
-                        .unwrap()
-                        .borrow()
-                        .clone();
-
-                    wire_fft.resize(4 * self.key.borrow().circuit_size + 4, Fr::zero());
-
-                    self.key
-                        .borrow()
-                        .large_domain
-                        .coset_fft_inplace(wire_fft.coefficients.as_mut_slice());
-                    for i in 0..4 {
-                        wire_fft[4 * self.key.borrow().circuit_size + i] = wire_fft[i];
-                    }
-
-                    (*self.key)
-                        .borrow_mut()
-                        .polynomial_store
-                        .insert(&format!("{}_fft", item.tag.clone()), wire_fft);
+    /// Interleave the `index`'th quarter of `tag`'s coset FFT into `{tag}_fft`, creating it if this is the first
+    /// quarter to arrive.
+    fn insert_small_fft_result(&self, tag: &str, index: usize, result: &[C::Fr]) -> Result<()> {
+        let name = format!("{}_fft", tag);
+        let mut key = self.key.write().unwrap();
+        let n = key.circuit_size;
+        ensure!(
+            index < 4,
+            "small fft index {} is not a quarter of the coset",
+            index
+        );
+        ensure!(
+            result.len() >= n,
+            "small fft of {} has {} evaluations, expected {}",
+            tag,
+            result.len(),
+            n
+        );
+        let wire_fft = match key.polynomial_store.get(&name) {
+            Ok(wire_fft) => wire_fft,
+            Err(_) => {
+                key.polynomial_store
+                    .insert(&name, Polynomial::new(4 * n + 4))?;
+                key.polynomial_store.get(&name)?
+            }
+        };
+        let mut wire_fft = wire_fft.write().unwrap();
+        for i in 0..n {
+            wire_fft[4 * i + index] = result[i];
+        }
+        wire_fft[4 * n + index] = result[0];
+        Ok(())
+    }
+
+    /// FFTs run on rayon and may wait on nested parallel work, so none of them runs while we hold a lock on the
+    /// key: each item copies its input and domain out of the key and only goes back in to store its result.
+    fn process_item(&self, item: &WorkItem<C::Fr>) -> Result<()> {
+        match &item.work {
+            Work::ScalarMultiplication {
+                constant,
+                mul_scalars,
+            } => {
+                let msm_size = to_usize(constant)?;
+
+                let reference_string = self.key.read().unwrap().reference_string.clone();
+                let srs_size = reference_string.read().unwrap().get_monomial_size();
+                ensure!(
+                    msm_size <= srs_size,
+                    "commitment to {} of size {} exceeds the {} points of the reference string",
+                    item.tag,
+                    msm_size,
+                    srs_size
+                );
+
+                let srs_points = reference_string.write().unwrap().get_monomial_points();
+
+                let mut mul_scalars = mul_scalars.write().unwrap();
+                // the size is the degree bound the commitment is made with, and coefficients past the end of
+                // the polynomial are zero
+                let msm_size = std::cmp::min(msm_size, mul_scalars.coefficients.len());
+                let mut runtime_state: PippengerRuntimeState<C::Fr, C::G1Affine> =
+                    PippengerRuntimeState::new(msm_size);
+                let result = runtime_state.pippenger_unsafe(
+                    mul_scalars.coefficients.as_mut_slice(),
+                    &srs_points,
+                    msm_size,
+                );
+
+                self.transcript
+                    .write()
+                    .unwrap()
+                    .add_group_element(&item.tag, &result);
+            }
+            Work::SmallFft { index, constant } => {
+                let (wire, small_domain) = {
+                    let key = self.key.read().unwrap();
+                    (
+                        key.polynomial_store.get(&item.tag)?,
+                        key.small_domain.clone(),
+                    )
+                };
+                // the other quarters still need the monomial form, so transform a copy
+                let mut wire_copy = wire.read().unwrap().coefficients.clone();
+                wire_copy.resize(small_domain.size, C::Fr::zero());
+                small_domain.coset_fft_with_generator_shift(&mut wire_copy, *constant);
+
+                self.insert_small_fft_result(&item.tag, *index, &wire_copy)?;
+            }
+            Work::Fft { .. } => {
+                let (wire, n, large_domain) = {
+                    let key = self.key.read().unwrap();
+                    (
+                        key.polynomial_store.get(&item.tag)?,
+                        key.circuit_size,
+                        key.large_domain.clone(),
+                    )
+                };
+                let mut wire_fft = wire.read().unwrap().clone();
+
+                wire_fft.resize(4 * n + 4, C::Fr::zero());
+                large_domain.coset_fft_inplace(wire_fft.coefficients.as_mut_slice());
+                for i in 0..4 {
+                    wire_fft[4 * n + i] = wire_fft[i];
                 }
-                Work::Ifft => {
-                    let wire_lagrange = self
-                        .key
-                        .borrow()
-                        .polynomial_store
-                        .get(&(format!("{}_lagrange", item.tag.clone())))
-                        .unwrap();
-
-                    let mut wire_monomial = Polynomial::new(self.key.borrow().circuit_size);
-                    self.key.borrow().small_domain.ifft(
-                        wire_lagrange.borrow_mut().coefficients.as_mut_slice(),
-                        wire_monomial.coefficients.as_mut_slice(),
-                    );
-                    (*self.key)
-                        .borrow_mut()
-                        .polynomial_store
-                        .insert(&item.tag, wire_monomial);
+
+                self.key
+                    .write()
+                    .unwrap()
+                    .polynomial_store
+                    .insert(&format!("{}_fft", item.tag), wire_fft)?;
+            }
+            Work::Ifft => {
+                let (wire_lagrange, n, small_domain) = {
+                    let key = self.key.read().unwrap();
+                    (
+                        key.polynomial_store
+                            .get(&format!("{}_lagrange", item.tag))?,
+                        key.circuit_size,
+                        key.small_domain.clone(),
+                    )
+                };
+                let mut wire_lagrange = wire_lagrange.read().unwrap().coefficients.clone();
+
+                let mut wire_monomial = Polynomial::new(n);
+                small_domain.ifft(
+                    wire_lagrange.as_mut_slice(),
+                    wire_monomial.coefficients.as_mut_slice(),
+                );
+                self.key
+                    .write()
+                    .unwrap()
+                    .polynomial_store
+                    .insert(&item.tag, wire_monomial)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run each batch of items on its own thread; items within a batch run in order.
+    fn process_batches(&self, batches: &[Vec<&WorkItem<C::Fr>>]) -> Result<()> {
+        parallel_map(batches.len(), |i| {
+            batches[i]
+                .iter()
+                .try_for_each(|item| self.process_item(item))
+        })
+        .into_iter()
+        .collect()
+    }
+
+    /// Process everything in the queue, then clear it.
+    ///
+    /// If an executor is set it gets first go at the queue. The remaining items are independent of each
+    /// other except that an IFFT produces a monomial polynomial a later FFT may read, and the small FFTs
+    /// of one tag all write into the same `{tag}_fft` polynomial. So we run the IFFTs first, then
+    /// everything else, with each tag's small FFTs kept together in one batch.
+    pub(crate) fn process_queue(&mut self) -> Result<()> {
+        if let Some(executor) = self.executor.clone() {
+            executor.execute(self)?;
+        }
+
+        let completed_items = std::mem::take(&mut *self.completed_items.write().unwrap());
+        let mut iffts = Vec::new();
+        let mut others = Vec::new();
+        let mut small_ffts: HashMap<&str, Vec<&WorkItem<C::Fr>>> = HashMap::new();
+        for (idx, item) in self.work_items.iter().enumerate() {
+            if completed_items.contains(&idx) {
+                continue;
+            }
+            match item.work {
+                Work::Ifft => iffts.push(vec![item]),
+                Work::SmallFft { .. } => {
+                    small_ffts.entry(item.tag.as_str()).or_default().push(item)
                 }
+                Work::Fft { .. } | Work::ScalarMultiplication { .. } => others.push(vec![item]),
             }
         }
+        others.extend(small_ffts.into_values());
+
+        self.process_batches(&iffts)?;
+        self.process_batches(&others)?;
+
         self.work_items.clear();
         Ok(())
     }
 
-    fn get_queue(&self) -> &Vec<WorkItem<Fr>> {
+    fn get_queue(&self) -> &Vec<WorkItem<C::Fr>> {
         &self.work_items
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, RwLock};
+
+    use ark_bn254::{Fr, G1Affine, G1Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::{Field, UniformRand, Zero};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{Work, WorkExecutor, WorkItem, WorkQueue};
+    use crate::{
+        ecc::{curves::Bn254, PippengerRuntimeState},
+        plonk::proof_system::{proving_key::ProvingKey, types::PolynomialManifest},
+        polynomials::{
+            evaluation_domain::EvaluationDomain, polynomial_arithmetic::evaluate, Polynomial,
+        },
+        proof_system::polynomial_store::PolynomialStore,
+        srs::reference_string::ProverReferenceString,
+        transcript::{Keccak256, Transcript},
+    };
+
+    struct TestReferenceString {
+        points: Arc<Vec<G1Affine>>,
+    }
+
+    impl ProverReferenceString<G1Affine> for TestReferenceString {
+        fn get_monomial_points(&mut self) -> Arc<Vec<G1Affine>> {
+            self.points.clone()
+        }
+        fn get_monomial_size(&self) -> usize {
+            self.points.len()
+        }
+    }
+
+    /// Stands in for a host application: does every MSM naively, in reverse order.
+    struct NaiveMsmExecutor {
+        points: Arc<Vec<G1Affine>>,
+    }
+
+    impl<'a> WorkExecutor<'a, Keccak256, Bn254> for NaiveMsmExecutor {
+        fn execute(&self, queue: &WorkQueue<'a, Keccak256, Bn254>) -> anyhow::Result<()> {
+            let num_msms = queue.get_queued_work_item_info().num_scalar_multiplications;
+            for i in (0..num_msms).rev() {
+                let scalars = queue.get_scalar_multiplication_data(i).unwrap();
+                let result = naive_msm(&self.points, &scalars.read().unwrap().coefficients);
+                queue.put_scalar_multiplication_data(result, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn naive_msm(points: &[G1Affine], scalars: &[Fr]) -> G1Affine {
+        points
+            .iter()
+            .zip(scalars)
+            .fold(G1Projective::zero(), |acc, (p, s)| acc + *p * s)
+            .into_affine()
+    }
+
+    fn test_key(points: Arc<Vec<G1Affine>>) -> ProvingKey<'static, Bn254> {
+        let n = points.len();
+        let mut small_domain = EvaluationDomain::new(n, None);
+        let mut large_domain = EvaluationDomain::new(4 * n, None);
+        small_domain.compute_lookup_table();
+        large_domain.compute_lookup_table();
+        ProvingKey {
+            composer_type: 0,
+            circuit_size: n,
+            log_circuit_size: 0,
+            num_public_inputs: 0,
+            contains_recursive_proof: false,
+            recursive_proof_public_input_indices: vec![],
+            memory_read_records: vec![],
+            memory_write_records: vec![],
+            polynomial_store: PolynomialStore::new(),
+            small_domain,
+            large_domain,
+            reference_string: Arc::new(RwLock::new(TestReferenceString { points })),
+            quotient_polynomial_parts: Default::default(),
+            pippenger_runtime_state: PippengerRuntimeState::default(),
+            polynomial_manifest: PolynomialManifest::default(),
+        }
+    }
+
+    #[test]
+    fn executor_results_land_on_the_right_tags() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 16;
+        let points: Arc<Vec<G1Affine>> = Arc::new(
+            (0..n)
+                .map(|_| G1Projective::rand(&mut rng).into_affine())
+                .collect(),
+        );
+        let key = Arc::new(RwLock::new(test_key(points.clone())));
+        let transcript = Arc::new(RwLock::new(Transcript::<Keccak256, Bn254>::default()));
+        let mut queue = WorkQueue::new(Some(key), Some(transcript.clone()));
+
+        let mut expected = Vec::new();
+        for tag in ["W_1", "W_2", "W_3"] {
+            let mut scalars = Polynomial::new(n);
+            for coeff in scalars.coefficients.iter_mut() {
+                *coeff = Fr::rand(&mut rng);
+            }
+            expected.push((tag, naive_msm(&points, &scalars.coefficients)));
+            queue.add_to_queue(WorkItem {
+                work: Work::ScalarMultiplication {
+                    constant: Fr::from(n as u64),
+                    mul_scalars: Arc::new(RwLock::new(scalars)),
+                },
+                tag: tag.to_string(),
+            });
+        }
+
+        queue.set_executor(Arc::new(NaiveMsmExecutor { points }));
+        queue.process_queue().unwrap();
+
+        let transcript = transcript.read().unwrap();
+        for (tag, commitment) in expected {
+            assert_eq!(transcript.get_group_element(tag).unwrap(), commitment);
+        }
+        assert_eq!(
+            queue.get_queued_work_item_info().num_scalar_multiplications,
+            0
+        );
+    }
+
+    #[test]
+    fn commitments_larger_than_the_reference_string_are_errors() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let n = 16;
+        let points: Arc<Vec<G1Affine>> = Arc::new(
+            (0..n)
+                .map(|_| G1Projective::rand(&mut rng).into_affine())
+                .collect(),
+        );
+        let key = Arc::new(RwLock::new(test_key(points)));
+        let transcript = Arc::new(RwLock::new(Transcript::<Keccak256, Bn254>::default()));
+        let mut queue = WorkQueue::new(Some(key), Some(transcript));
+        queue.add_to_queue(WorkItem {
+            work: Work::ScalarMultiplication {
+                constant: Fr::from(n as u64 + 1),
+                mul_scalars: Arc::new(RwLock::new(random_polynomial(n + 1, &mut rng))),
+            },
+            tag: "W_1".to_string(),
+        });
+        assert!(queue.process_queue().is_err());
+    }
+
+    fn random_polynomial(n: usize, rng: &mut StdRng) -> Polynomial<Fr> {
+        let mut polynomial = Polynomial::new(n);
+        for coeff in polynomial.coefficients.iter_mut() {
+            *coeff = Fr::rand(rng);
+        }
+        polynomial
+    }
+
+    /// A queue over a circuit of size `n` whose store holds `monomial` under each of `tags`.
+    fn queue_with(
+        n: usize,
+        monomial: &Polynomial<Fr>,
+        tags: &[&str],
+    ) -> (
+        WorkQueue<'static, Keccak256, Bn254>,
+        Arc<RwLock<ProvingKey<'static, Bn254>>>,
+    ) {
+        let mut key = test_key(Arc::new(vec![G1Affine::default(); n]));
+        for tag in tags {
+            key.polynomial_store
+                .put(tag.to_string(), monomial.clone())
+                .unwrap();
+        }
+        let key = Arc::new(RwLock::new(key));
+        (WorkQueue::new(Some(key.clone()), None), key)
+    }
+
+    /// The coset FFT of `monomial` over the large domain, with the first four evaluations repeated at the end.
+    fn expected_fft(key: &ProvingKey<'_, Bn254>, monomial: &Polynomial<Fr>) -> Vec<Fr> {
+        let n = key.circuit_size;
+        let mut expected: Vec<Fr> = (0..4 * n)
+            .map(|k| {
+                let x = key.large_domain.generator * key.large_domain.root.pow([k as u64]);
+                evaluate(&monomial.coefficients, &x, n)
+            })
+            .collect();
+        expected.extend_from_within(..4);
+        expected
+    }
+
+    fn small_ffts(tag: &str, large_root: Fr) -> Vec<WorkItem<Fr>> {
+        (0..4)
+            .map(|index| WorkItem {
+                work: Work::SmallFft {
+                    constant: large_root.pow([index as u64]),
+                    index,
+                },
+                tag: tag.to_string(),
+            })
+            .collect()
+    }
+
+    fn get(key: &RwLock<ProvingKey<'_, Bn254>>, name: &str) -> Vec<Fr> {
+        let polynomial = key
+            .read()
+            .unwrap()
+            .polynomial_store
+            .get(&name.to_string())
+            .unwrap();
+        let coefficients = polynomial.read().unwrap().coefficients.clone();
+        coefficients
+    }
+
+    #[test]
+    fn fft_and_small_ffts_compute_the_large_coset_fft() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = 16;
+        let monomial = random_polynomial(n, &mut rng);
+        let (mut queue, key) = queue_with(n, &monomial, &["W_1", "W_2"]);
+        let mut lagrange = monomial.clone();
+        key.read()
+            .unwrap()
+            .small_domain
+            .fft_inplace(&mut lagrange.coefficients);
+        key.write()
+            .unwrap()
+            .polynomial_store
+            .put("W_3_lagrange".to_string(), lagrange)
+            .unwrap();
+
+        let large_root = key.read().unwrap().large_domain.root;
+        queue.add_to_queue(WorkItem {
+            work: Work::Fft { index: 0 },
+            tag: "W_1".to_string(),
+        });
+        for item in small_ffts("W_2", large_root) {
+            queue.add_to_queue(item);
+        }
+        queue.add_to_queue(WorkItem {
+            work: Work::Ifft,
+            tag: "W_3".to_string(),
+        });
+        queue.process_queue().unwrap();
+
+        let expected = expected_fft(&key.read().unwrap(), &monomial);
+        assert_eq!(get(&key, "W_1_fft"), expected);
+        assert_eq!(get(&key, "W_2_fft"), expected);
+        // the small FFTs leave the monomial form alone
+        assert_eq!(get(&key, "W_2"), monomial.coefficients);
+        assert_eq!(get(&key, "W_3"), monomial.coefficients);
+    }
+
+    #[test]
+    fn small_fft_results_can_arrive_in_any_order() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let n = 8;
+        let monomial = random_polynomial(n, &mut rng);
+        let (mut queue, key) = queue_with(n, &monomial, &["W_1"]);
+        let large_root = key.read().unwrap().large_domain.root;
+        for item in small_ffts("W_1", large_root) {
+            queue.add_to_queue(item);
+        }
+
+        // an executor that hands back the last quarter first
+        for i in (0..4).rev() {
+            let inputs = queue.get_fft_data(i).unwrap();
+            let mut result = inputs.data.read().unwrap().coefficients.clone();
+            key.read()
+                .unwrap()
+                .small_domain
+                .coset_fft_with_generator_shift(&mut result, inputs.shift_factor);
+            queue.put_fft_data(&result, i).unwrap();
+        }
+        queue.process_queue().unwrap();
+
+        assert_eq!(
+            get(&key, "W_1_fft"),
+            expected_fft(&key.read().unwrap(), &monomial)
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! Reading and writing structured reference strings.
+//!
+//! Two layouts are read. [`Srs::read`] reads this crate's own: `[τ]₂` followed by the monomial points `[τⁱ]₁`,
+//! every point in the uncompressed encoding of [`serialize`](crate::ecc::serialize), so it serves any pairing
+//! curve. [`Srs::read_ignition`] reads the transcripts of barretenberg's ignition ceremony for BN254, and
+//! [`Srs::write`] converts what it read to the former. There is no reader for a BLS12-381 ceremony yet.
+//!
+//! An ignition transcript is a [`Manifest`], then its share of the points `[τⁱ]₁`, then `[τ]₂` and any other
+//! G2 points, then a checksum, which is not checked here. A coordinate is a canonical integer written as 64 bit
+//! limbs, least significant first, each limb big-endian. The transcripts start from `[τ]₁`, so `[1]₁` is
+//! prepended.
+
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{ensure, Context, Result};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::Field;
+
+use crate::ecc::{fields::encoded_size, serialize::PointEncoding};
+
+use super::reference_string::{ProverReferenceString, VerifierReferenceString};
+
+/// A structured reference string held in memory, both the prover's and the verifier's halves.
+#[derive(Debug, Clone)]
+pub(crate) struct Srs<E: Pairing> {
+    pub(crate) monomial_points: Arc<Vec<E::G1Affine>>,
+    pub(crate) g2_x: E::G2Affine,
+}
+
+impl<E: Pairing> Srs<E>
+where
+    E::G1Affine: PointEncoding,
+    E::G2Affine: PointEncoding,
+{
+    /// The first `num_points` monomial points of the SRS encoded in `bytes`.
+    pub(crate) fn read(bytes: &[u8], num_points: usize) -> Result<Self> {
+        let g2_size = E::G2Affine::UNCOMPRESSED_SIZE;
+        let g1_size = E::G1Affine::UNCOMPRESSED_SIZE;
+        ensure!(
+            bytes.len() >= g2_size + num_points * g1_size,
+            "SRS of {} bytes holds fewer than {} points",
+            bytes.len(),
+            num_points
+        );
+        let g2_x = E::G2Affine::from_uncompressed(&bytes[..g2_size]).context("reading [τ]₂")?;
+        let monomial_points = bytes[g2_size..]
+            .chunks_exact(g1_size)
+            .take(num_points)
+            .enumerate()
+            .map(|(i, chunk)| {
+                E::G1Affine::from_uncompressed(chunk)
+                    .with_context(|| format!("reading monomial point {}", i))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            monomial_points: Arc::new(monomial_points),
+            g2_x,
+        })
+    }
+
+    /// As [`read`](Self::read), from the file at `path`.
+    pub(crate) fn read_from_path(path: impl AsRef<Path>, num_points: usize) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).with_context(|| format!("reading SRS from {}", path.display()))?;
+        Self::read(&bytes, num_points)
+    }
+
+    /// The first `num_points` monomial points and `[τ]₂` of the ignition transcripts in `dir`.
+    pub(crate) fn read_ignition(dir: impl AsRef<Path>, num_points: usize) -> Result<Self> {
+        let dir = dir.as_ref();
+        Ok(Self {
+            monomial_points: Arc::new(read_ignition_g1(dir, num_points)?),
+            g2_x: read_ignition_g2(dir)?,
+        })
+    }
+
+    pub(crate) fn write(&self) -> Vec<u8> {
+        let mut bytes = self.g2_x.to_uncompressed();
+        for point in self.monomial_points.iter() {
+            bytes.extend(point.to_uncompressed());
+        }
+        bytes
+    }
+}
+
+/// Bytes in a [`Manifest`].
+const MANIFEST_SIZE: usize = 28;
+
+/// The header of an ignition transcript, seven big-endian `u32`s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Manifest {
+    pub(crate) transcript_number: u32,
+    pub(crate) total_transcripts: u32,
+    pub(crate) total_g1_points: u32,
+    pub(crate) total_g2_points: u32,
+    pub(crate) num_g1_points: u32,
+    pub(crate) num_g2_points: u32,
+    /// index among all the transcripts' G1 points of this one's first
+    pub(crate) start_from: u32,
+}
+
+impl Manifest {
+    pub(crate) fn read(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() >= MANIFEST_SIZE,
+            "manifest of {} bytes, expected {}",
+            bytes.len(),
+            MANIFEST_SIZE
+        );
+        let field = |i: usize| u32::from_be_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap());
+        Ok(Self {
+            transcript_number: field(0),
+            total_transcripts: field(1),
+            total_g1_points: field(2),
+            total_g2_points: field(3),
+            num_g1_points: field(4),
+            num_g2_points: field(5),
+            start_from: field(6),
+        })
+    }
+
+    pub(crate) fn write(&self) -> Vec<u8> {
+        [
+            self.transcript_number,
+            self.total_transcripts,
+            self.total_g1_points,
+            self.total_g2_points,
+            self.num_g1_points,
+            self.num_g2_points,
+            self.start_from,
+        ]
+        .iter()
+        .flat_map(|field| field.to_be_bytes())
+        .collect()
+    }
+}
+
+/// `transcript00.dat`, `transcript01.dat`, ... in `dir`.
+pub(crate) fn transcript_path(dir: &Path, number: usize) -> PathBuf {
+    dir.join(format!("transcript{:02}.dat", number))
+}
+
+/// Converts between the ignition encoding of a point and the uncompressed one, which differ only in the order
+/// of each coordinate's limbs.
+pub(crate) fn swap_limb_order<G: AffineRepr>(bytes: &[u8]) -> Vec<u8> {
+    let coordinate_size = encoded_size::<<G::BaseField as Field>::BasePrimeField>();
+    bytes
+        .chunks(coordinate_size)
+        .flat_map(|coordinate| coordinate.rchunks(8).flatten().copied())
+        .collect()
+}
+
+/// Reads the manifest of transcript `number` in `dir`, leaving the file at its first G1 point.
+fn open_transcript(dir: &Path, number: usize) -> Result<(File, Manifest)> {
+    let path = transcript_path(dir, number);
+    let mut file =
+        File::open(&path).with_context(|| format!("opening transcript {}", path.display()))?;
+    let mut manifest = [0u8; MANIFEST_SIZE];
+    file.read_exact(&mut manifest)
+        .with_context(|| format!("reading the manifest of {}", path.display()))?;
+    Ok((file, Manifest::read(&manifest)?))
+}
+
+/// `count` points in the ignition encoding from `reader`.
+fn read_points<G: PointEncoding>(reader: &mut impl Read, count: usize) -> Result<Vec<G>> {
+    let mut bytes = vec![0u8; count * G::UNCOMPRESSED_SIZE];
+    reader
+        .read_exact(&mut bytes)
+        .context("transcript is truncated")?;
+    bytes
+        .chunks_exact(G::UNCOMPRESSED_SIZE)
+        .map(|point| G::from_uncompressed(&swap_limb_order::<G>(point)))
+        .collect()
+}
+
+/// The number of monomial points the ignition transcripts in `dir` hold, `[1]₁` included.
+pub(crate) fn ignition_size(dir: &Path) -> Result<usize> {
+    let (_, manifest) = open_transcript(dir, 0)?;
+    Ok(manifest.total_g1_points as usize + 1)
+}
+
+/// `[1]₁` and the next `num_points - 1` monomial points, from as many of the ignition transcripts in `dir` as
+/// they span.
+pub(crate) fn read_ignition_g1<G: PointEncoding>(dir: &Path, num_points: usize) -> Result<Vec<G>> {
+    let mut points = Vec::with_capacity(num_points);
+    points.push(G::generator());
+    let mut number = 0;
+    while points.len() < num_points {
+        let (mut file, manifest) = open_transcript(dir, number)?;
+        ensure!(
+            manifest.start_from as usize == points.len() - 1,
+            "transcript {} starts from point {}, expected {}",
+            number,
+            manifest.start_from,
+            points.len() - 1
+        );
+        ensure!(
+            manifest.num_g1_points > 0,
+            "transcript {} holds no G1 points, {} of {} read",
+            number,
+            points.len(),
+            num_points
+        );
+        let count = std::cmp::min(manifest.num_g1_points as usize, num_points - points.len());
+        points.extend(
+            read_points::<G>(&mut file, count)
+                .with_context(|| format!("reading G1 points of transcript {}", number))?,
+        );
+        number += 1;
+    }
+    Ok(points)
+}
+
+/// `[τ]₂`, the first G2 point of the first ignition transcript in `dir`.
+pub(crate) fn read_ignition_g2<G: PointEncoding>(dir: &Path) -> Result<G> {
+    let (file, manifest) = open_transcript(dir, 0)?;
+    ensure!(
+        manifest.num_g2_points > 0,
+        "transcript 0 holds no G2 points"
+    );
+    // a G1 point is two coordinates over the prime field that G2's coordinates extend
+    let g1_size = 2 * encoded_size::<<G::BaseField as Field>::BasePrimeField>() as u64;
+    let mut g1_points = file.take(manifest.num_g1_points as u64 * g1_size);
+    std::io::copy(&mut g1_points, &mut std::io::sink())
+        .context("reading G1 points of transcript 0")?;
+    let g2 =
+        read_points::<G>(&mut g1_points.into_inner(), 1).context("reading [τ]₂ of transcript 0")?;
+    Ok(g2[0])
+}
+
+impl<E: Pairing> ProverReferenceString<E::G1Affine> for Srs<E> {
+    fn get_monomial_points(&mut self) -> Arc<Vec<E::G1Affine>> {
+        self.monomial_points.clone()
+    }
+
+    fn get_monomial_size(&self) -> usize {
+        self.monomial_points.len()
+    }
+}
+
+impl<E: Pairing> VerifierReferenceString<E::G2Affine> for Srs<E> {
+    fn get_g2x(&self) -> E::G2Affine {
+        self.g2_x
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{Field, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// An SRS of `size` points with a known τ.
+    pub(crate) fn mock_srs<E: Pairing>(size: usize) -> Srs<E> {
+        let tau = E::ScalarField::rand(&mut StdRng::seed_from_u64(0));
+        let powers: Vec<_> = (0..size)
+            .map(|i| E::G1Affine::generator() * tau.pow([i as u64]))
+            .collect();
+        Srs {
+            monomial_points: Arc::new(E::G1::normalize_batch(&powers)),
+            g2_x: (E::G2Affine::generator() * tau).into_affine(),
+        }
+    }
+
+    fn check_round_trip<E: Pairing>()
+    where
+        E::G1Affine: PointEncoding,
+        E::G2Affine: PointEncoding,
+    {
+        let srs = mock_srs::<E>(8);
+        let bytes = srs.write();
+        let read = Srs::<E>::read(&bytes, 5).unwrap();
+        assert_eq!(read.g2_x, srs.g2_x);
+        assert_eq!(read.monomial_points[..], srs.monomial_points[..5]);
+        assert!(Srs::<E>::read(&bytes, 9).is_err());
+        assert!(Srs::<E>::read(&bytes[1..], 1).is_err());
+
+        let path = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(path.path(), &bytes).unwrap();
+        let mut read = Srs::<E>::read_from_path(path.path(), 8).unwrap();
+        assert_eq!(read.get_monomial_size(), 8);
+        assert_eq!(read.get_monomial_points()[7], srs.monomial_points[7]);
+    }
+
+    /// Writes `srs` as ignition transcripts of `points_per_transcript` G1 points each, `[τ]₂` in the first.
+    pub(crate) fn write_ignition<E: Pairing>(srs: &Srs<E>, dir: &Path, points_per_transcript: usize)
+    where
+        E::G1Affine: PointEncoding,
+        E::G2Affine: PointEncoding,
+    {
+        let chunks: Vec<_> = srs.monomial_points[1..]
+            .chunks(points_per_transcript)
+            .collect();
+        for (number, chunk) in chunks.iter().enumerate() {
+            let num_g2_points = if number == 0 { 1 } else { 0 };
+            let mut bytes = Manifest {
+                transcript_number: number as u32,
+                total_transcripts: chunks.len() as u32,
+                total_g1_points: (srs.monomial_points.len() - 1) as u32,
+                total_g2_points: 1,
+                num_g1_points: chunk.len() as u32,
+                num_g2_points,
+                start_from: (number * points_per_transcript) as u32,
+            }
+            .write();
+            for point in chunk.iter() {
+                bytes.extend(swap_limb_order::<E::G1Affine>(&point.to_uncompressed()));
+            }
+            if number == 0 {
+                bytes.extend(swap_limb_order::<E::G2Affine>(&srs.g2_x.to_uncompressed()));
+            }
+            bytes.extend([0u8; 64]);
+            std::fs::write(transcript_path(dir, number), bytes).unwrap();
+        }
+    }
+
+    #[test]
+    fn manifest_round_trips() {
+        let manifest = Manifest {
+            transcript_number: 1,
+            total_transcripts: 20,
+            total_g1_points: 100_800_000,
+            total_g2_points: 1,
+            num_g1_points: 5_040_000,
+            num_g2_points: 0,
+            start_from: 5_040_000,
+        };
+        let bytes = manifest.write();
+        assert_eq!(bytes.len(), MANIFEST_SIZE);
+        assert_eq!(bytes[..8], [0, 0, 0, 1, 0, 0, 0, 20]);
+        assert_eq!(Manifest::read(&bytes).unwrap(), manifest);
+        assert!(Manifest::read(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn ignition_coordinates_are_little_endian_limbs() {
+        // [1]₁ of BN254 is (1, 2)
+        let bytes = swap_limb_order::<ark_bn254::G1Affine>(
+            &ark_bn254::G1Affine::generator().to_uncompressed(),
+        );
+        let mut expected = [0u8; 64];
+        expected[7] = 1;
+        expected[32 + 7] = 2;
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn ignition_transcripts_are_read_across_files() {
+        let srs = mock_srs::<ark_bn254::Bn254>(8);
+        let dir = tempfile::tempdir().unwrap();
+        write_ignition(&srs, dir.path(), 4);
+
+        let read = Srs::<ark_bn254::Bn254>::read_ignition(dir.path(), 8).unwrap();
+        assert_eq!(read.g2_x, srs.g2_x);
+        assert_eq!(read.monomial_points, srs.monomial_points);
+        let read = Srs::<ark_bn254::Bn254>::read_ignition(dir.path(), 3).unwrap();
+        assert_eq!(read.monomial_points[..], srs.monomial_points[..3]);
+        // converted to this crate's own layout
+        let converted = Srs::<ark_bn254::Bn254>::read(&srs.write(), 8).unwrap();
+        assert_eq!(
+            converted.monomial_points,
+            read_ignition_g1(dir.path(), 8).unwrap().into()
+        );
+
+        assert_eq!(ignition_size(dir.path()).unwrap(), 8);
+        assert!(Srs::<ark_bn254::Bn254>::read_ignition(dir.path(), 9).is_err());
+        let mut transcript = std::fs::read(transcript_path(dir.path(), 1)).unwrap();
+        transcript[MANIFEST_SIZE] ^= 1;
+        std::fs::write(transcript_path(dir.path(), 1), transcript).unwrap();
+        assert!(Srs::<ark_bn254::Bn254>::read_ignition(dir.path(), 8).is_err());
+    }
+
+    #[test]
+    fn bn254_srs_round_trips() {
+        check_round_trip::<ark_bn254::Bn254>();
+    }
+
+    #[test]
+    fn bls12_381_srs_round_trips() {
+        check_round_trip::<ark_bls12_381::Bls12_381>();
+    }
+}
@@ -1 +1,2 @@
+pub(crate) mod io;
 pub(crate) mod reference_string;
@@ -1,30 +1,29 @@
-use std::{cell::RefCell, marker::PhantomData, rc::Rc};
+use std::{
+    marker::PhantomData,
+    path::Path,
+    sync::{Arc, RwLock},
+};
 
+use anyhow::Result;
 use ark_ec::AffineRepr;
 
-use super::{
-    pippenger_reference_string::Pippenger, MillerLines, ProverReferenceString,
-    ReferenceStringFactory, VerifierReferenceString,
+use crate::{
+    ecc::serialize::PointEncoding,
+    srs::io::{ignition_size, read_ignition_g1, read_ignition_g2},
 };
 
+use super::{ProverReferenceString, ReferenceStringFactory, VerifierReferenceString};
+
+/// `[τ]₂` of the ignition transcripts in a directory.
 pub(crate) struct VerifierFileReferenceString<G2Affine: AffineRepr> {
     g2_x: G2Affine,
-    precomputed_g2_lines: Rc<Vec<MillerLines>>,
 }
 
-impl<G2Affine: AffineRepr> VerifierFileReferenceString<G2Affine> {
-    pub(crate) fn new(_path: &str) -> Self {
-        // Please replace the actual types and functions with ones that you have in your Rust codebase.
-        // let g2_x: G2Affine = read_transcript_g2(path);
-        // let precomputed_g2_lines: Vec<MillerLines> = vec![MillerLines::default(); 2];
-
-        // precompute_miller_lines(g2_x, &mut precomputed_g2_lines[1]);
-
-        // Self {
-        //     g2_x,
-        //     precomputed_g2_lines,
-        // }
-        unimplemented!()
+impl<G2Affine: PointEncoding> VerifierFileReferenceString<G2Affine> {
+    pub(crate) fn new(path: &str) -> Result<Self> {
+        Ok(Self {
+            g2_x: read_ignition_g2(Path::new(path))?,
+        })
     }
 }
 
@@ -34,51 +33,47 @@ impl<G2Affine: AffineRepr> VerifierReferenceString<G2Affine>
     fn get_g2x(&self) -> G2Affine {
         self.g2_x
     }
-
-    fn get_precomputed_g2_lines(&self) -> Rc<Vec<MillerLines>> {
-        self.precomputed_g2_lines.clone()
-    }
 }
 
+/// Monomial points read from the ignition transcripts in a directory, see [`io`](crate::srs::io).
 pub(crate) struct FileReferenceString<G1Affine: AffineRepr> {
-    num_points: usize,
-    pippenger: Pippenger,
-    phantom: PhantomData<G1Affine>,
+    monomial_points: Arc<Vec<G1Affine>>,
 }
 
-impl<G1Affine: AffineRepr> FileReferenceString<G1Affine> {
-    pub(crate) fn new(_num_points: usize, _path: &str) -> Self {
-        // Implementation depends on your project.
-        todo!("FileReferenceString::new")
+impl<G1Affine: PointEncoding> FileReferenceString<G1Affine> {
+    /// The first `num_points` monomial points of the transcripts in `path`.
+    pub(crate) fn new(num_points: usize, path: &str) -> Result<Self> {
+        Ok(Self {
+            monomial_points: Arc::new(read_ignition_g1(Path::new(path), num_points)?),
+        })
     }
 
-    pub(crate) fn read_from_path(_path: &str) -> Result<Self, std::io::Error> {
-        // Implementation depends on your project.
-        todo!("FileReferenceString::read_from_path")
+    /// Every monomial point of the transcripts in `path`.
+    pub(crate) fn read_from_path(path: &str) -> Result<Self> {
+        Self::new(ignition_size(Path::new(path))?, path)
     }
 }
 
 impl<G1Affine: AffineRepr> Default for FileReferenceString<G1Affine> {
     fn default() -> Self {
         Self {
-            num_points: 0,
-            pippenger: Pippenger::default(),
-            phantom: PhantomData,
+            monomial_points: Arc::new(vec![]),
         }
     }
 }
 
 impl<G1Affine: AffineRepr> ProverReferenceString<G1Affine> for FileReferenceString<G1Affine> {
-    fn get_monomial_points(&mut self) -> Rc<Vec<G1Affine>> {
-        // Implementation depends on your project.
-        todo!()
+    fn get_monomial_points(&mut self) -> Arc<Vec<G1Affine>> {
+        self.monomial_points.clone()
     }
 
     fn get_monomial_size(&self) -> usize {
-        self.num_points
+        self.monomial_points.len()
     }
 }
 
+/// Reads reference strings from the ignition transcripts in `path` when asked for them, `None` if they cannot
+/// be read.
 pub(crate) struct FileReferenceStringFactory<G1Affine: AffineRepr, G2Affine: AffineRepr> {
     path: String,
     phantom: PhantomData<(G1Affine, G2Affine)>,
@@ -92,60 +87,112 @@ impl<G1Affine: AffineRepr, G2Affine: AffineRepr> FileReferenceStringFactory<G1Af
         }
     }
 }
-impl<G1Affine: AffineRepr, G2Affine: AffineRepr> ReferenceStringFactory<G1Affine, G2Affine>
+impl<G1Affine: PointEncoding, G2Affine: PointEncoding> ReferenceStringFactory<G1Affine, G2Affine>
     for FileReferenceStringFactory<G1Affine, G2Affine>
 {
-    fn get_prover_crs(&self, degree: usize) -> Option<Rc<dyn ProverReferenceString<G1Affine>>> {
-        Some(Rc::new(FileReferenceString::<G1Affine>::new(
-            degree, &self.path,
-        )))
+    fn get_prover_crs(
+        &self,
+        degree: usize,
+    ) -> Option<Arc<RwLock<dyn ProverReferenceString<G1Affine>>>> {
+        let crs = FileReferenceString::<G1Affine>::new(degree, &self.path).ok()?;
+        Some(Arc::new(RwLock::new(crs)))
     }
 
-    fn get_verifier_crs(&self) -> Option<Rc<dyn VerifierReferenceString<G2Affine>>> {
-        Some(Rc::new(VerifierFileReferenceString::new(&self.path)))
+    fn get_verifier_crs(&self) -> Option<Arc<dyn VerifierReferenceString<G2Affine>>> {
+        let crs = VerifierFileReferenceString::new(&self.path).ok()?;
+        Some(Arc::new(crs))
     }
 }
 
+/// As [`FileReferenceStringFactory`], keeping the last prover reference string read and rereading it only
+/// when a different degree is asked for.
 pub(crate) struct DynamicFileReferenceStringFactory<G1Affine: AffineRepr, G2Affine: AffineRepr> {
     path: String,
-    degree: RefCell<usize>,
-    prover_crs: RefCell<Rc<FileReferenceString<G1Affine>>>,
-    verifier_crs: Rc<VerifierFileReferenceString<G2Affine>>,
+    degree: RwLock<usize>,
+    prover_crs: RwLock<Arc<RwLock<FileReferenceString<G1Affine>>>>,
+    verifier_crs: Arc<VerifierFileReferenceString<G2Affine>>,
     phantom: PhantomData<(G1Affine, G2Affine)>,
 }
 
-impl<G1Affine: AffineRepr, G2Affine: AffineRepr>
+impl<G1Affine: PointEncoding, G2Affine: PointEncoding>
     DynamicFileReferenceStringFactory<G1Affine, G2Affine>
 {
-    pub(crate) fn new(path: String, initial_degree: usize) -> Self {
-        let verifier_crs = Rc::new(VerifierFileReferenceString::new(&path));
-        let prover_crs = RefCell::new(Rc::new(FileReferenceString::<G1Affine>::new(
+    pub(crate) fn new(path: String, initial_degree: usize) -> Result<Self> {
+        let verifier_crs = Arc::new(VerifierFileReferenceString::new(&path)?);
+        let prover_crs = RwLock::new(Arc::new(RwLock::new(FileReferenceString::<G1Affine>::new(
             initial_degree,
             &path,
-        )));
-        Self {
+        )?)));
+        Ok(Self {
             path,
-            degree: RefCell::new(initial_degree),
+            degree: RwLock::new(initial_degree),
             prover_crs,
             verifier_crs,
             phantom: PhantomData,
-        }
+        })
     }
 }
 
-impl<G1Affine: AffineRepr, G2Affine: AffineRepr> ReferenceStringFactory<G1Affine, G2Affine>
+impl<G1Affine: PointEncoding, G2Affine: PointEncoding> ReferenceStringFactory<G1Affine, G2Affine>
     for DynamicFileReferenceStringFactory<G1Affine, G2Affine>
 {
-    fn get_prover_crs(&self, degree: usize) -> Option<Rc<dyn ProverReferenceString<G1Affine>>> {
-        if degree != *self.degree.borrow() {
-            *self.prover_crs.borrow_mut() =
-                Rc::new(FileReferenceString::<G1Affine>::new(degree, &self.path));
-            *self.degree.borrow_mut() = degree;
+    fn get_prover_crs(
+        &self,
+        degree: usize,
+    ) -> Option<Arc<RwLock<dyn ProverReferenceString<G1Affine>>>> {
+        let mut current_degree = self.degree.write().unwrap();
+        if degree != *current_degree {
+            *self.prover_crs.write().unwrap() = Arc::new(RwLock::new(
+                FileReferenceString::<G1Affine>::new(degree, &self.path).ok()?,
+            ));
+            *current_degree = degree;
         }
-        Some((self.prover_crs.borrow_mut()).clone())
+        Some(self.prover_crs.read().unwrap().clone())
     }
 
-    fn get_verifier_crs(&self) -> Option<Rc<dyn VerifierReferenceString<G2Affine>>> {
+    fn get_verifier_crs(&self) -> Option<Arc<dyn VerifierReferenceString<G2Affine>>> {
         Some(self.verifier_crs.clone())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::srs::io::test::{mock_srs, write_ignition};
+
+    #[test]
+    fn factories_read_ignition_transcripts() {
+        let srs = mock_srs::<ark_bn254::Bn254>(8);
+        let dir = tempfile::tempdir().unwrap();
+        write_ignition(&srs, dir.path(), 3);
+        let path = dir.path().to_str().unwrap().to_string();
+
+        let factory = FileReferenceStringFactory::<ark_bn254::G1Affine, ark_bn254::G2Affine>::new(
+            path.clone(),
+        );
+        let prover_crs = factory.get_prover_crs(5).unwrap();
+        assert_eq!(prover_crs.read().unwrap().get_monomial_size(), 5);
+        assert_eq!(
+            prover_crs.write().unwrap().get_monomial_points()[..],
+            srs.monomial_points[..5]
+        );
+        assert_eq!(factory.get_verifier_crs().unwrap().get_g2x(), srs.g2_x);
+        assert!(factory.get_prover_crs(9).is_none());
+
+        let factory =
+            DynamicFileReferenceStringFactory::<ark_bn254::G1Affine, ark_bn254::G2Affine>::new(
+                path.clone(),
+                2,
+            )
+            .unwrap();
+        let prover_crs = factory.get_prover_crs(8).unwrap();
+        assert_eq!(
+            prover_crs.write().unwrap().get_monomial_points(),
+            srs.monomial_points
+        );
+
+        let mut crs = FileReferenceString::<ark_bn254::G1Affine>::read_from_path(&path).unwrap();
+        assert_eq!(crs.get_monomial_points(), srs.monomial_points);
+        assert!(FileReferenceString::<ark_bn254::G1Affine>::read_from_path("missing").is_err());
+    }
+}
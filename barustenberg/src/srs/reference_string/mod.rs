@@ -2,29 +2,34 @@ pub(crate) mod file_reference_string;
 pub(crate) mod mem_reference_string;
 pub(crate) mod pippenger_reference_string;
 
-use std::{marker::PhantomData, rc::Rc};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, RwLock},
+};
 
 use ark_ec::AffineRepr;
 
-// TODO do this later properly
-#[derive(Clone, Default)]
-pub(crate) struct MillerLines {}
-
-pub(crate) trait VerifierReferenceString<G2Affine: AffineRepr> {
+/// The verifier's half of an SRS. Barretenberg also hands out the Miller loop lines of `[1]₂` and `[τ]₂` here,
+/// precomputed for its own pairing; arkworks prepares G2 points itself when pairing, so only `[τ]₂` is kept.
+pub(crate) trait VerifierReferenceString<G2Affine: AffineRepr>: Send + Sync {
     fn get_g2x(&self) -> G2Affine;
-    fn get_precomputed_g2_lines(&self) -> Rc<Vec<MillerLines>>;
 }
 
-pub(crate) trait ProverReferenceString<G1Affine: AffineRepr> {
-    fn get_monomial_points(&mut self) -> Rc<Vec<G1Affine>>;
+pub(crate) trait ProverReferenceString<G1Affine: AffineRepr>: Send + Sync {
+    fn get_monomial_points(&mut self) -> Arc<Vec<G1Affine>>;
     fn get_monomial_size(&self) -> usize;
 }
-pub(crate) trait ReferenceStringFactory<G1Affine: AffineRepr, G2Affine: AffineRepr> {
-    fn get_prover_crs(&self, _size: usize) -> Option<Rc<dyn ProverReferenceString<G1Affine>>> {
+pub(crate) trait ReferenceStringFactory<G1Affine: AffineRepr, G2Affine: AffineRepr>:
+    Send + Sync
+{
+    fn get_prover_crs(
+        &self,
+        _size: usize,
+    ) -> Option<Arc<RwLock<dyn ProverReferenceString<G1Affine>>>> {
         todo!()
     }
 
-    fn get_verifier_crs(&self) -> Option<Rc<dyn VerifierReferenceString<G2Affine>>> {
+    fn get_verifier_crs(&self) -> Option<Arc<dyn VerifierReferenceString<G2Affine>>> {
         todo!()
     }
 }
@@ -0,0 +1,21 @@
+//! BN254 entry points into the curve arithmetic, for the benchmarks in `barustenberg-benches`.
+
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::VariableBaseMSM;
+
+use crate::ecc::endomorphism;
+
+/// `scalar·point` through the GLV endomorphism.
+pub fn glv_mul(point: &G1Affine, scalar: &Fr) -> G1Projective {
+    endomorphism::glv_mul(point, scalar)
+}
+
+/// `Σᵢ scalarsᵢ·pointsᵢ` as a Pippenger over the split scalars.
+pub fn glv_msm(points: &[G1Affine], scalars: &[Fr]) -> G1Projective {
+    endomorphism::glv_msm(points, scalars)
+}
+
+/// `Σᵢ scalarsᵢ·pointsᵢ` with the arkworks MSM the prover uses.
+pub fn pippenger(points: &[G1Affine], scalars: &[Fr]) -> G1Projective {
+    G1Projective::msm_unchecked(points, scalars)
+}
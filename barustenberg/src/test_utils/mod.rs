@@ -1,3 +1,6 @@
+/// Curve arithmetic for the benchmarks.
+#[cfg(feature = "test_utils")]
+pub mod ecc;
 /// Random value generator for sampling data.
 #[cfg(feature = "test_utils")]
 mod rvg;
@@ -0,0 +1,1081 @@
+use anyhow::{anyhow, ensure, Error};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use generic_array::{ArrayLength, GenericArray};
+use sha3::Digest;
+
+use std::{collections::HashMap, marker::PhantomData, sync::Mutex};
+use tracing::info;
+use typenum::{Unsigned, U16, U32};
+
+use crate::ecc::{
+    curves::{Bn254, CurveConfig},
+    fields::{encoded_size, from_be_bytes, to_be_bytes},
+    serialize::PointEncoding,
+};
+
+use self::trace::TranscriptEvent;
+
+pub(crate) mod trace;
+
+/// BarretenHasher is a trait that defines the hash function used for Fiat-Shamir.
+pub(crate) trait BarretenHasher: Send + Sync {
+    /// The size of the security parameter in bytes.
+    type SecurityParameterSize: ArrayLength<u8>;
+    /// The size of the PRNG output in bytes.
+    type PrngOutputSize: ArrayLength<u8>;
+
+    /// Hashes the given buffer.
+    fn hash(buffer: &[u8]) -> GenericArray<u8, Self::PrngOutputSize>;
+}
+
+/// Keccak256 hasher, the original Keccak padding as in Ethereum rather than SHA3-256.
+#[derive(Debug, Default)]
+pub(crate) struct Keccak256 {}
+
+impl BarretenHasher for Keccak256 {
+    type SecurityParameterSize = U32;
+    type PrngOutputSize = U32;
+
+    fn hash(buffer: &[u8]) -> GenericArray<u8, Self::PrngOutputSize> {
+        sha3::Keccak256::digest(buffer)
+    }
+}
+
+/// Pedersen with blake3s. barretenberg compresses a round's buffer with a Pedersen hash before
+/// taking its blake3s; only the blake3s is done here.
+#[derive(Debug, Default)]
+pub(crate) struct PedersenBlake3s {}
+
+impl BarretenHasher for PedersenBlake3s {
+    type SecurityParameterSize = U16;
+    type PrngOutputSize = U32;
+
+    fn hash(input: &[u8]) -> GenericArray<u8, Self::PrngOutputSize> {
+        GenericArray::clone_from_slice(blake3::hash(input).as_bytes())
+    }
+}
+
+/// PlookupPedersenBlake3s
+#[derive(Debug, Default)]
+pub(crate) struct PlookupPedersenBlake3s {}
+
+impl BarretenHasher for PlookupPedersenBlake3s {
+    type SecurityParameterSize = U16;
+    type PrngOutputSize = U32;
+    fn hash(_buffer: &[u8]) -> GenericArray<u8, Self::PrngOutputSize> {
+        // TODO from original codebase
+        /*
+        std::vector<uint8_t> compressed_buffer = crypto::pedersen_commitment::lookup::compress_native(buffer);
+        std::array<uint8_t, PRNG_OUTPUT_SIZE> result;
+        for (size_t i = 0; i < PRNG_OUTPUT_SIZE; ++i) {
+            result[i] = compressed_buffer[i];
+        }
+        return result;
+         */
+        todo!("check comment to see what gpt told us to do")
+    }
+}
+
+/// ManifestEntry describes one piece of data that is used in a particular round of the protocol.
+#[derive(Clone, Default, Debug)]
+pub struct ManifestEntry {
+    /// The name of the element for fiat-shamir.
+    pub name: String,
+    /// The number of bytes in the element.
+    pub num_bytes: usize,
+    /// Whether the element is derived by the verifier.
+    pub derived_by_verifier: bool,
+    /// The index of the element in the challenge map.
+    pub challenge_map_index: i32,
+}
+
+impl ManifestEntry {
+    /// An element `name` of `num_bytes` bytes, batched by the `challenge_map_index`-th challenge of its round
+    /// if the round maps its challenges.
+    pub fn new(
+        name: &str,
+        num_bytes: usize,
+        derived_by_verifier: bool,
+        challenge_map_index: i32,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            num_bytes,
+            derived_by_verifier,
+            challenge_map_index,
+        }
+    }
+}
+
+/// The RoundManifest struct describes the data used in one round of the protocol
+/// and the challenge(s) created from that data.
+#[derive(Clone, Default, Debug)]
+pub struct RoundManifest {
+    /// Data used in the round.
+    pub elements: Vec<ManifestEntry>,
+    /// The name of the challenge (alpha, beta, etc.).
+    pub challenge: String,
+    /// The number of challenges to generate (sometimes we need more than one, e.g in permutation_widget).
+    pub num_challenges: usize,
+    /// Whether to put elements in a challenge_map in the transcript.
+    pub map_challenges: bool,
+}
+
+impl RoundManifest {
+    /// A round hashing `elements` into `num_challenges` challenges named `challenge`.
+    pub fn new(
+        elements: Vec<ManifestEntry>,
+        challenge: &str,
+        num_challenges: usize,
+        map_challenges: bool,
+    ) -> Self {
+        Self {
+            elements,
+            challenge: challenge.to_string(),
+            num_challenges,
+            map_challenges,
+        }
+    }
+
+    /// Checks if there is an element in the list with the given name.
+    ///
+    /// # Arguments
+    /// * `element_name` - The name to search for.
+    ///
+    /// # Returns
+    /// * `true` if the name is found in the list, `false` otherwise.
+    pub fn includes_element(&self, element_name: &str) -> bool {
+        self.elements.iter().any(|e| e.name == element_name)
+    }
+}
+
+/// Manifest is used by composers to define the structure of the protocol. It specifies:
+/// 1. What data is used in each round of the protocols.
+/// 2. Which information is used to create challenges.
+#[derive(Clone, Default, Debug)]
+pub struct Manifest {
+    /// The list of round manifests.
+    pub(crate) round_manifests: Vec<RoundManifest>,
+    /// The number of rounds in the protocol.
+    pub(crate) num_rounds: usize,
+}
+
+impl Manifest {
+    /// A manifest of `round_manifests`, in the order they are run.
+    pub fn new(round_manifests: Vec<RoundManifest>) -> Self {
+        let num_rounds = round_manifests.len();
+        Self {
+            round_manifests,
+            num_rounds,
+        }
+    }
+    /// The number of rounds in the protocol.
+    pub fn get_num_rounds(&self) -> usize {
+        self.num_rounds
+    }
+    /// The description of round `round`.
+    pub fn get_round_manifest(&self, round: usize) -> &RoundManifest {
+        &self.round_manifests[round]
+    }
+    pub(crate) fn push_round(&mut self, round_manifest: RoundManifest) {
+        self.round_manifests.push(round_manifest);
+        self.num_rounds += 1;
+    }
+}
+
+#[derive(Default)]
+struct Challenge<H: BarretenHasher> {
+    data: GenericArray<u8, H::PrngOutputSize>,
+}
+
+// derived Clone would want the hasher to be Clone
+impl<H: BarretenHasher> Clone for Challenge<H> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+        }
+    }
+}
+
+/// The curve defaults to BN254 for the streaming transcripts of [`honk`](crate::honk), which send arkworks
+/// elements of whatever curve they are over.
+pub(crate) struct Transcript<H: BarretenHasher, C: CurveConfig = Bn254> {
+    current_round: usize,
+    pub(crate) num_challenge_bytes: usize,
+    elements: HashMap<String, Vec<u8>>,
+    challenges: HashMap<String, Vec<Challenge<H>>>,
+    current_challenge: Challenge<H>,
+    manifest: Manifest,
+    challenge_map: HashMap<String, i32>,
+    /// Every interaction so far, when tracing is enabled.
+    trace: Option<Mutex<Vec<TranscriptEvent>>>,
+    /// Elements sent since the last challenge of a streaming transcript, see [`Transcript::new_streaming`].
+    open_round: Vec<ManifestEntry>,
+    /// The proof a streaming verifier reads its elements from, and how far it has read.
+    proof_data: Vec<u8>,
+    read_offset: usize,
+    phantom: PhantomData<C>,
+}
+
+impl<H: BarretenHasher, C: CurveConfig> Default for Transcript<H, C> {
+    fn default() -> Self {
+        Self {
+            current_round: 0,
+            num_challenge_bytes: 0,
+            elements: HashMap::new(),
+            challenges: HashMap::new(),
+            current_challenge: Challenge {
+                data: GenericArray::default(),
+            },
+            manifest: Manifest::default(),
+            challenge_map: HashMap::new(),
+            trace: None,
+            open_round: vec![],
+            proof_data: vec![],
+            read_offset: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<H: BarretenHasher, C: CurveConfig> Transcript<H, C> {
+    pub(crate) fn add_element(&mut self, element_name: &str, buffer: Vec<u8>) {
+        info!("Adding element {} to transcript", element_name);
+        self.record(|| TranscriptEvent::AddElement {
+            round: self.current_round,
+            name: element_name.to_string(),
+            bytes: buffer.clone(),
+        });
+        // from elements.insert({ element_name, buffer });
+        self.elements.insert(element_name.to_string(), buffer);
+    }
+
+    /// Create a new transcript based on the manifest
+    /// # Arguments
+    /// input_manifest:  The manifest with round descriptions.
+    /// hash_type: The hash used for Fiat-Shamir.
+    /// challenge_bytes: The number of bytes per challenge to generate.
+    pub(crate) fn new(input_manifest: Option<Manifest>, num_challenge_bytes: usize) -> Self {
+        let mut ret = Transcript::<H, C> {
+            num_challenge_bytes,
+            manifest: input_manifest.unwrap_or_default(),
+            ..Default::default()
+        };
+        ret.compute_challenge_map();
+        ret
+    }
+    /// Constructs a new `Transcript` from a serialized transcript, a `Manifest`, a `HashType` and a challenge byte size.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_transcript` - The serialized transcript.
+    /// * `input_manifest` - The manifest which governs the parsing.
+    /// * `hash_type` - The hash used for Fiat-Shamir.
+    /// * `challenge_bytes` - The number of bytes per challenge to generate.
+    ///
+    /// # Panics
+    ///
+    /// If the serialized transcript does not contain the required number of bytes, a panic occurs.
+    ///
+    pub(crate) fn new_from_transcript(
+        input_transcript: &[u8],
+        input_manifest: Manifest,
+        num_challenge_bytes: usize,
+    ) -> Self {
+        let num_rounds = input_manifest.get_num_rounds();
+        let mut count = 0;
+        // Compute how much data we need according to the manifest
+        let mut total_required_size = 0;
+        for i in 0..num_rounds {
+            for manifest_element in &input_manifest.get_round_manifest(i).elements {
+                if !manifest_element.derived_by_verifier {
+                    total_required_size += manifest_element.num_bytes;
+                }
+            }
+        }
+        // Check that the total required size is equal to the size of the input_transcript
+        if total_required_size != input_transcript.len() {
+            panic!("Serialized transcript does not contain the required number of bytes");
+        }
+
+        let mut elements = std::collections::HashMap::new();
+        for i in 0..num_rounds {
+            for manifest_element in &input_manifest.get_round_manifest(i).elements {
+                if !manifest_element.derived_by_verifier {
+                    let end = count + manifest_element.num_bytes;
+                    let element_data = input_transcript[count..end].to_vec();
+                    elements.insert(manifest_element.name.clone(), element_data);
+                    count += manifest_element.num_bytes;
+                }
+            }
+        }
+
+        let mut transcript = Self {
+            num_challenge_bytes,
+            manifest: input_manifest,
+            elements,
+            challenges: std::collections::HashMap::new(),
+            current_round: 0,
+            current_challenge: Challenge {
+                data: GenericArray::default(),
+            },
+            challenge_map: std::collections::HashMap::new(),
+            trace: None,
+            open_round: vec![],
+            proof_data: vec![],
+            read_offset: 0,
+            phantom: PhantomData,
+        };
+        transcript.compute_challenge_map();
+        transcript
+    }
+
+    /// A prover transcript without a manifest, for protocols whose rounds depend on the circuit, such as Honk's
+    /// sumcheck. It builds the manifest as it goes: every element sent joins the open round, and every challenge
+    /// closes it. Challenges take the whole hash, of the previous challenge and the elements of their round.
+    ///
+    /// Elements are sent in their arkworks encoding, so the curve only matters to
+    /// [`add_field_element`](Self::add_field_element) and the other typed helpers.
+    pub(crate) fn new_streaming() -> Self {
+        Self {
+            num_challenge_bytes: H::PrngOutputSize::USIZE,
+            ..Default::default()
+        }
+    }
+
+    /// The verifier side of [`new_streaming`](Self::new_streaming), reading the prover's elements from
+    /// `proof_data` in the order they were sent.
+    pub(crate) fn new_streaming_from_proof(proof_data: &[u8]) -> Self {
+        Self {
+            proof_data: proof_data.to_vec(),
+            ..Self::new_streaming()
+        }
+    }
+
+    /// Adds `element` to the open round of a streaming transcript.
+    pub(crate) fn send_to_verifier<T: CanonicalSerialize>(&mut self, label: &str, element: &T) {
+        self.add_to_open_round(label, serialize(element), false);
+    }
+
+    /// Adds `element`, which the verifier knows already, to the open round of a streaming transcript. The next
+    /// challenges depend on it, but like any element derived by the verifier it is not part of the proof.
+    pub(crate) fn absorb<T: CanonicalSerialize>(&mut self, label: &str, element: &T) {
+        self.add_to_open_round(label, serialize(element), true);
+    }
+
+    /// Reads the next element of the proof into the open round of a streaming transcript.
+    pub(crate) fn receive_from_prover<T: CanonicalDeserialize + CanonicalSerialize>(
+        &mut self,
+        label: &str,
+    ) -> Result<T, Error> {
+        ensure!(
+            !self.elements.contains_key(label),
+            "the proof already had an element {}",
+            label
+        );
+        let mut reader = &self.proof_data[self.read_offset..];
+        let element = T::deserialize_uncompressed(&mut reader)
+            .map_err(|e| anyhow!("failed to read {} from the proof: {}", label, e))?;
+        let end = self.read_offset + element.uncompressed_size();
+        let buffer = self.proof_data[self.read_offset..end].to_vec();
+        self.read_offset = end;
+        self.add_to_open_round(label, buffer, false);
+        Ok(element)
+    }
+
+    /// Whether a streaming verifier has read the whole proof.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.read_offset == self.proof_data.len()
+    }
+
+    /// Closes the open round of a streaming transcript with challenge `label`, and reads the challenge as a
+    /// big-endian integer reduced into `F`.
+    pub(crate) fn derive_challenge<F: PrimeField>(&mut self, label: &str) -> F {
+        let elements = std::mem::take(&mut self.open_round);
+        self.manifest
+            .push_round(RoundManifest::new(elements, label, 1, false));
+        // the round was just added with all of its elements, and the challenge is the whole hash
+        self.apply_fiat_shamir(label)
+            .expect("a streamed round is complete");
+        let challenge = self
+            .get_challenge(label, None)
+            .expect("the challenge was just derived");
+        F::from_be_bytes_mod_order(challenge)
+    }
+
+    fn add_to_open_round(&mut self, label: &str, buffer: Vec<u8>, derived_by_verifier: bool) {
+        debug_assert!(
+            !self.elements.contains_key(label),
+            "element {} added twice",
+            label
+        );
+        self.open_round.push(ManifestEntry::new(
+            label,
+            buffer.len(),
+            derived_by_verifier,
+            0,
+        ));
+        self.add_element(label, buffer);
+    }
+
+    fn from_serialized(
+        _input_transcript: Vec<u8>,
+        _manifest: Manifest,
+        _challenge_bytes: usize,
+    ) -> Self {
+        todo!()
+    }
+
+    pub(crate) fn get_manifest(&self) -> Manifest {
+        self.manifest.clone()
+    }
+
+    /// Apply the Fiat-Shamir transform to create challenges for the current round.
+    /// The challenges are saved to transcript. Round number is increased.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_name` - Challenge name (needed to check if the challenge fits the current round).
+    ///
+    /// # Errors
+    ///
+    /// If the manifest has no round left, names a different challenge for this round, or the round's
+    /// elements have not all been added, or if a challenge does not fit in the hash output.
+    pub(crate) fn apply_fiat_shamir(&mut self, challenge_name: &str) -> Result<(), Error> {
+        // For reference, see the relevant manifest, which is defined in plonk/composer/manifest.rs
+        ensure!(
+            self.current_round < self.manifest.get_num_rounds(),
+            "apply_fiat_shamir(): no round left for challenge {}",
+            challenge_name
+        );
+        let round = self.manifest.get_round_manifest(self.current_round).clone();
+        info!(
+            "apply_fiat_shamir(): challenge {}, expected {}",
+            challenge_name, round.challenge
+        );
+        ensure!(
+            challenge_name == round.challenge,
+            "apply_fiat_shamir(): challenge {}, expected {}",
+            challenge_name,
+            round.challenge
+        );
+
+        let num_challenges = round.num_challenges;
+        if num_challenges == 0 {
+            self.record(|| TranscriptEvent::FiatShamir {
+                round: self.current_round,
+                challenge: challenge_name.to_string(),
+                previous: vec![],
+                inputs: vec![],
+                challenges: vec![],
+            });
+            self.current_round += 1;
+            return Ok(());
+        }
+
+        // Depending on the settings, we might be able to chunk the bytes of a single hash across multiple
+        // challenges. Each challenge still occupies PRNG_OUTPUT_SIZE bytes, but only its num_challenge_bytes
+        // rhs bytes are nonzero.
+        let output_size = H::PrngOutputSize::USIZE;
+        let num_challenge_bytes = self.num_challenge_bytes;
+        ensure!(
+            (1..=output_size).contains(&num_challenge_bytes),
+            "apply_fiat_shamir(): {}-byte challenges do not fit in a {}-byte hash",
+            num_challenge_bytes,
+            output_size
+        );
+        let challenges_per_hash = output_size / num_challenge_bytes;
+
+        // Combine the very last challenge from the previous fiat-shamir round (which is, inductively, a hash
+        // containing the manifest data of all previous rounds), plus the manifest data for this round, into a
+        // buffer. This buffer will ultimately be hashed, to form this round's fiat-shamir challenge(s).
+        let mut buffer = vec![];
+        if self.current_round > 0 {
+            buffer.extend_from_slice(&self.current_challenge.data);
+        }
+        let previous_len = buffer.len();
+        for manifest_element in &round.elements {
+            let element_data = self.elements.get(&manifest_element.name).ok_or_else(|| {
+                anyhow!(
+                    "apply_fiat_shamir(): transcript has no element {}",
+                    manifest_element.name
+                )
+            })?;
+            ensure!(
+                manifest_element.derived_by_verifier
+                    || manifest_element.num_bytes == element_data.len(),
+                "apply_fiat_shamir(): element {} has {} bytes, expected {}",
+                manifest_element.name,
+                element_data.len(),
+                manifest_element.num_bytes
+            );
+            buffer.extend_from_slice(element_data);
+        }
+
+        let base_hash = H::hash(&buffer);
+
+        let mut round_challenges: Vec<Challenge<H>> = Vec::with_capacity(num_challenges);
+        let mut take_challenges = |hash: &[u8], first: usize| {
+            for j in 0..challenges_per_hash {
+                if first + j < num_challenges {
+                    let mut data = GenericArray::default();
+                    data[output_size - num_challenge_bytes..].copy_from_slice(
+                        &hash[j * num_challenge_bytes..(j + 1) * num_challenge_bytes],
+                    );
+                    round_challenges.push(Challenge { data });
+                }
+            }
+        };
+        take_challenges(&base_hash, 0);
+
+        // Compute how many hashes we need so that we have enough distinct chunks of 'random' bytes to
+        // distribute across the num_challenges, each one hash(base_hash, i).
+        let num_hashes = (num_challenges + challenges_per_hash - 1) / challenges_per_hash;
+        let mut rolling_buffer = base_hash.to_vec();
+        rolling_buffer.push(0);
+        for i in 1..num_hashes {
+            *rolling_buffer.last_mut().unwrap() = i as u8;
+            take_challenges(&H::hash(&rolling_buffer), challenges_per_hash * i);
+        }
+
+        self.record(|| {
+            let mut offset = previous_len;
+            let inputs = round
+                .elements
+                .iter()
+                .map(|element| {
+                    let len = self.elements[&element.name].len();
+                    offset += len;
+                    (element.name.clone(), buffer[offset - len..offset].to_vec())
+                })
+                .collect();
+            TranscriptEvent::FiatShamir {
+                round: self.current_round,
+                challenge: challenge_name.to_string(),
+                previous: buffer[..previous_len].to_vec(),
+                inputs,
+                challenges: round_challenges.iter().map(|c| c.data.to_vec()).collect(),
+            }
+        });
+
+        // Remember the very last challenge, as it will be included in the buffer of the next fiat-shamir round
+        // (since this challenge is effectively a hash of _all_ previous rounds' manifest data).
+        self.current_challenge = round_challenges.last().unwrap().clone();
+        self.challenges
+            .insert(challenge_name.to_string(), round_challenges);
+        self.current_round += 1;
+        Ok(())
+    }
+
+    /// Get the challenge with the given name at index.
+    /// Will fail if there is no challenge with such name
+    /// or there are not enough subchallenges in the vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_name` - The name of the challenge.
+    /// * `idx` - The idx of subchallenge.
+    ///
+    /// # Returns
+    ///
+    /// The challenge value.
+    pub(crate) fn get_challenge(
+        &self,
+        challenge_name: &str,
+        idx: Option<usize>,
+    ) -> Result<&GenericArray<u8, H::PrngOutputSize>, Error> {
+        let idx = idx.unwrap_or(0);
+        info!("get_challenge(): {}", challenge_name);
+        assert!(self.challenges.contains_key(challenge_name));
+        let challenge = &self.challenges.get(challenge_name).unwrap()[idx].data;
+        self.record(|| TranscriptEvent::GetChallenge {
+            round: self.current_round,
+            name: challenge_name.to_string(),
+            index: idx,
+            challenge: challenge.to_vec(),
+        });
+        Ok(challenge)
+    }
+
+    /// Start recording every interaction with the transcript, see [`trace`].
+    pub(crate) fn enable_trace(&mut self) {
+        self.trace = Some(Mutex::new(vec![]));
+    }
+
+    /// The interactions recorded since tracing was enabled, empty if it is not.
+    pub(crate) fn take_trace(&mut self) -> Vec<TranscriptEvent> {
+        self.trace
+            .as_mut()
+            .map(|trace| std::mem::take(trace.get_mut().unwrap()))
+            .unwrap_or_default()
+    }
+
+    /// Logs and records the event built by `event`, if tracing is enabled.
+    fn record(&self, event: impl FnOnce() -> TranscriptEvent) {
+        if let Some(trace) = &self.trace {
+            let event = event();
+            event.log();
+            trace.lock().unwrap().push(event);
+        }
+    }
+
+    /// Get the challenge index from map (needed when we name subchallenges).
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_map_name` - The name of the subchallenge.
+    ///
+    /// # Returns
+    ///
+    /// The index of the subchallenge in the vector corresponding to the challenge.
+    pub(crate) fn get_challenge_index_from_map(
+        &self,
+        challenge_map_name: &str,
+    ) -> Result<isize, Error> {
+        Ok(self.challenge_map[challenge_map_name].try_into()?)
+    }
+
+    /// Check if a challenge exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_name` - The name of the challenge.
+    ///
+    /// # Returns
+    ///
+    /// true if exists, false if not.
+    pub(crate) fn has_challenge(&self, challenge_name: &str) -> bool {
+        self.challenges.contains_key(challenge_name)
+    }
+
+    /// Get a particular subchallenge value by the name of the subchallenge.
+    /// For example, we use it with (nu, r).
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_name` - The name of the challenge.
+    /// * `challenge_map_name` - The name of the subchallenge.
+    ///
+    /// # Returns
+    ///
+    /// The value of the subchallenge.
+    pub(crate) fn get_challenge_from_map(
+        &self,
+        challenge_name: &str,
+        challenge_map_name: &str,
+    ) -> GenericArray<u8, H::PrngOutputSize> {
+        let key = self.challenge_map[challenge_map_name];
+        if key == -1 {
+            let mut result = GenericArray::default();
+            result[<H::PrngOutputSize as Unsigned>::USIZE - 1] = 1;
+            return result;
+        }
+        let value = &self.challenges[challenge_name][key as usize];
+        value.data.clone()
+    }
+
+    /// Get the number of challenges in the transcript.
+    /// fails if no challenges with such name.
+    /// we use it with beta/gamma which need to be created in one fiat-shamir transform
+    ///
+    /// # Arguments
+    /// * `challenge_name` - The name of the challenge.
+    ///
+    /// # Returns
+    ///
+    /// The number of challenges.
+    pub(crate) fn get_num_challenges(&self, challenge_name: &str) -> usize {
+        assert!(self.challenges.contains_key(challenge_name));
+        self.challenges.get(challenge_name).unwrap().len()
+    }
+
+    /// gets the value of an element in the transcript.
+    /// fails if no element with such name.
+    ///
+    /// # Arguments
+    /// * `element_name` - The name of the element.
+    ///
+    /// # Returns
+    ///
+    /// The value of the element.
+    pub(crate) fn get_element(&self, element_name: &str) -> Vec<u8> {
+        assert!(self.elements.contains_key(element_name));
+        self.elements.get(element_name).unwrap().clone()
+    }
+
+    /// gets the size of an element in the transcript.
+    ///
+    /// # Arguments
+    /// * `element_name` - The name of the element.
+    ///
+    /// # Returns
+    ///
+    /// The size of the element. otherwise -1
+    pub(crate) fn get_element_size(&self, element_name: &str) -> usize {
+        for manifest in &self.manifest.round_manifests {
+            for element in &manifest.elements {
+                if element.name == element_name {
+                    return element.num_bytes;
+                }
+            }
+        }
+        usize::MAX
+    }
+
+    /// serialize the transcript to a byte vector.
+    ///
+    /// # Returns
+    ///
+    /// The serialized transcript.
+    pub(crate) fn export_transcript(&self) -> Vec<u8> {
+        let mut buf: Vec<u8> = vec![];
+        for manifest in &self.manifest.round_manifests {
+            for element in &manifest.elements {
+                if element.derived_by_verifier {
+                    continue;
+                }
+                let element_data = self
+                    .elements
+                    .get(&element.name)
+                    .unwrap_or_else(|| panic!("transcript has no element {}", element.name));
+                assert_eq!(
+                    element.num_bytes,
+                    element_data.len(),
+                    "element {} has the wrong size",
+                    element.name
+                );
+                buf.extend_from_slice(element_data);
+            }
+        }
+        // what a streaming prover sent after its last challenge
+        for element in self.open_round.iter().filter(|e| !e.derived_by_verifier) {
+            buf.extend_from_slice(&self.elements[&element.name]);
+        }
+        buf
+    }
+
+    /// Insert element names from all rounds of the manifest
+    /// into the challenge_map.
+    pub(crate) fn compute_challenge_map(&mut self) {
+        self.challenge_map.clear();
+        for manifest in &self.manifest.round_manifests {
+            if manifest.map_challenges {
+                for element in &manifest.elements {
+                    self.challenge_map
+                        .insert(element.name.clone(), element.challenge_map_index);
+                }
+            }
+        }
+    }
+
+    /// Mock prover transcript interactions up to fiat-shamir of a given challenge.
+    ///
+    /// This is useful for testing individual parts of the prover since all
+    /// transcript interactions must occur sequentially according to the manifest.
+    /// Function allows for optional input of circuit_size since this is needed in some
+    /// test cases, e.g. instantiating a Sumcheck from a mocked transcript.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_in` - The challenge name up to which to mock the transcript interactions.
+    pub(crate) fn mock_inputs_prior_to_challenge(
+        &mut self,
+        challenge_in: &str,
+        circuit_size: usize,
+    ) -> Result<(), Error> {
+        // Perform operations only up to fiat-shamir of challenge_in
+        // TODO this clone isn't great but it satisfies the borrow checker
+        for manifest in &self.manifest.round_manifests.clone() {
+            // loop over RoundManifests
+            for entry in &manifest.elements {
+                // loop over ManifestEntrys
+                if entry.name == "circuit_size" {
+                    self.add_element(
+                        "circuit_size",
+                        vec![
+                            (circuit_size >> 24) as u8,
+                            (circuit_size >> 16) as u8,
+                            (circuit_size >> 8) as u8,
+                            circuit_size as u8,
+                        ],
+                    );
+                } else {
+                    let buffer = vec![1; entry.num_bytes]; // arbitrary buffer of 1's
+                    self.add_element(&entry.name, buffer);
+                }
+            }
+            if challenge_in == manifest.challenge {
+                break;
+            } else {
+                self.apply_fiat_shamir(&manifest.challenge)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds `element` in barretenberg's big-endian encoding, see [`fields`](crate::ecc::fields).
+    pub(crate) fn add_field_element(&mut self, element_name: &str, element: &C::Fr) {
+        self.add_element(element_name, to_be_bytes(element));
+    }
+
+    /// Adds `element` in barretenberg's uncompressed encoding, see [`PointEncoding`].
+    pub(crate) fn add_group_element(&mut self, element_name: &str, element: &C::G1Affine) {
+        self.add_element(element_name, element.to_uncompressed());
+    }
+    pub(crate) fn get_field_element(&self, element_name: &str) -> C::Fr {
+        from_be_bytes(&self.get_element(element_name))
+            .expect("transcript field element is canonical")
+    }
+    /// Reads back a group element, failing if it is not a valid point.
+    pub(crate) fn get_group_element(&self, element_name: &str) -> Result<C::G1Affine, Error> {
+        C::G1Affine::from_uncompressed(&self.get_element(element_name))
+    }
+    pub(crate) fn get_field_element_vector(&self, element_name: &str) -> Vec<C::Fr> {
+        self.get_element(element_name)
+            .chunks(encoded_size::<C::Fr>())
+            .map(|chunk| from_be_bytes(chunk).expect("transcript field element is canonical"))
+            .collect()
+    }
+    pub(crate) fn put_field_element_vector(&mut self, element_name: &str, elements: &[C::Fr]) {
+        let buf = elements.iter().flat_map(to_be_bytes).collect();
+        self.add_element(element_name, buf);
+    }
+
+    /// Reads challenge `challenge_name` as a big-endian integer reduced into the field, as barretenberg does.
+    pub(crate) fn get_challenge_field_element(
+        &self,
+        challenge_name: &str,
+        idx: Option<usize>,
+    ) -> C::Fr {
+        let buf = self.get_challenge(challenge_name, idx);
+        C::Fr::from_be_bytes_mod_order(buf.unwrap().as_slice())
+    }
+    /// Reads the challenge that batches `challenge_map_name` in its round, as
+    /// [`get_challenge_field_element`](Self::get_challenge_field_element) does.
+    pub(crate) fn get_challenge_field_element_from_map(
+        &self,
+        challenge_name: &str,
+        challenge_map_name: &str,
+    ) -> C::Fr {
+        let buf = self.get_challenge_from_map(challenge_name, challenge_map_name);
+        C::Fr::from_be_bytes_mod_order(buf.as_slice())
+    }
+}
+
+/// `element` in its uncompressed arkworks encoding, as the streaming transcript sends it.
+fn serialize<T: CanonicalSerialize>(element: &T) -> Vec<u8> {
+    let mut buffer = vec![];
+    element
+        .serialize_uncompressed(&mut buffer)
+        .expect("serializing into a vector cannot fail");
+    buffer
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::{Fr, G1Affine};
+    use ark_ec::AffineRepr;
+
+    type StandardTranscript = Transcript<Keccak256, Bn254>;
+
+    fn one_round(element: &[u8], num_challenges: usize) -> Manifest {
+        Manifest::new(vec![RoundManifest::new(
+            vec![ManifestEntry::new("x", element.len(), false, 0)],
+            "init",
+            num_challenges,
+            false,
+        )])
+    }
+
+    fn challenges(
+        element: &[u8],
+        num_challenges: usize,
+        num_challenge_bytes: usize,
+    ) -> Vec<String> {
+        let mut transcript = StandardTranscript::new(
+            Some(one_round(element, num_challenges)),
+            num_challenge_bytes,
+        );
+        transcript.add_element("x", element.to_vec());
+        transcript.apply_fiat_shamir("init").unwrap();
+        (0..num_challenges)
+            .map(|i| {
+                let challenge = transcript.get_challenge("init", Some(i)).unwrap();
+                challenge
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The first round hashes its elements alone, so its challenges are chunks of the published Keccak-256
+    /// digests of "" and "abc", which are what barretenberg's keccak transcript derives.
+    #[test]
+    fn challenges_match_barretenberg_keccak() {
+        assert_eq!(
+            challenges(b"", 1, 32),
+            ["c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"]
+        );
+        assert_eq!(
+            challenges(b"abc", 2, 16),
+            [
+                "000000000000000000000000000000004e03657aea45a94fc7d47ba826c8d667",
+                "00000000000000000000000000000000c0d1e6e33a64a036ec44f58fa12d6c45",
+            ]
+        );
+    }
+
+    #[test]
+    fn further_challenges_rehash_the_first_hash() {
+        let mut transcript = StandardTranscript::new(Some(one_round(&[42], 5)), 16);
+        transcript.add_element("x", vec![42]);
+        transcript.apply_fiat_shamir("init").unwrap();
+        let base_hash = Keccak256::hash(&[42]);
+        let mut rolling = base_hash.to_vec();
+        rolling.push(2);
+        let third_hash = Keccak256::hash(&rolling);
+        assert_eq!(transcript.get_num_challenges("init"), 5);
+        assert_eq!(
+            transcript.get_challenge("init", Some(4)).unwrap()[16..],
+            third_hash[..16]
+        );
+    }
+
+    #[test]
+    fn challenges_read_as_big_endian_field_elements() {
+        let mut transcript = StandardTranscript::new(Some(one_round(&[42], 1)), 16);
+        transcript.add_element("x", vec![42]);
+        transcript.apply_fiat_shamir("init").unwrap();
+        let base_hash = Keccak256::hash(&[42]);
+        assert_eq!(
+            transcript.get_challenge_field_element("init", None),
+            Fr::from_be_bytes_mod_order(&base_hash[..16])
+        );
+    }
+
+    #[test]
+    fn oversized_challenges_are_rejected() {
+        for num_challenge_bytes in [0, 33] {
+            let mut transcript =
+                StandardTranscript::new(Some(one_round(&[1], 1)), num_challenge_bytes);
+            transcript.add_element("x", vec![1]);
+            assert!(transcript.apply_fiat_shamir("init").is_err());
+        }
+    }
+
+    #[test]
+    fn rounds_must_follow_the_manifest() {
+        let mut transcript = StandardTranscript::new(Some(one_round(&[1], 1)), 16);
+        assert!(
+            transcript.apply_fiat_shamir("init").is_err(),
+            "x is missing"
+        );
+        transcript.add_element("x", vec![1, 2]);
+        assert!(
+            transcript.apply_fiat_shamir("init").is_err(),
+            "x is too long"
+        );
+        transcript.add_element("x", vec![1]);
+        assert!(transcript.apply_fiat_shamir("beta").is_err());
+        transcript.apply_fiat_shamir("init").unwrap();
+        assert!(transcript.apply_fiat_shamir("init").is_err());
+    }
+
+    #[test]
+    fn streamed_challenges_chain_the_hashes() {
+        let mut transcript = StandardTranscript::new_streaming();
+        transcript.send_to_verifier("a", &Fr::from(5u64));
+        let alpha: Fr = transcript.derive_challenge("alpha");
+        let beta: Fr = transcript.derive_challenge("beta");
+
+        let first_hash = Keccak256::hash(&transcript.export_transcript());
+        assert_eq!(alpha, Fr::from_be_bytes_mod_order(&first_hash));
+        assert_eq!(
+            beta,
+            Fr::from_be_bytes_mod_order(&Keccak256::hash(&first_hash))
+        );
+    }
+
+    #[test]
+    fn streaming_prover_and_verifier_derive_the_same_challenges() {
+        let mut prover = StandardTranscript::new_streaming();
+        prover.send_to_verifier("a", &Fr::from(5u64));
+        prover.send_to_verifier("P", &G1Affine::generator());
+        let alpha: Fr = prover.derive_challenge("alpha");
+        prover.send_to_verifier("b", &[Fr::from(1u64), Fr::from(2u64)]);
+        let beta: Fr = prover.derive_challenge("beta");
+        let gamma: Fr = prover.derive_challenge("gamma");
+        assert_ne!(beta, gamma);
+        prover.send_to_verifier("c", &Fr::from(7u64));
+
+        let mut verifier =
+            StandardTranscript::new_streaming_from_proof(&prover.export_transcript());
+        assert_eq!(
+            verifier.receive_from_prover::<Fr>("a").unwrap(),
+            Fr::from(5u64)
+        );
+        assert_eq!(
+            verifier.receive_from_prover::<G1Affine>("P").unwrap(),
+            G1Affine::generator()
+        );
+        assert_eq!(verifier.derive_challenge::<Fr>("alpha"), alpha);
+        verifier.receive_from_prover::<[Fr; 2]>("b").unwrap();
+        assert_eq!(verifier.derive_challenge::<Fr>("beta"), beta);
+        assert_eq!(verifier.derive_challenge::<Fr>("gamma"), gamma);
+        assert!(!verifier.is_exhausted());
+        assert_eq!(
+            verifier.receive_from_prover::<Fr>("c").unwrap(),
+            Fr::from(7u64)
+        );
+        assert!(verifier.is_exhausted());
+        assert!(verifier.receive_from_prover::<Fr>("d").is_err());
+    }
+
+    #[test]
+    fn tampered_stream_changes_the_challenge() {
+        let mut prover = StandardTranscript::new_streaming();
+        prover.send_to_verifier("a", &Fr::from(5u64));
+        let alpha: Fr = prover.derive_challenge("alpha");
+
+        let mut proof_data = prover.export_transcript();
+        proof_data[0] ^= 1;
+        let mut verifier = StandardTranscript::new_streaming_from_proof(&proof_data);
+        verifier.receive_from_prover::<Fr>("a").unwrap();
+        assert_ne!(verifier.derive_challenge::<Fr>("alpha"), alpha);
+    }
+
+    #[test]
+    fn streaming_verifier_rejects_a_repeated_label() {
+        let mut prover = StandardTranscript::new_streaming();
+        prover.send_to_verifier("a", &Fr::from(5u64));
+        prover.send_to_verifier("b", &Fr::from(6u64));
+
+        let mut verifier =
+            StandardTranscript::new_streaming_from_proof(&prover.export_transcript());
+        verifier.receive_from_prover::<Fr>("a").unwrap();
+        assert!(verifier.receive_from_prover::<Fr>("a").is_err());
+    }
+
+    #[test]
+    fn absorbed_elements_bind_the_challenges_but_stay_out_of_the_proof() {
+        let challenge = |absorbed: u64| {
+            let mut transcript = StandardTranscript::new_streaming();
+            transcript.absorb("known", &Fr::from(absorbed));
+            transcript.send_to_verifier("a", &Fr::from(5u64));
+            let alpha: Fr = transcript.derive_challenge("alpha");
+            (alpha, transcript.export_transcript())
+        };
+        let (alpha, proof_data) = challenge(1);
+        assert_ne!(alpha, challenge(2).0);
+        assert_eq!(proof_data, challenge(2).1);
+
+        let mut verifier = StandardTranscript::new_streaming_from_proof(&proof_data);
+        verifier.absorb("known", &Fr::from(1u64));
+        verifier.receive_from_prover::<Fr>("a").unwrap();
+        assert_eq!(verifier.derive_challenge::<Fr>("alpha"), alpha);
+        assert!(verifier.is_exhausted());
+    }
+}
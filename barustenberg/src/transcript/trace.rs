@@ -0,0 +1,435 @@
+//! Opt-in tracing of transcript interactions, for finding where a prover and a verifier diverge.
+//!
+//! A transcript with [`enable_trace`](super::Transcript::enable_trace) called records every element it is
+//! given, every Fiat-Shamir round and every challenge read back, and logs each one as a debug level `tracing`
+//! event with target `barustenberg::transcript`. The two sides add elements and read challenges in different
+//! orders, but they must hash the same bytes in the same rounds, so [`diff_traces`] compares the Fiat-Shamir
+//! rounds and reports the first element that differs.
+//!
+//! Traces from two processes, say a native prover and a verifier running elsewhere, can be compared through
+//! trace files written with [`write_trace`] and read back with [`read_trace`], one JSON event per line. The
+//! prover's and verifier's `trace_to` write them, and the `diff_traces` example compares two of them.
+
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// One recorded transcript interaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum TranscriptEvent {
+    AddElement {
+        round: usize,
+        name: String,
+        bytes: Vec<u8>,
+    },
+    /// A Fiat-Shamir round. The bytes hashed are `previous` followed by the bytes of each of `inputs`.
+    FiatShamir {
+        round: usize,
+        challenge: String,
+        /// The last challenge of the round before, empty in the first round.
+        previous: Vec<u8>,
+        /// The round's manifest elements, in the order they were hashed.
+        inputs: Vec<(String, Vec<u8>)>,
+        /// The derived challenges, left padded to the hash output size.
+        challenges: Vec<Vec<u8>>,
+    },
+    GetChallenge {
+        round: usize,
+        name: String,
+        index: usize,
+        challenge: Vec<u8>,
+    },
+}
+
+impl TranscriptEvent {
+    pub(crate) fn log(&self) {
+        match self {
+            TranscriptEvent::AddElement { round, name, bytes } => debug!(
+                target: "barustenberg::transcript",
+                round,
+                element = name.as_str(),
+                bytes = %Hex(bytes),
+                "add_element"
+            ),
+            TranscriptEvent::FiatShamir {
+                round,
+                challenge,
+                previous,
+                inputs,
+                challenges,
+            } => {
+                let hashed: Vec<u8> = previous
+                    .iter()
+                    .chain(inputs.iter().flat_map(|(_, bytes)| bytes))
+                    .copied()
+                    .collect();
+                let names: Vec<&str> = inputs.iter().map(|(name, _)| name.as_str()).collect();
+                debug!(
+                    target: "barustenberg::transcript",
+                    round,
+                    challenge = challenge.as_str(),
+                    elements = ?names,
+                    hashed = %Hex(&hashed),
+                    derived = ?challenges.iter().map(|c| Hex(c)).collect::<Vec<_>>(),
+                    "apply_fiat_shamir"
+                )
+            }
+            TranscriptEvent::GetChallenge {
+                round,
+                name,
+                index,
+                challenge,
+            } => debug!(
+                target: "barustenberg::transcript",
+                round,
+                challenge = name.as_str(),
+                index,
+                value = %Hex(challenge),
+                "get_challenge"
+            ),
+        }
+    }
+}
+
+/// Bytes as lowercase hex.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The first point at which a prover's and a verifier's transcripts disagree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceMismatch {
+    /// The sides derive different challenges in `round`, or only one of them reaches it.
+    Challenge {
+        /// index of the Fiat-Shamir round
+        round: usize,
+        /// the prover's challenge name, if it reaches the round
+        prover: Option<String>,
+        /// the verifier's challenge name, if it reaches the round
+        verifier: Option<String>,
+    },
+    /// An element hashed into `round` differs, or is hashed by one side only.
+    Element {
+        /// index of the Fiat-Shamir round
+        round: usize,
+        /// name of the round's challenge
+        challenge: String,
+        /// name of the element
+        name: String,
+        /// the bytes the prover hashed, if any
+        prover: Option<Vec<u8>>,
+        /// the bytes the verifier hashed, if any
+        verifier: Option<Vec<u8>>,
+    },
+    /// The sides hash the same bytes and still derive different challenges, so they disagree on the hash or
+    /// the challenge size.
+    Derivation {
+        /// index of the Fiat-Shamir round
+        round: usize,
+        /// name of the round's challenge
+        challenge: String,
+    },
+}
+
+impl fmt::Display for TraceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |bytes: &Option<Vec<u8>>| match bytes {
+            Some(bytes) => Hex(bytes).to_string(),
+            None => "nothing".to_string(),
+        };
+        match self {
+            TraceMismatch::Challenge {
+                round,
+                prover,
+                verifier,
+            } => write!(
+                f,
+                "round {}: prover derives {}, verifier derives {}",
+                round,
+                prover.as_deref().unwrap_or("nothing"),
+                verifier.as_deref().unwrap_or("nothing")
+            ),
+            TraceMismatch::Element {
+                round,
+                challenge,
+                name,
+                prover,
+                verifier,
+            } => write!(
+                f,
+                "round {} ({}): element {} differs, prover hashed {}, verifier hashed {}",
+                round,
+                challenge,
+                name,
+                side(prover),
+                side(verifier)
+            ),
+            TraceMismatch::Derivation { round, challenge } => write!(
+                f,
+                "round {} ({}): same input, different challenges",
+                round, challenge
+            ),
+        }
+    }
+}
+
+/// A Fiat-Shamir round of a trace: its challenge name, hashed elements and derived challenges.
+type Round<'a> = (&'a String, &'a [(String, Vec<u8>)], &'a [Vec<u8>]);
+
+fn fiat_shamir_rounds(trace: &[TranscriptEvent]) -> Vec<Round<'_>> {
+    trace
+        .iter()
+        .filter_map(|event| match event {
+            TranscriptEvent::FiatShamir {
+                challenge,
+                inputs,
+                challenges,
+                ..
+            } => Some((challenge, inputs.as_slice(), challenges.as_slice())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Compares the Fiat-Shamir rounds of a prover's and a verifier's traces, returning where they first diverge.
+pub(crate) fn diff_traces(
+    prover: &[TranscriptEvent],
+    verifier: &[TranscriptEvent],
+) -> Option<TraceMismatch> {
+    let (prover, verifier) = (fiat_shamir_rounds(prover), fiat_shamir_rounds(verifier));
+    for round in 0..prover.len().max(verifier.len()) {
+        let ((challenge, p_inputs, p_challenges), (_, v_inputs, v_challenges)) =
+            match (prover.get(round), verifier.get(round)) {
+                (Some(p), Some(v)) if p.0 == v.0 => (*p, *v),
+                (p, v) => {
+                    return Some(TraceMismatch::Challenge {
+                        round,
+                        prover: p.map(|p| p.0.clone()),
+                        verifier: v.map(|v| v.0.clone()),
+                    })
+                }
+            };
+        for i in 0..p_inputs.len().max(v_inputs.len()) {
+            let (p_input, v_input) = (p_inputs.get(i), v_inputs.get(i));
+            if p_input != v_input {
+                let (name, _) = p_input.or(v_input).expect("one side hashed the element");
+                return Some(TraceMismatch::Element {
+                    round,
+                    challenge: challenge.clone(),
+                    name: name.clone(),
+                    prover: p_input.map(|(_, bytes)| bytes.clone()),
+                    verifier: v_input.map(|(_, bytes)| bytes.clone()),
+                });
+            }
+        }
+        if p_challenges != v_challenges {
+            return Some(TraceMismatch::Derivation {
+                round,
+                challenge: challenge.clone(),
+            });
+        }
+    }
+    None
+}
+
+/// Writes `trace` to `path` as JSON lines, one event per line.
+pub(crate) fn write_trace(path: impl AsRef<Path>, trace: &[TranscriptEvent]) -> Result<()> {
+    let path = path.as_ref();
+    let mut file = BufWriter::new(
+        File::create(path).with_context(|| format!("creating trace file {}", path.display()))?,
+    );
+    for event in trace {
+        serde_json::to_writer(&mut file, event)?;
+        file.write_all(b"\n")?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Reads a trace written by [`write_trace`].
+pub(crate) fn read_trace(path: impl AsRef<Path>) -> Result<Vec<TranscriptEvent>> {
+    let path = path.as_ref();
+    let file =
+        File::open(path).with_context(|| format!("opening trace file {}", path.display()))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(i, line)| {
+            serde_json::from_str(&line?)
+                .with_context(|| format!("{}:{}: not a transcript event", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// Compares the trace files a prover and a verifier wrote of the same proof, returning where their Fiat-Shamir
+/// rounds first diverge, `None` if they agree.
+pub fn diff_trace_files(
+    prover: impl AsRef<Path>,
+    verifier: impl AsRef<Path>,
+) -> Result<Option<TraceMismatch>> {
+    Ok(diff_traces(&read_trace(prover)?, &read_trace(verifier)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ecc::curves::Bn254,
+        plonk::composer::manifest::standard_manifest,
+        transcript::{BarretenHasher, Keccak256, Transcript},
+    };
+
+    type StandardTranscript = Transcript<Keccak256, Bn254>;
+
+    /// The standard manifest's first three rounds with `w_2` as the second wire commitment. The verifier
+    /// receives the commitments before the public inputs, which must not matter.
+    fn run(w_2: u8, verifier: bool) -> Vec<TranscriptEvent> {
+        let mut transcript = StandardTranscript::new(Some(standard_manifest(1)), 16);
+        transcript.enable_trace();
+        transcript.add_element("circuit_size", vec![0, 0, 0, 16]);
+        transcript.add_element("public_input_size", vec![0, 0, 0, 1]);
+        transcript.apply_fiat_shamir("init").unwrap();
+        transcript.apply_fiat_shamir("eta").unwrap();
+        if !verifier {
+            transcript.add_element("public_inputs", vec![7; 32]);
+        }
+        for (name, byte) in [("W_1", 1), ("W_2", w_2), ("W_3", 3)] {
+            transcript.add_element(name, vec![byte; 64]);
+        }
+        if verifier {
+            transcript.add_element("public_inputs", vec![7; 32]);
+        }
+        transcript.apply_fiat_shamir("beta").unwrap();
+        transcript.get_challenge("beta", Some(1)).unwrap();
+        transcript.take_trace()
+    }
+
+    #[test]
+    fn challenges_are_padded_chunks_of_the_hash() {
+        let trace = run(2, false);
+        assert_eq!(trace.len(), 10);
+        let (previous, inputs, challenges) = match &trace[8] {
+            TranscriptEvent::FiatShamir {
+                previous,
+                inputs,
+                challenges,
+                ..
+            } => (previous, inputs, challenges),
+            event => panic!("expected the beta round, got {:?}", event),
+        };
+        let names: Vec<_> = inputs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["public_inputs", "W_1", "W_2", "W_3"]);
+        let hashed: Vec<u8> = previous
+            .iter()
+            .chain(inputs.iter().flat_map(|(_, b)| b))
+            .copied()
+            .collect();
+        let hash = Keccak256::hash(&hashed);
+        assert_eq!(challenges.len(), 2);
+        for (j, challenge) in challenges.iter().enumerate() {
+            assert_eq!(challenge[..16], [0; 16]);
+            assert_eq!(challenge[16..], hash[16 * j..16 * (j + 1)]);
+        }
+        assert_eq!(
+            trace[9],
+            TranscriptEvent::GetChallenge {
+                round: 3,
+                name: "beta".to_string(),
+                index: 1,
+                challenge: challenges[1].clone(),
+            }
+        );
+    }
+
+    #[test]
+    fn untraced_transcripts_record_nothing() {
+        let mut transcript = StandardTranscript::new(Some(standard_manifest(1)), 16);
+        transcript.add_element("circuit_size", vec![0, 0, 0, 16]);
+        transcript.add_element("public_input_size", vec![0, 0, 0, 1]);
+        transcript.apply_fiat_shamir("init").unwrap();
+        assert!(transcript.take_trace().is_empty());
+    }
+
+    #[test]
+    fn matching_traces_have_no_mismatch() {
+        assert_eq!(diff_traces(&run(2, false), &run(2, true)), None);
+    }
+
+    #[test]
+    fn diff_points_at_the_first_differing_element() {
+        let mismatch = diff_traces(&run(2, false), &run(5, true)).unwrap();
+        assert_eq!(
+            mismatch,
+            TraceMismatch::Element {
+                round: 2,
+                challenge: "beta".to_string(),
+                name: "W_2".to_string(),
+                prover: Some(vec![2; 64]),
+                verifier: Some(vec![5; 64]),
+            }
+        );
+        assert!(mismatch
+            .to_string()
+            .starts_with("round 2 (beta): element W_2 differs"));
+
+        let mut verifier = run(2, true);
+        verifier.truncate(6);
+        assert_eq!(
+            diff_traces(&run(2, false), &verifier),
+            Some(TraceMismatch::Challenge {
+                round: 2,
+                prover: Some("beta".to_string()),
+                verifier: None,
+            })
+        );
+    }
+
+    #[test]
+    fn trace_files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let (prover, verifier) = (
+            dir.path().join("prover.jsonl"),
+            dir.path().join("verifier.jsonl"),
+        );
+        write_trace(&prover, &run(2, false)).unwrap();
+        write_trace(&verifier, &run(5, true)).unwrap();
+        assert_eq!(read_trace(&prover).unwrap(), run(2, false));
+
+        let mismatch = diff_trace_files(&prover, &verifier).unwrap().unwrap();
+        assert_eq!(
+            mismatch,
+            diff_traces(&run(2, false), &run(5, true)).unwrap()
+        );
+        let json = serde_json::to_string(&mismatch).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TraceMismatch>(&json).unwrap(),
+            mismatch
+        );
+
+        std::fs::write(&verifier, "{\"AddElement\":{}}\n").unwrap();
+        let error = diff_trace_files(&prover, &verifier).unwrap_err();
+        assert!(format!("{:#}", error).contains("verifier.jsonl:1"));
+        assert!(read_trace(dir.path().join("missing.jsonl")).is_err());
+    }
+}
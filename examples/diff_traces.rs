@@ -0,0 +1,32 @@
+//! Compares the transcript traces a prover and a verifier wrote of the same proof.
+//!
+//! ```text
+//! cargo run --example diff_traces -- prover.jsonl verifier.jsonl
+//! ```
+//!
+//! Prints the first Fiat-Shamir round at which the two disagree and exits with status 1, exits with status 0
+//! if they agree, and with status 2 if the traces cannot be read.
+
+use std::{env, process};
+
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (prover, verifier) = match args.as_slice() {
+        [prover, verifier] => (prover, verifier),
+        _ => {
+            eprintln!("usage: diff_traces <prover trace> <verifier trace>");
+            process::exit(2);
+        }
+    };
+    match barustenberg::diff_trace_files(prover, verifier) {
+        Ok(Some(mismatch)) => {
+            println!("{}", mismatch);
+            process::exit(1);
+        }
+        Ok(None) => println!("the transcripts agree"),
+        Err(error) => {
+            eprintln!("{:#}", error);
+            process::exit(2);
+        }
+    }
+}